@@ -39,6 +39,8 @@ pub enum FileType {
     TitleScreen = 9,
     #[strum(serialize = "Manual Scan")]
     ManualScan = 10,
+    #[strum(serialize = "BIOS / Firmware")]
+    Firmware = 11,
 }
 
 impl FileType {
@@ -54,6 +56,7 @@ impl FileType {
             FileType::LoadingScreen => "loading_screen",
             FileType::TitleScreen => "title_screen",
             FileType::ManualScan => "manual_scan",
+            FileType::Firmware => "firmware",
         }
     }
 }
@@ -0,0 +1,12 @@
+ALTER TABLE emulator ADD COLUMN working_directory TEXT;
+ALTER TABLE emulator ADD COLUMN pre_launch_command TEXT;
+ALTER TABLE emulator ADD COLUMN post_launch_command TEXT;
+
+CREATE TABLE emulator_environment_variable (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    emulator_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    value TEXT NOT NULL,
+    UNIQUE (emulator_id, name),
+    FOREIGN KEY (emulator_id) REFERENCES emulator(id) ON DELETE CASCADE
+);
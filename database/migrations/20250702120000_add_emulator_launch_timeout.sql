@@ -0,0 +1 @@
+ALTER TABLE emulator ADD COLUMN launch_timeout_seconds INTEGER;
@@ -0,0 +1,13 @@
+CREATE TABLE system_default_emulator (
+    system_id INTEGER PRIMARY KEY NOT NULL,
+    emulator_id INTEGER NOT NULL,
+    FOREIGN KEY (system_id) REFERENCES system(id) ON DELETE CASCADE,
+    FOREIGN KEY (emulator_id) REFERENCES emulator(id) ON DELETE CASCADE
+);
+
+CREATE TABLE release_emulator (
+    release_id INTEGER PRIMARY KEY NOT NULL,
+    emulator_id INTEGER NOT NULL,
+    FOREIGN KEY (release_id) REFERENCES release(id) ON DELETE CASCADE,
+    FOREIGN KEY (emulator_id) REFERENCES emulator(id) ON DELETE CASCADE
+);
@@ -0,0 +1,11 @@
+ALTER TABLE file_set_file_info ADD COLUMN ordinal INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE file_set_file_info ADD COLUMN is_entry_point BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- existing files are ordered by file name
+UPDATE file_set_file_info
+SET ordinal = (
+    SELECT COUNT(*)
+    FROM file_set_file_info other
+    WHERE other.file_set_id = file_set_file_info.file_set_id
+      AND other.file_name < file_set_file_info.file_name
+);
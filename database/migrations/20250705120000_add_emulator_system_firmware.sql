@@ -0,0 +1,10 @@
+CREATE TABLE emulator_system_firmware (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    emulator_system_id INTEGER NOT NULL,
+    file_set_id INTEGER NOT NULL,
+    target_path TEXT NOT NULL,
+    required BOOLEAN NOT NULL DEFAULT TRUE,
+    UNIQUE (emulator_system_id, file_set_id),
+    FOREIGN KEY (emulator_system_id) REFERENCES emulator_system(id) ON DELETE CASCADE,
+    FOREIGN KEY (file_set_id) REFERENCES file_set(id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- 1 = binary, 2 = flatpak, 3 = AppImage, 4 = Wine
+ALTER TABLE emulator ADD COLUMN executable_kind INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE emulator ADD COLUMN wine_prefix TEXT;
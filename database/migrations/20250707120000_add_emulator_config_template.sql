@@ -0,0 +1,19 @@
+-- Emulator configuration file rendered into the launch directory. Template without a release is
+-- the default of the emulator system, release specific template overrides it.
+CREATE TABLE emulator_config_template (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    emulator_system_id INTEGER NOT NULL,
+    release_id INTEGER,
+    file_name TEXT NOT NULL,
+    content TEXT NOT NULL,
+    FOREIGN KEY (emulator_system_id) REFERENCES emulator_system(id) ON DELETE CASCADE,
+    FOREIGN KEY (release_id) REFERENCES release(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX emulator_config_template_default
+    ON emulator_config_template (emulator_system_id)
+    WHERE release_id IS NULL;
+
+CREATE UNIQUE INDEX emulator_config_template_release
+    ON emulator_config_template (emulator_system_id, release_id)
+    WHERE release_id IS NOT NULL;
@@ -0,0 +1,13 @@
+CREATE TABLE release_emulator_compatibility (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    release_id INTEGER NOT NULL,
+    emulator_id INTEGER NOT NULL,
+    system_id INTEGER NOT NULL,
+    status INTEGER NOT NULL,
+    notes TEXT,
+    tested_at TEXT NOT NULL DEFAULT (date('now')),
+    UNIQUE (release_id, emulator_id, system_id),
+    FOREIGN KEY (release_id) REFERENCES release(id) ON DELETE CASCADE,
+    FOREIGN KEY (emulator_id) REFERENCES emulator(id) ON DELETE CASCADE,
+    FOREIGN KEY (system_id) REFERENCES system(id) ON DELETE CASCADE
+);
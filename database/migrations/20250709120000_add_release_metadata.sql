@@ -0,0 +1,19 @@
+-- release date is YYYY, YYYY-MM or YYYY-MM-DD when the month or day is unknown
+ALTER TABLE release ADD COLUMN release_date TEXT;
+ALTER TABLE release ADD COLUMN version TEXT;
+ALTER TABLE release ADD COLUMN product_code TEXT;
+ALTER TABLE release ADD COLUMN barcode TEXT;
+
+CREATE TABLE release_region (
+    release_id INTEGER NOT NULL,
+    region TEXT NOT NULL,
+    PRIMARY KEY (release_id, region),
+    FOREIGN KEY (release_id) REFERENCES release(id) ON DELETE CASCADE
+);
+
+CREATE TABLE release_language (
+    release_id INTEGER NOT NULL,
+    language TEXT NOT NULL,
+    PRIMARY KEY (release_id, language),
+    FOREIGN KEY (release_id) REFERENCES release(id) ON DELETE CASCADE
+);
@@ -0,0 +1,24 @@
+CREATE TABLE company (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name TEXT NOT NULL
+);
+
+-- Developers of the software titles.
+CREATE TABLE software_title_company (
+    software_title_id INTEGER NOT NULL,
+    company_id INTEGER NOT NULL,
+    PRIMARY KEY (software_title_id, company_id),
+    FOREIGN KEY (software_title_id) REFERENCES software_title(id) ON DELETE CASCADE,
+    FOREIGN KEY (company_id) REFERENCES company(id) ON DELETE CASCADE
+);
+
+-- Publishers (2) and distributors (3) of the releases, developers (1) are linked to the
+-- software titles.
+CREATE TABLE release_company (
+    release_id INTEGER NOT NULL,
+    company_id INTEGER NOT NULL,
+    role INTEGER NOT NULL CHECK (role IN (2, 3)),
+    PRIMARY KEY (release_id, company_id, role),
+    FOREIGN KEY (release_id) REFERENCES release(id) ON DELETE CASCADE,
+    FOREIGN KEY (company_id) REFERENCES company(id) ON DELETE CASCADE
+);
@@ -0,0 +1,14 @@
+-- Name the title is sorted by, e.g. "Legend of Zelda, The". Derived from the name when not set.
+ALTER TABLE software_title ADD COLUMN sort_name TEXT;
+
+-- Other names of the title, e.g. regional names like "Probotector" for "Contra".
+CREATE TABLE software_title_alias (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    software_title_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    language TEXT,
+    region TEXT,
+    FOREIGN KEY (software_title_id) REFERENCES software_title(id) ON DELETE CASCADE
+);
+
+CREATE INDEX software_title_alias_software_title_id ON software_title_alias(software_title_id);
@@ -0,0 +1,29 @@
+-- Hierarchy levels are separated with a slash, e.g. "genre/shmup" is a subtag of "genre".
+CREATE TABLE tag (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE
+);
+
+CREATE TABLE software_title_tag (
+    software_title_id INTEGER NOT NULL,
+    tag_id INTEGER NOT NULL,
+    PRIMARY KEY (software_title_id, tag_id),
+    FOREIGN KEY (software_title_id) REFERENCES software_title(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tag(id) ON DELETE CASCADE
+);
+
+CREATE TABLE release_tag (
+    release_id INTEGER NOT NULL,
+    tag_id INTEGER NOT NULL,
+    PRIMARY KEY (release_id, tag_id),
+    FOREIGN KEY (release_id) REFERENCES release(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tag(id) ON DELETE CASCADE
+);
+
+CREATE TABLE file_set_tag (
+    file_set_id INTEGER NOT NULL,
+    tag_id INTEGER NOT NULL,
+    PRIMARY KEY (file_set_id, tag_id),
+    FOREIGN KEY (file_set_id) REFERENCES file_set(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tag(id) ON DELETE CASCADE
+);
@@ -0,0 +1,23 @@
+-- User curated, manually ordered collections of releases. The built-in favorites collection
+-- can't be renamed or deleted.
+CREATE TABLE collection (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    is_favorites INTEGER NOT NULL DEFAULT 0 CHECK (is_favorites IN (0, 1))
+);
+
+CREATE UNIQUE INDEX idx_collection_favorites ON collection(is_favorites) WHERE is_favorites = 1;
+
+INSERT INTO collection (name, is_favorites) VALUES ('Favorites', 1);
+
+-- Releases are listed in the order of their positions.
+CREATE TABLE collection_release (
+    collection_id INTEGER NOT NULL,
+    release_id INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (collection_id, release_id),
+    FOREIGN KEY (collection_id) REFERENCES collection(id) ON DELETE CASCADE,
+    FOREIGN KEY (release_id) REFERENCES release(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_collection_release_position ON collection_release(collection_id, position);
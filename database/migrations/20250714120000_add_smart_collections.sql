@@ -0,0 +1,13 @@
+-- Releases of a smart collection are the releases matching its criteria, releases can't be
+-- added to it by hand.
+ALTER TABLE collection ADD COLUMN is_smart INTEGER NOT NULL DEFAULT 0 CHECK (is_smart IN (0, 1));
+
+CREATE TABLE collection_criterion (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    collection_id INTEGER NOT NULL,
+    kind INTEGER NOT NULL,
+    value TEXT NOT NULL,
+    FOREIGN KEY (collection_id) REFERENCES collection(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_collection_criterion_collection ON collection_criterion(collection_id);
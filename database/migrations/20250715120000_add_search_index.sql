@@ -0,0 +1,98 @@
+-- Names found by the full-text search, kept in sync with the named rows by the triggers below.
+--
+-- Caveat: a statement failing after the triggers have written to the FTS5 index, e.g. on a
+-- foreign key, leaves the index read open and the database locked for the other connections
+-- until the connection uses the index again. Autocommit writes to the indexed tables go through
+-- `execute_indexed_write`, writes in a transaction are released by the rollback.
+CREATE TABLE search_entry (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    -- 1 software title, 2 software title alias, 3 release, 4 file of a file set
+    kind INTEGER NOT NULL,
+    -- id of the software title, alias, release or file set
+    entity_id INTEGER NOT NULL,
+    -- file info id of a file, 0 for the other kinds
+    file_info_id INTEGER NOT NULL DEFAULT 0,
+    name TEXT NOT NULL,
+    UNIQUE (kind, entity_id, file_info_id)
+);
+
+-- Matches regardless of case and diacritics, "pokemon" finds "Pokémon".
+CREATE VIRTUAL TABLE search_index USING fts5(
+    name,
+    content = 'search_entry',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER search_entry_insert AFTER INSERT ON search_entry BEGIN
+    INSERT INTO search_index (rowid, name) VALUES (new.id, new.name);
+END;
+
+CREATE TRIGGER search_entry_delete AFTER DELETE ON search_entry BEGIN
+    INSERT INTO search_index (search_index, rowid, name) VALUES ('delete', old.id, old.name);
+END;
+
+CREATE TRIGGER search_entry_update AFTER UPDATE ON search_entry BEGIN
+    INSERT INTO search_index (search_index, rowid, name) VALUES ('delete', old.id, old.name);
+    INSERT INTO search_index (rowid, name) VALUES (new.id, new.name);
+END;
+
+CREATE TRIGGER software_title_search_insert AFTER INSERT ON software_title BEGIN
+    INSERT INTO search_entry (kind, entity_id, name) VALUES (1, new.id, new.name);
+END;
+
+CREATE TRIGGER software_title_search_update AFTER UPDATE OF name ON software_title BEGIN
+    UPDATE search_entry SET name = new.name WHERE kind = 1 AND entity_id = new.id;
+END;
+
+CREATE TRIGGER software_title_search_delete AFTER DELETE ON software_title BEGIN
+    DELETE FROM search_entry WHERE kind = 1 AND entity_id = old.id;
+END;
+
+CREATE TRIGGER software_title_alias_search_insert AFTER INSERT ON software_title_alias BEGIN
+    INSERT INTO search_entry (kind, entity_id, name) VALUES (2, new.id, new.name);
+END;
+
+CREATE TRIGGER software_title_alias_search_update AFTER UPDATE OF name ON software_title_alias
+BEGIN
+    UPDATE search_entry SET name = new.name WHERE kind = 2 AND entity_id = new.id;
+END;
+
+CREATE TRIGGER software_title_alias_search_delete AFTER DELETE ON software_title_alias BEGIN
+    DELETE FROM search_entry WHERE kind = 2 AND entity_id = old.id;
+END;
+
+CREATE TRIGGER release_search_insert AFTER INSERT ON release BEGIN
+    INSERT INTO search_entry (kind, entity_id, name) VALUES (3, new.id, new.name);
+END;
+
+CREATE TRIGGER release_search_update AFTER UPDATE OF name ON release BEGIN
+    UPDATE search_entry SET name = new.name WHERE kind = 3 AND entity_id = new.id;
+END;
+
+CREATE TRIGGER release_search_delete AFTER DELETE ON release BEGIN
+    DELETE FROM search_entry WHERE kind = 3 AND entity_id = old.id;
+END;
+
+CREATE TRIGGER file_set_file_info_search_insert AFTER INSERT ON file_set_file_info BEGIN
+    INSERT INTO search_entry (kind, entity_id, file_info_id, name)
+    VALUES (4, new.file_set_id, new.file_info_id, new.file_name);
+END;
+
+CREATE TRIGGER file_set_file_info_search_update
+AFTER UPDATE OF file_set_id, file_info_id, file_name ON file_set_file_info BEGIN
+    UPDATE search_entry
+    SET entity_id = new.file_set_id, file_info_id = new.file_info_id, name = new.file_name
+    WHERE kind = 4 AND entity_id = old.file_set_id AND file_info_id = old.file_info_id;
+END;
+
+CREATE TRIGGER file_set_file_info_search_delete AFTER DELETE ON file_set_file_info BEGIN
+    DELETE FROM search_entry
+    WHERE kind = 4 AND entity_id = old.file_set_id AND file_info_id = old.file_info_id;
+END;
+
+INSERT INTO search_entry (kind, entity_id, name) SELECT 1, id, name FROM software_title;
+INSERT INTO search_entry (kind, entity_id, name) SELECT 2, id, name FROM software_title_alias;
+INSERT INTO search_entry (kind, entity_id, name) SELECT 3, id, name FROM release;
+INSERT INTO search_entry (kind, entity_id, file_info_id, name)
+SELECT 4, file_set_id, file_info_id, file_name FROM file_set_file_info;
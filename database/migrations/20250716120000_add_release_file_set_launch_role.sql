@@ -0,0 +1,6 @@
+-- Role of a file set when the release is launched with several file sets, e.g. `bios` or
+-- `patch`. Emulator arguments refer to the file set with `{role}`, file sets without a role are
+-- only launched on their own.
+ALTER TABLE release_file_set ADD COLUMN launch_role TEXT;
+
+CREATE UNIQUE INDEX idx_release_file_set_launch_role ON release_file_set (release_id, launch_role);
@@ -0,0 +1,14 @@
+-- Launch history, a launch is added when the emulator has exited. Launches are kept when the
+-- emulator or the system is deleted.
+CREATE TABLE release_launch (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    release_id INTEGER NOT NULL,
+    emulator_id INTEGER,
+    system_id INTEGER,
+    launched_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (release_id) REFERENCES release(id) ON DELETE CASCADE,
+    FOREIGN KEY (emulator_id) REFERENCES emulator(id) ON DELETE SET NULL,
+    FOREIGN KEY (system_id) REFERENCES system(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_release_launch_release_id ON release_launch (release_id, launched_at);
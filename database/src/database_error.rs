@@ -14,6 +14,13 @@ pub enum DatabaseError {
     DbError(String),
 }
 
+impl DatabaseError {
+    /// Whether a single row was queried and it doesn't exist.
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, DatabaseError::SqlxError(SqlxError::RowNotFound))
+    }
+}
+
 impl PartialEq for DatabaseError {
     fn eq(&self, other: &Self) -> bool {
         std::mem::discriminant(self) == std::mem::discriminant(other)
@@ -13,7 +13,5 @@ pub fn get_database_url() -> String {
 }
 
 pub fn get_database_file_path() -> PathBuf {
-    let db_path = get_database_path();
-    println!("Database path: {}", db_path.display());
-    db_path
+    get_database_path()
 }
@@ -1,16 +1,22 @@
 pub mod database_error;
 mod database_path;
 pub mod models;
+mod release_query;
 mod repository;
 pub mod repository_manager;
+pub mod search_query;
 
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 use database_path::get_database_file_path;
 use sqlx::{migrate, sqlite::SqliteConnectOptions, Pool, Sqlite, SqlitePool};
 
 pub async fn get_db_pool() -> Result<Arc<Pool<Sqlite>>, sqlx::Error> {
-    let db_file_path = get_database_file_path();
+    get_db_pool_with_path(&get_database_file_path()).await
+}
+
+/// Opens the database from the given file, the file is created and migrated if needed.
+pub async fn get_db_pool_with_path(db_file_path: &Path) -> Result<Arc<Pool<Sqlite>>, sqlx::Error> {
     let pool = SqlitePool::connect_with(
         SqliteConnectOptions::new()
             .filename(db_file_path)
@@ -45,3 +51,43 @@ pub async fn setup_test_db() -> SqlitePool {
 
     pool
 }
+
+/// Adds a file set with a single file of the same name, the checksum has to be unique.
+#[cfg(test)]
+pub(crate) async fn add_test_file_set(
+    pool: &Arc<Pool<Sqlite>>,
+    name: &str,
+    file_type: models::FileType,
+    checksum: u8,
+) -> i64 {
+    repository::file_set_repository::FileSetRepository::new(Arc::clone(pool))
+        .add_file_set(
+            name.to_string(),
+            file_type,
+            vec![core_types::ImportedFile {
+                original_file_name: name.to_string(),
+                archive_file_name: format!("archive_{}", checksum),
+                sha1_checksum: [checksum; 20],
+                file_size: 1,
+            }],
+            &[],
+        )
+        .await
+        .unwrap()
+}
+
+/// Ids of the releases selected by a release query, in the order of the query.
+#[cfg(test)]
+pub(crate) async fn fetch_release_ids(
+    pool: &Pool<Sqlite>,
+    mut query_builder: sqlx::QueryBuilder<'_, Sqlite>,
+) -> Vec<i64> {
+    query_builder
+        .build_query_as::<models::Release>()
+        .fetch_all(pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|release| release.id)
+        .collect()
+}
@@ -1,9 +1,14 @@
 use core::fmt;
 use core_types::FileType as CoreFileType;
-use std::fmt::{Display, Formatter};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
 
 use sqlx::FromRow;
 
+use crate::database_error::Error;
+
 // TODO: use only FileType from core_types
 #[derive(Debug, Clone, PartialEq, Copy)]
 pub enum FileType {
@@ -17,6 +22,7 @@ pub enum FileType {
     LoadingScreen = 8,
     TitleScreen = 9,
     ManualScan = 10,
+    Firmware = 11,
 }
 
 impl From<FileType> for CoreFileType {
@@ -32,6 +38,7 @@ impl From<FileType> for CoreFileType {
             FileType::LoadingScreen => CoreFileType::LoadingScreen,
             FileType::TitleScreen => CoreFileType::TitleScreen,
             FileType::ManualScan => CoreFileType::ManualScan,
+            FileType::Firmware => CoreFileType::Firmware,
         }
     }
 }
@@ -49,6 +56,7 @@ impl From<CoreFileType> for FileType {
             CoreFileType::LoadingScreen => FileType::LoadingScreen,
             CoreFileType::TitleScreen => FileType::TitleScreen,
             CoreFileType::ManualScan => FileType::ManualScan,
+            CoreFileType::Firmware => FileType::Firmware,
         }
     }
 }
@@ -66,6 +74,7 @@ impl FileType {
             FileType::LoadingScreen => "loading_screen",
             FileType::TitleScreen => "title_screen",
             FileType::ManualScan => "manual_scan",
+            FileType::Firmware => "firmware",
         }
     }
 }
@@ -83,6 +92,7 @@ impl From<FileType> for i64 {
             FileType::LoadingScreen => 8,
             FileType::TitleScreen => 9,
             FileType::ManualScan => 10,
+            FileType::Firmware => 11,
         }
     }
 }
@@ -101,6 +111,7 @@ impl TryFrom<i64> for FileType {
             8 => Ok(FileType::LoadingScreen),
             9 => Ok(FileType::TitleScreen),
             10 => Ok(FileType::ManualScan),
+            11 => Ok(FileType::Firmware),
             _ => Err(sqlx::Error::ColumnDecode {
                 index: "file_type".into(),
                 source: Box::new(std::io::Error::new(
@@ -125,6 +136,7 @@ impl Display for FileType {
             FileType::LoadingScreen => write!(f, "Loading Screen"),
             FileType::TitleScreen => write!(f, "Title Screen"),
             FileType::ManualScan => write!(f, "Manual Scan"),
+            FileType::Firmware => write!(f, "BIOS / Firmware"),
         }
     }
 }
@@ -162,6 +174,10 @@ pub struct FileSetFileInfo {
     pub sha1_checksum: Vec<u8>,
     pub file_size: i64,
     pub archive_file_name: String,
+    /// Position of the file in the file set, e.g. the disk order in a playlist.
+    pub ordinal: i64,
+    /// File the emulator is started with by default.
+    pub is_entry_point: bool,
 }
 
 impl Display for FileSetFileInfo {
@@ -170,7 +186,7 @@ impl Display for FileSetFileInfo {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, FromRow)]
 pub struct Release {
     pub id: i64,
     pub name: String,
@@ -185,6 +201,169 @@ pub struct ReleaseExtended {
     pub file_types: Vec<FileType>,
 }
 
+/// Release date where the day, or the month and day, may be unknown. Stored as `YYYY`,
+/// `YYYY-MM` or `YYYY-MM-DD` so that the dates sort as text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PartialDate {
+    pub year: u16,
+    pub month: Option<u8>,
+    pub day: Option<u8>,
+}
+
+impl PartialDate {
+    fn days_in_month(year: u16, month: u8) -> u8 {
+        let is_leap_year =
+            year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+        match month {
+            2 if is_leap_year => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+}
+
+impl FromStr for PartialDate {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            || Error::ParseError(format!("Invalid date {}, expected YYYY[-MM[-DD]]", value));
+        let parts = value.trim().split('-').collect::<Vec<_>>();
+        // integer parsing accepts a leading `+`, only digits are allowed
+        if parts.len() > 3
+            || parts[0].len() != 4
+            || parts[1..].iter().any(|part| part.len() != 2)
+            || !parts
+                .iter()
+                .all(|part| part.bytes().all(|byte| byte.is_ascii_digit()))
+        {
+            return Err(invalid());
+        }
+        let year = parts[0].parse::<u16>().map_err(|_| invalid())?;
+        let month = parts
+            .get(1)
+            .map(|month| month.parse::<u8>().map_err(|_| invalid()))
+            .transpose()?;
+        let day = parts
+            .get(2)
+            .map(|day| day.parse::<u8>().map_err(|_| invalid()))
+            .transpose()?;
+        if month.is_some_and(|month| !(1..=12).contains(&month)) {
+            return Err(invalid());
+        }
+        if let (Some(month), Some(day)) = (month, day) {
+            if day == 0 || day > Self::days_in_month(year, month) {
+                return Err(invalid());
+            }
+        }
+        Ok(PartialDate { year, month, day })
+    }
+}
+
+impl Display for PartialDate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}", self.year)?;
+        if let Some(month) = self.month {
+            write!(f, "-{:02}", month)?;
+        }
+        if let Some(day) = self.day {
+            write!(f, "-{:02}", day)?;
+        }
+        Ok(())
+    }
+}
+
+/// Catalogue details of a release.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReleaseMetadata {
+    pub release_date: Option<PartialDate>,
+    /// Regions the release was published in, e.g. `Europe` or `USA`.
+    pub regions: Vec<String>,
+    /// Languages of the release, e.g. `en` or `de`.
+    pub languages: Vec<String>,
+    /// Version or revision, e.g. `1.1` or `Rev A`.
+    pub version: Option<String>,
+    /// Product code or serial number printed on the release.
+    pub product_code: Option<String>,
+    pub barcode: Option<String>,
+}
+
+/// Role of a file set of a release when the release is launched with several file sets, e.g. a
+/// BIOS or a patch along with the game.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct FileSetLaunchRole {
+    pub file_set_id: i64,
+    /// Name emulator arguments refer to the file set with, e.g. `bios` for `{bios}`.
+    pub role: String,
+}
+
+/// Releases matching all the given metadata, a release date range includes the partial dates
+/// within it, e.g. `1990-05` is within `1990`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReleaseMetadataFilter {
+    pub region: Option<String>,
+    pub language: Option<String>,
+    pub released_from: Option<PartialDate>,
+    pub released_to: Option<PartialDate>,
+    /// Tag of the release, its software titles or file sets, subtags included.
+    pub tag: Option<String>,
+}
+
+/// Order of listed releases, releases with the same key are ordered by id.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReleaseSortKey {
+    /// Name, ignoring case.
+    #[default]
+    Name,
+    /// Release date, undated releases first.
+    ReleaseDate,
+    /// Order the releases were added in.
+    Id,
+}
+
+impl FromStr for ReleaseSortKey {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "name" => Ok(ReleaseSortKey::Name),
+            "date" | "release_date" => Ok(ReleaseSortKey::ReleaseDate),
+            "id" | "added" => Ok(ReleaseSortKey::Id),
+            _ => Err(Error::ParseError(format!(
+                "Invalid sort key '{}', expected name, date or id",
+                value
+            ))),
+        }
+    }
+}
+
+/// Releases to list, their order and the page of them to return. Releases match all the
+/// given filters and any of the values of a filter, empty filters match all releases,
+/// including the releases without systems, software titles or file sets.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReleaseListQuery {
+    pub release_ids: Vec<i64>,
+    pub system_ids: Vec<i64>,
+    pub software_title_ids: Vec<i64>,
+    /// Releases with a file set of one of the file types.
+    pub file_types: Vec<FileType>,
+    /// Whole names ignoring case, `*` matches any characters and `?` a single character.
+    pub name_patterns: Vec<String>,
+    pub metadata: ReleaseMetadataFilter,
+    /// Releases the company is credited for, for the software titles of the release when a
+    /// developer.
+    pub company_id: Option<i64>,
+    /// Role of the company, any role if not set.
+    pub company_role: Option<CompanyRole>,
+    pub sort_key: ReleaseSortKey,
+    pub descending: bool,
+    /// Keyset pagination, only the releases after this release in the sort order.
+    pub after_release_id: Option<i64>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct System {
     pub id: i64,
@@ -213,12 +392,168 @@ pub struct EmulatorSystem {
     pub arguments: String,
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct EmulatorSystemUpdateModel {
     pub id: Option<i64>,
     pub system_id: i64,
     pub arguments: String,
 }
 
+/// Firmware file set (e.g. a BIOS or Kickstart ROM) an emulator needs for a system. Files of the
+/// file set are exported to the target path before the emulator is launched.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct EmulatorSystemFirmware {
+    pub id: i64,
+    pub emulator_system_id: i64,
+    pub file_set_id: i64,
+    pub file_set_name: String,
+    /// Directory the firmware files are exported to, relative path is resolved against the
+    /// launch directory.
+    pub target_path: String,
+    /// Emulator is not launched when required firmware is missing.
+    pub required: bool,
+}
+
+/// Emulator configuration file template of an emulator system. The template is rendered into the
+/// launch directory and passed to the emulator with the `{config}` argument placeholder. Template
+/// with a release id overrides the default template of the emulator system for that release.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct EmulatorConfigTemplate {
+    pub id: i64,
+    pub emulator_system_id: i64,
+    pub release_id: Option<i64>,
+    /// File name of the rendered configuration, relative to the launch directory.
+    pub file_name: String,
+    pub content: String,
+}
+
+/// How well a release runs on an emulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CompatibilityStatus {
+    Works = 1,
+    MinorIssues = 2,
+    Broken = 3,
+}
+
+impl From<CompatibilityStatus> for i64 {
+    fn from(value: CompatibilityStatus) -> Self {
+        value as i64
+    }
+}
+
+impl TryFrom<i64> for CompatibilityStatus {
+    type Error = sqlx::Error;
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(CompatibilityStatus::Works),
+            2 => Ok(CompatibilityStatus::MinorIssues),
+            3 => Ok(CompatibilityStatus::Broken),
+            _ => Err(sqlx::Error::ColumnDecode {
+                index: "status".into(),
+                source: Box::new(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "Invalid compatibility status",
+                )),
+            }),
+        }
+    }
+}
+
+impl Display for CompatibilityStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompatibilityStatus::Works => write!(f, "Works"),
+            CompatibilityStatus::MinorIssues => write!(f, "Minor issues"),
+            CompatibilityStatus::Broken => write!(f, "Broken"),
+        }
+    }
+}
+
+/// Recorded compatibility of a release with an emulator on a system.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleaseEmulatorCompatibility {
+    pub id: i64,
+    pub release_id: i64,
+    pub emulator_id: i64,
+    pub system_id: i64,
+    pub status: CompatibilityStatus,
+    pub notes: Option<String>,
+    /// Date the release was last tested on the emulator, `YYYY-MM-DD`.
+    pub tested_at: String,
+}
+
+/// How the emulator executable is run.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ExecutableKind {
+    /// Executable name on the system path or the full path to the executable.
+    #[default]
+    Binary = 1,
+    /// Flatpak application id, run with `flatpak run`.
+    Flatpak = 2,
+    /// Path to an AppImage.
+    AppImage = 3,
+    /// Path to a Windows executable, run with Wine.
+    Wine = 4,
+}
+
+impl From<ExecutableKind> for i64 {
+    fn from(value: ExecutableKind) -> Self {
+        value as i64
+    }
+}
+
+impl TryFrom<i64> for ExecutableKind {
+    type Error = sqlx::Error;
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(ExecutableKind::Binary),
+            2 => Ok(ExecutableKind::Flatpak),
+            3 => Ok(ExecutableKind::AppImage),
+            4 => Ok(ExecutableKind::Wine),
+            _ => Err(sqlx::Error::ColumnDecode {
+                index: "executable_kind".into(),
+                source: Box::new(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "Invalid executable kind",
+                )),
+            }),
+        }
+    }
+}
+
+impl Display for ExecutableKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecutableKind::Binary => write!(f, "Binary"),
+            ExecutableKind::Flatpak => write!(f, "Flatpak"),
+            ExecutableKind::AppImage => write!(f, "AppImage"),
+            ExecutableKind::Wine => write!(f, "Wine"),
+        }
+    }
+}
+
+/// Settings applied when an emulator is launched: how the executable is run, environment
+/// variables (e.g. `SDL_*` or `WINEPREFIX`), working directory and commands run before and after
+/// the emulator.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EmulatorLaunchSettings {
+    pub executable_kind: ExecutableKind,
+    /// Wine prefix used with `ExecutableKind::Wine`, Wine default prefix if not set.
+    pub wine_prefix: Option<String>,
+    pub working_directory: Option<String>,
+    pub pre_launch_command: Option<String>,
+    pub post_launch_command: Option<String>,
+    /// Running emulator is killed after this many seconds, no timeout if not set.
+    pub launch_timeout_seconds: Option<i64>,
+    pub environment_variables: Vec<EnvironmentVariable>,
+}
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct EnvironmentVariable {
+    pub name: String,
+    pub value: String,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Franchise {
     pub id: i64,
@@ -230,6 +565,356 @@ pub struct SoftwareTitle {
     pub id: i64,
     pub name: String,
     pub franchise_id: Option<i64>,
+    /// Name the title is sorted by, derived from the name when not set.
+    pub sort_name: Option<String>,
+}
+
+/// Leading articles moved to the end of the name for sorting.
+const LEADING_ARTICLES: [&str; 13] = [
+    "the", "a", "an", "der", "die", "das", "le", "la", "les", "el", "los", "las", "il",
+];
+
+impl SoftwareTitle {
+    /// Sort name of the title, or the name with a leading article moved to the end, e.g.
+    /// `Legend of Zelda, The`.
+    pub fn sort_key(&self) -> String {
+        match &self.sort_name {
+            Some(sort_name) if !sort_name.trim().is_empty() => sort_name.clone(),
+            _ => Self::default_sort_name(&self.name),
+        }
+    }
+
+    pub fn default_sort_name(name: &str) -> String {
+        let name = name.trim();
+        if let Some((article, rest)) = name.split_once(' ') {
+            let rest = rest.trim_start();
+            if !rest.is_empty() && LEADING_ARTICLES.contains(&article.to_lowercase().as_str()) {
+                return format!("{}, {}", rest, article);
+            }
+        }
+        name.to_string()
+    }
+}
+
+/// Another name of a software title, optionally tagged with the language and region it is
+/// used in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SoftwareTitleAlias {
+    pub id: i64,
+    pub software_title_id: i64,
+    pub name: String,
+    pub language: Option<String>,
+    pub region: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Company {
+    pub id: i64,
+    pub name: String,
+}
+
+/// Role of a company, developers are linked to the software titles and publishers and
+/// distributors to the releases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CompanyRole {
+    Developer = 1,
+    Publisher = 2,
+    Distributor = 3,
+}
+
+impl From<CompanyRole> for i64 {
+    fn from(value: CompanyRole) -> Self {
+        value as i64
+    }
+}
+
+impl TryFrom<i64> for CompanyRole {
+    type Error = sqlx::Error;
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(CompanyRole::Developer),
+            2 => Ok(CompanyRole::Publisher),
+            3 => Ok(CompanyRole::Distributor),
+            _ => Err(sqlx::Error::ColumnDecode {
+                index: "role".into(),
+                source: Box::new(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "Invalid company role",
+                )),
+            }),
+        }
+    }
+}
+
+impl FromStr for CompanyRole {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "developer" => Ok(CompanyRole::Developer),
+            "publisher" => Ok(CompanyRole::Publisher),
+            "distributor" => Ok(CompanyRole::Distributor),
+            _ => Err(Error::ParseError(format!(
+                "Invalid company role {}, expected developer, publisher or distributor",
+                value
+            ))),
+        }
+    }
+}
+
+impl Display for CompanyRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompanyRole::Developer => write!(f, "Developer"),
+            CompanyRole::Publisher => write!(f, "Publisher"),
+            CompanyRole::Distributor => write!(f, "Distributor"),
+        }
+    }
+}
+
+/// Company credited for a release, developers come from the software titles of the release.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReleaseCompany {
+    pub company: Company,
+    pub role: CompanyRole,
+}
+
+/// Free-form tag, levels of hierarchical tags are separated with a slash, e.g. `genre/shmup`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+}
+
+impl Tag {
+    /// Trims the levels of the tag and drops empty levels, `None` if nothing is left.
+    pub fn normalize_name(name: &str) -> Option<String> {
+        let levels = name
+            .split('/')
+            .map(str::trim)
+            .filter(|level| !level.is_empty())
+            .collect::<Vec<_>>();
+        (!levels.is_empty()).then(|| levels.join("/"))
+    }
+
+    /// Name of the parent tag, `None` for a top level tag.
+    pub fn parent_name(&self) -> Option<&str> {
+        self.name.rsplit_once('/').map(|(parent, _)| parent)
+    }
+}
+
+/// Entity a tag is attached to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TagTarget {
+    SoftwareTitle(i64),
+    Release(i64),
+    FileSet(i64),
+}
+
+/// User curated collection of releases, releases are in the order the user arranged them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Collection {
+    pub id: i64,
+    pub name: String,
+    /// The built-in favorites collection, it can't be renamed or deleted.
+    pub is_favorites: bool,
+    /// Releases of a smart collection are the releases matching its criteria.
+    pub is_smart: bool,
+}
+
+/// Criterion of a smart collection. Criteria of different kinds must all match; of criteria of
+/// the same kind, any system, region or language matches, the others must all match.
+///
+/// Written as `<key>=<value>`, e.g. `system=1`, `no-file-type=cover_scan` or `tag=genre/shmup`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReleaseCriterion {
+    System(i64),
+    /// Release has a file set of the type.
+    FileType(FileType),
+    /// Release has no file set of the type.
+    NoFileType(FileType),
+    Region(String),
+    Language(String),
+    /// Release dated within or after the date.
+    ReleasedFrom(PartialDate),
+    /// Release dated within or before the date.
+    ReleasedTo(PartialDate),
+    /// Release, its software titles or file sets are tagged with the tag or its subtags.
+    Tag(String),
+    /// Neither the release nor its software titles or file sets are tagged with the tag or its
+    /// subtags.
+    NoTag(String),
+    /// Release name contains the text.
+    NameContains(String),
+    /// Release has been launched within the number of days.
+    PlayedWithin(u32),
+}
+
+impl ReleaseCriterion {
+    /// Kind of the criterion as stored in the database.
+    pub fn kind(&self) -> i64 {
+        match self {
+            ReleaseCriterion::System(_) => 1,
+            ReleaseCriterion::FileType(_) => 2,
+            ReleaseCriterion::NoFileType(_) => 3,
+            ReleaseCriterion::Region(_) => 4,
+            ReleaseCriterion::Language(_) => 5,
+            ReleaseCriterion::ReleasedFrom(_) => 6,
+            ReleaseCriterion::ReleasedTo(_) => 7,
+            ReleaseCriterion::Tag(_) => 8,
+            ReleaseCriterion::NoTag(_) => 9,
+            ReleaseCriterion::NameContains(_) => 10,
+            ReleaseCriterion::PlayedWithin(_) => 11,
+        }
+    }
+
+    /// Value of the criterion as stored in the database.
+    pub fn value(&self) -> String {
+        match self {
+            ReleaseCriterion::System(id) => id.to_string(),
+            ReleaseCriterion::PlayedWithin(days) => days.to_string(),
+            ReleaseCriterion::FileType(file_type) | ReleaseCriterion::NoFileType(file_type) => {
+                i64::from(*file_type).to_string()
+            }
+            ReleaseCriterion::ReleasedFrom(date) | ReleaseCriterion::ReleasedTo(date) => {
+                date.to_string()
+            }
+            ReleaseCriterion::Region(value)
+            | ReleaseCriterion::Language(value)
+            | ReleaseCriterion::Tag(value)
+            | ReleaseCriterion::NoTag(value)
+            | ReleaseCriterion::NameContains(value) => value.clone(),
+        }
+    }
+
+    /// Criterion from the kind and the value stored in the database.
+    pub fn from_stored(kind: i64, value: String) -> Result<Self, Error> {
+        let parse_i64 = |value: &str| {
+            value.parse::<i64>().map_err(|err| {
+                Error::ParseError(format!("Invalid criterion value {}: {}", value, err))
+            })
+        };
+        let parse_file_type = |value: &str| {
+            FileType::try_from(parse_i64(value)?).map_err(|err| Error::ParseError(err.to_string()))
+        };
+        match kind {
+            1 => Ok(ReleaseCriterion::System(parse_i64(&value)?)),
+            2 => Ok(ReleaseCriterion::FileType(parse_file_type(&value)?)),
+            3 => Ok(ReleaseCriterion::NoFileType(parse_file_type(&value)?)),
+            4 => Ok(ReleaseCriterion::Region(value)),
+            5 => Ok(ReleaseCriterion::Language(value)),
+            6 => Ok(ReleaseCriterion::ReleasedFrom(value.parse()?)),
+            7 => Ok(ReleaseCriterion::ReleasedTo(value.parse()?)),
+            8 => Ok(ReleaseCriterion::Tag(value)),
+            9 => Ok(ReleaseCriterion::NoTag(value)),
+            10 => Ok(ReleaseCriterion::NameContains(value)),
+            11 => value
+                .parse()
+                .map(ReleaseCriterion::PlayedWithin)
+                .map_err(|err| {
+                    Error::ParseError(format!("Invalid criterion value {}: {}", value, err))
+                }),
+            _ => Err(Error::ParseError(format!(
+                "Invalid criterion kind {}",
+                kind
+            ))),
+        }
+    }
+}
+
+impl FromStr for ReleaseCriterion {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (key, value) = value.split_once('=').ok_or_else(|| {
+            Error::ParseError(format!(
+                "Invalid criterion {}, expected <key>=<value>",
+                value
+            ))
+        })?;
+        let value = value.trim();
+        let parse_file_type = |value: &str| {
+            (1..=11)
+                .filter_map(|file_type| FileType::try_from(file_type).ok())
+                .find(|file_type| file_type.dir_name() == value)
+                .ok_or_else(|| Error::ParseError(format!("Invalid file type {}", value)))
+        };
+        match key.trim() {
+            "system" => value
+                .parse()
+                .map(ReleaseCriterion::System)
+                .map_err(|err| Error::ParseError(format!("Invalid system id {}: {}", value, err))),
+            "file-type" => parse_file_type(value).map(ReleaseCriterion::FileType),
+            "no-file-type" => parse_file_type(value).map(ReleaseCriterion::NoFileType),
+            "region" => Ok(ReleaseCriterion::Region(value.to_string())),
+            "language" => Ok(ReleaseCriterion::Language(value.to_string())),
+            "released-from" => value.parse().map(ReleaseCriterion::ReleasedFrom),
+            "released-to" => value.parse().map(ReleaseCriterion::ReleasedTo),
+            "tag" => Ok(ReleaseCriterion::Tag(value.to_string())),
+            "no-tag" => Ok(ReleaseCriterion::NoTag(value.to_string())),
+            "name" => Ok(ReleaseCriterion::NameContains(value.to_string())),
+            "played-within" => value
+                .parse()
+                .map(ReleaseCriterion::PlayedWithin)
+                .map_err(|err| Error::ParseError(format!("Invalid days {}: {}", value, err))),
+            key => Err(Error::ParseError(format!(
+                "Invalid criterion {}, expected one of system, file-type, no-file-type, region, \
+                 language, released-from, released-to, tag, no-tag, name or played-within",
+                key
+            ))),
+        }
+    }
+}
+
+impl Display for ReleaseCriterion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReleaseCriterion::System(id) => write!(f, "system={}", id),
+            ReleaseCriterion::FileType(file_type) => {
+                write!(f, "file-type={}", file_type.dir_name())
+            }
+            ReleaseCriterion::NoFileType(file_type) => {
+                write!(f, "no-file-type={}", file_type.dir_name())
+            }
+            ReleaseCriterion::Region(region) => write!(f, "region={}", region),
+            ReleaseCriterion::Language(language) => write!(f, "language={}", language),
+            ReleaseCriterion::ReleasedFrom(date) => write!(f, "released-from={}", date),
+            ReleaseCriterion::ReleasedTo(date) => write!(f, "released-to={}", date),
+            ReleaseCriterion::Tag(tag) => write!(f, "tag={}", tag),
+            ReleaseCriterion::NoTag(tag) => write!(f, "no-tag={}", tag),
+            ReleaseCriterion::NameContains(name) => write!(f, "name={}", name),
+            ReleaseCriterion::PlayedWithin(days) => write!(f, "played-within={}", days),
+        }
+    }
+}
+
+/// Kind of the entity found by the full-text search.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SearchResultKind {
+    SoftwareTitle,
+    Release,
+    FileSet,
+}
+
+impl Display for SearchResultKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchResultKind::SoftwareTitle => write!(f, "software title"),
+            SearchResultKind::Release => write!(f, "release"),
+            SearchResultKind::FileSet => write!(f, "file set"),
+        }
+    }
+}
+
+/// Entity found by the full-text search.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchResult {
+    pub kind: SearchResultKind,
+    pub id: i64,
+    pub name: String,
+    /// Name the search matched, an alias of a software title or a file of a file set can differ
+    /// from the entity name.
+    pub matched_name: String,
 }
 
 pub enum SettingName {
@@ -243,3 +928,90 @@ impl SettingName {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_date() {
+        for value in ["1987", "1987-06", "1988-02-29"] {
+            assert_eq!(value.parse::<PartialDate>().unwrap().to_string(), value);
+        }
+        for value in [
+            "87",
+            "1987-6",
+            "1987-13",
+            "1987-02-29",
+            "1987-06-00",
+            "1987-06-01-01",
+            "+199",
+            "1990-+1",
+            "1990-01-+1",
+            "199a",
+        ] {
+            assert!(value.parse::<PartialDate>().is_err(), "{}", value);
+        }
+        assert!("1987".parse::<PartialDate>().unwrap() < "1987-01".parse().unwrap());
+    }
+
+    #[test]
+    fn test_software_title_sort_key() {
+        let mut software_title = SoftwareTitle {
+            id: 1,
+            name: "The Legend of Zelda".to_string(),
+            franchise_id: None,
+            sort_name: None,
+        };
+        assert_eq!(software_title.sort_key(), "Legend of Zelda, The");
+        software_title.name = "Theatre Europe".to_string();
+        assert_eq!(software_title.sort_key(), "Theatre Europe");
+        software_title.name = "A".to_string();
+        assert_eq!(software_title.sort_key(), "A");
+        software_title.sort_name = Some("Zelda".to_string());
+        assert_eq!(software_title.sort_key(), "Zelda");
+    }
+
+    #[test]
+    fn test_tag_names() {
+        assert_eq!(
+            Tag::normalize_name(" genre / shmup/ ").as_deref(),
+            Some("genre/shmup")
+        );
+        assert_eq!(Tag::normalize_name(" / "), None);
+        let tag = Tag {
+            id: 1,
+            name: "genre/shmup/horizontal".to_string(),
+        };
+        assert_eq!(tag.parent_name(), Some("genre/shmup"));
+    }
+
+    #[test]
+    fn test_release_criterion() {
+        for text in [
+            "system=3",
+            "no-file-type=cover_scan",
+            "released-to=1989-06",
+            "tag=genre/shmup",
+            "played-within=30",
+        ] {
+            let criterion: ReleaseCriterion = text.parse().unwrap();
+            assert_eq!(criterion.to_string(), text);
+            assert_eq!(
+                ReleaseCriterion::from_stored(criterion.kind(), criterion.value()).unwrap(),
+                criterion
+            );
+        }
+        assert_eq!(
+            "file-type = disk_image"
+                .parse::<ReleaseCriterion>()
+                .unwrap(),
+            ReleaseCriterion::FileType(FileType::DiskImage)
+        );
+        assert!("file-type=floppy".parse::<ReleaseCriterion>().is_err());
+        assert!("played=30".parse::<ReleaseCriterion>().is_err());
+        assert!("played-within=-1".parse::<ReleaseCriterion>().is_err());
+        assert!("system".parse::<ReleaseCriterion>().is_err());
+        assert!(ReleaseCriterion::from_stored(99, String::new()).is_err());
+    }
+}
@@ -0,0 +1,696 @@
+use sqlx::{QueryBuilder, Sqlite};
+
+use crate::models::{
+    FileType, PartialDate, ReleaseCriterion, ReleaseListQuery, ReleaseSortKey, Tag,
+};
+
+/// Separates the names concatenated by the release list query, names may contain commas.
+pub(crate) const NAME_SEPARATOR: char = '\u{1f}';
+
+/// Conditions on the releases of `r` shared by the release queries. Releases have to match all
+/// the conditions, ids, regions, languages and groups match any of their values.
+#[derive(Debug, Default)]
+struct ReleaseConditions {
+    system_ids: Vec<i64>,
+    software_title_ids: Vec<i64>,
+    /// Releases with a file set of one of the file types, for each group.
+    file_types: Vec<Vec<FileType>>,
+    /// Releases without a file set of the file type.
+    excluded_file_types: Vec<FileType>,
+    regions: Vec<String>,
+    languages: Vec<String>,
+    released_from: Vec<PartialDate>,
+    released_to: Vec<PartialDate>,
+    /// Normalized tags, see `push_tag_condition`.
+    tags: Vec<String>,
+    excluded_tags: Vec<String>,
+    /// Names matching one of the `LIKE` patterns escaped with `\`, for each group.
+    name_patterns: Vec<Vec<String>>,
+    /// Releases launched within the number of days.
+    played_within: Vec<u32>,
+}
+
+impl ReleaseConditions {
+    fn from_criteria(criteria: &[ReleaseCriterion]) -> Self {
+        let mut conditions = ReleaseConditions::default();
+        for criterion in criteria {
+            match criterion {
+                ReleaseCriterion::System(id) => conditions.system_ids.push(*id),
+                ReleaseCriterion::FileType(file_type) => {
+                    conditions.file_types.push(vec![*file_type])
+                }
+                ReleaseCriterion::NoFileType(file_type) => {
+                    conditions.excluded_file_types.push(*file_type)
+                }
+                ReleaseCriterion::Region(region) => conditions.regions.push(region.clone()),
+                ReleaseCriterion::Language(language) => conditions.languages.push(language.clone()),
+                ReleaseCriterion::ReleasedFrom(date) => conditions.released_from.push(*date),
+                ReleaseCriterion::ReleasedTo(date) => conditions.released_to.push(*date),
+                ReleaseCriterion::Tag(tag) => conditions.tags.extend(Tag::normalize_name(tag)),
+                ReleaseCriterion::NoTag(tag) => {
+                    conditions.excluded_tags.extend(Tag::normalize_name(tag))
+                }
+                ReleaseCriterion::NameContains(name) => conditions
+                    .name_patterns
+                    .push(vec![format!("%{}%", escape_like(name))]),
+                ReleaseCriterion::PlayedWithin(days) => conditions.played_within.push(*days),
+            }
+        }
+        conditions
+    }
+
+    fn from_list_query(query: &ReleaseListQuery) -> Self {
+        let metadata = &query.metadata;
+        let name_patterns = query
+            .name_patterns
+            .iter()
+            .map(|pattern| like_pattern(pattern))
+            .collect::<Vec<_>>();
+        ReleaseConditions {
+            system_ids: query.system_ids.clone(),
+            software_title_ids: query.software_title_ids.clone(),
+            file_types: if query.file_types.is_empty() {
+                vec![]
+            } else {
+                vec![query.file_types.clone()]
+            },
+            regions: metadata.region.iter().cloned().collect(),
+            languages: metadata.language.iter().cloned().collect(),
+            released_from: metadata.released_from.into_iter().collect(),
+            released_to: metadata.released_to.into_iter().collect(),
+            tags: metadata
+                .tag
+                .as_deref()
+                .and_then(Tag::normalize_name)
+                .into_iter()
+                .collect(),
+            name_patterns: if name_patterns.is_empty() {
+                vec![]
+            } else {
+                vec![name_patterns]
+            },
+            ..Default::default()
+        }
+    }
+
+    fn push(&self, query_builder: &mut QueryBuilder<'static, Sqlite>) {
+        if !self.system_ids.is_empty() {
+            query_builder.push(
+                " AND EXISTS (SELECT 1 FROM release_system rs
+                    WHERE rs.release_id = r.id AND rs.system_id IN (",
+            );
+            push_values(query_builder, &self.system_ids);
+            query_builder.push(")");
+        }
+        if !self.software_title_ids.is_empty() {
+            query_builder.push(
+                " AND EXISTS (SELECT 1 FROM release_software_title rst
+                    WHERE rst.release_id = r.id AND rst.software_title_id IN (",
+            );
+            push_values(query_builder, &self.software_title_ids);
+            query_builder.push(")");
+        }
+        for file_types in &self.file_types {
+            query_builder.push(" AND ");
+            push_file_type_condition(query_builder, file_types);
+        }
+        for file_type in &self.excluded_file_types {
+            query_builder.push(" AND NOT ");
+            push_file_type_condition(query_builder, &[*file_type]);
+        }
+        if !self.regions.is_empty() {
+            query_builder.push(
+                " AND EXISTS (SELECT 1 FROM release_region rr
+                    WHERE rr.release_id = r.id AND rr.region COLLATE NOCASE IN (",
+            );
+            push_values(query_builder, &self.regions);
+            query_builder.push(")");
+        }
+        if !self.languages.is_empty() {
+            query_builder.push(
+                " AND EXISTS (SELECT 1 FROM release_language rl
+                    WHERE rl.release_id = r.id AND rl.language COLLATE NOCASE IN (",
+            );
+            push_values(query_builder, &self.languages);
+            query_builder.push(")");
+        }
+        for date in &self.released_from {
+            query_builder
+                .push(" AND r.release_date >= ")
+                .push_bind(date.to_string());
+        }
+        for date in &self.released_to {
+            // a date without the day or month matches the whole month or year
+            let date = date.to_string();
+            query_builder
+                .push(" AND substr(r.release_date, 1, ")
+                .push(date.len())
+                .push(") <= ")
+                .push_bind(date);
+        }
+        for tag in &self.tags {
+            query_builder.push(" AND ");
+            push_tag_condition(query_builder, tag.clone());
+        }
+        for tag in &self.excluded_tags {
+            query_builder.push(" AND NOT ");
+            push_tag_condition(query_builder, tag.clone());
+        }
+        for patterns in &self.name_patterns {
+            query_builder.push(" AND (");
+            let mut separated = query_builder.separated(" OR ");
+            for pattern in patterns {
+                separated
+                    .push("r.name LIKE ")
+                    .push_bind_unseparated(pattern.clone())
+                    .push_unseparated(" ESCAPE '\\'");
+            }
+            separated.push_unseparated(")");
+        }
+        for days in &self.played_within {
+            query_builder
+                .push(
+                    " AND EXISTS (SELECT 1 FROM release_launch rh
+                    WHERE rh.release_id = r.id AND rh.launched_at >= datetime('now', ",
+                )
+                .push_bind(format!("-{} days", days))
+                .push("))");
+        }
+    }
+}
+
+/// Pushes the values separated by commas and closes the `IN (` list.
+fn push_values<T>(query_builder: &mut QueryBuilder<'static, Sqlite>, values: &[T])
+where
+    T: Clone + Send + sqlx::Encode<'static, Sqlite> + sqlx::Type<Sqlite> + 'static,
+{
+    let mut separated = query_builder.separated(", ");
+    for value in values {
+        separated.push_bind(value.clone());
+    }
+    separated.push_unseparated(")");
+}
+
+/// Builds the query of the releases matching the criteria, see `ReleaseCriterion` for how the
+/// criteria are combined. Selects the id and the name of the releases ordered by name.
+pub(crate) fn build_release_query(criteria: &[ReleaseCriterion]) -> QueryBuilder<'static, Sqlite> {
+    let mut query_builder =
+        QueryBuilder::<Sqlite>::new("SELECT r.id, r.name FROM release r WHERE 1 = 1");
+    ReleaseConditions::from_criteria(criteria).push(&mut query_builder);
+    query_builder.push(" ORDER BY r.name");
+    query_builder
+}
+
+/// Builds the query counting the releases matching the criteria.
+pub(crate) fn build_release_count_query(
+    criteria: &[ReleaseCriterion],
+) -> QueryBuilder<'static, Sqlite> {
+    let mut query_builder =
+        QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM release r WHERE 1 = 1");
+    ReleaseConditions::from_criteria(criteria).push(&mut query_builder);
+    query_builder
+}
+
+/// Builds the query of the releases listed by the query. Selects the id and the name of the
+/// releases with the names of their systems and software titles separated by `NAME_SEPARATOR`
+/// and the ids of the file types of their file sets separated by commas. The names and file
+/// types are left joined so that releases missing them are listed too.
+pub(crate) fn build_release_list_query(query: &ReleaseListQuery) -> QueryBuilder<'static, Sqlite> {
+    let mut query_builder = QueryBuilder::<Sqlite>::new(
+        "SELECT r.id, r.name, sn.names AS system_names, stn.names AS software_title_names,
+            ft.file_types
+         FROM release r
+         LEFT JOIN (
+            SELECT rs.release_id, GROUP_CONCAT(s.name, char(31)) AS names
+            FROM release_system rs
+            INNER JOIN system s ON rs.system_id = s.id
+            GROUP BY rs.release_id
+         ) sn ON sn.release_id = r.id
+         LEFT JOIN (
+            SELECT rst.release_id, GROUP_CONCAT(st.name, char(31)) AS names
+            FROM release_software_title rst
+            INNER JOIN software_title st ON rst.software_title_id = st.id
+            GROUP BY rst.release_id
+         ) stn ON stn.release_id = r.id
+         LEFT JOIN (
+            SELECT rfs.release_id, GROUP_CONCAT(DISTINCT fs.file_type) AS file_types
+            FROM release_file_set rfs
+            INNER JOIN file_set fs ON rfs.file_set_id = fs.id
+            GROUP BY rfs.release_id
+         ) ft ON ft.release_id = r.id
+         WHERE 1 = 1",
+    );
+
+    if !query.release_ids.is_empty() {
+        query_builder.push(" AND r.id IN (");
+        push_values(&mut query_builder, &query.release_ids);
+    }
+    ReleaseConditions::from_list_query(query).push(&mut query_builder);
+    if let Some(company_id) = query.company_id {
+        let role = query.company_role.map(i64::from);
+        // developers are credited for the software titles of the release
+        query_builder
+            .push(" AND (((")
+            .push_bind(role)
+            .push(" IS NULL OR ")
+            .push_bind(role)
+            .push(
+                " = 1) AND EXISTS (SELECT 1 FROM release_software_title rst
+                    INNER JOIN software_title_company stc
+                        ON rst.software_title_id = stc.software_title_id
+                    WHERE rst.release_id = r.id AND stc.company_id = ",
+            )
+            .push_bind(company_id)
+            .push(
+                ")) OR EXISTS (SELECT 1 FROM release_company rc
+                    WHERE rc.release_id = r.id AND rc.company_id = ",
+            )
+            .push_bind(company_id)
+            .push(" AND (")
+            .push_bind(role)
+            .push(" IS NULL OR rc.role = ")
+            .push_bind(role)
+            .push(")))");
+    }
+
+    let collation = match query.sort_key {
+        ReleaseSortKey::Name => " COLLATE NOCASE",
+        ReleaseSortKey::ReleaseDate | ReleaseSortKey::Id => "",
+    };
+    let (comparison, direction) = if query.descending {
+        ("<", "DESC")
+    } else {
+        (">", "ASC")
+    };
+    if let Some(after_release_id) = query.after_release_id {
+        // a release that no longer exists has no position, nothing is after it
+        query_builder
+            .push(format!(
+                " AND ({}{}, r.id) {} (SELECT {}, a.id FROM release a WHERE a.id = ",
+                sort_expression(query.sort_key, "r"),
+                collation,
+                comparison,
+                sort_expression(query.sort_key, "a"),
+            ))
+            .push_bind(after_release_id)
+            .push(")");
+    }
+    query_builder.push(format!(
+        " ORDER BY {}{} {}, r.id {}",
+        sort_expression(query.sort_key, "r"),
+        collation,
+        direction,
+        direction
+    ));
+
+    match (query.limit, query.offset) {
+        (Some(limit), offset) => {
+            query_builder.push(" LIMIT ").push_bind(limit);
+            if let Some(offset) = offset {
+                query_builder.push(" OFFSET ").push_bind(offset);
+            }
+        }
+        (None, Some(offset)) => {
+            query_builder.push(" LIMIT -1 OFFSET ").push_bind(offset);
+        }
+        (None, None) => {}
+    }
+    query_builder
+}
+
+fn sort_expression(sort_key: ReleaseSortKey, table_alias: &str) -> String {
+    match sort_key {
+        ReleaseSortKey::Name => format!("{}.name", table_alias),
+        ReleaseSortKey::ReleaseDate => format!("COALESCE({}.release_date, '')", table_alias),
+        ReleaseSortKey::Id => format!("{}.id", table_alias),
+    }
+}
+
+/// Converts a name pattern with `*` and `?` wildcards to a `LIKE` pattern escaped with `\`.
+fn like_pattern(pattern: &str) -> String {
+    escape_like(pattern).replace('*', "%").replace('?', "_")
+}
+
+/// Escapes the `LIKE` wildcards and the escape character `\`.
+fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Pushes a condition matching the releases of `r` with a file set of one of the file types.
+pub(crate) fn push_file_type_condition(
+    query_builder: &mut QueryBuilder<'static, Sqlite>,
+    file_types: &[FileType],
+) {
+    query_builder.push(
+        "EXISTS (SELECT 1 FROM release_file_set rfs
+            INNER JOIN file_set fs ON rfs.file_set_id = fs.id
+            WHERE rfs.release_id = r.id AND fs.file_type IN (",
+    );
+    let mut separated = query_builder.separated(", ");
+    for file_type in file_types {
+        separated.push_bind(i64::from(*file_type));
+    }
+    separated.push_unseparated("))");
+}
+
+/// Pushes a condition matching the releases of `r` tagged with the normalized tag or its
+/// subtags, tags of the software titles and file sets of the release count too.
+pub(crate) fn push_tag_condition(query_builder: &mut QueryBuilder<'static, Sqlite>, tag: String) {
+    let subtag_prefix = format!("{}/", tag);
+    query_builder
+        .push("EXISTS (SELECT 1 FROM tag t WHERE (t.name = ")
+        .push_bind(tag)
+        .push(" OR substr(t.name, 1, ")
+        .push(subtag_prefix.chars().count())
+        .push(") = ")
+        .push_bind(subtag_prefix)
+        .push(
+            " COLLATE NOCASE)
+            AND (
+                t.id IN (SELECT tag_id FROM release_tag WHERE release_id = r.id)
+                OR t.id IN (
+                    SELECT stt.tag_id FROM software_title_tag stt
+                    INNER JOIN release_software_title rst
+                        ON stt.software_title_id = rst.software_title_id
+                    WHERE rst.release_id = r.id)
+                OR t.id IN (
+                    SELECT fst.tag_id FROM file_set_tag fst
+                    INNER JOIN release_file_set rfs ON fst.file_set_id = rfs.file_set_id
+                    WHERE rfs.release_id = r.id)
+            ))",
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        add_test_file_set, fetch_release_ids,
+        models::{FileType, ReleaseMetadata, ReleaseMetadataFilter, TagTarget},
+        repository::{
+            release_repository::ReleaseRepository, system_repository::SystemRepository,
+            tag_repository::TagRepository,
+        },
+        setup_test_db,
+    };
+
+    #[async_std::test]
+    async fn test_build_release_query() {
+        let pool = Arc::new(setup_test_db().await);
+        let release_repository = ReleaseRepository::new(Arc::clone(&pool));
+        let system_repository = SystemRepository::new(Arc::clone(&pool));
+        let c64_id = system_repository
+            .add_system(&"Commodore 64".to_string())
+            .await
+            .unwrap();
+        let amiga_id = system_repository
+            .add_system(&"Amiga".to_string())
+            .await
+            .unwrap();
+
+        let disk_id = add_test_file_set(&pool, "Katakis.d64", FileType::DiskImage, 1).await;
+        let cover_id = add_test_file_set(&pool, "Katakis cover.jpg", FileType::CoverScan, 2).await;
+        let katakis_id = release_repository
+            .add_release_full(
+                "Katakis".to_string(),
+                vec![],
+                vec![disk_id, cover_id],
+                vec![c64_id],
+            )
+            .await
+            .unwrap();
+        let disk_id = add_test_file_set(&pool, "Armalyte.d64", FileType::DiskImage, 3).await;
+        let armalyte_id = release_repository
+            .add_release_full("Armalyte".to_string(), vec![], vec![disk_id], vec![c64_id])
+            .await
+            .unwrap();
+        let adf_id = add_test_file_set(&pool, "Lionheart.adf", FileType::DiskImage, 4).await;
+        let lionheart_id = release_repository
+            .add_release_full(
+                "Lionheart".to_string(),
+                vec![],
+                vec![adf_id],
+                vec![amiga_id],
+            )
+            .await
+            .unwrap();
+        release_repository
+            .update_release_metadata(
+                katakis_id,
+                &ReleaseMetadata {
+                    release_date: "1988".parse().ok(),
+                    regions: vec!["Europe".to_string()],
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        release_repository
+            .update_release_metadata(
+                lionheart_id,
+                &ReleaseMetadata {
+                    release_date: "1993-03".parse().ok(),
+                    regions: vec!["USA".to_string()],
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        TagRepository::new(Arc::clone(&pool))
+            .add_tag(TagTarget::FileSet(disk_id), "genre/shmup")
+            .await
+            .unwrap();
+
+        let release_ids = |criteria: Vec<ReleaseCriterion>| {
+            fetch_release_ids(&pool, build_release_query(&criteria))
+        };
+
+        assert_eq!(
+            release_ids(vec![]).await,
+            vec![armalyte_id, katakis_id, lionheart_id]
+        );
+        assert_eq!(
+            release_ids(vec![
+                ReleaseCriterion::System(c64_id),
+                ReleaseCriterion::FileType(FileType::DiskImage),
+                ReleaseCriterion::NoFileType(FileType::CoverScan),
+            ])
+            .await,
+            vec![armalyte_id]
+        );
+        assert_eq!(
+            release_ids(vec![
+                ReleaseCriterion::System(c64_id),
+                ReleaseCriterion::System(amiga_id),
+                ReleaseCriterion::ReleasedTo("1993".parse().unwrap()),
+            ])
+            .await,
+            vec![katakis_id, lionheart_id]
+        );
+        assert_eq!(
+            release_ids(vec![
+                ReleaseCriterion::Region("europe".to_string()),
+                ReleaseCriterion::Region("Japan".to_string()),
+            ])
+            .await,
+            vec![katakis_id]
+        );
+        assert_eq!(
+            release_ids(vec![ReleaseCriterion::ReleasedFrom(
+                "1989".parse().unwrap()
+            )])
+            .await,
+            vec![lionheart_id]
+        );
+        assert_eq!(
+            release_ids(vec![ReleaseCriterion::Tag("Genre".to_string())]).await,
+            vec![armalyte_id]
+        );
+        assert_eq!(
+            release_ids(vec![
+                ReleaseCriterion::NoTag("genre".to_string()),
+                ReleaseCriterion::NameContains("a".to_string()),
+            ])
+            .await,
+            vec![katakis_id, lionheart_id]
+        );
+
+        release_repository
+            .add_release_launch(katakis_id, None, Some(c64_id), None)
+            .await
+            .unwrap();
+        release_repository
+            .add_release_launch(
+                lionheart_id,
+                None,
+                Some(amiga_id),
+                Some("2000-01-01 12:00:00".to_string()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            release_ids(vec![ReleaseCriterion::PlayedWithin(30)]).await,
+            vec![katakis_id]
+        );
+        assert_eq!(
+            release_ids(vec![
+                ReleaseCriterion::PlayedWithin(36500),
+                ReleaseCriterion::System(amiga_id),
+            ])
+            .await,
+            vec![lionheart_id]
+        );
+    }
+
+    #[async_std::test]
+    async fn test_build_release_list_query() {
+        let pool = Arc::new(setup_test_db().await);
+        let release_repository = ReleaseRepository::new(Arc::clone(&pool));
+        let system_repository = SystemRepository::new(Arc::clone(&pool));
+        let c64_id = system_repository
+            .add_system(&"Commodore 64".to_string())
+            .await
+            .unwrap();
+        let amiga_id = system_repository
+            .add_system(&"Amiga".to_string())
+            .await
+            .unwrap();
+        let spectrum_id = system_repository
+            .add_system(&"ZX Spectrum".to_string())
+            .await
+            .unwrap();
+
+        let disk_id = add_test_file_set(&pool, "Uridium.d64", FileType::DiskImage, 1).await;
+        let tape_id = add_test_file_set(&pool, "Exolon.tap", FileType::TapeImage, 2).await;
+        let rom_id = add_test_file_set(&pool, "Turrican.rom", FileType::Rom, 3).await;
+        let mut release_ids_by_name = Vec::new();
+        for (name, file_set_id, system_id, date, region) in [
+            ("uridium", disk_id, c64_id, "1986", "Europe"),
+            ("Exolon", tape_id, spectrum_id, "1987-06", "Europe"),
+            ("Turrican", rom_id, amiga_id, "1990", "USA"),
+            ("Turrican II", rom_id, amiga_id, "1991", "Japan"),
+        ] {
+            let release_id = release_repository
+                .add_release_full(name.to_string(), vec![], vec![file_set_id], vec![system_id])
+                .await
+                .unwrap();
+            release_repository
+                .update_release_metadata(
+                    release_id,
+                    &ReleaseMetadata {
+                        release_date: date.parse().ok(),
+                        regions: vec![region.to_string()],
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+            release_ids_by_name.push(release_id);
+        }
+        let [uridium_id, exolon_id, turrican_id, turrican_2_id] = release_ids_by_name[..] else {
+            unreachable!()
+        };
+
+        let release_ids =
+            |query: ReleaseListQuery| fetch_release_ids(&pool, build_release_list_query(&query));
+
+        // names are sorted ignoring case
+        assert_eq!(
+            release_ids(ReleaseListQuery::default()).await,
+            vec![exolon_id, turrican_id, turrican_2_id, uridium_id]
+        );
+        // values of a filter match any of them, filters all match
+        assert_eq!(
+            release_ids(ReleaseListQuery {
+                system_ids: vec![c64_id, spectrum_id],
+                ..Default::default()
+            })
+            .await,
+            vec![exolon_id, uridium_id]
+        );
+        assert_eq!(
+            release_ids(ReleaseListQuery {
+                file_types: vec![FileType::TapeImage, FileType::Rom],
+                name_patterns: vec!["exo*".to_string(), "turrican".to_string()],
+                ..Default::default()
+            })
+            .await,
+            vec![exolon_id, turrican_id]
+        );
+        assert_eq!(
+            release_ids(ReleaseListQuery {
+                name_patterns: vec!["Turrican ??".to_string(), "%".to_string()],
+                ..Default::default()
+            })
+            .await,
+            vec![turrican_2_id]
+        );
+        assert_eq!(
+            release_ids(ReleaseListQuery {
+                system_ids: vec![c64_id, spectrum_id, amiga_id],
+                metadata: ReleaseMetadataFilter {
+                    region: Some("europe".to_string()),
+                    released_to: "1987".parse().ok(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .await,
+            vec![exolon_id, uridium_id]
+        );
+
+        // sorted by release date, latest first
+        assert_eq!(
+            release_ids(ReleaseListQuery {
+                sort_key: ReleaseSortKey::ReleaseDate,
+                descending: true,
+                ..Default::default()
+            })
+            .await,
+            vec![turrican_2_id, turrican_id, exolon_id, uridium_id]
+        );
+
+        // keyset paging continues after the last listed release in the sort order
+        let first_page = release_ids(ReleaseListQuery {
+            limit: Some(2),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(first_page, vec![exolon_id, turrican_id]);
+        let next_page = release_ids(ReleaseListQuery {
+            after_release_id: first_page.last().copied(),
+            limit: Some(2),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(next_page, vec![turrican_2_id, uridium_id]);
+        assert_eq!(
+            release_ids(ReleaseListQuery {
+                sort_key: ReleaseSortKey::ReleaseDate,
+                descending: true,
+                after_release_id: Some(turrican_id),
+                ..Default::default()
+            })
+            .await,
+            vec![exolon_id, uridium_id]
+        );
+        assert_eq!(
+            release_ids(ReleaseListQuery {
+                limit: Some(1),
+                offset: Some(3),
+                ..Default::default()
+            })
+            .await,
+            vec![uridium_id]
+        );
+    }
+}
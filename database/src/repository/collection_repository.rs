@@ -0,0 +1,534 @@
+use std::sync::Arc;
+
+use sqlx::{Pool, Sqlite, SqliteConnection};
+
+use crate::{
+    database_error::DatabaseError,
+    models::{Collection, Release, ReleaseCriterion},
+    release_query::{build_release_count_query, build_release_query},
+};
+
+#[derive(Debug)]
+pub struct CollectionRepository {
+    pool: Arc<Pool<Sqlite>>,
+}
+
+impl CollectionRepository {
+    pub fn new(pool: Arc<Pool<Sqlite>>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_collection(&self, id: i64) -> Result<Collection, DatabaseError> {
+        let collection = sqlx::query_as!(
+            Collection,
+            r#"SELECT id, name, is_favorites AS "is_favorites: bool", is_smart AS "is_smart: bool"
+             FROM collection WHERE id = ?"#,
+            id
+        )
+        .fetch_one(&*self.pool)
+        .await?;
+        Ok(collection)
+    }
+
+    pub async fn get_favorites_collection(&self) -> Result<Collection, DatabaseError> {
+        let collection = sqlx::query_as!(
+            Collection,
+            r#"SELECT id, name, is_favorites AS "is_favorites: bool", is_smart AS "is_smart: bool"
+             FROM collection WHERE is_favorites = 1"#
+        )
+        .fetch_one(&*self.pool)
+        .await?;
+        Ok(collection)
+    }
+
+    /// Returns the favorites collection first and the other collections ordered by name.
+    pub async fn get_all_collections(&self) -> Result<Vec<Collection>, DatabaseError> {
+        let collections = sqlx::query_as!(
+            Collection,
+            r#"SELECT id, name, is_favorites AS "is_favorites: bool", is_smart AS "is_smart: bool"
+             FROM collection
+             ORDER BY is_favorites DESC, name"#
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        Ok(collections)
+    }
+
+    pub async fn add_collection(&self, name: &str) -> Result<i64, DatabaseError> {
+        let result = sqlx::query!("INSERT INTO collection (name) VALUES (?)", name)
+            .execute(&*self.pool)
+            .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    pub async fn rename_collection(&self, id: i64, name: &str) -> Result<(), DatabaseError> {
+        if self.get_collection(id).await?.is_favorites {
+            return Err(DatabaseError::DbError(
+                "Favorites collection can't be renamed".to_string(),
+            ));
+        }
+        sqlx::query!("UPDATE collection SET name = ? WHERE id = ?", name, id)
+            .execute(&*self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Adds a smart collection of the releases matching the criteria.
+    pub async fn add_smart_collection(
+        &self,
+        name: &str,
+        criteria: &[ReleaseCriterion],
+    ) -> Result<i64, DatabaseError> {
+        let mut transaction = self.pool.begin().await?;
+        let id = sqlx::query!(
+            "INSERT INTO collection (name, is_smart) VALUES (?, 1)",
+            name
+        )
+        .execute(&mut *transaction)
+        .await?
+        .last_insert_rowid();
+        insert_criteria(&mut transaction, id, criteria).await?;
+        transaction.commit().await?;
+        Ok(id)
+    }
+
+    pub async fn get_collection_criteria(
+        &self,
+        collection_id: i64,
+    ) -> Result<Vec<ReleaseCriterion>, DatabaseError> {
+        let rows = sqlx::query!(
+            "SELECT kind, value FROM collection_criterion WHERE collection_id = ? ORDER BY id",
+            collection_id
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        rows.into_iter()
+            .map(|row| {
+                ReleaseCriterion::from_stored(row.kind, row.value)
+                    .map_err(|err| DatabaseError::DbError(err.to_string()))
+            })
+            .collect()
+    }
+
+    /// Replaces the criteria of the smart collection.
+    pub async fn set_collection_criteria(
+        &self,
+        collection_id: i64,
+        criteria: &[ReleaseCriterion],
+    ) -> Result<(), DatabaseError> {
+        if !self.get_collection(collection_id).await?.is_smart {
+            return Err(DatabaseError::DbError(
+                "Only smart collections have criteria".to_string(),
+            ));
+        }
+        let mut transaction = self.pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM collection_criterion WHERE collection_id = ?",
+            collection_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+        insert_criteria(&mut transaction, collection_id, criteria).await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Deletes the collection, the releases in it are not deleted.
+    pub async fn delete_collection(&self, id: i64) -> Result<(), DatabaseError> {
+        if self.get_collection(id).await?.is_favorites {
+            return Err(DatabaseError::DbError(
+                "Favorites collection can't be deleted".to_string(),
+            ));
+        }
+        sqlx::query!("DELETE FROM collection WHERE id = ?", id)
+            .execute(&*self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Returns the releases of the collection in the order of the collection. Releases of a
+    /// smart collection are the releases matching its criteria, ordered by name.
+    pub async fn get_collection_releases(
+        &self,
+        collection_id: i64,
+    ) -> Result<Vec<Release>, DatabaseError> {
+        if self.get_collection(collection_id).await?.is_smart {
+            let criteria = self.get_collection_criteria(collection_id).await?;
+            let releases = build_release_query(&criteria)
+                .build_query_as::<Release>()
+                .fetch_all(&*self.pool)
+                .await?;
+            return Ok(releases);
+        }
+        let releases = sqlx::query_as!(
+            Release,
+            "SELECT r.id, r.name
+             FROM release r
+             INNER JOIN collection_release cr ON r.id = cr.release_id
+             WHERE cr.collection_id = ?
+             ORDER BY cr.position",
+            collection_id
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        Ok(releases)
+    }
+
+    /// Returns the number of releases in the collection, or matching the criteria of a smart
+    /// collection.
+    pub async fn get_collection_release_count(
+        &self,
+        collection_id: i64,
+    ) -> Result<i64, DatabaseError> {
+        if self.get_collection(collection_id).await?.is_smart {
+            let criteria = self.get_collection_criteria(collection_id).await?;
+            let count = build_release_count_query(&criteria)
+                .build_query_scalar::<i64>()
+                .fetch_one(&*self.pool)
+                .await?;
+            return Ok(count);
+        }
+        let count = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM collection_release WHERE collection_id = ?",
+            collection_id
+        )
+        .fetch_one(&*self.pool)
+        .await?;
+        Ok(count)
+    }
+
+    /// Returns the collections the release has been added to, smart collections are not
+    /// included.
+    pub async fn get_collections_by_release(
+        &self,
+        release_id: i64,
+    ) -> Result<Vec<Collection>, DatabaseError> {
+        let collections = sqlx::query_as!(
+            Collection,
+            r#"SELECT c.id, c.name, c.is_favorites AS "is_favorites: bool", c.is_smart AS "is_smart: bool"
+             FROM collection c
+             INNER JOIN collection_release cr ON c.id = cr.collection_id
+             WHERE cr.release_id = ?
+             ORDER BY c.is_favorites DESC, c.name"#,
+            release_id
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        Ok(collections)
+    }
+
+    /// Adds the release to the end of the collection. Nothing is done if the release is in the
+    /// collection already.
+    pub async fn add_release_to_collection(
+        &self,
+        collection_id: i64,
+        release_id: i64,
+    ) -> Result<(), DatabaseError> {
+        if self.get_collection(collection_id).await?.is_smart {
+            return Err(DatabaseError::DbError(
+                "Releases can't be added to a smart collection".to_string(),
+            ));
+        }
+        sqlx::query!(
+            "INSERT OR IGNORE INTO collection_release (collection_id, release_id, position)
+             SELECT ?1, ?2, COALESCE(MAX(position) + 1, 0)
+             FROM collection_release WHERE collection_id = ?1",
+            collection_id,
+            release_id
+        )
+        .execute(&*self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove_release_from_collection(
+        &self,
+        collection_id: i64,
+        release_id: i64,
+    ) -> Result<(), DatabaseError> {
+        let mut transaction = self.pool.begin().await?;
+        let mut release_ids = get_release_ids(&mut transaction, collection_id).await?;
+        release_ids.retain(|id| *id != release_id);
+        sqlx::query!(
+            "DELETE FROM collection_release WHERE collection_id = ? AND release_id = ?",
+            collection_id,
+            release_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+        set_positions(&mut transaction, collection_id, &release_ids).await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Moves the release to the position in the collection, a position past the end moves the
+    /// release last.
+    pub async fn move_release_in_collection(
+        &self,
+        collection_id: i64,
+        release_id: i64,
+        position: usize,
+    ) -> Result<(), DatabaseError> {
+        let mut transaction = self.pool.begin().await?;
+        let mut release_ids = get_release_ids(&mut transaction, collection_id).await?;
+        let index = release_ids
+            .iter()
+            .position(|id| *id == release_id)
+            .ok_or(DatabaseError::SqlxError(sqlx::Error::RowNotFound))?;
+        release_ids.remove(index);
+        release_ids.insert(position.min(release_ids.len()), release_id);
+        set_positions(&mut transaction, collection_id, &release_ids).await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+}
+
+async fn insert_criteria(
+    connection: &mut SqliteConnection,
+    collection_id: i64,
+    criteria: &[ReleaseCriterion],
+) -> Result<(), DatabaseError> {
+    for criterion in criteria {
+        let kind = criterion.kind();
+        let value = criterion.value();
+        sqlx::query!(
+            "INSERT INTO collection_criterion (collection_id, kind, value) VALUES (?, ?, ?)",
+            collection_id,
+            kind,
+            value
+        )
+        .execute(&mut *connection)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn get_release_ids(
+    connection: &mut SqliteConnection,
+    collection_id: i64,
+) -> Result<Vec<i64>, DatabaseError> {
+    let release_ids = sqlx::query_scalar!(
+        "SELECT release_id FROM collection_release WHERE collection_id = ? ORDER BY position",
+        collection_id
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+    Ok(release_ids)
+}
+
+/// Numbers the releases of the collection from zero in the given order.
+async fn set_positions(
+    connection: &mut SqliteConnection,
+    collection_id: i64,
+    release_ids: &[i64],
+) -> Result<(), DatabaseError> {
+    for (position, release_id) in release_ids.iter().enumerate() {
+        let position = position as i64;
+        sqlx::query!(
+            "UPDATE collection_release SET position = ?
+             WHERE collection_id = ? AND release_id = ?",
+            position,
+            collection_id,
+            release_id
+        )
+        .execute(&mut *connection)
+        .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{repository::release_repository::ReleaseRepository, setup_test_db};
+
+    #[async_std::test]
+    async fn test_collection_repository() {
+        let pool = Arc::new(setup_test_db().await);
+        let collection_repository = CollectionRepository::new(Arc::clone(&pool));
+        let release_repository = ReleaseRepository::new(Arc::clone(&pool));
+
+        let favorites = collection_repository
+            .get_favorites_collection()
+            .await
+            .unwrap();
+        assert!(favorites.is_favorites);
+        assert!(collection_repository
+            .rename_collection(favorites.id, "Best")
+            .await
+            .is_err());
+        assert!(collection_repository
+            .delete_collection(favorites.id)
+            .await
+            .is_err());
+
+        let collection_id = collection_repository
+            .add_collection("Party games")
+            .await
+            .unwrap();
+        let mut release_ids = vec![];
+        for name in ["Summer Games", "Winter Games", "California Games"] {
+            let release_id = release_repository.add_release(name).await.unwrap();
+            collection_repository
+                .add_release_to_collection(collection_id, release_id)
+                .await
+                .unwrap();
+            release_ids.push(release_id);
+        }
+        // adding again keeps the position
+        collection_repository
+            .add_release_to_collection(collection_id, release_ids[0])
+            .await
+            .unwrap();
+        collection_repository
+            .add_release_to_collection(favorites.id, release_ids[1])
+            .await
+            .unwrap();
+
+        let collection_release_ids = || async {
+            collection_repository
+                .get_collection_releases(collection_id)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|release| release.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(collection_release_ids().await, release_ids);
+        assert_eq!(
+            collection_repository
+                .get_collection_release_count(collection_id)
+                .await
+                .unwrap(),
+            release_ids.len() as i64
+        );
+
+        collection_repository
+            .move_release_in_collection(collection_id, release_ids[2], 0)
+            .await
+            .unwrap();
+        assert_eq!(
+            collection_release_ids().await,
+            vec![release_ids[2], release_ids[0], release_ids[1]]
+        );
+        collection_repository
+            .move_release_in_collection(collection_id, release_ids[2], 10)
+            .await
+            .unwrap();
+        assert_eq!(collection_release_ids().await, release_ids);
+
+        collection_repository
+            .remove_release_from_collection(collection_id, release_ids[1])
+            .await
+            .unwrap();
+        collection_repository
+            .add_release_to_collection(collection_id, release_ids[1])
+            .await
+            .unwrap();
+        assert_eq!(
+            collection_release_ids().await,
+            vec![release_ids[0], release_ids[2], release_ids[1]]
+        );
+        assert!(collection_repository
+            .move_release_in_collection(favorites.id, release_ids[0], 0)
+            .await
+            .is_err());
+
+        let collections = collection_repository
+            .get_collections_by_release(release_ids[1])
+            .await
+            .unwrap();
+        assert_eq!(
+            collections
+                .iter()
+                .map(|collection| collection.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Favorites", "Party games"]
+        );
+
+        collection_repository
+            .rename_collection(collection_id, "Sports games")
+            .await
+            .unwrap();
+        collection_repository
+            .delete_collection(collection_id)
+            .await
+            .unwrap();
+        let collections = collection_repository.get_all_collections().await.unwrap();
+        assert_eq!(collections, vec![favorites]);
+    }
+
+    #[async_std::test]
+    async fn test_smart_collection() {
+        let pool = Arc::new(setup_test_db().await);
+        let collection_repository = CollectionRepository::new(Arc::clone(&pool));
+        let release_repository = ReleaseRepository::new(Arc::clone(&pool));
+        let summer_games_id = release_repository
+            .add_release("Summer Games")
+            .await
+            .unwrap();
+        let winter_games_id = release_repository
+            .add_release("Winter Games")
+            .await
+            .unwrap();
+        release_repository.add_release("Katakis").await.unwrap();
+
+        let criteria = vec![ReleaseCriterion::NameContains("games".to_string())];
+        let collection_id = collection_repository
+            .add_smart_collection("Games", &criteria)
+            .await
+            .unwrap();
+        let collection = collection_repository
+            .get_collection(collection_id)
+            .await
+            .unwrap();
+        assert!(collection.is_smart);
+        assert_eq!(
+            collection_repository
+                .get_collection_criteria(collection_id)
+                .await
+                .unwrap(),
+            criteria
+        );
+        let release_ids = collection_repository
+            .get_collection_releases(collection_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|release| release.id)
+            .collect::<Vec<_>>();
+        assert_eq!(release_ids, vec![summer_games_id, winter_games_id]);
+        assert_eq!(
+            collection_repository
+                .get_collection_release_count(collection_id)
+                .await
+                .unwrap(),
+            2
+        );
+        assert!(collection_repository
+            .add_release_to_collection(collection_id, summer_games_id)
+            .await
+            .is_err());
+
+        let criteria = vec![ReleaseCriterion::NameContains("winter".to_string())];
+        collection_repository
+            .set_collection_criteria(collection_id, &criteria)
+            .await
+            .unwrap();
+        let releases = collection_repository
+            .get_collection_releases(collection_id)
+            .await
+            .unwrap();
+        assert_eq!(releases.len(), 1);
+        assert_eq!(releases[0].id, winter_games_id);
+
+        let favorites = collection_repository
+            .get_favorites_collection()
+            .await
+            .unwrap();
+        assert!(collection_repository
+            .set_collection_criteria(favorites.id, &criteria)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,370 @@
+use std::sync::Arc;
+
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    database_error::DatabaseError,
+    models::{Company, CompanyRole, Release, ReleaseCompany},
+};
+
+#[derive(Debug)]
+pub struct CompanyRepository {
+    pool: Arc<Pool<Sqlite>>,
+}
+
+impl CompanyRepository {
+    pub fn new(pool: Arc<Pool<Sqlite>>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_company(&self, id: i64) -> Result<Company, DatabaseError> {
+        let company = sqlx::query_as!(Company, "SELECT id, name FROM company WHERE id = ?", id)
+            .fetch_one(&*self.pool)
+            .await?;
+        Ok(company)
+    }
+
+    pub async fn get_all_companies(&self) -> Result<Vec<Company>, DatabaseError> {
+        let companies = sqlx::query_as!(Company, "SELECT id, name FROM company ORDER BY name")
+            .fetch_all(&*self.pool)
+            .await?;
+        Ok(companies)
+    }
+
+    pub async fn add_company(&self, name: &str) -> Result<i64, DatabaseError> {
+        let result = sqlx::query!("INSERT INTO company (name) VALUES (?)", name)
+            .execute(&*self.pool)
+            .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    pub async fn update_company(&self, company: &Company) -> Result<i64, DatabaseError> {
+        sqlx::query!(
+            "UPDATE company SET name = ? WHERE id = ?",
+            company.name,
+            company.id
+        )
+        .execute(&*self.pool)
+        .await?;
+        Ok(company.id)
+    }
+
+    pub async fn delete_company(&self, id: i64) -> Result<(), DatabaseError> {
+        if self.is_company_in_use(id).await? {
+            return Err(DatabaseError::InUse);
+        }
+        sqlx::query!("DELETE FROM company WHERE id = ?", id)
+            .execute(&*self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn is_company_in_use(&self, id: i64) -> Result<bool, DatabaseError> {
+        let count = sqlx::query_scalar!(
+            "SELECT (SELECT COUNT(*) FROM software_title_company WHERE company_id = ?)
+                  + (SELECT COUNT(*) FROM release_company WHERE company_id = ?)",
+            id,
+            id
+        )
+        .fetch_one(&*self.pool)
+        .await?;
+        Ok(count > 0)
+    }
+
+    pub async fn get_developers_by_software_title(
+        &self,
+        software_title_id: i64,
+    ) -> Result<Vec<Company>, DatabaseError> {
+        let companies = sqlx::query_as!(
+            Company,
+            "SELECT c.id, c.name
+             FROM company c
+             INNER JOIN software_title_company stc ON c.id = stc.company_id
+             WHERE stc.software_title_id = ?
+             ORDER BY c.name",
+            software_title_id
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        Ok(companies)
+    }
+
+    pub async fn add_developer_to_software_title(
+        &self,
+        software_title_id: i64,
+        company_id: i64,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query!(
+            "INSERT OR IGNORE INTO software_title_company (software_title_id, company_id)
+             VALUES (?, ?)",
+            software_title_id,
+            company_id
+        )
+        .execute(&*self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove_developer_from_software_title(
+        &self,
+        software_title_id: i64,
+        company_id: i64,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query!(
+            "DELETE FROM software_title_company WHERE software_title_id = ? AND company_id = ?",
+            software_title_id,
+            company_id
+        )
+        .execute(&*self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Links the company to the release as a publisher or distributor. Developers are linked to
+    /// the software titles with `add_developer_to_software_title`.
+    pub async fn add_company_to_release(
+        &self,
+        release_id: i64,
+        company_id: i64,
+        role: CompanyRole,
+    ) -> Result<(), DatabaseError> {
+        if role == CompanyRole::Developer {
+            return Err(DatabaseError::DbError(
+                "Developers are linked to software titles".to_string(),
+            ));
+        }
+        let role = i64::from(role);
+        sqlx::query!(
+            "INSERT OR IGNORE INTO release_company (release_id, company_id, role)
+             VALUES (?, ?, ?)",
+            release_id,
+            company_id,
+            role
+        )
+        .execute(&*self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove_company_from_release(
+        &self,
+        release_id: i64,
+        company_id: i64,
+        role: CompanyRole,
+    ) -> Result<(), DatabaseError> {
+        let role = i64::from(role);
+        sqlx::query!(
+            "DELETE FROM release_company WHERE release_id = ? AND company_id = ? AND role = ?",
+            release_id,
+            company_id,
+            role
+        )
+        .execute(&*self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the publishers and distributors of the release together with the developers of
+    /// its software titles, ordered by role and name.
+    pub async fn get_companies_by_release(
+        &self,
+        release_id: i64,
+    ) -> Result<Vec<ReleaseCompany>, DatabaseError> {
+        let rows = sqlx::query!(
+            r#"SELECT c.id AS "id!", c.name AS "name!", 1 AS "role!: i64"
+             FROM company c
+             INNER JOIN software_title_company stc ON c.id = stc.company_id
+             INNER JOIN release_software_title rst ON stc.software_title_id = rst.software_title_id
+             WHERE rst.release_id = ?
+             UNION
+             SELECT c.id, c.name, rc.role
+             FROM company c
+             INNER JOIN release_company rc ON c.id = rc.company_id
+             WHERE rc.release_id = ?
+             ORDER BY 3, 2"#,
+            release_id,
+            release_id
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        let companies = rows
+            .into_iter()
+            .map(|row| {
+                Ok(ReleaseCompany {
+                    company: Company {
+                        id: row.id,
+                        name: row.name,
+                    },
+                    role: row.role.try_into()?,
+                })
+            })
+            .collect::<Result<Vec<_>, sqlx::Error>>()?;
+        Ok(companies)
+    }
+
+    /// Returns the releases the company is credited for, in any role if no role is given.
+    /// Developed releases are the releases of the software titles the company developed.
+    pub async fn get_releases_by_company(
+        &self,
+        company_id: i64,
+        role: Option<CompanyRole>,
+        system_id: Option<i64>,
+    ) -> Result<Vec<Release>, DatabaseError> {
+        let role = role.map(i64::from);
+        let releases = sqlx::query_as!(
+            Release,
+            r#"SELECT r.id, r.name
+             FROM release r
+             WHERE (
+                ((?2 IS NULL OR ?2 = 1) AND EXISTS (
+                    SELECT 1
+                    FROM release_software_title rst
+                    INNER JOIN software_title_company stc
+                        ON rst.software_title_id = stc.software_title_id
+                    WHERE rst.release_id = r.id AND stc.company_id = ?1
+                ))
+                OR EXISTS (
+                    SELECT 1
+                    FROM release_company rc
+                    WHERE rc.release_id = r.id AND rc.company_id = ?1
+                        AND (?2 IS NULL OR rc.role = ?2)
+                )
+             )
+             AND (?3 IS NULL OR EXISTS (
+                SELECT 1 FROM release_system rs WHERE rs.release_id = r.id AND rs.system_id = ?3
+             ))
+             ORDER BY r.name"#,
+            company_id,
+            role,
+            system_id
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        Ok(releases)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        repository::{
+            release_repository::ReleaseRepository,
+            software_title_repository::SoftwareTitleRepository,
+            system_repository::SystemRepository,
+        },
+        setup_test_db,
+    };
+
+    #[async_std::test]
+    async fn test_company_repository() {
+        let pool = Arc::new(setup_test_db().await);
+        let company_repository = CompanyRepository::new(Arc::clone(&pool));
+        let release_repository = ReleaseRepository::new(Arc::clone(&pool));
+        let software_title_repository = SoftwareTitleRepository::new(Arc::clone(&pool));
+        let system_repository = SystemRepository::new(Arc::clone(&pool));
+
+        let ocean_id = company_repository.add_company("Ocean").await.unwrap();
+        let sensible_id = company_repository
+            .add_company("Sensible Software")
+            .await
+            .unwrap();
+
+        let c64_id = system_repository
+            .add_system(&"Commodore 64".to_string())
+            .await
+            .unwrap();
+        let amiga_id = system_repository
+            .add_system(&"Amiga".to_string())
+            .await
+            .unwrap();
+
+        let title_id = software_title_repository
+            .add_software_title(&"Wizball".to_string(), None)
+            .await
+            .unwrap();
+        company_repository
+            .add_developer_to_software_title(title_id, sensible_id)
+            .await
+            .unwrap();
+
+        let c64_release_id = release_repository
+            .add_release_full("Wizball".to_string(), vec![title_id], vec![], vec![c64_id])
+            .await
+            .unwrap();
+        company_repository
+            .add_company_to_release(c64_release_id, ocean_id, CompanyRole::Publisher)
+            .await
+            .unwrap();
+
+        let amiga_release_id = release_repository
+            .add_release_full(
+                "Wizball (Amiga)".to_string(),
+                vec![title_id],
+                vec![],
+                vec![amiga_id],
+            )
+            .await
+            .unwrap();
+
+        assert!(company_repository
+            .add_company_to_release(amiga_release_id, sensible_id, CompanyRole::Developer)
+            .await
+            .is_err());
+
+        let companies = company_repository
+            .get_companies_by_release(c64_release_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            companies
+                .iter()
+                .map(|c| (c.company.name.as_str(), c.role))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Sensible Software", CompanyRole::Developer),
+                ("Ocean", CompanyRole::Publisher)
+            ]
+        );
+
+        let published = company_repository
+            .get_releases_by_company(ocean_id, Some(CompanyRole::Publisher), None)
+            .await
+            .unwrap();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].id, c64_release_id);
+
+        let developed = company_repository
+            .get_releases_by_company(sensible_id, None, None)
+            .await
+            .unwrap();
+        assert_eq!(developed.len(), 2);
+
+        let developed_on_amiga = company_repository
+            .get_releases_by_company(sensible_id, Some(CompanyRole::Developer), Some(amiga_id))
+            .await
+            .unwrap();
+        assert_eq!(developed_on_amiga.len(), 1);
+        assert_eq!(developed_on_amiga[0].id, amiga_release_id);
+
+        let distributed = company_repository
+            .get_releases_by_company(ocean_id, Some(CompanyRole::Distributor), None)
+            .await
+            .unwrap();
+        assert!(distributed.is_empty());
+
+        assert_eq!(
+            company_repository.delete_company(ocean_id).await,
+            Err(DatabaseError::InUse)
+        );
+        company_repository
+            .remove_company_from_release(c64_release_id, ocean_id, CompanyRole::Publisher)
+            .await
+            .unwrap();
+        company_repository.delete_company(ocean_id).await.unwrap();
+
+        let companies = company_repository.get_all_companies().await.unwrap();
+        assert_eq!(companies.len(), 1);
+        assert_eq!(companies[0].name, "Sensible Software");
+    }
+}
@@ -0,0 +1,222 @@
+use std::sync::Arc;
+
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    database_error::DatabaseError,
+    models::{CompatibilityStatus, ReleaseEmulatorCompatibility},
+};
+
+#[derive(Debug)]
+pub struct CompatibilityRepository {
+    pool: Arc<Pool<Sqlite>>,
+}
+
+impl CompatibilityRepository {
+    pub fn new(pool: Arc<Pool<Sqlite>>) -> Self {
+        Self { pool }
+    }
+
+    /// Returns the recorded compatibility of the release with all emulators on the system.
+    pub async fn get_release_compatibility(
+        &self,
+        release_id: i64,
+        system_id: i64,
+    ) -> Result<Vec<ReleaseEmulatorCompatibility>, DatabaseError> {
+        let rows = sqlx::query!(
+            "SELECT id, release_id, emulator_id, system_id, status, notes, tested_at
+             FROM release_emulator_compatibility
+             WHERE release_id = ? AND system_id = ?
+             ORDER BY emulator_id",
+            release_id,
+            system_id
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        let compatibility = rows
+            .into_iter()
+            .map(|row| {
+                Ok(ReleaseEmulatorCompatibility {
+                    id: row.id,
+                    release_id: row.release_id,
+                    emulator_id: row.emulator_id,
+                    system_id: row.system_id,
+                    status: row.status.try_into()?,
+                    notes: row.notes,
+                    tested_at: row.tested_at,
+                })
+            })
+            .collect::<Result<Vec<_>, sqlx::Error>>()?;
+        Ok(compatibility)
+    }
+
+    pub async fn get_compatibility(
+        &self,
+        release_id: i64,
+        emulator_id: i64,
+        system_id: i64,
+    ) -> Result<Option<ReleaseEmulatorCompatibility>, DatabaseError> {
+        let compatibility = self
+            .get_release_compatibility(release_id, system_id)
+            .await?
+            .into_iter()
+            .find(|compatibility| compatibility.emulator_id == emulator_id);
+        Ok(compatibility)
+    }
+
+    /// Records the compatibility of the release with the emulator on the system, replacing the
+    /// earlier record. Tested date defaults to the current date.
+    pub async fn set_compatibility(
+        &self,
+        release_id: i64,
+        emulator_id: i64,
+        system_id: i64,
+        status: CompatibilityStatus,
+        notes: Option<String>,
+        tested_at: Option<String>,
+    ) -> Result<i64, DatabaseError> {
+        let status = i64::from(status);
+        let id = sqlx::query_scalar!(
+            "INSERT INTO release_emulator_compatibility (
+                release_id,
+                emulator_id,
+                system_id,
+                status,
+                notes,
+                tested_at
+             ) VALUES (?, ?, ?, ?, ?, COALESCE(?, date('now')))
+             ON CONFLICT(release_id, emulator_id, system_id) DO UPDATE SET
+                status = excluded.status,
+                notes = excluded.notes,
+                tested_at = excluded.tested_at
+             RETURNING id",
+            release_id,
+            emulator_id,
+            system_id,
+            status,
+            notes,
+            tested_at
+        )
+        .fetch_one(&*self.pool)
+        .await?;
+        Ok(id)
+    }
+
+    pub async fn delete_compatibility(&self, id: i64) -> Result<(), DatabaseError> {
+        sqlx::query!(
+            "DELETE FROM release_emulator_compatibility WHERE id = ?",
+            id
+        )
+        .execute(&*self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::{EmulatorLaunchSettings, EmulatorSystemUpdateModel},
+        repository::{
+            emulator_repository::EmulatorRepository, release_repository::ReleaseRepository,
+            system_repository::SystemRepository,
+        },
+        setup_test_db,
+    };
+
+    use super::*;
+
+    #[async_std::test]
+    async fn test_compatibility() {
+        let pool = Arc::new(setup_test_db().await);
+        let repo = CompatibilityRepository::new(pool.clone());
+        let system_id = SystemRepository::new(pool.clone())
+            .add_system(&"Commodore 64".to_string())
+            .await
+            .unwrap();
+        let release_id = ReleaseRepository::new(pool.clone())
+            .add_release("Test Release")
+            .await
+            .unwrap();
+        let emulator_id = EmulatorRepository::new(pool.clone())
+            .add_emulator_with_systems(
+                "VICE".to_string(),
+                "x64sc".to_string(),
+                true,
+                vec![EmulatorSystemUpdateModel {
+                    id: None,
+                    system_id,
+                    arguments: String::new(),
+                }],
+                EmulatorLaunchSettings::default(),
+            )
+            .await
+            .unwrap();
+
+        let id = repo
+            .set_compatibility(
+                release_id,
+                emulator_id,
+                system_id,
+                CompatibilityStatus::Broken,
+                Some("Crashes on loader".to_string()),
+                Some("2025-06-01".to_string()),
+            )
+            .await
+            .unwrap();
+        // recording again replaces the earlier record
+        let updated_id = repo
+            .set_compatibility(
+                release_id,
+                emulator_id,
+                system_id,
+                CompatibilityStatus::MinorIssues,
+                Some("Works with true drive emulation".to_string()),
+                Some("2025-07-01".to_string()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated_id, id);
+
+        assert_eq!(
+            repo.get_compatibility(release_id, emulator_id, system_id)
+                .await
+                .unwrap(),
+            Some(ReleaseEmulatorCompatibility {
+                id,
+                release_id,
+                emulator_id,
+                system_id,
+                status: CompatibilityStatus::MinorIssues,
+                notes: Some("Works with true drive emulation".to_string()),
+                tested_at: "2025-07-01".to_string(),
+            })
+        );
+
+        // tested date defaults to today
+        repo.set_compatibility(
+            release_id,
+            emulator_id,
+            system_id,
+            CompatibilityStatus::Works,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let compatibility = repo
+            .get_release_compatibility(release_id, system_id)
+            .await
+            .unwrap();
+        assert_eq!(compatibility.len(), 1);
+        assert_eq!(compatibility[0].status, CompatibilityStatus::Works);
+        assert_eq!(compatibility[0].tested_at.len(), "YYYY-MM-DD".len());
+
+        repo.delete_compatibility(id).await.unwrap();
+        assert!(repo
+            .get_compatibility(release_id, emulator_id, system_id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
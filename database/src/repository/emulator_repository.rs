@@ -4,7 +4,10 @@ use sqlx::{Pool, Sqlite};
 
 use crate::{
     database_error::{DatabaseError, Error},
-    models::{Emulator, EmulatorSystem, EmulatorSystemUpdateModel},
+    models::{
+        Emulator, EmulatorConfigTemplate, EmulatorLaunchSettings, EmulatorSystem,
+        EmulatorSystemFirmware, EmulatorSystemUpdateModel, EnvironmentVariable,
+    },
 };
 
 #[derive(Debug)]
@@ -81,30 +84,94 @@ impl EmulatorRepository {
         Ok((emulator, emulator_systems))
     }
 
+    pub async fn get_emulator_launch_settings(
+        &self,
+        emulator_id: i64,
+    ) -> Result<EmulatorLaunchSettings, DatabaseError> {
+        let emulator = sqlx::query!(
+            "SELECT working_directory, pre_launch_command, post_launch_command,
+                launch_timeout_seconds, executable_kind, wine_prefix
+             FROM emulator WHERE id = ?",
+            emulator_id
+        )
+        .fetch_one(&*self.pool)
+        .await?;
+
+        let environment_variables = sqlx::query_as!(
+            EnvironmentVariable,
+            "SELECT name, value
+             FROM emulator_environment_variable
+             WHERE emulator_id = ?
+             ORDER BY name",
+            emulator_id
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(EmulatorLaunchSettings {
+            executable_kind: emulator.executable_kind.try_into()?,
+            wine_prefix: emulator.wine_prefix,
+            working_directory: emulator.working_directory,
+            pre_launch_command: emulator.pre_launch_command,
+            post_launch_command: emulator.post_launch_command,
+            launch_timeout_seconds: emulator.launch_timeout_seconds,
+            environment_variables,
+        })
+    }
+
     pub async fn add_emulator_with_systems(
         &self,
         name: String,
         executable: String,
         extract_files: bool,
         systems: Vec<EmulatorSystemUpdateModel>,
+        launch_settings: EmulatorLaunchSettings,
     ) -> Result<i64, Error> {
         let mut transaction = self.pool.begin().await?;
+        let executable_kind = i64::from(launch_settings.executable_kind);
 
         let result = sqlx::query!(
             "INSERT INTO emulator (
                 name, 
                 executable, 
-                extract_files 
-            ) VALUES (?, ?, ?)",
+                extract_files,
+                executable_kind,
+                wine_prefix,
+                working_directory,
+                pre_launch_command,
+                post_launch_command,
+                launch_timeout_seconds
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
             name,
             executable,
             extract_files,
+            executable_kind,
+            launch_settings.wine_prefix,
+            launch_settings.working_directory,
+            launch_settings.pre_launch_command,
+            launch_settings.post_launch_command,
+            launch_settings.launch_timeout_seconds,
         )
         .execute(&mut *transaction)
         .await?;
 
         let emulator_id = result.last_insert_rowid();
 
+        for variable in launch_settings.environment_variables {
+            sqlx::query!(
+                "INSERT INTO emulator_environment_variable (
+                    emulator_id,
+                    name,
+                    value
+                ) VALUES (?, ?, ?)",
+                emulator_id,
+                variable.name,
+                variable.value,
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+
         for system in systems {
             sqlx::query!(
                 "INSERT INTO emulator_system (
@@ -131,9 +198,11 @@ impl EmulatorRepository {
         executable: String,
         extract_files: bool,
         systems: Vec<EmulatorSystemUpdateModel>,
+        launch_settings: EmulatorLaunchSettings,
     ) -> Result<i64, Error> {
         let mut transaction = self.pool.begin().await?;
         dbg!("Updating emulator with id: {}", emulator_id);
+        let executable_kind = i64::from(launch_settings.executable_kind);
 
         // update first the emulator
         sqlx::query!(
@@ -141,16 +210,52 @@ impl EmulatorRepository {
              SET 
                 name = ?, 
                 executable = ?, 
-                extract_files = ? 
+                extract_files = ?,
+                executable_kind = ?,
+                wine_prefix = ?,
+                working_directory = ?,
+                pre_launch_command = ?,
+                post_launch_command = ?,
+                launch_timeout_seconds = ?
                 WHERE id = ?",
             name,
             executable,
             extract_files,
+            executable_kind,
+            launch_settings.wine_prefix,
+            launch_settings.working_directory,
+            launch_settings.pre_launch_command,
+            launch_settings.post_launch_command,
+            launch_settings.launch_timeout_seconds,
+            emulator_id,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        // environment variables are replaced as a whole
+        sqlx::query!(
+            "DELETE FROM emulator_environment_variable 
+             WHERE emulator_id = ?",
             emulator_id,
         )
         .execute(&mut *transaction)
         .await?;
 
+        for variable in launch_settings.environment_variables {
+            sqlx::query!(
+                "INSERT INTO emulator_environment_variable (
+                    emulator_id,
+                    name,
+                    value
+                ) VALUES (?, ?, ?)",
+                emulator_id,
+                variable.name,
+                variable.value,
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+
         // split emulator system to ones that are new and ones that are existing
         let (systems_to_update, systems_to_insert): (Vec<_>, Vec<_>) =
             systems.iter().partition(|s| s.id.is_some());
@@ -179,8 +284,6 @@ impl EmulatorRepository {
             .filter(|id| !systems_to_update_ids.contains(id))
             .collect::<Vec<i64>>();
 
-        println!("removable_system_ids: {:?}", removable_system_ids);
-
         for id in &removable_system_ids {
             sqlx::query!(
                 "DELETE FROM emulator_system 
@@ -251,6 +354,242 @@ impl EmulatorRepository {
         Ok(result.last_insert_rowid())
     }
 
+    pub async fn get_default_emulator_for_system(
+        &self,
+        system_id: i64,
+    ) -> Result<Option<i64>, DatabaseError> {
+        let emulator_id = sqlx::query_scalar!(
+            "SELECT emulator_id FROM system_default_emulator WHERE system_id = ?",
+            system_id
+        )
+        .fetch_optional(&*self.pool)
+        .await?;
+        Ok(emulator_id)
+    }
+
+    /// Sets or clears (with `None`) the emulator used by default for the system.
+    pub async fn set_default_emulator_for_system(
+        &self,
+        system_id: i64,
+        emulator_id: Option<i64>,
+    ) -> Result<(), DatabaseError> {
+        match emulator_id {
+            Some(emulator_id) => {
+                sqlx::query!(
+                    "INSERT INTO system_default_emulator (system_id, emulator_id)
+                     VALUES (?, ?)
+                     ON CONFLICT(system_id) DO UPDATE SET emulator_id = excluded.emulator_id",
+                    system_id,
+                    emulator_id
+                )
+                .execute(&*self.pool)
+                .await?;
+            }
+            None => {
+                sqlx::query!(
+                    "DELETE FROM system_default_emulator WHERE system_id = ?",
+                    system_id
+                )
+                .execute(&*self.pool)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the emulator overriding the system defaults for the release.
+    pub async fn get_release_emulator(
+        &self,
+        release_id: i64,
+    ) -> Result<Option<i64>, DatabaseError> {
+        let emulator_id = sqlx::query_scalar!(
+            "SELECT emulator_id FROM release_emulator WHERE release_id = ?",
+            release_id
+        )
+        .fetch_optional(&*self.pool)
+        .await?;
+        Ok(emulator_id)
+    }
+
+    /// Sets or clears (with `None`) the emulator overriding the system defaults for the release.
+    pub async fn set_release_emulator(
+        &self,
+        release_id: i64,
+        emulator_id: Option<i64>,
+    ) -> Result<(), DatabaseError> {
+        match emulator_id {
+            Some(emulator_id) => {
+                sqlx::query!(
+                    "INSERT INTO release_emulator (release_id, emulator_id)
+                     VALUES (?, ?)
+                     ON CONFLICT(release_id) DO UPDATE SET emulator_id = excluded.emulator_id",
+                    release_id,
+                    emulator_id
+                )
+                .execute(&*self.pool)
+                .await?;
+            }
+            None => {
+                sqlx::query!(
+                    "DELETE FROM release_emulator WHERE release_id = ?",
+                    release_id
+                )
+                .execute(&*self.pool)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the firmware the emulator needs for the system.
+    pub async fn get_emulator_system_firmware(
+        &self,
+        emulator_id: i64,
+        system_id: i64,
+    ) -> Result<Vec<EmulatorSystemFirmware>, DatabaseError> {
+        let firmware = sqlx::query_as!(
+            EmulatorSystemFirmware,
+            "SELECT 
+                esf.id,
+                esf.emulator_system_id,
+                esf.file_set_id,
+                fs.file_name AS file_set_name,
+                esf.target_path,
+                esf.required as \"required: bool\"
+             FROM emulator_system_firmware esf
+             JOIN emulator_system es ON esf.emulator_system_id = es.id
+             JOIN file_set fs ON esf.file_set_id = fs.id
+             WHERE es.emulator_id = ? AND es.system_id = ?
+             ORDER BY esf.id",
+            emulator_id,
+            system_id
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        Ok(firmware)
+    }
+
+    /// Links a firmware file set to the emulator system, the files are exported to the target
+    /// path when the emulator is launched for the system. Linking the same file set again updates
+    /// the target path and whether the firmware is required.
+    pub async fn add_emulator_system_firmware(
+        &self,
+        emulator_system_id: i64,
+        file_set_id: i64,
+        target_path: String,
+        required: bool,
+    ) -> Result<i64, DatabaseError> {
+        let id = sqlx::query_scalar!(
+            "INSERT INTO emulator_system_firmware (
+                emulator_system_id,
+                file_set_id,
+                target_path,
+                required
+             ) VALUES (?, ?, ?, ?)
+             ON CONFLICT(emulator_system_id, file_set_id) DO UPDATE SET
+                target_path = excluded.target_path,
+                required = excluded.required
+             RETURNING id",
+            emulator_system_id,
+            file_set_id,
+            target_path,
+            required
+        )
+        .fetch_one(&*self.pool)
+        .await?;
+        Ok(id)
+    }
+
+    pub async fn remove_emulator_system_firmware(&self, id: i64) -> Result<(), DatabaseError> {
+        sqlx::query!("DELETE FROM emulator_system_firmware WHERE id = ?", id)
+            .execute(&*self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Returns the configuration template of the emulator system for the release: the release
+    /// specific template if there is one, otherwise the default template of the emulator system.
+    pub async fn get_emulator_config_template(
+        &self,
+        emulator_system_id: i64,
+        release_id: i64,
+    ) -> Result<Option<EmulatorConfigTemplate>, DatabaseError> {
+        let template = sqlx::query_as!(
+            EmulatorConfigTemplate,
+            "SELECT id, emulator_system_id, release_id, file_name, content
+             FROM emulator_config_template
+             WHERE emulator_system_id = ? AND (release_id = ? OR release_id IS NULL)
+             ORDER BY release_id IS NULL
+             LIMIT 1",
+            emulator_system_id,
+            release_id
+        )
+        .fetch_optional(&*self.pool)
+        .await?;
+        Ok(template)
+    }
+
+    /// Sets the configuration template of the emulator system, for the release or as the default
+    /// (with `None`). Existing template is replaced.
+    pub async fn set_emulator_config_template(
+        &self,
+        emulator_system_id: i64,
+        release_id: Option<i64>,
+        file_name: String,
+        content: String,
+    ) -> Result<i64, DatabaseError> {
+        let id = match release_id {
+            Some(release_id) => {
+                sqlx::query_scalar!(
+                    "INSERT INTO emulator_config_template (
+                        emulator_system_id,
+                        release_id,
+                        file_name,
+                        content
+                     ) VALUES (?, ?, ?, ?)
+                     ON CONFLICT(emulator_system_id, release_id) WHERE release_id IS NOT NULL
+                     DO UPDATE SET
+                        file_name = excluded.file_name,
+                        content = excluded.content
+                     RETURNING id",
+                    emulator_system_id,
+                    release_id,
+                    file_name,
+                    content
+                )
+                .fetch_one(&*self.pool)
+                .await?
+            }
+            None => {
+                sqlx::query_scalar!(
+                    "INSERT INTO emulator_config_template (
+                        emulator_system_id,
+                        file_name,
+                        content
+                     ) VALUES (?, ?, ?)
+                     ON CONFLICT(emulator_system_id) WHERE release_id IS NULL
+                     DO UPDATE SET
+                        file_name = excluded.file_name,
+                        content = excluded.content
+                     RETURNING id",
+                    emulator_system_id,
+                    file_name,
+                    content
+                )
+                .fetch_one(&*self.pool)
+                .await?
+            }
+        };
+        Ok(id)
+    }
+
+    pub async fn remove_emulator_config_template(&self, id: i64) -> Result<(), DatabaseError> {
+        sqlx::query!("DELETE FROM emulator_config_template WHERE id = ?", id)
+            .execute(&*self.pool)
+            .await?;
+        Ok(())
+    }
+
     pub async fn remove_emulator_system(
         &self,
         emulator_id: i64,
@@ -269,7 +608,14 @@ impl EmulatorRepository {
 
 #[cfg(test)]
 mod tests {
-    use crate::{repository::system_repository::SystemRepository, setup_test_db};
+    use crate::{
+        models::{ExecutableKind, FileType},
+        repository::{
+            file_set_repository::FileSetRepository, release_repository::ReleaseRepository,
+            system_repository::SystemRepository,
+        },
+        setup_test_db,
+    };
 
     use super::*;
 
@@ -313,6 +659,7 @@ mod tests {
                 "test_executable".to_string(),
                 true,
                 emulator_systems,
+                EmulatorLaunchSettings::default(),
             )
             .await
             .unwrap();
@@ -370,6 +717,7 @@ mod tests {
             emulator.executable.clone(),
             emulator.extract_files,
             updated_emulator_systems,
+            EmulatorLaunchSettings::default(),
         )
         .await
         .unwrap();
@@ -412,4 +760,324 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[async_std::test]
+    async fn test_emulator_launch_settings() {
+        let pool = Arc::new(setup_test_db().await);
+        let repo = EmulatorRepository::new(pool.clone());
+
+        let launch_settings = EmulatorLaunchSettings {
+            executable_kind: ExecutableKind::Wine,
+            wine_prefix: Some("/home/user/.wine-emu".to_string()),
+            working_directory: Some("/opt/emulator".to_string()),
+            pre_launch_command: Some("mount-config.sh".to_string()),
+            post_launch_command: Some("restore-controller.sh".to_string()),
+            launch_timeout_seconds: Some(3600),
+            environment_variables: vec![EnvironmentVariable {
+                name: "WINEPREFIX".to_string(),
+                value: "/home/user/.wine-emu".to_string(),
+            }],
+        };
+
+        let emulator_id = repo
+            .add_emulator_with_systems(
+                "Test Emulator".to_string(),
+                "test_executable".to_string(),
+                false,
+                vec![],
+                launch_settings.clone(),
+            )
+            .await
+            .unwrap();
+
+        let result = repo
+            .get_emulator_launch_settings(emulator_id)
+            .await
+            .unwrap();
+        assert_eq!(result, launch_settings);
+
+        let updated_launch_settings = EmulatorLaunchSettings {
+            executable_kind: ExecutableKind::Flatpak,
+            wine_prefix: None,
+            working_directory: None,
+            pre_launch_command: None,
+            post_launch_command: Some("restore-controller.sh".to_string()),
+            launch_timeout_seconds: None,
+            environment_variables: vec![
+                EnvironmentVariable {
+                    name: "SDL_AUDIODRIVER".to_string(),
+                    value: "pulseaudio".to_string(),
+                },
+                EnvironmentVariable {
+                    name: "SDL_VIDEODRIVER".to_string(),
+                    value: "x11".to_string(),
+                },
+            ],
+        };
+
+        repo.update_emulator_with_systems(
+            emulator_id,
+            "Test Emulator".to_string(),
+            "test_executable".to_string(),
+            false,
+            vec![],
+            updated_launch_settings.clone(),
+        )
+        .await
+        .unwrap();
+
+        let result = repo
+            .get_emulator_launch_settings(emulator_id)
+            .await
+            .unwrap();
+        assert_eq!(result, updated_launch_settings);
+    }
+
+    #[async_std::test]
+    async fn test_default_emulators() {
+        let pool = Arc::new(setup_test_db().await);
+        let repo = EmulatorRepository::new(pool.clone());
+        let system_id = SystemRepository::new(pool.clone())
+            .add_system(&"Test System".to_string())
+            .await
+            .unwrap();
+        let release_id = ReleaseRepository::new(pool.clone())
+            .add_release("Test Release")
+            .await
+            .unwrap();
+
+        let mut emulator_ids = vec![];
+        for name in ["Emulator 1", "Emulator 2"] {
+            let id = repo
+                .add_emulator_with_systems(
+                    name.to_string(),
+                    "emu".to_string(),
+                    false,
+                    vec![],
+                    EmulatorLaunchSettings::default(),
+                )
+                .await
+                .unwrap();
+            emulator_ids.push(id);
+        }
+
+        assert_eq!(
+            repo.get_default_emulator_for_system(system_id)
+                .await
+                .unwrap(),
+            None
+        );
+        repo.set_default_emulator_for_system(system_id, Some(emulator_ids[0]))
+            .await
+            .unwrap();
+        repo.set_default_emulator_for_system(system_id, Some(emulator_ids[1]))
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.get_default_emulator_for_system(system_id)
+                .await
+                .unwrap(),
+            Some(emulator_ids[1])
+        );
+
+        repo.set_release_emulator(release_id, Some(emulator_ids[0]))
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.get_release_emulator(release_id).await.unwrap(),
+            Some(emulator_ids[0])
+        );
+
+        // deleting the emulator removes it as a default and as a release override
+        repo.delete_emulator(emulator_ids[0]).await.unwrap();
+        assert_eq!(repo.get_release_emulator(release_id).await.unwrap(), None);
+
+        repo.set_default_emulator_for_system(system_id, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.get_default_emulator_for_system(system_id)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[async_std::test]
+    async fn test_emulator_system_firmware() {
+        let pool = Arc::new(setup_test_db().await);
+        let repo = EmulatorRepository::new(pool.clone());
+        let system_id = SystemRepository::new(pool.clone())
+            .add_system(&"Amiga".to_string())
+            .await
+            .unwrap();
+        let file_set_id = FileSetRepository::new(pool.clone())
+            .add_file_set(
+                "Kickstart 1.3".to_string(),
+                FileType::Firmware,
+                vec![],
+                &[system_id],
+            )
+            .await
+            .unwrap();
+        let emulator_id = repo
+            .add_emulator_with_systems(
+                "FS-UAE".to_string(),
+                "fs-uae".to_string(),
+                true,
+                vec![EmulatorSystemUpdateModel {
+                    id: None,
+                    system_id,
+                    arguments: String::new(),
+                }],
+                EmulatorLaunchSettings::default(),
+            )
+            .await
+            .unwrap();
+        let (_, emulator_systems) = repo.get_emulator_with_systems(emulator_id).await.unwrap();
+        let emulator_system_id = emulator_systems[0].id;
+
+        let firmware_id = repo
+            .add_emulator_system_firmware(
+                emulator_system_id,
+                file_set_id,
+                "roms".to_string(),
+                false,
+            )
+            .await
+            .unwrap();
+        // linking the same file set again updates the existing link
+        let updated_firmware_id = repo
+            .add_emulator_system_firmware(
+                emulator_system_id,
+                file_set_id,
+                "kickstarts".to_string(),
+                true,
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated_firmware_id, firmware_id);
+
+        let firmware = repo
+            .get_emulator_system_firmware(emulator_id, system_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            firmware,
+            vec![EmulatorSystemFirmware {
+                id: firmware_id,
+                emulator_system_id,
+                file_set_id,
+                file_set_name: "Kickstart 1.3".to_string(),
+                target_path: "kickstarts".to_string(),
+                required: true,
+            }]
+        );
+
+        repo.remove_emulator_system_firmware(firmware_id)
+            .await
+            .unwrap();
+        assert!(repo
+            .get_emulator_system_firmware(emulator_id, system_id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[async_std::test]
+    async fn test_emulator_config_template() {
+        let pool = Arc::new(setup_test_db().await);
+        let repo = EmulatorRepository::new(pool.clone());
+        let system_id = SystemRepository::new(pool.clone())
+            .add_system(&"Commodore 64".to_string())
+            .await
+            .unwrap();
+        let release_repo = ReleaseRepository::new(pool.clone());
+        let release_id = release_repo.add_release("Test Release").await.unwrap();
+        let other_release_id = release_repo.add_release("Other Release").await.unwrap();
+        let emulator_id = repo
+            .add_emulator_with_systems(
+                "VICE".to_string(),
+                "x64sc".to_string(),
+                true,
+                vec![EmulatorSystemUpdateModel {
+                    id: None,
+                    system_id,
+                    arguments: "-config {config}".to_string(),
+                }],
+                EmulatorLaunchSettings::default(),
+            )
+            .await
+            .unwrap();
+        let (_, emulator_systems) = repo.get_emulator_with_systems(emulator_id).await.unwrap();
+        let emulator_system_id = emulator_systems[0].id;
+
+        assert_eq!(
+            repo.get_emulator_config_template(emulator_system_id, release_id)
+                .await
+                .unwrap(),
+            None
+        );
+
+        let default_id = repo
+            .set_emulator_config_template(
+                emulator_system_id,
+                None,
+                "vicerc".to_string(),
+                "DriveTrueEmulation=0".to_string(),
+            )
+            .await
+            .unwrap();
+        let release_template_id = repo
+            .set_emulator_config_template(
+                emulator_system_id,
+                Some(release_id),
+                "vicerc".to_string(),
+                "DriveTrueEmulation=0".to_string(),
+            )
+            .await
+            .unwrap();
+        // setting the template again replaces the existing one
+        let updated_id = repo
+            .set_emulator_config_template(
+                emulator_system_id,
+                Some(release_id),
+                "vicerc".to_string(),
+                "DriveTrueEmulation=1".to_string(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated_id, release_template_id);
+        assert_ne!(release_template_id, default_id);
+
+        assert_eq!(
+            repo.get_emulator_config_template(emulator_system_id, release_id)
+                .await
+                .unwrap(),
+            Some(EmulatorConfigTemplate {
+                id: release_template_id,
+                emulator_system_id,
+                release_id: Some(release_id),
+                file_name: "vicerc".to_string(),
+                content: "DriveTrueEmulation=1".to_string(),
+            })
+        );
+        // other releases get the default template
+        let template = repo
+            .get_emulator_config_template(emulator_system_id, other_release_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(template.id, default_id);
+
+        repo.remove_emulator_config_template(release_template_id)
+            .await
+            .unwrap();
+        let template = repo
+            .get_emulator_config_template(emulator_system_id, release_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(template.id, default_id);
+    }
 }
@@ -110,10 +110,6 @@ impl FileSetRepository {
         file_type: FileType,
         system_ids: &[i64],
     ) -> Result<Vec<FileSet>, DatabaseError> {
-        println!(
-            "Getting file sets for file type: {:?} and systems: {:?}",
-            file_type, system_ids
-        );
         let placeholders = system_ids
             .iter()
             .map(|_| "?")
@@ -161,7 +157,8 @@ impl FileSetRepository {
         .await?;
         let collection_file_id = result.last_insert_rowid();
 
-        for file in files_in_fileset {
+        for (ordinal, file) in files_in_fileset.into_iter().enumerate() {
+            let ordinal = ordinal as i64;
             let checksum = file.sha1_checksum.to_vec();
             // if file_info exists, use its id, otherwise insert new file_info
             let existing_file_info = sqlx::query_scalar!(
@@ -230,11 +227,13 @@ impl FileSetRepository {
                 "INSERT INTO file_set_file_info (
                     file_set_id, 
                     file_info_id, 
-                    file_name
-                 ) VALUES (?, ?, ?)",
+                    file_name,
+                    ordinal
+                 ) VALUES (?, ?, ?, ?)",
                 collection_file_id,
                 file_info_id,
-                file.original_file_name
+                file.original_file_name,
+                ordinal
             )
             .execute(&mut *transaction)
             .await?;
@@ -289,16 +288,106 @@ impl FileSetRepository {
                 fsfi.file_name, 
                 fi.sha1_checksum, 
                 fi.file_size, 
-                fi.archive_file_name
+                fi.archive_file_name,
+                fsfi.ordinal,
+                fsfi.is_entry_point as \"is_entry_point: bool\"
              FROM file_set_file_info fsfi
              JOIN file_info fi ON fsfi.file_info_id = fi.id
-             WHERE fsfi.file_set_id = ?",
+             WHERE fsfi.file_set_id = ?
+             ORDER BY fsfi.ordinal, fsfi.file_name",
             file_set_id
         )
         .fetch_all(&*self.pool)
         .await?;
         Ok(file_infos)
     }
+
+    /// Orders the files of the file set in the order of the given file info ids, which have to
+    /// contain all the files of the file set.
+    pub async fn reorder_file_set_files(
+        &self,
+        file_set_id: i64,
+        file_info_ids: &[i64],
+    ) -> Result<(), DatabaseError> {
+        let mut transaction = self.pool.begin().await?;
+
+        let existing_file_info_ids = sqlx::query_scalar!(
+            "SELECT file_info_id FROM file_set_file_info WHERE file_set_id = ?",
+            file_set_id
+        )
+        .fetch_all(&mut *transaction)
+        .await?
+        .into_iter()
+        .collect::<HashSet<_>>();
+
+        let new_file_info_ids = file_info_ids.iter().copied().collect::<HashSet<_>>();
+        if new_file_info_ids != existing_file_info_ids
+            || new_file_info_ids.len() != file_info_ids.len()
+        {
+            return Err(DatabaseError::DbError(format!(
+                "File order doesn't match the files of file set {}",
+                file_set_id
+            )));
+        }
+
+        for (ordinal, file_info_id) in file_info_ids.iter().enumerate() {
+            let ordinal = ordinal as i64;
+            sqlx::query!(
+                "UPDATE file_set_file_info 
+                 SET ordinal = ?
+                 WHERE file_set_id = ? AND file_info_id = ?",
+                ordinal,
+                file_set_id,
+                file_info_id
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Marks the file as the entry point of the file set, `None` clears the entry point. A file
+    /// set has at most one entry point.
+    pub async fn set_file_set_entry_point(
+        &self,
+        file_set_id: i64,
+        file_info_id: Option<i64>,
+    ) -> Result<(), DatabaseError> {
+        let mut transaction = self.pool.begin().await?;
+
+        sqlx::query!(
+            "UPDATE file_set_file_info 
+             SET is_entry_point = FALSE
+             WHERE file_set_id = ?",
+            file_set_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        if let Some(file_info_id) = file_info_id {
+            let result = sqlx::query!(
+                "UPDATE file_set_file_info 
+                 SET is_entry_point = TRUE
+                 WHERE file_set_id = ? AND file_info_id = ?",
+                file_set_id,
+                file_info_id
+            )
+            .execute(&mut *transaction)
+            .await?;
+
+            if result.rows_affected() == 0 {
+                return Err(DatabaseError::DbError(format!(
+                    "File {} not found in file set {}",
+                    file_info_id, file_set_id
+                )));
+            }
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -585,4 +674,83 @@ mod tests {
         .unwrap();
         result.last_insert_rowid()
     }
+
+    #[async_std::test]
+    async fn test_file_set_file_order_and_entry_point() {
+        let pool = Arc::new(setup_test_db().await);
+        let repo = FileSetRepository { pool: pool.clone() };
+        let files = ["disk 2", "disk 1", "disk 3"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| ImportedFile {
+                sha1_checksum: [i as u8; 20],
+                file_size: 123,
+                original_file_name: name.to_string(),
+                archive_file_name: format!("archive_{}", i),
+            })
+            .collect();
+        let system_id = SystemRepository::new(pool.clone())
+            .add_system(&"Test System".to_string())
+            .await
+            .unwrap();
+        let file_set_id = repo
+            .add_file_set("Game".to_string(), FileType::DiskImage, files, &[system_id])
+            .await
+            .unwrap();
+
+        // files are in the import order
+        let files = repo.get_file_set_file_info(file_set_id).await.unwrap();
+        let names = files
+            .iter()
+            .map(|f| f.file_name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["disk 2", "disk 1", "disk 3"]);
+        assert!(files.iter().all(|f| !f.is_entry_point));
+
+        let new_order = vec![
+            files[1].file_info_id,
+            files[0].file_info_id,
+            files[2].file_info_id,
+        ];
+        repo.reorder_file_set_files(file_set_id, &new_order)
+            .await
+            .unwrap();
+        repo.set_file_set_entry_point(file_set_id, Some(files[1].file_info_id))
+            .await
+            .unwrap();
+        repo.set_file_set_entry_point(file_set_id, Some(files[0].file_info_id))
+            .await
+            .unwrap();
+
+        let files = repo.get_file_set_file_info(file_set_id).await.unwrap();
+        let names = files
+            .iter()
+            .map(|f| f.file_name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["disk 1", "disk 2", "disk 3"]);
+        assert_eq!(
+            files.iter().map(|f| f.ordinal).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        let entry_points = files
+            .iter()
+            .filter(|f| f.is_entry_point)
+            .map(|f| f.file_name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(entry_points, vec!["disk 2"]);
+
+        // order has to contain all the files of the file set
+        let result = repo
+            .reorder_file_set_files(file_set_id, &new_order[..2])
+            .await;
+        assert!(result.is_err());
+        let result = repo.set_file_set_entry_point(file_set_id, Some(-1)).await;
+        assert!(result.is_err());
+
+        repo.set_file_set_entry_point(file_set_id, None)
+            .await
+            .unwrap();
+        let files = repo.get_file_set_file_info(file_set_id).await.unwrap();
+        assert!(files.iter().all(|f| !f.is_entry_point));
+    }
 }
@@ -14,39 +14,41 @@ impl FranchiseRepository {
         Self { pool }
     }
 
-    async fn get_all_franchises(&self) -> Result<Vec<Franchise>, DatabaseError> {
-        let franchises = sqlx::query_as!(Franchise, "SELECT id, name FROM franchise")
+    pub async fn get_franchise(&self, id: i64) -> Result<Franchise, DatabaseError> {
+        let franchise =
+            sqlx::query_as!(Franchise, "SELECT id, name FROM franchise WHERE id = ?", id)
+                .fetch_one(&*self.pool)
+                .await?;
+        Ok(franchise)
+    }
+
+    pub async fn get_all_franchises(&self) -> Result<Vec<Franchise>, DatabaseError> {
+        let franchises = sqlx::query_as!(Franchise, "SELECT id, name FROM franchise ORDER BY name")
             .fetch_all(&*self.pool)
             .await?;
         Ok(franchises)
     }
 
-    async fn add_franchise(&self, name: &str) -> Result<i64, DatabaseError> {
+    pub async fn add_franchise(&self, name: &str) -> Result<i64, DatabaseError> {
         let result = sqlx::query!("INSERT INTO franchise (name) VALUES (?)", name)
             .execute(&*self.pool)
             .await?;
         Ok(result.last_insert_rowid())
     }
 
-    async fn update_franchise(&self, franchise: &Franchise) -> Result<i64, DatabaseError> {
-        let result = sqlx::query!(
+    pub async fn update_franchise(&self, franchise: &Franchise) -> Result<i64, DatabaseError> {
+        sqlx::query!(
             "UPDATE franchise SET name = ? WHERE id = ?",
             franchise.name,
             franchise.id
         )
         .execute(&*self.pool)
         .await?;
-        Ok(result.last_insert_rowid())
+        Ok(franchise.id)
     }
 
-    async fn delete_franchise(&self, id: i64) -> Result<(), DatabaseError> {
-        let count = sqlx::query_scalar!(
-            "SELECT COUNT(*) FROM software_title WHERE franchise_id = ?",
-            id
-        )
-        .fetch_one(&*self.pool)
-        .await?;
-        if count > 0 {
+    pub async fn delete_franchise(&self, id: i64) -> Result<(), DatabaseError> {
+        if self.is_franchise_in_use(id).await? {
             return Err(DatabaseError::InUse);
         }
         sqlx::query!("DELETE FROM franchise WHERE id = ?", id)
@@ -54,12 +56,22 @@ impl FranchiseRepository {
             .await?;
         Ok(())
     }
+
+    pub async fn is_franchise_in_use(&self, id: i64) -> Result<bool, DatabaseError> {
+        let count = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM software_title WHERE franchise_id = ?",
+            id
+        )
+        .fetch_one(&*self.pool)
+        .await?;
+        Ok(count > 0)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::setup_test_db;
+    use crate::{repository::software_title_repository::SoftwareTitleRepository, setup_test_db};
 
     #[async_std::test]
     async fn test_franchise_repository() {
@@ -90,6 +102,25 @@ mod tests {
         let updated_franchises = franchise_repository.get_all_franchises().await.unwrap();
         assert_eq!(updated_franchises[0].name, "Updated Franchise");
 
+        // Franchise with software titles can't be deleted
+        let software_title_repository = SoftwareTitleRepository::new(pool.clone());
+        let software_title_id = software_title_repository
+            .add_software_title(&"Test Software Title".to_string(), Some(franchise_id))
+            .await
+            .unwrap();
+        assert!(franchise_repository
+            .is_franchise_in_use(franchise_id)
+            .await
+            .unwrap());
+        assert_eq!(
+            franchise_repository.delete_franchise(franchise_id).await,
+            Err(DatabaseError::InUse)
+        );
+        software_title_repository
+            .set_software_title_franchise(software_title_id, None)
+            .await
+            .unwrap();
+
         // Delete the franchise
         franchise_repository
             .delete_franchise(franchise_id)
@@ -1,8 +1,13 @@
+pub mod collection_repository;
+pub mod company_repository;
+pub mod compatibility_repository;
 pub mod emulator_repository;
 pub mod file_info_repository;
 pub mod file_set_repository;
 pub mod franchise_repository;
 pub mod release_repository;
+pub mod search_repository;
 pub mod setting_repository;
 pub mod software_title_repository;
 pub mod system_repository;
+pub mod tag_repository;
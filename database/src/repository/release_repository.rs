@@ -1,10 +1,15 @@
 use std::sync::Arc;
 
-use sqlx::{query_as, Pool, Sqlite};
+use sqlx::{Pool, Sqlite};
 
 use crate::{
     database_error::{DatabaseError, Error},
-    models::{FileType, Release, ReleaseExtended},
+    models::{
+        FileSetLaunchRole, FileType, PartialDate, Release, ReleaseExtended, ReleaseListQuery,
+        ReleaseMetadata,
+    },
+    release_query::{build_release_list_query, NAME_SEPARATOR},
+    repository::search_repository::execute_indexed_write,
 };
 
 #[derive(Debug)]
@@ -39,67 +44,30 @@ impl ReleaseRepository {
         Ok(release)
     }
 
+    /// Lists the releases matching the query, see `ReleaseListQuery`.
     pub async fn get_releases(
         &self,
-        system_id: Option<i64>,
-        software_title_id: Option<i64>,
+        query: &ReleaseListQuery,
     ) -> Result<Vec<ReleaseExtended>, Error> {
-        let query = r#"
-            SELECT
-                r.id as id,
-                r.name as name,
-                GROUP_CONCAT(DISTINCT s.name) as system_names,
-                GROUP_CONCAT(DISTINCT st.name) as software_title_names,
-                GROUP_CONCAT(DISTINCT fs.file_type) as file_types
-             FROM
-                release r
-             INNER JOIN
-                release_software_title rst ON r.id = rst.release_id
-             INNER JOIN
-                software_title st ON rst.software_title_id = st.id
-             INNER JOIN
-                release_system rs ON r.id = rs.release_id
-             INNER JOIN
-                system s ON rs.system_id = s.id
-             INNER JOIN
-                release_file_set rfs ON r.id = rfs.release_id
-             INNER JOIN
-                file_set fs ON rfs.file_set_id = fs.id
-            WHERE
-                (? IS NULL OR s.id = ?)
-                AND (? IS NULL OR st.id = ?)
-             GROUP BY
-                r.id, r.name;
-        "#;
-
-        let raw_releases: Vec<ReleaseExtendedRaw> = query_as(query)
-            .bind(system_id)
-            .bind(system_id)
-            .bind(software_title_id)
-            .bind(software_title_id)
+        let raw_releases = build_release_list_query(query)
+            .build_query_as::<ReleaseExtendedRaw>()
             .fetch_all(&*self.pool)
             .await?;
 
+        let names = |names: Option<String>| {
+            names
+                .map(|names| names.split(NAME_SEPARATOR).map(String::from).collect())
+                .unwrap_or_default()
+        };
         let mut releases: Vec<ReleaseExtended> = Vec::new();
 
         for raw in raw_releases {
-            dbg!("Raw release: {}", &raw);
-            let system_names = raw
-                .system_names
-                .unwrap_or_default()
-                .split(',')
-                .map(String::from)
-                .collect();
-            let software_title_names = raw
-                .software_title_names
-                .unwrap_or_default()
-                .split(',')
-                .map(String::from)
-                .collect();
             let file_types = raw
                 .file_types
+                .as_deref()
+                .map(|file_types| file_types.split(',').collect::<Vec<_>>())
                 .unwrap_or_default()
-                .split(',')
+                .into_iter()
                 .map(|ft| {
                     ft.parse::<i64>()
                         .map_err(|e| {
@@ -115,8 +83,8 @@ impl ReleaseRepository {
             releases.push(ReleaseExtended {
                 id: raw.id,
                 name: raw.name,
-                system_names,
-                software_title_names,
+                system_names: names(raw.system_names),
+                software_title_names: names(raw.software_title_names),
                 file_types,
             });
         }
@@ -144,9 +112,11 @@ impl ReleaseRepository {
     }
 
     pub async fn add_release(&self, release_name: &str) -> Result<i64, DatabaseError> {
-        let result = sqlx::query!("INSERT INTO release (name) VALUES (?)", release_name)
-            .execute(&*self.pool)
-            .await?;
+        let result = execute_indexed_write(
+            &self.pool,
+            sqlx::query!("INSERT INTO release (name) VALUES (?)", release_name),
+        )
+        .await?;
         Ok(result.last_insert_rowid())
     }
 
@@ -210,9 +180,13 @@ impl ReleaseRepository {
     ) -> Result<i64, Error> {
         let mut transaction = self.pool.begin().await?;
 
-        sqlx::query!("UPDATE release SET name = ?", release_name)
-            .execute(&mut *transaction)
-            .await?;
+        sqlx::query!(
+            "UPDATE release SET name = ? WHERE id = ?",
+            release_name,
+            release_id
+        )
+        .execute(&mut *transaction)
+        .await?;
 
         // update software titles
 
@@ -356,14 +330,149 @@ impl ReleaseRepository {
     }
 
     pub async fn update_release(&self, release: &Release) -> Result<u64, DatabaseError> {
+        let result = execute_indexed_write(
+            &self.pool,
+            sqlx::query!(
+                "UPDATE release SET name = ? WHERE id = ?",
+                release.name,
+                release.id
+            ),
+        )
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn get_release_metadata(&self, release_id: i64) -> Result<ReleaseMetadata, Error> {
+        let release = sqlx::query!(
+            "SELECT release_date, version, product_code, barcode FROM release WHERE id = ?",
+            release_id
+        )
+        .fetch_one(&*self.pool)
+        .await?;
+        let regions = sqlx::query_scalar!(
+            "SELECT region FROM release_region WHERE release_id = ? ORDER BY region",
+            release_id
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        let languages = sqlx::query_scalar!(
+            "SELECT language FROM release_language WHERE release_id = ? ORDER BY language",
+            release_id
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(ReleaseMetadata {
+            release_date: release
+                .release_date
+                .map(|date| date.parse::<PartialDate>())
+                .transpose()?,
+            regions,
+            languages,
+            version: release.version,
+            product_code: release.product_code,
+            barcode: release.barcode,
+        })
+    }
+
+    /// Replaces the metadata of the release.
+    pub async fn update_release_metadata(
+        &self,
+        release_id: i64,
+        metadata: &ReleaseMetadata,
+    ) -> Result<(), Error> {
+        let mut transaction = self.pool.begin().await?;
+        let release_date = metadata.release_date.map(|date| date.to_string());
+        sqlx::query!(
+            "UPDATE release
+             SET release_date = ?, version = ?, product_code = ?, barcode = ?
+             WHERE id = ?",
+            release_date,
+            metadata.version,
+            metadata.product_code,
+            metadata.barcode,
+            release_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM release_region WHERE release_id = ?",
+            release_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+        for region in &metadata.regions {
+            sqlx::query!(
+                "INSERT OR IGNORE INTO release_region (release_id, region) VALUES (?, ?)",
+                release_id,
+                region
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        sqlx::query!(
+            "DELETE FROM release_language WHERE release_id = ?",
+            release_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+        for language in &metadata.languages {
+            sqlx::query!(
+                "INSERT OR IGNORE INTO release_language (release_id, language) VALUES (?, ?)",
+                release_id,
+                language
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Launch roles of the file sets of the release, file sets without a role are left out.
+    pub async fn get_file_set_launch_roles(
+        &self,
+        release_id: i64,
+    ) -> Result<Vec<FileSetLaunchRole>, DatabaseError> {
+        let roles = sqlx::query_as!(
+            FileSetLaunchRole,
+            r#"SELECT file_set_id, launch_role AS "role!"
+             FROM release_file_set
+             WHERE release_id = ? AND launch_role IS NOT NULL
+             ORDER BY launch_role"#,
+            release_id
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        Ok(roles)
+    }
+
+    /// Sets or clears the launch role of a file set of the release. A role is used by one file
+    /// set of the release at a time.
+    pub async fn set_file_set_launch_role(
+        &self,
+        release_id: i64,
+        file_set_id: i64,
+        role: Option<&str>,
+    ) -> Result<(), DatabaseError> {
         let result = sqlx::query!(
-            "UPDATE release SET name = ? WHERE id = ?",
-            release.name,
-            release.id
+            "UPDATE release_file_set SET launch_role = ? WHERE release_id = ? AND file_set_id = ?",
+            role,
+            release_id,
+            file_set_id
         )
         .execute(&*self.pool)
         .await?;
-        Ok(result.rows_affected())
+        if result.rows_affected() == 0 {
+            return Err(DatabaseError::DbError(format!(
+                "File set {} is not in release {}",
+                file_set_id, release_id
+            )));
+        }
+        Ok(())
     }
 
     pub async fn delete_release(&self, id: i64) -> Result<i64, DatabaseError> {
@@ -377,9 +486,11 @@ impl ReleaseRepository {
         if count > 0 {
             return Err(DatabaseError::InUse);
         }
-        sqlx::query!("DELETE FROM release WHERE id = ?", id)
-            .execute(&*self.pool)
-            .await?;
+        execute_indexed_write(
+            &self.pool,
+            sqlx::query!("DELETE FROM release WHERE id = ?", id),
+        )
+        .await?;
         Ok(id)
     }
 
@@ -413,6 +524,28 @@ impl ReleaseRepository {
         Ok(())
     }
 
+    /// Adds a launch of the release to the launch history. Launch time defaults to the current
+    /// time.
+    pub async fn add_release_launch(
+        &self,
+        release_id: i64,
+        emulator_id: Option<i64>,
+        system_id: Option<i64>,
+        launched_at: Option<String>,
+    ) -> Result<i64, DatabaseError> {
+        let result = sqlx::query!(
+            "INSERT INTO release_launch (release_id, emulator_id, system_id, launched_at)
+             VALUES (?, ?, ?, COALESCE(?, datetime('now')))",
+            release_id,
+            emulator_id,
+            system_id,
+            launched_at
+        )
+        .execute(&*self.pool)
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
     pub async fn has_release_files(&self, release_id: i64) -> Result<bool, DatabaseError> {
         let count = sqlx::query_scalar!(
             "SELECT COUNT(*) FROM release_file_set WHERE release_id = ?",
@@ -430,8 +563,10 @@ mod tests {
 
     use super::*;
     use crate::{
+        add_test_file_set,
+        models::{CompanyRole, ReleaseMetadataFilter, ReleaseSortKey},
         repository::{
-            file_set_repository::FileSetRepository,
+            company_repository::CompanyRepository, file_set_repository::FileSetRepository,
             software_title_repository::SoftwareTitleRepository,
             system_repository::SystemRepository,
         },
@@ -692,4 +827,457 @@ mod tests {
         assert_eq!(systems[0].system_id, system_2_id);
         assert_eq!(systems[1].system_id, system_3_id);
     }
+
+    #[async_std::test]
+    async fn test_release_metadata() {
+        let pool = Arc::new(setup_test_db().await);
+        let release_repository = ReleaseRepository::new(pool.clone());
+        let system_id = SystemRepository::new(pool.clone())
+            .add_system(&"Amiga".to_string())
+            .await
+            .unwrap();
+        let software_title_id = SoftwareTitleRepository::new(pool.clone())
+            .add_software_title(&"Game".to_string(), None)
+            .await
+            .unwrap();
+        let (mut release_ids, mut file_set_ids) = (vec![], vec![]);
+        for (i, name) in ["Game (Europe)", "Game (USA)"].iter().enumerate() {
+            let file_set_id = FileSetRepository::new(pool.clone())
+                .add_file_set(
+                    name.to_string(),
+                    FileType::DiskImage,
+                    vec![ImportedFile {
+                        original_file_name: format!("{}.adf", name),
+                        archive_file_name: format!("archive_{}", i),
+                        file_size: 1024,
+                        sha1_checksum: [i as u8; 20],
+                    }],
+                    &[system_id],
+                )
+                .await
+                .unwrap();
+            file_set_ids.push(file_set_id);
+            release_ids.push(
+                release_repository
+                    .add_release_full(
+                        name.to_string(),
+                        vec![software_title_id],
+                        vec![file_set_id],
+                        vec![system_id],
+                    )
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        let metadata = ReleaseMetadata {
+            release_date: Some("1990-05".parse().unwrap()),
+            regions: vec!["Europe".to_string()],
+            languages: vec!["de".to_string(), "en".to_string()],
+            version: Some("Rev A".to_string()),
+            product_code: Some("ABC-123".to_string()),
+            barcode: None,
+        };
+        release_repository
+            .update_release_metadata(release_ids[0], &metadata)
+            .await
+            .unwrap();
+        release_repository
+            .update_release_metadata(
+                release_ids[1],
+                &ReleaseMetadata {
+                    release_date: Some("1991".parse().unwrap()),
+                    regions: vec!["USA".to_string()],
+                    languages: vec!["en".to_string()],
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            release_repository
+                .get_release_metadata(release_ids[0])
+                .await
+                .unwrap(),
+            metadata
+        );
+
+        // updating a release doesn't rename the others
+        release_repository
+            .update_release_full(
+                release_ids[0],
+                "Game (Europe) (Rev A)".to_string(),
+                vec![software_title_id],
+                vec![file_set_ids[0]],
+                vec![system_id],
+            )
+            .await
+            .unwrap();
+        let release = release_repository
+            .get_release(release_ids[1])
+            .await
+            .unwrap();
+        assert_eq!(release.name, "Game (USA)");
+
+        let release_names = |filter: ReleaseMetadataFilter| {
+            let release_repository = &release_repository;
+            async move {
+                release_repository
+                    .get_releases(&ReleaseListQuery {
+                        metadata: filter,
+                        ..Default::default()
+                    })
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|release| release.name)
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(
+            release_names(ReleaseMetadataFilter {
+                region: Some("usa".to_string()),
+                ..Default::default()
+            })
+            .await,
+            vec!["Game (USA)".to_string()]
+        );
+        assert_eq!(
+            release_names(ReleaseMetadataFilter {
+                language: Some("en".to_string()),
+                ..Default::default()
+            })
+            .await
+            .len(),
+            2
+        );
+        assert_eq!(
+            release_names(ReleaseMetadataFilter {
+                released_from: Some("1990-06".parse().unwrap()),
+                ..Default::default()
+            })
+            .await,
+            vec!["Game (USA)".to_string()]
+        );
+        assert_eq!(
+            release_names(ReleaseMetadataFilter {
+                released_to: Some("1990".parse().unwrap()),
+                ..Default::default()
+            })
+            .await,
+            vec!["Game (Europe) (Rev A)".to_string()]
+        );
+    }
+
+    #[async_std::test]
+    async fn test_get_releases() {
+        let pool = Arc::new(setup_test_db().await);
+        let release_repository = ReleaseRepository::new(pool.clone());
+        let software_title_repository = SoftwareTitleRepository::new(pool.clone());
+        let company_repository = CompanyRepository::new(pool.clone());
+        let system_repository = SystemRepository::new(pool.clone());
+        let c64_id = system_repository
+            .add_system(&"Commodore 64".to_string())
+            .await
+            .unwrap();
+        let amiga_id = system_repository
+            .add_system(&"Amiga".to_string())
+            .await
+            .unwrap();
+        let beta_title_id = software_title_repository
+            .add_software_title(&"Beta, The".to_string(), None)
+            .await
+            .unwrap();
+        let gamma_title_id = software_title_repository
+            .add_software_title(&"Gamma".to_string(), None)
+            .await
+            .unwrap();
+        let disk_id = add_test_file_set(&pool, "Beta.d64", FileType::DiskImage, 1).await;
+        let rom_id = add_test_file_set(&pool, "Gamma.rom", FileType::Rom, 2).await;
+
+        // releases missing systems, software titles or file sets are listed too
+        let alpha_id = release_repository
+            .add_release_full("Alpha".to_string(), vec![], vec![], vec![c64_id])
+            .await
+            .unwrap();
+        let beta_id = release_repository
+            .add_release_full(
+                "beta".to_string(),
+                vec![beta_title_id],
+                vec![disk_id],
+                vec![c64_id, amiga_id],
+            )
+            .await
+            .unwrap();
+        let gamma_id = release_repository
+            .add_release_full(
+                "gamma".to_string(),
+                vec![gamma_title_id],
+                vec![rom_id],
+                vec![],
+            )
+            .await
+            .unwrap();
+        let delta_id = release_repository.add_release("Delta 100%").await.unwrap();
+        for (release_id, date) in [(beta_id, "1989"), (gamma_id, "1987-02")] {
+            release_repository
+                .update_release_metadata(
+                    release_id,
+                    &ReleaseMetadata {
+                        release_date: date.parse().ok(),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+        }
+        let company_id = company_repository.add_company("Thalamus").await.unwrap();
+        company_repository
+            .add_developer_to_software_title(beta_title_id, company_id)
+            .await
+            .unwrap();
+        company_repository
+            .add_company_to_release(gamma_id, company_id, CompanyRole::Publisher)
+            .await
+            .unwrap();
+
+        let releases = release_repository
+            .get_releases(&ReleaseListQuery::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            releases,
+            vec![
+                ReleaseExtended {
+                    id: alpha_id,
+                    name: "Alpha".to_string(),
+                    system_names: vec!["Commodore 64".to_string()],
+                    software_title_names: vec![],
+                    file_types: vec![],
+                },
+                ReleaseExtended {
+                    id: beta_id,
+                    name: "beta".to_string(),
+                    system_names: releases[1].system_names.clone(),
+                    software_title_names: vec!["Beta, The".to_string()],
+                    file_types: vec![FileType::DiskImage],
+                },
+                ReleaseExtended {
+                    id: delta_id,
+                    name: "Delta 100%".to_string(),
+                    system_names: vec![],
+                    software_title_names: vec![],
+                    file_types: vec![],
+                },
+                ReleaseExtended {
+                    id: gamma_id,
+                    name: "gamma".to_string(),
+                    system_names: vec![],
+                    software_title_names: vec!["Gamma".to_string()],
+                    file_types: vec![FileType::Rom],
+                },
+            ]
+        );
+        assert_eq!(releases[1].system_names.len(), 2);
+
+        let release_ids = |query: ReleaseListQuery| {
+            let release_repository = &release_repository;
+            async move {
+                release_repository
+                    .get_releases(&query)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|release| release.id)
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(
+            release_ids(ReleaseListQuery {
+                system_ids: vec![c64_id, amiga_id],
+                ..Default::default()
+            })
+            .await,
+            vec![alpha_id, beta_id]
+        );
+        assert_eq!(
+            release_ids(ReleaseListQuery {
+                file_types: vec![FileType::DiskImage, FileType::Rom],
+                software_title_ids: vec![gamma_title_id],
+                ..Default::default()
+            })
+            .await,
+            vec![gamma_id]
+        );
+        assert_eq!(
+            release_ids(ReleaseListQuery {
+                name_patterns: vec!["a*".to_string(), "*100%".to_string(), "?amma".to_string()],
+                ..Default::default()
+            })
+            .await,
+            vec![alpha_id, delta_id, gamma_id]
+        );
+        assert!(release_ids(ReleaseListQuery {
+            name_patterns: vec!["delta 1_0%".to_string()],
+            ..Default::default()
+        })
+        .await
+        .is_empty());
+        assert_eq!(
+            release_ids(ReleaseListQuery {
+                company_id: Some(company_id),
+                ..Default::default()
+            })
+            .await,
+            vec![beta_id, gamma_id]
+        );
+        assert_eq!(
+            release_ids(ReleaseListQuery {
+                company_id: Some(company_id),
+                company_role: Some(CompanyRole::Publisher),
+                ..Default::default()
+            })
+            .await,
+            vec![gamma_id]
+        );
+
+        // undated releases first, ordered by id
+        assert_eq!(
+            release_ids(ReleaseListQuery {
+                sort_key: ReleaseSortKey::ReleaseDate,
+                ..Default::default()
+            })
+            .await,
+            vec![alpha_id, delta_id, gamma_id, beta_id]
+        );
+        assert_eq!(
+            release_ids(ReleaseListQuery {
+                sort_key: ReleaseSortKey::Id,
+                descending: true,
+                ..Default::default()
+            })
+            .await,
+            vec![delta_id, gamma_id, beta_id, alpha_id]
+        );
+
+        assert_eq!(
+            release_ids(ReleaseListQuery {
+                limit: Some(2),
+                offset: Some(1),
+                ..Default::default()
+            })
+            .await,
+            vec![beta_id, delta_id]
+        );
+        assert_eq!(
+            release_ids(ReleaseListQuery {
+                offset: Some(3),
+                ..Default::default()
+            })
+            .await,
+            vec![gamma_id]
+        );
+        assert_eq!(
+            release_ids(ReleaseListQuery {
+                after_release_id: Some(beta_id),
+                limit: Some(1),
+                ..Default::default()
+            })
+            .await,
+            vec![delta_id]
+        );
+        assert_eq!(
+            release_ids(ReleaseListQuery {
+                sort_key: ReleaseSortKey::ReleaseDate,
+                descending: true,
+                after_release_id: Some(gamma_id),
+                ..Default::default()
+            })
+            .await,
+            vec![delta_id, alpha_id]
+        );
+    }
+
+    #[async_std::test]
+    async fn test_file_set_launch_roles() {
+        let pool = Arc::new(setup_test_db().await);
+        let release_repository = ReleaseRepository::new(pool.clone());
+        let mut file_set_ids = Vec::new();
+        for (i, name) in ["Game.bin", "BIOS.bin", "Patch.ips"].iter().enumerate() {
+            file_set_ids.push(add_test_file_set(&pool, name, FileType::Rom, i as u8).await);
+        }
+        let [game_id, bios_id, patch_id] = file_set_ids[..] else {
+            unreachable!()
+        };
+        let release_id = release_repository
+            .add_release_full(
+                "Game".to_string(),
+                vec![],
+                vec![game_id, bios_id, patch_id],
+                vec![],
+            )
+            .await
+            .unwrap();
+
+        release_repository
+            .set_file_set_launch_role(release_id, bios_id, Some("bios"))
+            .await
+            .unwrap();
+        release_repository
+            .set_file_set_launch_role(release_id, patch_id, Some("patch"))
+            .await
+            .unwrap();
+        // a role is used by one file set of the release
+        assert!(release_repository
+            .set_file_set_launch_role(release_id, game_id, Some("bios"))
+            .await
+            .is_err());
+        // file set has to be in the release
+        assert!(release_repository
+            .set_file_set_launch_role(release_id + 1, game_id, Some("game"))
+            .await
+            .is_err());
+        assert_eq!(
+            release_repository
+                .get_file_set_launch_roles(release_id)
+                .await
+                .unwrap(),
+            vec![
+                FileSetLaunchRole {
+                    file_set_id: bios_id,
+                    role: "bios".to_string(),
+                },
+                FileSetLaunchRole {
+                    file_set_id: patch_id,
+                    role: "patch".to_string(),
+                },
+            ]
+        );
+
+        // roles of kept file sets survive updating the release
+        release_repository
+            .update_release_full(
+                release_id,
+                "Game".to_string(),
+                vec![],
+                vec![game_id, bios_id],
+                vec![],
+            )
+            .await
+            .unwrap();
+        release_repository
+            .set_file_set_launch_role(release_id, game_id, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            release_repository
+                .get_file_set_launch_roles(release_id)
+                .await
+                .unwrap(),
+            vec![FileSetLaunchRole {
+                file_set_id: bios_id,
+                role: "bios".to_string(),
+            }]
+        );
+    }
 }
@@ -0,0 +1,376 @@
+use std::sync::Arc;
+
+use sqlx::{sqlite::SqliteQueryResult, Execute, Executor, Pool, Sqlite};
+
+use crate::{
+    database_error::DatabaseError,
+    models::{Release, SearchResult, SearchResultKind},
+    search_query::SearchQuery,
+};
+
+#[derive(Debug)]
+pub struct SearchRepository {
+    pool: Arc<Pool<Sqlite>>,
+}
+
+impl SearchRepository {
+    pub fn new(pool: Arc<Pool<Sqlite>>) -> Self {
+        Self { pool }
+    }
+
+    /// Searches the names of software titles and their aliases, releases and the files of file
+    /// sets for names with words starting with each of the words of the query, regardless of
+    /// case and diacritics. Returns each entity once, best matches first.
+    pub async fn search(
+        &self,
+        query: &str,
+        limit: i64,
+    ) -> Result<Vec<SearchResult>, DatabaseError> {
+        let Some(match_expression) = match_expression(query) else {
+            return Ok(vec![]);
+        };
+        // aliases are returned as their software titles
+        let rows = sqlx::query!(
+            r#"SELECT m.kind AS "kind!: i64", m.id AS "id!: i64",
+                COALESCE(st.name, r.name, fs.file_name) AS "name!: String",
+                m.matched_name AS "matched_name!: String"
+             FROM (
+                SELECT kind, id, matched_name, MIN(rank) AS rank
+                FROM (
+                    SELECT CASE se.kind WHEN 2 THEN 1 ELSE se.kind END AS kind,
+                        CASE se.kind WHEN 2 THEN sta.software_title_id ELSE se.entity_id END AS id,
+                        se.name AS matched_name,
+                        search_index.rank AS rank
+                    FROM search_index
+                    INNER JOIN search_entry se ON se.id = search_index.rowid
+                    LEFT JOIN software_title_alias sta ON se.kind = 2 AND sta.id = se.entity_id
+                    WHERE search_index MATCH ?
+                )
+                GROUP BY kind, id
+             ) m
+             LEFT JOIN software_title st ON m.kind = 1 AND st.id = m.id
+             LEFT JOIN release r ON m.kind = 3 AND r.id = m.id
+             LEFT JOIN file_set fs ON m.kind = 4 AND fs.id = m.id
+             ORDER BY m.rank
+             LIMIT ?"#,
+            match_expression,
+            limit
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let kind = match row.kind {
+                    1 => SearchResultKind::SoftwareTitle,
+                    3 => SearchResultKind::Release,
+                    4 => SearchResultKind::FileSet,
+                    kind => {
+                        return Err(DatabaseError::DbError(format!(
+                            "Invalid search entry kind {}",
+                            kind
+                        )))
+                    }
+                };
+                Ok(SearchResult {
+                    kind,
+                    id: row.id,
+                    name: row.name,
+                    matched_name: row.matched_name,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the releases matching the query ordered by name.
+    pub async fn search_releases(
+        &self,
+        query: &SearchQuery,
+    ) -> Result<Vec<Release>, DatabaseError> {
+        let releases = query
+            .build_release_query()
+            .build_query_as::<Release>()
+            .fetch_all(&*self.pool)
+            .await?;
+        Ok(releases)
+    }
+}
+
+/// Quotes the words of the query as FTS5 prefix phrases so that query syntax characters are
+/// matched literally, `None` if the query has no words.
+pub(crate) fn match_expression(query: &str) -> Option<String> {
+    let phrases = query
+        .split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect::<Vec<_>>();
+    (!phrases.is_empty()).then(|| phrases.join(" "))
+}
+
+/// Executes a statement writing to a table kept in the search index by the triggers. When the
+/// statement fails after the triggers have written to the FTS5 index, e.g. on a foreign key,
+/// FTS5 keeps reading the index and the database stays locked for the other connections until
+/// the connection uses the index again, so the index is queried after a failing statement.
+///
+/// Every autocommit write to the indexed tables, `software_title`, `software_title_alias`,
+/// `release` and `file_set_file_info`, or to tables cascading to them goes through here. Writes
+/// in a transaction don't, a transaction dropped on an error is rolled back, which releases the
+/// index too.
+pub(crate) async fn execute_indexed_write<'q, E>(
+    pool: &Pool<Sqlite>,
+    statement: E,
+) -> Result<SqliteQueryResult, sqlx::Error>
+where
+    E: 'q + Execute<'q, Sqlite>,
+{
+    let mut connection = pool.acquire().await?;
+    let result = connection.execute(statement).await;
+    if result.is_err() {
+        sqlx::query("SELECT 1 FROM search_index LIMIT 0")
+            .execute(&mut *connection)
+            .await?;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        add_test_file_set, get_db_pool_with_path,
+        models::FileType,
+        repository::{
+            file_set_repository::FileSetRepository, release_repository::ReleaseRepository,
+            software_title_repository::SoftwareTitleRepository,
+        },
+        setup_test_db,
+    };
+
+    #[async_std::test]
+    async fn test_search() {
+        let pool = Arc::new(setup_test_db().await);
+        let search_repository = SearchRepository::new(Arc::clone(&pool));
+        let software_title_repository = SoftwareTitleRepository::new(Arc::clone(&pool));
+        let release_repository = ReleaseRepository::new(Arc::clone(&pool));
+        let file_set_repository = FileSetRepository::new(Arc::clone(&pool));
+
+        let pokemon_id = software_title_repository
+            .add_software_title(&"Pokémon Red".to_string(), None)
+            .await
+            .unwrap();
+        let contra_id = software_title_repository
+            .add_software_title(&"Contra".to_string(), None)
+            .await
+            .unwrap();
+        software_title_repository
+            .add_alias(contra_id, "Probotector", None, None)
+            .await
+            .unwrap();
+        let release_id = release_repository
+            .add_release("Pokemon Red (Europe)")
+            .await
+            .unwrap();
+        let file_set_id = file_set_repository
+            .add_file_set(
+                "Pocket Monsters".to_string(),
+                FileType::Rom,
+                vec![core_types::ImportedFile {
+                    original_file_name: "pokemon_red.gb".to_string(),
+                    archive_file_name: "archive".to_string(),
+                    sha1_checksum: [1; 20],
+                    file_size: 1,
+                }],
+                &[],
+            )
+            .await
+            .unwrap();
+
+        let results = search_repository.search("POKE red", 10).await.unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results.contains(&SearchResult {
+            kind: SearchResultKind::SoftwareTitle,
+            id: pokemon_id,
+            name: "Pokémon Red".to_string(),
+            matched_name: "Pokémon Red".to_string(),
+        }));
+        assert!(results.contains(&SearchResult {
+            kind: SearchResultKind::Release,
+            id: release_id,
+            name: "Pokemon Red (Europe)".to_string(),
+            matched_name: "Pokemon Red (Europe)".to_string(),
+        }));
+        assert!(results.contains(&SearchResult {
+            kind: SearchResultKind::FileSet,
+            id: file_set_id,
+            name: "Pocket Monsters".to_string(),
+            matched_name: "pokemon_red.gb".to_string(),
+        }));
+
+        let results = search_repository.search("probo", 10).await.unwrap();
+        assert_eq!(
+            results,
+            vec![SearchResult {
+                kind: SearchResultKind::SoftwareTitle,
+                id: contra_id,
+                name: "Contra".to_string(),
+                matched_name: "Probotector".to_string(),
+            }]
+        );
+
+        // the index follows renames and deletions
+        release_repository
+            .update_release(&Release {
+                id: release_id,
+                name: "Pocket Monsters: Red".to_string(),
+            })
+            .await
+            .unwrap();
+        let results = search_repository
+            .search("pokemon (europe", 10)
+            .await
+            .unwrap();
+        assert!(results.is_empty());
+        software_title_repository
+            .delete_software_title(pokemon_id)
+            .await
+            .unwrap();
+        let results = search_repository.search("\"pokémon\"", 10).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].kind, SearchResultKind::FileSet);
+
+        assert!(search_repository
+            .search(" - ", 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[async_std::test]
+    async fn test_search_index_concurrent_writes() {
+        // connections to the in-memory test database share a cache with table level locks
+        let dir = tempfile::tempdir().unwrap();
+        let pool = get_db_pool_with_path(&dir.path().join("test.db"))
+            .await
+            .unwrap();
+        let search_repository = SearchRepository::new(Arc::clone(&pool));
+        let software_title_repository = SoftwareTitleRepository::new(Arc::clone(&pool));
+        let release_repository = ReleaseRepository::new(Arc::clone(&pool));
+
+        let title_id = software_title_repository
+            .add_software_title(&"Turrican".to_string(), None)
+            .await
+            .unwrap();
+        let release_id = release_repository
+            .add_release("Turrican (Europe)")
+            .await
+            .unwrap();
+        release_repository
+            .add_software_title_to_release(release_id, title_id)
+            .await
+            .unwrap();
+
+        // statements failing on the foreign keys after the triggers have written to the index,
+        // while another connection is in use
+        let mut connection = pool.acquire().await.unwrap();
+        assert!(release_repository.delete_release(release_id).await.is_err());
+        assert!(software_title_repository
+            .add_software_title(&"Turrican II".to_string(), Some(1))
+            .await
+            .is_err());
+
+        // the other connections can still write to the index
+        sqlx::query("INSERT INTO release (name) VALUES ('Turrican (USA)')")
+            .execute(&mut *connection)
+            .await
+            .unwrap();
+        drop(connection);
+        let writers = (0..4)
+            .map(|i| {
+                let pool = Arc::clone(&pool);
+                async_std::task::spawn(async move {
+                    let software_title_repository = SoftwareTitleRepository::new(Arc::clone(&pool));
+                    let title_id = software_title_repository
+                        .add_software_title(&format!("Katakis {}", i), None)
+                        .await
+                        .unwrap();
+                    software_title_repository
+                        .add_alias(title_id, &format!("Denaris {}", i), None, None)
+                        .await
+                        .unwrap();
+                    ReleaseRepository::new(pool)
+                        .add_release(&format!("Katakis {} (Europe)", i))
+                        .await
+                        .unwrap();
+                })
+            })
+            .collect::<Vec<_>>();
+        async_std::future::timeout(std::time::Duration::from_secs(10), async {
+            for writer in writers {
+                writer.await;
+            }
+        })
+        .await
+        .expect("writes to the search index were blocked");
+
+        assert_eq!(
+            search_repository.search("katakis", 20).await.unwrap().len(),
+            8
+        );
+        assert_eq!(
+            search_repository.search("denaris", 20).await.unwrap().len(),
+            4
+        );
+        assert_eq!(
+            search_repository
+                .search("turrican", 20)
+                .await
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+
+    #[async_std::test]
+    async fn test_search_index_failed_file_set_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = get_db_pool_with_path(&dir.path().join("test.db"))
+            .await
+            .unwrap();
+        let search_repository = SearchRepository::new(Arc::clone(&pool));
+        let file_set_repository = FileSetRepository::new(Arc::clone(&pool));
+        let file_set_id = add_test_file_set(&pool, "Turrican.d64", FileType::DiskImage, 1).await;
+        // deleting the file set fails after its files have been deleted from the index
+        sqlx::query(
+            "CREATE TRIGGER file_set_delete_failure BEFORE DELETE ON file_set BEGIN
+                SELECT RAISE(ABORT, 'file set is locked');
+            END",
+        )
+        .execute(&*pool)
+        .await
+        .unwrap();
+
+        let mut connection = pool.acquire().await.unwrap();
+        assert!(file_set_repository
+            .delete_file_set(file_set_id)
+            .await
+            .is_err());
+
+        async_std::future::timeout(std::time::Duration::from_secs(10), async {
+            sqlx::query("INSERT INTO release (name) VALUES ('Turrican (USA)')")
+                .execute(&mut *connection)
+                .await
+                .unwrap();
+        })
+        .await
+        .expect("writes to the search index were blocked");
+        drop(connection);
+        assert_eq!(
+            search_repository
+                .search("turrican", 20)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
@@ -4,7 +4,8 @@ use sqlx::{Pool, Sqlite};
 
 use crate::{
     database_error::{DatabaseError, Error},
-    models::SoftwareTitle,
+    models::{SoftwareTitle, SoftwareTitleAlias, Tag},
+    repository::search_repository::execute_indexed_write,
 };
 
 #[derive(Debug)]
@@ -20,7 +21,7 @@ impl SoftwareTitleRepository {
     pub async fn get_software_title(&self, id: i64) -> Result<SoftwareTitle, DatabaseError> {
         let software_title = sqlx::query_as!(
             SoftwareTitle,
-            "SELECT id, name, franchise_id FROM software_title WHERE id = ?",
+            "SELECT id, name, franchise_id, sort_name FROM software_title WHERE id = ?",
             id
         )
         .fetch_one(&*self.pool)
@@ -32,7 +33,7 @@ impl SoftwareTitleRepository {
     pub async fn get_all_software_titles(&self) -> Result<Vec<SoftwareTitle>, DatabaseError> {
         let software_titles = sqlx::query_as!(
             SoftwareTitle,
-            "SELECT id, name, franchise_id FROM software_title"
+            "SELECT id, name, franchise_id, sort_name FROM software_title"
         )
         .fetch_all(&*self.pool)
         .await?;
@@ -45,7 +46,7 @@ impl SoftwareTitleRepository {
     ) -> Result<Vec<SoftwareTitle>, DatabaseError> {
         let software_titles = sqlx::query_as!(
             SoftwareTitle,
-            "SELECT st.id, st.name, st.franchise_id 
+            "SELECT st.id, st.name, st.franchise_id, st.sort_name 
              FROM software_title st
              INNER JOIN release_software_title rst ON st.id = rst.software_title_id
              WHERE rst.release_id = ?",
@@ -56,17 +57,55 @@ impl SoftwareTitleRepository {
         Ok(software_titles)
     }
 
+    pub async fn get_software_titles_by_franchise(
+        &self,
+        franchise_id: i64,
+    ) -> Result<Vec<SoftwareTitle>, DatabaseError> {
+        let software_titles = sqlx::query_as!(
+            SoftwareTitle,
+            "SELECT id, name, franchise_id, sort_name
+             FROM software_title
+             WHERE franchise_id = ?
+             ORDER BY name",
+            franchise_id
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        Ok(software_titles)
+    }
+
+    /// Returns the software titles the company developed.
+    pub async fn get_software_titles_by_developer(
+        &self,
+        company_id: i64,
+    ) -> Result<Vec<SoftwareTitle>, DatabaseError> {
+        let software_titles = sqlx::query_as!(
+            SoftwareTitle,
+            "SELECT st.id, st.name, st.franchise_id, st.sort_name
+             FROM software_title st
+             INNER JOIN software_title_company stc ON st.id = stc.software_title_id
+             WHERE stc.company_id = ?
+             ORDER BY st.name",
+            company_id
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        Ok(software_titles)
+    }
+
     pub async fn add_software_title(
         &self,
         name: &String,
         franchise_id: Option<i64>,
     ) -> Result<i64, Error> {
-        let result = sqlx::query!(
-            "INSERT INTO software_title (name, franchise_id) VALUES (?, ?)",
-            name,
-            franchise_id
+        let result = execute_indexed_write(
+            &self.pool,
+            sqlx::query!(
+                "INSERT INTO software_title (name, franchise_id) VALUES (?, ?)",
+                name,
+                franchise_id
+            ),
         )
-        .execute(&*self.pool)
         .await?;
         Ok(result.last_insert_rowid())
     }
@@ -75,24 +114,132 @@ impl SoftwareTitleRepository {
         &self,
         software_title: &SoftwareTitle,
     ) -> Result<i64, Error> {
-        sqlx::query!(
-            "UPDATE software_title SET name = ?, franchise_id = ? WHERE id = ?",
-            software_title.name,
-            software_title.franchise_id,
-            software_title.id
+        execute_indexed_write(
+            &self.pool,
+            sqlx::query!(
+                "UPDATE software_title SET name = ?, franchise_id = ?, sort_name = ? WHERE id = ?",
+                software_title.name,
+                software_title.franchise_id,
+                software_title.sort_name,
+                software_title.id
+            ),
         )
-        .execute(&*self.pool)
         .await?;
         Ok(software_title.id)
     }
 
+    /// Assigns the software title to the franchise, or removes it from its franchise.
+    pub async fn set_software_title_franchise(
+        &self,
+        id: i64,
+        franchise_id: Option<i64>,
+    ) -> Result<(), DatabaseError> {
+        execute_indexed_write(
+            &self.pool,
+            sqlx::query!(
+                "UPDATE software_title SET franchise_id = ? WHERE id = ?",
+                franchise_id,
+                id
+            ),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the software titles whose name, sort name or any alias contains the query,
+    /// ignoring case.
+    /// Returns the software titles whose name, sort name or an alias contains the query and that
+    /// are tagged with the tag or one of its subtags. Unset criteria match all titles.
+    pub async fn search_software_titles(
+        &self,
+        query: Option<&str>,
+        tag: Option<&str>,
+    ) -> Result<Vec<SoftwareTitle>, DatabaseError> {
+        let pattern = query.map(|query| format!("%{}%", query.trim()));
+        let tag = tag.and_then(Tag::normalize_name);
+        let software_titles = sqlx::query_as!(
+            SoftwareTitle,
+            "SELECT st.id, st.name, st.franchise_id, st.sort_name
+             FROM software_title st
+             WHERE (?1 IS NULL
+                OR st.name LIKE ?1
+                OR st.sort_name LIKE ?1
+                OR EXISTS (
+                    SELECT 1 FROM software_title_alias sta
+                    WHERE sta.software_title_id = st.id AND sta.name LIKE ?1
+                ))
+             AND (?2 IS NULL OR EXISTS (
+                SELECT 1
+                FROM software_title_tag stt
+                INNER JOIN tag t ON stt.tag_id = t.id
+                WHERE stt.software_title_id = st.id
+                    AND (t.name = ?2 OR substr(t.name, 1, length(?2) + 1) = ?2 || '/' COLLATE NOCASE)
+             ))",
+            pattern,
+            tag
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        Ok(software_titles)
+    }
+
+    pub async fn get_aliases(
+        &self,
+        software_title_id: i64,
+    ) -> Result<Vec<SoftwareTitleAlias>, DatabaseError> {
+        let aliases = sqlx::query_as!(
+            SoftwareTitleAlias,
+            "SELECT id, software_title_id, name, language, region
+             FROM software_title_alias
+             WHERE software_title_id = ?
+             ORDER BY name",
+            software_title_id
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        Ok(aliases)
+    }
+
+    pub async fn add_alias(
+        &self,
+        software_title_id: i64,
+        name: &str,
+        language: Option<&str>,
+        region: Option<&str>,
+    ) -> Result<i64, DatabaseError> {
+        let result = execute_indexed_write(
+            &self.pool,
+            sqlx::query!(
+                "INSERT INTO software_title_alias (software_title_id, name, language, region)
+                 VALUES (?, ?, ?, ?)",
+                software_title_id,
+                name,
+                language,
+                region
+            ),
+        )
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    pub async fn delete_alias(&self, id: i64) -> Result<(), DatabaseError> {
+        execute_indexed_write(
+            &self.pool,
+            sqlx::query!("DELETE FROM software_title_alias WHERE id = ?", id),
+        )
+        .await?;
+        Ok(())
+    }
+
     pub async fn delete_software_title(&self, id: i64) -> Result<i64, DatabaseError> {
         if self.is_software_title_in_use(id).await? {
             return Err(DatabaseError::InUse);
         }
-        sqlx::query!("DELETE FROM software_title WHERE id = ?", id)
-            .execute(&*self.pool)
-            .await?;
+        execute_indexed_write(
+            &self.pool,
+            sqlx::query!("DELETE FROM software_title WHERE id = ?", id),
+        )
+        .await?;
         Ok(id)
     }
 
@@ -137,6 +284,7 @@ mod tests {
             id: software_title_id,
             name: "Updated Software Title".to_string(),
             franchise_id: None,
+            sort_name: Some("Software Title, Updated".to_string()),
         };
         software_title_repository
             .update_software_title(&updated_software_title)
@@ -150,6 +298,10 @@ mod tests {
 
         assert_eq!(all_software_titles.len(), 1);
         assert_eq!(all_software_titles[0].name, "Updated Software Title");
+        assert_eq!(
+            all_software_titles[0].sort_name.as_deref(),
+            Some("Software Title, Updated")
+        );
 
         let is_in_use = software_title_repository
             .is_software_title_in_use(software_title_id)
@@ -181,4 +333,60 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[async_std::test]
+    async fn test_software_title_aliases() {
+        let pool = Arc::new(setup_test_db().await);
+        let software_title_repository = SoftwareTitleRepository::new(Arc::clone(&pool));
+        let contra_id = software_title_repository
+            .add_software_title(&"Contra".to_string(), None)
+            .await
+            .unwrap();
+        software_title_repository
+            .add_software_title(&"Gryzor".to_string(), None)
+            .await
+            .unwrap();
+        let alias_id = software_title_repository
+            .add_alias(contra_id, "Probotector", Some("en"), Some("Europe"))
+            .await
+            .unwrap();
+
+        let aliases = software_title_repository
+            .get_aliases(contra_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            aliases,
+            vec![SoftwareTitleAlias {
+                id: alias_id,
+                software_title_id: contra_id,
+                name: "Probotector".to_string(),
+                language: Some("en".to_string()),
+                region: Some("Europe".to_string()),
+            }]
+        );
+
+        let found = software_title_repository
+            .search_software_titles(Some("probo"), None)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, contra_id);
+
+        let found = software_title_repository
+            .search_software_titles(Some("CONTRA"), None)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+
+        software_title_repository
+            .delete_alias(alias_id)
+            .await
+            .unwrap();
+        let found = software_title_repository
+            .search_software_titles(Some("probo"), None)
+            .await
+            .unwrap();
+        assert!(found.is_empty());
+    }
 }
@@ -0,0 +1,545 @@
+use std::sync::Arc;
+
+use sqlx::{Pool, Sqlite, SqliteConnection};
+
+use crate::{
+    database_error::DatabaseError,
+    models::{Tag, TagTarget},
+};
+
+#[derive(Debug)]
+pub struct TagRepository {
+    pool: Arc<Pool<Sqlite>>,
+}
+
+impl TagRepository {
+    pub fn new(pool: Arc<Pool<Sqlite>>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_tag(&self, id: i64) -> Result<Tag, DatabaseError> {
+        let tag = sqlx::query_as!(Tag, "SELECT id, name FROM tag WHERE id = ?", id)
+            .fetch_one(&*self.pool)
+            .await?;
+        Ok(tag)
+    }
+
+    /// Returns all tags ordered by name, subtags follow their parent tag.
+    pub async fn get_all_tags(&self) -> Result<Vec<Tag>, DatabaseError> {
+        let tags = sqlx::query_as!(Tag, "SELECT id, name FROM tag ORDER BY name")
+            .fetch_all(&*self.pool)
+            .await?;
+        Ok(tags)
+    }
+
+    pub async fn get_tags(&self, target: TagTarget) -> Result<Vec<Tag>, DatabaseError> {
+        let tags = match target {
+            TagTarget::SoftwareTitle(id) => {
+                sqlx::query_as!(
+                    Tag,
+                    "SELECT t.id, t.name FROM tag t
+                     INNER JOIN software_title_tag stt ON t.id = stt.tag_id
+                     WHERE stt.software_title_id = ?
+                     ORDER BY t.name",
+                    id
+                )
+                .fetch_all(&*self.pool)
+                .await?
+            }
+            TagTarget::Release(id) => {
+                sqlx::query_as!(
+                    Tag,
+                    "SELECT t.id, t.name FROM tag t
+                     INNER JOIN release_tag rt ON t.id = rt.tag_id
+                     WHERE rt.release_id = ?
+                     ORDER BY t.name",
+                    id
+                )
+                .fetch_all(&*self.pool)
+                .await?
+            }
+            TagTarget::FileSet(id) => {
+                sqlx::query_as!(
+                    Tag,
+                    "SELECT t.id, t.name FROM tag t
+                     INNER JOIN file_set_tag fst ON t.id = fst.tag_id
+                     WHERE fst.file_set_id = ?
+                     ORDER BY t.name",
+                    id
+                )
+                .fetch_all(&*self.pool)
+                .await?
+            }
+        };
+        Ok(tags)
+    }
+
+    /// Tags the target, the tag is created if it doesn't exist. Returns the id of the tag.
+    pub async fn add_tag(&self, target: TagTarget, name: &str) -> Result<i64, DatabaseError> {
+        let name = normalize_name(name)?;
+        let mut transaction = self.pool.begin().await?;
+        let tag_id = get_or_add_tag(&mut transaction, &name).await?;
+        link_tag(&mut transaction, target, tag_id).await?;
+        transaction.commit().await?;
+        Ok(tag_id)
+    }
+
+    pub async fn remove_tag(&self, target: TagTarget, tag_id: i64) -> Result<(), DatabaseError> {
+        match target {
+            TagTarget::SoftwareTitle(id) => {
+                sqlx::query!(
+                    "DELETE FROM software_title_tag WHERE software_title_id = ? AND tag_id = ?",
+                    id,
+                    tag_id
+                )
+                .execute(&*self.pool)
+                .await?;
+            }
+            TagTarget::Release(id) => {
+                sqlx::query!(
+                    "DELETE FROM release_tag WHERE release_id = ? AND tag_id = ?",
+                    id,
+                    tag_id
+                )
+                .execute(&*self.pool)
+                .await?;
+            }
+            TagTarget::FileSet(id) => {
+                sqlx::query!(
+                    "DELETE FROM file_set_tag WHERE file_set_id = ? AND tag_id = ?",
+                    id,
+                    tag_id
+                )
+                .execute(&*self.pool)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Renames the tag and moves its subtags along, e.g. renaming `genre` to `genres` renames
+    /// `genre/shmup` to `genres/shmup`. Fails if a tag with the new name exists, those are merged
+    /// with `merge_tags`.
+    pub async fn rename_tag(&self, id: i64, name: &str) -> Result<(), DatabaseError> {
+        let name = normalize_name(name)?;
+        let mut transaction = self.pool.begin().await?;
+        let tag = sqlx::query_as!(Tag, "SELECT id, name FROM tag WHERE id = ?", id)
+            .fetch_one(&mut *transaction)
+            .await?;
+        if name
+            .to_lowercase()
+            .starts_with(&format!("{}/", tag.name.to_lowercase()))
+        {
+            return Err(DatabaseError::DbError(format!(
+                "Tag {} can't be moved under itself",
+                tag.name
+            )));
+        }
+        for (subtag_id, subtag_name) in get_subtags(&mut transaction, &tag.name).await? {
+            let subtag_name = format!("{}{}", name, &subtag_name[tag.name.len()..]);
+            sqlx::query!(
+                "UPDATE tag SET name = ? WHERE id = ?",
+                subtag_name,
+                subtag_id
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Moves everything tagged with the source tag to the target tag and deletes the source tag.
+    /// Subtags of the source are merged to the same subtags of the target.
+    pub async fn merge_tags(&self, source_id: i64, target_id: i64) -> Result<(), DatabaseError> {
+        if source_id == target_id {
+            return Err(DatabaseError::DbError(
+                "Tag can't be merged with itself".to_string(),
+            ));
+        }
+        let mut transaction = self.pool.begin().await?;
+        let source = sqlx::query_as!(Tag, "SELECT id, name FROM tag WHERE id = ?", source_id)
+            .fetch_one(&mut *transaction)
+            .await?;
+        let target = sqlx::query_as!(Tag, "SELECT id, name FROM tag WHERE id = ?", target_id)
+            .fetch_one(&mut *transaction)
+            .await?;
+        if target
+            .name
+            .to_lowercase()
+            .starts_with(&format!("{}/", source.name.to_lowercase()))
+        {
+            return Err(DatabaseError::DbError(format!(
+                "Tag {} can't be merged with its subtag",
+                source.name
+            )));
+        }
+        for (subtag_id, subtag_name) in get_subtags(&mut transaction, &source.name).await? {
+            let merged_name = format!("{}{}", target.name, &subtag_name[source.name.len()..]);
+            let merged_id = get_or_add_tag(&mut transaction, &merged_name).await?;
+            move_links(&mut transaction, subtag_id, merged_id).await?;
+            sqlx::query!("DELETE FROM tag WHERE id = ?", subtag_id)
+                .execute(&mut *transaction)
+                .await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Deletes the tag and its subtags, the tags are removed from everything tagged with them.
+    pub async fn delete_tag(&self, id: i64) -> Result<(), DatabaseError> {
+        let mut transaction = self.pool.begin().await?;
+        let tag = sqlx::query_as!(Tag, "SELECT id, name FROM tag WHERE id = ?", id)
+            .fetch_one(&mut *transaction)
+            .await?;
+        for (subtag_id, _) in get_subtags(&mut transaction, &tag.name).await? {
+            sqlx::query!("DELETE FROM software_title_tag WHERE tag_id = ?", subtag_id)
+                .execute(&mut *transaction)
+                .await?;
+            sqlx::query!("DELETE FROM release_tag WHERE tag_id = ?", subtag_id)
+                .execute(&mut *transaction)
+                .await?;
+            sqlx::query!("DELETE FROM file_set_tag WHERE tag_id = ?", subtag_id)
+                .execute(&mut *transaction)
+                .await?;
+            sqlx::query!("DELETE FROM tag WHERE id = ?", subtag_id)
+                .execute(&mut *transaction)
+                .await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+}
+
+fn normalize_name(name: &str) -> Result<String, DatabaseError> {
+    Tag::normalize_name(name)
+        .ok_or_else(|| DatabaseError::DbError("Tag name must not be empty".to_string()))
+}
+
+/// Returns the tag and its subtags, the tag first.
+async fn get_subtags(
+    connection: &mut SqliteConnection,
+    name: &str,
+) -> Result<Vec<(i64, String)>, DatabaseError> {
+    let rows = sqlx::query!(
+        "SELECT id, name FROM tag
+         WHERE name = ?1 OR substr(name, 1, length(?1) + 1) = ?1 || '/' COLLATE NOCASE
+         ORDER BY length(name)",
+        name
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+    Ok(rows.into_iter().map(|row| (row.id, row.name)).collect())
+}
+
+async fn get_or_add_tag(
+    connection: &mut SqliteConnection,
+    name: &str,
+) -> Result<i64, DatabaseError> {
+    sqlx::query!("INSERT OR IGNORE INTO tag (name) VALUES (?)", name)
+        .execute(&mut *connection)
+        .await?;
+    let id = sqlx::query_scalar!("SELECT id FROM tag WHERE name = ?", name)
+        .fetch_one(&mut *connection)
+        .await?;
+    Ok(id)
+}
+
+async fn link_tag(
+    connection: &mut SqliteConnection,
+    target: TagTarget,
+    tag_id: i64,
+) -> Result<(), DatabaseError> {
+    match target {
+        TagTarget::SoftwareTitle(id) => {
+            sqlx::query!(
+                "INSERT OR IGNORE INTO software_title_tag (software_title_id, tag_id)
+                 VALUES (?, ?)",
+                id,
+                tag_id
+            )
+            .execute(&mut *connection)
+            .await?;
+        }
+        TagTarget::Release(id) => {
+            sqlx::query!(
+                "INSERT OR IGNORE INTO release_tag (release_id, tag_id) VALUES (?, ?)",
+                id,
+                tag_id
+            )
+            .execute(&mut *connection)
+            .await?;
+        }
+        TagTarget::FileSet(id) => {
+            sqlx::query!(
+                "INSERT OR IGNORE INTO file_set_tag (file_set_id, tag_id) VALUES (?, ?)",
+                id,
+                tag_id
+            )
+            .execute(&mut *connection)
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Tags everything tagged with the source tag with the target tag and removes the source tag.
+async fn move_links(
+    connection: &mut SqliteConnection,
+    source_id: i64,
+    target_id: i64,
+) -> Result<(), DatabaseError> {
+    sqlx::query!(
+        "INSERT OR IGNORE INTO software_title_tag (software_title_id, tag_id)
+         SELECT software_title_id, ? FROM software_title_tag WHERE tag_id = ?",
+        target_id,
+        source_id
+    )
+    .execute(&mut *connection)
+    .await?;
+    sqlx::query!(
+        "INSERT OR IGNORE INTO release_tag (release_id, tag_id)
+         SELECT release_id, ? FROM release_tag WHERE tag_id = ?",
+        target_id,
+        source_id
+    )
+    .execute(&mut *connection)
+    .await?;
+    sqlx::query!(
+        "INSERT OR IGNORE INTO file_set_tag (file_set_id, tag_id)
+         SELECT file_set_id, ? FROM file_set_tag WHERE tag_id = ?",
+        target_id,
+        source_id
+    )
+    .execute(&mut *connection)
+    .await?;
+    sqlx::query!("DELETE FROM software_title_tag WHERE tag_id = ?", source_id)
+        .execute(&mut *connection)
+        .await?;
+    sqlx::query!("DELETE FROM release_tag WHERE tag_id = ?", source_id)
+        .execute(&mut *connection)
+        .await?;
+    sqlx::query!("DELETE FROM file_set_tag WHERE tag_id = ?", source_id)
+        .execute(&mut *connection)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::{ReleaseListQuery, ReleaseMetadataFilter},
+        repository::{
+            file_set_repository::FileSetRepository, release_repository::ReleaseRepository,
+            software_title_repository::SoftwareTitleRepository,
+            system_repository::SystemRepository,
+        },
+        setup_test_db,
+    };
+
+    fn tag_names(tags: &[Tag]) -> Vec<&str> {
+        tags.iter().map(|tag| tag.name.as_str()).collect()
+    }
+
+    #[async_std::test]
+    async fn test_tag_repository() {
+        let pool = Arc::new(setup_test_db().await);
+        let tag_repository = TagRepository::new(Arc::clone(&pool));
+        let software_title_repository = SoftwareTitleRepository::new(Arc::clone(&pool));
+        let release_repository = ReleaseRepository::new(Arc::clone(&pool));
+
+        let title_id = software_title_repository
+            .add_software_title(&"R-Type".to_string(), None)
+            .await
+            .unwrap();
+        let release_id = release_repository.add_release("R-Type").await.unwrap();
+
+        let shmup_id = tag_repository
+            .add_tag(TagTarget::SoftwareTitle(title_id), " genre / shmup ")
+            .await
+            .unwrap();
+        tag_repository
+            .add_tag(TagTarget::Release(release_id), "needs review")
+            .await
+            .unwrap();
+        let genre_id = tag_repository
+            .add_tag(TagTarget::Release(release_id), "Genre")
+            .await
+            .unwrap();
+        assert_eq!(
+            tag_repository
+                .get_tags(TagTarget::SoftwareTitle(title_id))
+                .await
+                .unwrap(),
+            vec![Tag {
+                id: shmup_id,
+                name: "genre/shmup".to_string()
+            }]
+        );
+        assert!(tag_repository
+            .add_tag(TagTarget::Release(release_id), " / ")
+            .await
+            .is_err());
+
+        // subtags are renamed along
+        tag_repository.rename_tag(genre_id, "genres").await.unwrap();
+        let tags = tag_repository.get_all_tags().await.unwrap();
+        assert_eq!(
+            tag_names(&tags),
+            vec!["genres", "genres/shmup", "needs review"]
+        );
+        assert!(tag_repository
+            .rename_tag(genre_id, "genres/shmup/more")
+            .await
+            .is_err());
+
+        // merging moves the tagged entities and the subtags
+        let shooter_id = tag_repository
+            .add_tag(TagTarget::Release(release_id), "shooter")
+            .await
+            .unwrap();
+        tag_repository
+            .merge_tags(genre_id, shooter_id)
+            .await
+            .unwrap();
+        let tags = tag_repository.get_all_tags().await.unwrap();
+        assert_eq!(
+            tag_names(&tags),
+            vec!["needs review", "shooter", "shooter/shmup"]
+        );
+        assert_eq!(
+            tag_names(
+                &tag_repository
+                    .get_tags(TagTarget::SoftwareTitle(title_id))
+                    .await
+                    .unwrap()
+            ),
+            vec!["shooter/shmup"]
+        );
+        assert_eq!(
+            tag_names(
+                &tag_repository
+                    .get_tags(TagTarget::Release(release_id))
+                    .await
+                    .unwrap()
+            ),
+            vec!["needs review", "shooter"]
+        );
+
+        tag_repository.delete_tag(shooter_id).await.unwrap();
+        let tags = tag_repository.get_all_tags().await.unwrap();
+        assert_eq!(tag_names(&tags), vec!["needs review"]);
+        assert!(tag_repository
+            .get_tags(TagTarget::SoftwareTitle(title_id))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[async_std::test]
+    async fn test_filter_releases_by_tag() {
+        let pool = Arc::new(setup_test_db().await);
+        let tag_repository = TagRepository::new(Arc::clone(&pool));
+        let release_repository = ReleaseRepository::new(Arc::clone(&pool));
+        let system_id = SystemRepository::new(Arc::clone(&pool))
+            .add_system(&"Commodore 64".to_string())
+            .await
+            .unwrap();
+        let file_set_repository = FileSetRepository::new(Arc::clone(&pool));
+        let software_title_repository = SoftwareTitleRepository::new(Arc::clone(&pool));
+
+        let mut ids = vec![];
+        for (i, name) in ["Delta", "Bubble Bobble", "Paradroid"].iter().enumerate() {
+            let title_id = software_title_repository
+                .add_software_title(&name.to_string(), None)
+                .await
+                .unwrap();
+            let file_set_id = file_set_repository
+                .add_file_set(
+                    name.to_string(),
+                    crate::models::FileType::DiskImage,
+                    vec![core_types::ImportedFile {
+                        original_file_name: format!("{}.d64", name),
+                        archive_file_name: format!("archive_{}", i),
+                        sha1_checksum: [i as u8; 20],
+                        file_size: 1,
+                    }],
+                    &[system_id],
+                )
+                .await
+                .unwrap();
+            let release_id = release_repository
+                .add_release_full(
+                    name.to_string(),
+                    vec![title_id],
+                    vec![file_set_id],
+                    vec![system_id],
+                )
+                .await
+                .unwrap();
+            ids.push((title_id, release_id, file_set_id));
+        }
+        tag_repository
+            .add_tag(TagTarget::SoftwareTitle(ids[0].0), "genre/shmup")
+            .await
+            .unwrap();
+        tag_repository
+            .add_tag(TagTarget::Release(ids[1].1), "multiplayer")
+            .await
+            .unwrap();
+        tag_repository
+            .add_tag(TagTarget::FileSet(ids[2].2), "cracked by Fairlight")
+            .await
+            .unwrap();
+        tag_repository
+            .add_tag(TagTarget::Release(ids[2].1), "genres")
+            .await
+            .unwrap();
+
+        let release_names = |tag: &'static str| {
+            let release_repository = &release_repository;
+            async move {
+                release_repository
+                    .get_releases(&ReleaseListQuery {
+                        metadata: ReleaseMetadataFilter {
+                            tag: Some(tag.to_string()),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|release| release.name)
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(release_names("Genre").await, vec!["Delta".to_string()]);
+        assert_eq!(
+            release_names("genre/shmup").await,
+            vec!["Delta".to_string()]
+        );
+        assert_eq!(
+            release_names("multiplayer").await,
+            vec!["Bubble Bobble".to_string()]
+        );
+        assert_eq!(
+            release_names("cracked by fairlight").await,
+            vec!["Paradroid".to_string()]
+        );
+        assert!(release_names("genre/platformer").await.is_empty());
+
+        let software_titles = software_title_repository
+            .search_software_titles(None, Some("genre"))
+            .await
+            .unwrap();
+        assert_eq!(software_titles.len(), 1);
+        assert_eq!(software_titles[0].id, ids[0].0);
+        assert!(software_title_repository
+            .search_software_titles(Some("Delta"), Some("multiplayer"))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -3,10 +3,13 @@ use std::sync::Arc;
 use sqlx::{Pool, Sqlite};
 
 use crate::repository::{
-    emulator_repository::EmulatorRepository, file_info_repository::FileInfoRepository,
-    file_set_repository::FileSetRepository, franchise_repository::FranchiseRepository,
-    release_repository::ReleaseRepository, setting_repository::SettingRepository,
+    collection_repository::CollectionRepository, company_repository::CompanyRepository,
+    compatibility_repository::CompatibilityRepository, emulator_repository::EmulatorRepository,
+    file_info_repository::FileInfoRepository, file_set_repository::FileSetRepository,
+    franchise_repository::FranchiseRepository, release_repository::ReleaseRepository,
+    search_repository::SearchRepository, setting_repository::SettingRepository,
     software_title_repository::SoftwareTitleRepository, system_repository::SystemRepository,
+    tag_repository::TagRepository,
 };
 
 #[derive(Debug)]
@@ -19,6 +22,11 @@ pub struct RepositoryManager {
     release_repository: ReleaseRepository,
     software_title_repository: SoftwareTitleRepository,
     setting_repository: SettingRepository,
+    compatibility_repository: CompatibilityRepository,
+    company_repository: CompanyRepository,
+    tag_repository: TagRepository,
+    collection_repository: CollectionRepository,
+    search_repository: SearchRepository,
 }
 
 impl RepositoryManager {
@@ -31,6 +39,11 @@ impl RepositoryManager {
         let release_repository = ReleaseRepository::new(pool.clone());
         let software_title_repository = SoftwareTitleRepository::new(pool.clone());
         let setting_repository = SettingRepository::new(pool.clone());
+        let compatibility_repository = CompatibilityRepository::new(pool.clone());
+        let company_repository = CompanyRepository::new(pool.clone());
+        let tag_repository = TagRepository::new(pool.clone());
+        let collection_repository = CollectionRepository::new(pool.clone());
+        let search_repository = SearchRepository::new(pool.clone());
 
         Self {
             file_info_repository,
@@ -41,6 +54,11 @@ impl RepositoryManager {
             release_repository,
             software_title_repository,
             setting_repository,
+            compatibility_repository,
+            company_repository,
+            tag_repository,
+            collection_repository,
+            search_repository,
         }
     }
 
@@ -75,4 +93,24 @@ impl RepositoryManager {
     pub fn settings(&self) -> &SettingRepository {
         &self.setting_repository
     }
+
+    pub fn get_compatibility_repository(&self) -> &CompatibilityRepository {
+        &self.compatibility_repository
+    }
+
+    pub fn get_company_repository(&self) -> &CompanyRepository {
+        &self.company_repository
+    }
+
+    pub fn get_tag_repository(&self) -> &TagRepository {
+        &self.tag_repository
+    }
+
+    pub fn get_collection_repository(&self) -> &CollectionRepository {
+        &self.collection_repository
+    }
+
+    pub fn get_search_repository(&self) -> &SearchRepository {
+        &self.search_repository
+    }
 }
@@ -0,0 +1,593 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use core_types::FileType as CoreFileType;
+use sqlx::{QueryBuilder, Sqlite};
+use strum::IntoEnumIterator;
+
+use crate::{
+    models::{FileType, Tag},
+    release_query::{push_file_type_condition, push_tag_condition},
+    repository::search_repository::match_expression,
+};
+
+/// Release search query of terms separated by spaces, a release has to match all the terms:
+///
+/// - words and quoted phrases match the names of the release, its software titles and their
+///   aliases and its files the same way as the full-text search
+/// - `system:<name>` matches releases for the system
+/// - `type:<file type>` matches releases with a file set of the type, the first part of the type
+///   is enough, e.g. `type:disk` for `disk_image`
+/// - `year:<year>` matches the release year, `<`, `<=`, `>` or `>=` before the year compares it
+/// - `tag:<tag>` matches releases tagged with the tag or its subtags
+/// - `region:<region>` and `language:<language>` match the release metadata
+/// - `has:<file type>`, `has:title`, `has:system`, `has:tag` and `has:date` match releases that
+///   have them
+///
+/// Values with spaces are quoted, e.g. `system:"Commodore 64"`, and a leading `-` negates a
+/// term, e.g. `-has:manual`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchQuery {
+    terms: Vec<SearchTerm>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct SearchTerm {
+    negated: bool,
+    filter: SearchFilter,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum SearchFilter {
+    /// FTS5 match expression of the words.
+    Text(String),
+    System(String),
+    FileType(Vec<FileType>),
+    Year(Comparison, i64),
+    /// Normalized tag name.
+    Tag(String),
+    Region(String),
+    Language(String),
+    HasSoftwareTitle,
+    HasSystem,
+    HasTag,
+    HasReleaseDate,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Comparison {
+    Equal,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    fn operator(&self) -> &'static str {
+        match self {
+            Comparison::Equal => " = ",
+            Comparison::Less => " < ",
+            Comparison::LessOrEqual => " <= ",
+            Comparison::Greater => " > ",
+            Comparison::GreaterOrEqual => " >= ",
+        }
+    }
+}
+
+/// Syntax error in a search query.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchQueryError {
+    /// Zero based character position of the error in the query.
+    pub position: usize,
+    pub message: String,
+}
+
+impl Display for SearchQueryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for SearchQueryError {}
+
+impl FromStr for SearchQuery {
+    type Err = SearchQueryError;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        Parser {
+            chars: query.chars().collect(),
+            position: 0,
+        }
+        .parse()
+    }
+}
+
+impl SearchQuery {
+    /// Builds the query of the matching releases, selects the id and the name of the releases
+    /// ordered by name.
+    pub(crate) fn build_release_query(&self) -> QueryBuilder<'static, Sqlite> {
+        let mut query_builder =
+            QueryBuilder::<Sqlite>::new("SELECT r.id, r.name FROM release r WHERE 1 = 1");
+        for term in &self.terms {
+            if term.negated {
+                query_builder.push(" AND NOT (");
+            } else {
+                query_builder.push(" AND (");
+            }
+            push_filter_condition(&mut query_builder, &term.filter);
+            query_builder.push(")");
+        }
+        query_builder.push(" ORDER BY r.name");
+        query_builder
+    }
+}
+
+fn push_filter_condition(query_builder: &mut QueryBuilder<'static, Sqlite>, filter: &SearchFilter) {
+    match filter {
+        SearchFilter::Text(match_expression) => {
+            // aliases are matched through their software titles
+            query_builder
+                .push(
+                    "r.id IN (
+                        WITH matched AS (
+                            SELECT se.kind, se.entity_id FROM search_index
+                            INNER JOIN search_entry se ON se.id = search_index.rowid
+                            WHERE search_index MATCH ",
+                )
+                .push_bind(match_expression.clone())
+                .push(
+                    ")
+                        SELECT entity_id FROM matched WHERE kind = 3
+                        UNION
+                        SELECT rst.release_id FROM matched m
+                        INNER JOIN release_software_title rst
+                            ON m.kind = 1 AND rst.software_title_id = m.entity_id
+                        UNION
+                        SELECT rst.release_id FROM matched m
+                        INNER JOIN software_title_alias sta ON m.kind = 2 AND sta.id = m.entity_id
+                        INNER JOIN release_software_title rst
+                            ON rst.software_title_id = sta.software_title_id
+                        UNION
+                        SELECT rfs.release_id FROM matched m
+                        INNER JOIN release_file_set rfs
+                            ON m.kind = 4 AND rfs.file_set_id = m.entity_id
+                    )",
+                );
+        }
+        SearchFilter::System(name) => {
+            query_builder
+                .push(
+                    "EXISTS (SELECT 1 FROM release_system rs
+                        INNER JOIN system s ON rs.system_id = s.id
+                        WHERE rs.release_id = r.id AND s.name = ",
+                )
+                .push_bind(name.clone())
+                .push(" COLLATE NOCASE)");
+        }
+        SearchFilter::FileType(file_types) => push_file_type_condition(query_builder, file_types),
+        SearchFilter::Year(comparison, year) => {
+            query_builder
+                .push(
+                    "r.release_date IS NOT NULL
+                        AND CAST(substr(r.release_date, 1, 4) AS INTEGER)",
+                )
+                .push(comparison.operator())
+                .push_bind(*year);
+        }
+        SearchFilter::Tag(tag) => push_tag_condition(query_builder, tag.clone()),
+        SearchFilter::Region(region) => {
+            query_builder
+                .push(
+                    "EXISTS (SELECT 1 FROM release_region rr
+                        WHERE rr.release_id = r.id AND rr.region = ",
+                )
+                .push_bind(region.clone())
+                .push(" COLLATE NOCASE)");
+        }
+        SearchFilter::Language(language) => {
+            query_builder
+                .push(
+                    "EXISTS (SELECT 1 FROM release_language rl
+                        WHERE rl.release_id = r.id AND rl.language = ",
+                )
+                .push_bind(language.clone())
+                .push(" COLLATE NOCASE)");
+        }
+        SearchFilter::HasSoftwareTitle => {
+            query_builder
+                .push("EXISTS (SELECT 1 FROM release_software_title WHERE release_id = r.id)");
+        }
+        SearchFilter::HasSystem => {
+            query_builder.push("EXISTS (SELECT 1 FROM release_system WHERE release_id = r.id)");
+        }
+        SearchFilter::HasTag => {
+            query_builder.push(
+                "EXISTS (SELECT 1 FROM release_tag WHERE release_id = r.id)
+                OR EXISTS (
+                    SELECT 1 FROM software_title_tag stt
+                    INNER JOIN release_software_title rst
+                        ON stt.software_title_id = rst.software_title_id
+                    WHERE rst.release_id = r.id)
+                OR EXISTS (
+                    SELECT 1 FROM file_set_tag fst
+                    INNER JOIN release_file_set rfs ON fst.file_set_id = rfs.file_set_id
+                    WHERE rfs.release_id = r.id)",
+            );
+        }
+        SearchFilter::HasReleaseDate => {
+            query_builder.push("r.release_date IS NOT NULL");
+        }
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn parse(mut self) -> Result<SearchQuery, SearchQueryError> {
+        let mut terms = vec![];
+        loop {
+            while self.peek().is_some_and(char::is_whitespace) {
+                self.position += 1;
+            }
+            if self.peek().is_none() {
+                return Ok(SearchQuery { terms });
+            }
+            terms.push(self.parse_term()?);
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<SearchTerm, SearchQueryError> {
+        let negated = self.peek() == Some('-');
+        if negated {
+            self.position += 1;
+            if self.peek().is_none_or(char::is_whitespace) {
+                return Err(error(self.position - 1, "Expected a term after '-'"));
+            }
+        }
+
+        let start = self.position;
+        if self.peek() == Some('"') {
+            let text = self.parse_quoted()?;
+            return Ok(SearchTerm {
+                negated,
+                filter: text_filter(&text, start)?,
+            });
+        }
+        let word = self.take_while(|c| !c.is_whitespace() && c != ':');
+        if self.peek() != Some(':') {
+            return Ok(SearchTerm {
+                negated,
+                filter: text_filter(&word, start)?,
+            });
+        }
+        self.position += 1;
+
+        let value_start = self.position;
+        let value = if self.peek() == Some('"') {
+            self.parse_quoted()?
+        } else {
+            self.take_while(|c| !c.is_whitespace())
+        };
+        if value.trim().is_empty() {
+            return Err(error(
+                value_start,
+                format!("Expected a value after '{}:'", word),
+            ));
+        }
+        Ok(SearchTerm {
+            negated,
+            filter: key_filter(&word, start, value.trim(), value_start)?,
+        })
+    }
+
+    /// Parses a phrase in double quotes, a backslash escapes the next character.
+    fn parse_quoted(&mut self) -> Result<String, SearchQueryError> {
+        let start = self.position;
+        self.position += 1;
+        let mut text = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(error(start, "Unterminated quote")),
+                Some('"') => break,
+                Some('\\') if self.position + 1 < self.chars.len() => {
+                    text.push(self.chars[self.position + 1]);
+                    self.position += 2;
+                }
+                Some(c) => {
+                    text.push(c);
+                    self.position += 1;
+                }
+            }
+        }
+        self.position += 1;
+        if self.peek().is_some_and(|c| !c.is_whitespace()) {
+            return Err(error(
+                self.position,
+                "Expected a space after the closing quote",
+            ));
+        }
+        Ok(text)
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let start = self.position;
+        while self.peek().is_some_and(&predicate) {
+            self.position += 1;
+        }
+        self.chars[start..self.position].iter().collect()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+}
+
+fn error(position: usize, message: impl Into<String>) -> SearchQueryError {
+    SearchQueryError {
+        position,
+        message: message.into(),
+    }
+}
+
+fn text_filter(text: &str, position: usize) -> Result<SearchFilter, SearchQueryError> {
+    match_expression(text)
+        .map(SearchFilter::Text)
+        .ok_or_else(|| error(position, "Expected a word to search for"))
+}
+
+fn key_filter(
+    key: &str,
+    key_position: usize,
+    value: &str,
+    value_position: usize,
+) -> Result<SearchFilter, SearchQueryError> {
+    match key.to_lowercase().as_str() {
+        "system" => Ok(SearchFilter::System(value.to_string())),
+        "type" => parse_file_types(value, value_position).map(SearchFilter::FileType),
+        "year" => parse_year(value, value_position),
+        "tag" => Tag::normalize_name(value)
+            .map(SearchFilter::Tag)
+            .ok_or_else(|| error(value_position, "Expected a tag")),
+        "region" => Ok(SearchFilter::Region(value.to_string())),
+        "language" => Ok(SearchFilter::Language(value.to_string())),
+        "has" => match value.to_lowercase().as_str() {
+            "title" => Ok(SearchFilter::HasSoftwareTitle),
+            "system" => Ok(SearchFilter::HasSystem),
+            "tag" => Ok(SearchFilter::HasTag),
+            "date" => Ok(SearchFilter::HasReleaseDate),
+            _ => parse_file_types(value, value_position).map(SearchFilter::FileType),
+        },
+        _ => Err(error(
+            key_position,
+            format!(
+                "Unknown key '{}', expected one of system, type, year, tag, region, language \
+                 or has",
+                key
+            ),
+        )),
+    }
+}
+
+/// The whole file type name or its first part, e.g. `disk` for `disk_image`.
+fn parse_file_types(value: &str, position: usize) -> Result<Vec<FileType>, SearchQueryError> {
+    let value = value.to_lowercase();
+    let file_types = CoreFileType::iter().map(FileType::from).collect::<Vec<_>>();
+    if let Some(file_type) = file_types
+        .iter()
+        .find(|file_type| file_type.dir_name() == value)
+    {
+        return Ok(vec![*file_type]);
+    }
+    let prefix = format!("{}_", value);
+    let matching_file_types = file_types
+        .iter()
+        .filter(|file_type| file_type.dir_name().starts_with(&prefix))
+        .copied()
+        .collect::<Vec<_>>();
+    if matching_file_types.is_empty() {
+        let names = file_types
+            .iter()
+            .map(|file_type| file_type.dir_name())
+            .collect::<Vec<_>>();
+        return Err(error(
+            position,
+            format!(
+                "Unknown file type '{}', expected one of {}",
+                value,
+                names.join(", ")
+            ),
+        ));
+    }
+    Ok(matching_file_types)
+}
+
+fn parse_year(value: &str, position: usize) -> Result<SearchFilter, SearchQueryError> {
+    let (comparison, year) = if let Some(year) = value.strip_prefix("<=") {
+        (Comparison::LessOrEqual, year)
+    } else if let Some(year) = value.strip_prefix(">=") {
+        (Comparison::GreaterOrEqual, year)
+    } else if let Some(year) = value.strip_prefix('<') {
+        (Comparison::Less, year)
+    } else if let Some(year) = value.strip_prefix('>') {
+        (Comparison::Greater, year)
+    } else {
+        (Comparison::Equal, value.strip_prefix('=').unwrap_or(value))
+    };
+    let year_position = position + value.chars().count() - year.chars().count();
+    if year.len() != 4 || !year.chars().all(|c| c.is_ascii_digit()) {
+        return Err(error(year_position, "Expected a four digit year"));
+    }
+    Ok(SearchFilter::Year(
+        comparison,
+        year.parse().unwrap_or_default(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        add_test_file_set, fetch_release_ids,
+        models::{ReleaseMetadata, TagTarget},
+        repository::{
+            release_repository::ReleaseRepository,
+            software_title_repository::SoftwareTitleRepository,
+            system_repository::SystemRepository, tag_repository::TagRepository,
+        },
+        setup_test_db,
+    };
+
+    #[test]
+    fn test_parse_search_query() {
+        let query: SearchQuery = r#"system:"Commodore 64" type:disk year:<1990 -has:manual"#
+            .parse()
+            .unwrap();
+        assert_eq!(
+            query.terms,
+            vec![
+                SearchTerm {
+                    negated: false,
+                    filter: SearchFilter::System("Commodore 64".to_string()),
+                },
+                SearchTerm {
+                    negated: false,
+                    filter: SearchFilter::FileType(vec![FileType::DiskImage]),
+                },
+                SearchTerm {
+                    negated: false,
+                    filter: SearchFilter::Year(Comparison::Less, 1990),
+                },
+                SearchTerm {
+                    negated: true,
+                    filter: SearchFilter::FileType(vec![FileType::Manual]),
+                },
+            ]
+        );
+        let query: SearchQuery = r#"  Tag:" Genre / Shmup " "say \"hi\"" "#.parse().unwrap();
+        assert_eq!(
+            query.terms,
+            vec![
+                SearchTerm {
+                    negated: false,
+                    filter: SearchFilter::Tag("Genre/Shmup".to_string()),
+                },
+                SearchTerm {
+                    negated: false,
+                    filter: SearchFilter::Text(r#""say"* """hi"""*"#.to_string()),
+                },
+            ]
+        );
+        assert!("".parse::<SearchQuery>().unwrap().terms.is_empty());
+
+        let error_position = |query: &str| query.parse::<SearchQuery>().unwrap_err().position;
+        assert_eq!(error_position("type:disk genre:shmup"), 10);
+        assert_eq!(error_position("type:floppy"), 5);
+        assert_eq!(error_position("year:<=90"), 7);
+        assert_eq!(error_position("zak system:"), 11);
+        assert_eq!(error_position(r#"system:"C64"#), 7);
+        assert_eq!(error_position(r#""zak"mckracken"#), 5);
+        assert_eq!(error_position("zak - mckracken"), 4);
+        assert_eq!(error_position("tag:/"), 4);
+        assert_eq!(
+            "year:<=90".parse::<SearchQuery>().unwrap_err().to_string(),
+            "Expected a four digit year at position 7"
+        );
+    }
+
+    #[async_std::test]
+    async fn test_search_query() {
+        let pool = Arc::new(setup_test_db().await);
+        let release_repository = ReleaseRepository::new(Arc::clone(&pool));
+        let c64_id = SystemRepository::new(Arc::clone(&pool))
+            .add_system(&"C64".to_string())
+            .await
+            .unwrap();
+
+        let software_title_repository = SoftwareTitleRepository::new(Arc::clone(&pool));
+        let katakis_title_id = software_title_repository
+            .add_software_title(&"Katakis".to_string(), None)
+            .await
+            .unwrap();
+        software_title_repository
+            .add_alias(katakis_title_id, "Denaris", None, None)
+            .await
+            .unwrap();
+        let disk_id = add_test_file_set(&pool, "katakis.d64", FileType::DiskImage, 1).await;
+        let katakis_id = release_repository
+            .add_release_full(
+                "Katakis (Rainbow Arts)".to_string(),
+                vec![katakis_title_id],
+                vec![disk_id],
+                vec![c64_id],
+            )
+            .await
+            .unwrap();
+        let disk_id = add_test_file_set(&pool, "armalyte.d64", FileType::DiskImage, 2).await;
+        let manual_id = add_test_file_set(&pool, "armalyte manual.pdf", FileType::Manual, 3).await;
+        let armalyte_id = release_repository
+            .add_release_full(
+                "Armalyte".to_string(),
+                vec![],
+                vec![disk_id, manual_id],
+                vec![c64_id],
+            )
+            .await
+            .unwrap();
+        let incomplete_id = release_repository.add_release("Untitled").await.unwrap();
+        for (release_id, year) in [(katakis_id, "1988"), (armalyte_id, "1988-10")] {
+            release_repository
+                .update_release_metadata(
+                    release_id,
+                    &ReleaseMetadata {
+                        release_date: year.parse().ok(),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+        }
+        let tag_repository = TagRepository::new(Arc::clone(&pool));
+        tag_repository
+            .add_tag(TagTarget::SoftwareTitle(katakis_title_id), "shmup")
+            .await
+            .unwrap();
+        tag_repository
+            .add_tag(TagTarget::Release(armalyte_id), "shmup/horizontal")
+            .await
+            .unwrap();
+
+        let release_ids = |query: &str| {
+            let query = query.parse::<SearchQuery>().unwrap();
+            fetch_release_ids(&pool, query.build_release_query())
+        };
+
+        assert_eq!(
+            release_ids(r#"system:"C64" type:disk year:<1990 tag:shmup -has:manual"#).await,
+            vec![katakis_id]
+        );
+        assert_eq!(
+            release_ids("tag:shmup year:1988").await,
+            vec![armalyte_id, katakis_id]
+        );
+        assert_eq!(release_ids("denaris").await, vec![katakis_id]);
+        assert_eq!(release_ids("manual.pdf").await, vec![armalyte_id]);
+        assert_eq!(release_ids("-rainbow has:tag").await, vec![armalyte_id]);
+        assert_eq!(
+            release_ids("-has:title -has:date").await,
+            vec![incomplete_id]
+        );
+        assert_eq!(release_ids("-year:>=1988").await, vec![incomplete_id]);
+        assert_eq!(
+            release_ids("").await,
+            vec![armalyte_id, katakis_id, incomplete_id]
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{config_template::render_placeholders, error::EmulatorRunnerError};
+
+/// Splits emulator arguments into argv words.
+///
+/// Words are separated by whitespace, text within single or double quotes is kept in one word
+/// and the quotes are removed, e.g. `-cfg "my game.cfg"` is `-cfg` and `my game.cfg`.
+/// Backslashes are not escapes, so that Windows paths run with Wine are written as they are.
+///
+/// # errors
+/// * `EmulatorRunnerError::InvalidTemplate`: If a quote is not closed.
+pub fn split_arguments(arguments: &str) -> Result<Vec<String>, EmulatorRunnerError> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote: Option<char> = None;
+    for c in arguments.chars() {
+        match (quote, c) {
+            (Some(open_quote), c) if c == open_quote => quote = None,
+            (Some(_), c) => word.get_or_insert_with(String::new).push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                // empty quotes are an empty word
+                word.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err(EmulatorRunnerError::InvalidTemplate(format!(
+            "Unclosed quote: {}",
+            arguments
+        )));
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Splits the argument template into argv words and replaces the placeholders in each word, so
+/// that a value with spaces, e.g. a path, stays in one word.
+///
+/// # errors
+/// * `EmulatorRunnerError::InvalidTemplate`: If a quote or a placeholder is not closed.
+/// * Errors returned by `resolve`.
+pub(crate) fn render_argument_template(
+    template: &str,
+    resolve: impl Fn(&str) -> Result<String, EmulatorRunnerError>,
+) -> Result<Vec<String>, EmulatorRunnerError> {
+    split_arguments(template)?
+        .iter()
+        .map(|word| render_placeholders(word, &resolve))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_arguments() {
+        assert_eq!(
+            split_arguments("  -autostart  -cfg \"my game.cfg\" '' -title='A B'").unwrap(),
+            vec!["-autostart", "-cfg", "my game.cfg", "", "-title=A B"]
+        );
+        assert_eq!(
+            split_arguments("C:\\Games\\game.exe").unwrap(),
+            vec!["C:\\Games\\game.exe"]
+        );
+        assert!(split_arguments("").unwrap().is_empty());
+        assert!(matches!(
+            split_arguments("-cfg \"game.cfg"),
+            Err(EmulatorRunnerError::InvalidTemplate(_))
+        ));
+    }
+
+    #[test]
+    fn test_render_argument_template() {
+        let arguments = render_argument_template("-bios {bios} -patch {patch}", |placeholder| {
+            Ok(format!("/tmp/my launch/{}.bin", placeholder))
+        })
+        .unwrap();
+        assert_eq!(
+            arguments,
+            vec![
+                "-bios",
+                "/tmp/my launch/bios.bin",
+                "-patch",
+                "/tmp/my launch/patch.bin"
+            ]
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::{arguments::render_argument_template, error::EmulatorRunnerError};
+
+/// Argument placeholder replaced with the path of the rendered configuration file.
+pub const CONFIG_PLACEHOLDER: &str = "config";
+
+/// Whether the path is a non-empty relative path that stays within the launch directory, i.e.
+/// it has neither a root nor `.` or `..` components.
+pub(crate) fn is_within_launch_dir(relative_path: &Path) -> bool {
+    relative_path.components().count() > 0
+        && relative_path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+}
+
+/// Emulator configuration file rendered from a template, e.g. a VICE `vicerc` with true drive
+/// emulation enabled, a MAME per-game ini or a RetroArch core override.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedConfig {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+impl RenderedConfig {
+    /// Renders the template into the launch directory.
+    ///
+    /// `{name}` in the template is replaced with the value of the variable with that name and
+    /// `{{` and `}}` are written as literal braces.
+    ///
+    /// # errors
+    /// * `EmulatorRunnerError::InvalidTemplate`: If the file name is not a relative path within
+    ///   the launch directory or a placeholder is not closed.
+    /// * `EmulatorRunnerError::UnknownPlaceholder`: If a placeholder refers to an unknown variable.
+    pub fn render(
+        launch_dir: &Path,
+        file_name: &str,
+        template: &str,
+        variables: &[(&str, String)],
+    ) -> Result<Self, EmulatorRunnerError> {
+        let relative_path = Path::new(file_name);
+        if !is_within_launch_dir(relative_path) {
+            return Err(EmulatorRunnerError::InvalidTemplate(format!(
+                "Configuration file name has to be relative to the launch directory: {}",
+                file_name
+            )));
+        }
+        let content = render_placeholders(template, |placeholder| {
+            variables
+                .iter()
+                .find(|(name, _)| *name == placeholder)
+                .map(|(_, value)| value.clone())
+                .ok_or_else(|| EmulatorRunnerError::UnknownPlaceholder(placeholder.to_string()))
+        })?;
+        Ok(Self {
+            path: launch_dir.join(relative_path),
+            content,
+        })
+    }
+
+    /// Writes the configuration file, creating the parent directories.
+    pub fn write(&self) -> Result<(), EmulatorRunnerError> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                EmulatorRunnerError::IoError(format!(
+                    "Failed to create directory {}: {}",
+                    parent.display(),
+                    e
+                ))
+            })?;
+        }
+        std::fs::write(&self.path, &self.content).map_err(|e| {
+            EmulatorRunnerError::IoError(format!(
+                "Failed to write configuration file {}: {}",
+                self.path.display(),
+                e
+            ))
+        })
+    }
+}
+
+/// Splits the emulator arguments into argv words and replaces the `{config}` placeholder with the
+/// path of the configuration file, e.g. `-inipath {config}` is `-inipath` and the path.
+///
+/// # errors
+/// * `EmulatorRunnerError::InvalidTemplate`: If a placeholder or a quote is not closed, or the
+///   arguments refer to `{config}` without a configuration file.
+/// * `EmulatorRunnerError::UnknownPlaceholder`: If the arguments have other placeholders.
+pub fn render_config_arguments(
+    arguments: &str,
+    config: Option<&RenderedConfig>,
+) -> Result<Vec<String>, EmulatorRunnerError> {
+    render_argument_template(arguments, |placeholder| {
+        if placeholder == CONFIG_PLACEHOLDER {
+            config_path(config)
+        } else {
+            Err(EmulatorRunnerError::UnknownPlaceholder(
+                placeholder.to_string(),
+            ))
+        }
+    })
+}
+
+/// Value of the `{config}` placeholder.
+pub(crate) fn config_path(config: Option<&RenderedConfig>) -> Result<String, EmulatorRunnerError> {
+    config
+        .map(|config| config.path.to_string_lossy().to_string())
+        .ok_or_else(|| {
+            EmulatorRunnerError::InvalidTemplate(
+                "Arguments refer to {config} but there is no configuration template".to_string(),
+            )
+        })
+}
+
+/// Replaces `{placeholder}` in the template with the value resolved for it, `{{` and `}}` are
+/// written as literal braces.
+///
+/// # errors
+/// * `EmulatorRunnerError::InvalidTemplate`: If a placeholder is not closed.
+/// * Errors returned by `resolve`.
+pub(crate) fn render_placeholders(
+    template: &str,
+    resolve: impl Fn(&str) -> Result<String, EmulatorRunnerError>,
+) -> Result<String, EmulatorRunnerError> {
+    let mut rendered = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                rendered.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                rendered.push('}');
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => {
+                            return Err(EmulatorRunnerError::InvalidTemplate(format!(
+                                "Unclosed placeholder: {}",
+                                template
+                            )))
+                        }
+                    }
+                }
+                rendered.push_str(&resolve(&placeholder)?);
+            }
+            c => rendered.push(c),
+        }
+    }
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_render_config() {
+        let temp_dir = tempdir().unwrap();
+        let launch_dir = temp_dir.path().join("launch");
+        let variables = [
+            ("system", "Commodore 64".to_string()),
+            ("entry_point", "/launch/game.d64".to_string()),
+        ];
+        let config = RenderedConfig::render(
+            &launch_dir,
+            "vice/vicerc",
+            "[C64]\n# {system}\nDriveTrueEmulation=1\nAutostartPrgDiskImage=\"{entry_point}\"\n{{}}",
+            &variables,
+        )
+        .unwrap();
+        assert_eq!(config.path, launch_dir.join("vice").join("vicerc"));
+        assert_eq!(
+            config.content,
+            "[C64]\n# Commodore 64\nDriveTrueEmulation=1\nAutostartPrgDiskImage=\"/launch/game.d64\"\n{}"
+        );
+
+        config.write().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(launch_dir.join("vice").join("vicerc")).unwrap(),
+            config.content
+        );
+
+        assert_eq!(
+            render_config_arguments("-config {config}", Some(&config)).unwrap(),
+            vec![
+                "-config".to_string(),
+                config.path.to_string_lossy().to_string()
+            ]
+        );
+        assert!(matches!(
+            render_config_arguments("-config {rom}", Some(&config)),
+            Err(EmulatorRunnerError::UnknownPlaceholder(_))
+        ));
+    }
+
+    #[test]
+    fn test_render_config_arguments_with_spaces_in_path() {
+        let config =
+            RenderedConfig::render(Path::new("/tmp/my launch"), "game.ini", "", &[]).unwrap();
+        assert_eq!(
+            render_config_arguments("-inipath {config} -skip_gameinfo", Some(&config)).unwrap(),
+            vec!["-inipath", "/tmp/my launch/game.ini", "-skip_gameinfo"]
+        );
+        assert_eq!(
+            render_config_arguments("--appendconfig={config}", Some(&config)).unwrap(),
+            vec!["--appendconfig=/tmp/my launch/game.ini"]
+        );
+
+        // without a configuration template {config} is not passed on as it is
+        assert_eq!(
+            render_config_arguments("-autostart", None).unwrap(),
+            vec!["-autostart"]
+        );
+        assert!(matches!(
+            render_config_arguments("-inipath {config}", None),
+            Err(EmulatorRunnerError::InvalidTemplate(_))
+        ));
+    }
+
+    #[test]
+    fn test_render_config_errors() {
+        let launch_dir = Path::new("/launch");
+        assert!(matches!(
+            RenderedConfig::render(launch_dir, "vicerc", "{release}", &[]),
+            Err(EmulatorRunnerError::UnknownPlaceholder(_))
+        ));
+        assert!(matches!(
+            RenderedConfig::render(launch_dir, "vicerc", "{system", &[]),
+            Err(EmulatorRunnerError::InvalidTemplate(_))
+        ));
+        for file_name in ["", "../vicerc", "/etc/vicerc"] {
+            assert!(matches!(
+                RenderedConfig::render(launch_dir, file_name, "", &[]),
+                Err(EmulatorRunnerError::InvalidTemplate(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use serde::Deserialize;
+
+use crate::error::EmulatorRunnerError;
+
+const EMULATOR_DEFINITIONS: &str = include_str!("../data/emulator_definitions.toml");
+
+/// Known emulator from the bundled catalogue.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EmulatorDefinition {
+    pub id: String,
+    pub name: String,
+    /// Executable names in order of preference.
+    pub executables: Vec<String>,
+    pub extract_files: bool,
+    #[serde(default)]
+    pub systems: Vec<EmulatorDefinitionSystem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EmulatorDefinitionSystem {
+    /// Alternative names of the system, matched case-insensitively.
+    pub names: Vec<String>,
+    #[serde(default)]
+    pub arguments: String,
+}
+
+impl EmulatorDefinitionSystem {
+    pub fn matches(&self, system_name: &str) -> bool {
+        let system_name = system_name.trim();
+        self.names
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(system_name))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct EmulatorDefinitionCatalogue {
+    #[serde(default)]
+    emulator: Vec<EmulatorDefinition>,
+}
+
+/// Returns the emulator definitions bundled with the application.
+pub fn get_emulator_definitions() -> Result<Vec<EmulatorDefinition>, EmulatorRunnerError> {
+    parse_emulator_definitions(EMULATOR_DEFINITIONS)
+}
+
+/// Parses emulator definitions from a TOML catalogue with `[[emulator]]` tables.
+pub fn parse_emulator_definitions(
+    content: &str,
+) -> Result<Vec<EmulatorDefinition>, EmulatorRunnerError> {
+    let catalogue: EmulatorDefinitionCatalogue = toml::from_str(content)
+        .map_err(|e| EmulatorRunnerError::EmulatorDefinitionError(e.to_string()))?;
+    Ok(catalogue.emulator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_bundled_emulator_definitions() {
+        let definitions = get_emulator_definitions().unwrap();
+        assert!(!definitions.is_empty());
+
+        let ids = definitions.iter().map(|d| &d.id).collect::<HashSet<_>>();
+        assert_eq!(ids.len(), definitions.len(), "duplicate emulator ids");
+
+        for definition in &definitions {
+            assert!(!definition.executables.is_empty(), "{}", definition.id);
+            assert!(!definition.systems.is_empty(), "{}", definition.id);
+        }
+    }
+
+    #[test]
+    fn test_parse_emulator_definitions() {
+        let content = r#"
+            [[emulator]]
+            id = "vice-c64"
+            name = "VICE (C64)"
+            executables = ["x64sc", "x64"]
+            extract_files = true
+
+            [[emulator.systems]]
+            names = ["Commodore 64", "C64"]
+        "#;
+        let definitions = parse_emulator_definitions(content).unwrap();
+        assert_eq!(definitions.len(), 1);
+        assert_eq!(definitions[0].systems[0].arguments, "");
+        assert!(definitions[0].systems[0].matches("commodore 64"));
+        assert!(!definitions[0].systems[0].matches("Commodore 128"));
+    }
+
+    #[test]
+    fn test_parse_invalid_emulator_definitions() {
+        let result = parse_emulator_definitions("[[emulator]]\nid = 1");
+        assert!(matches!(
+            result,
+            Err(EmulatorRunnerError::EmulatorDefinitionError(_))
+        ));
+    }
+}
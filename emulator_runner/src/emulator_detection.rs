@@ -0,0 +1,178 @@
+use std::{
+    collections::HashSet,
+    env,
+    path::{Path, PathBuf},
+};
+
+use crate::emulator_definitions::EmulatorDefinition;
+
+/// Emulator from the catalogue that was found installed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectedEmulator {
+    pub definition: EmulatorDefinition,
+    pub executable_path: PathBuf,
+}
+
+/// Directories searched for emulator executables: directories on PATH followed by common
+/// install directories that are not necessarily on PATH.
+pub fn default_search_dirs() -> Vec<PathBuf> {
+    let mut search_dirs = env::var_os("PATH")
+        .map(|path| env::split_paths(&path).collect::<Vec<_>>())
+        .unwrap_or_default();
+    search_dirs.extend(common_install_dirs());
+
+    let mut seen = HashSet::new();
+    search_dirs.retain(|dir| !dir.as_os_str().is_empty() && seen.insert(dir.clone()));
+    search_dirs
+}
+
+#[cfg(unix)]
+fn common_install_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![
+        PathBuf::from("/usr/local/bin"),
+        PathBuf::from("/usr/bin"),
+        PathBuf::from("/usr/games"),
+        PathBuf::from("/snap/bin"),
+        PathBuf::from("/opt/homebrew/bin"),
+    ];
+    if let Some(home) = env::var_os("HOME") {
+        dirs.push(Path::new(&home).join(".local").join("bin"));
+    }
+    dirs
+}
+
+#[cfg(windows)]
+fn common_install_dirs() -> Vec<PathBuf> {
+    ["ProgramFiles", "ProgramFiles(x86)"]
+        .iter()
+        .filter_map(env::var_os)
+        .flat_map(|program_files| {
+            [
+                "RetroArch",
+                "MAME",
+                "DOSBox",
+                "Dolphin",
+                "DuckStation",
+                "PCSX2",
+            ]
+            .iter()
+            .map(move |dir| Path::new(&program_files).join(dir))
+            .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Finds the emulators of the given definitions from the search directories.
+///
+/// First found executable of each definition is used, executable names are tried in order of
+/// preference and directories in the order they are given.
+pub fn detect_emulators(
+    definitions: &[EmulatorDefinition],
+    search_dirs: &[PathBuf],
+) -> Vec<DetectedEmulator> {
+    definitions
+        .iter()
+        .filter_map(|definition| {
+            definition
+                .executables
+                .iter()
+                .find_map(|executable| find_executable(executable, search_dirs))
+                .map(|executable_path| DetectedEmulator {
+                    definition: definition.clone(),
+                    executable_path,
+                })
+        })
+        .collect()
+}
+
+/// Finds an executable file with the given name from the search directories.
+pub fn find_executable(executable: &str, search_dirs: &[PathBuf]) -> Option<PathBuf> {
+    search_dirs
+        .iter()
+        .flat_map(|dir| executable_file_names(executable).map(move |name| dir.join(name)))
+        .find(|path| is_executable(path))
+}
+
+#[cfg(unix)]
+fn executable_file_names(executable: &str) -> impl Iterator<Item = String> {
+    std::iter::once(executable.to_string())
+}
+
+#[cfg(windows)]
+fn executable_file_names(executable: &str) -> impl Iterator<Item = String> {
+    [executable.to_string(), format!("{}.exe", executable)].into_iter()
+}
+
+#[cfg(unix)]
+pub(crate) fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(windows)]
+pub(crate) fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emulator_definitions::EmulatorDefinitionSystem;
+    use tempfile::tempdir;
+
+    fn create_definition(id: &str, executables: &[&str]) -> EmulatorDefinition {
+        EmulatorDefinition {
+            id: id.to_string(),
+            name: id.to_string(),
+            executables: executables.iter().map(|e| e.to_string()).collect(),
+            extract_files: true,
+            systems: vec![EmulatorDefinitionSystem {
+                names: vec!["Commodore 64".to_string()],
+                arguments: String::new(),
+            }],
+        }
+    }
+
+    #[cfg(unix)]
+    fn create_executable(dir: &Path, name: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join(name);
+        std::fs::write(&path, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_detect_emulators() {
+        let temp_dir = tempdir().unwrap();
+        let first_dir = temp_dir.path().join("first");
+        let second_dir = temp_dir.path().join("second");
+        std::fs::create_dir_all(&first_dir).unwrap();
+        std::fs::create_dir_all(&second_dir).unwrap();
+
+        create_executable(&first_dir, "x64");
+        let preferred = create_executable(&second_dir, "x64sc");
+        // not executable
+        std::fs::write(first_dir.join("fs-uae"), "").unwrap();
+
+        let definitions = vec![
+            create_definition("vice", &["x64sc", "x64"]),
+            create_definition("fs-uae", &["fs-uae"]),
+            create_definition("mame", &["mame"]),
+        ];
+        let detected = detect_emulators(&definitions, &[first_dir, second_dir]);
+
+        assert_eq!(detected.len(), 1);
+        assert_eq!(detected[0].definition.id, "vice");
+        assert_eq!(detected[0].executable_path, preferred);
+    }
+
+    #[test]
+    fn test_default_search_dirs_are_unique() {
+        let search_dirs = default_search_dirs();
+        let unique = search_dirs.iter().collect::<HashSet<_>>();
+        assert_eq!(unique.len(), search_dirs.len());
+    }
+}
@@ -8,4 +8,30 @@ pub enum EmulatorRunnerError {
     NoFileSelected,
     #[error("File not found")]
     FileNotFound,
+    #[error("Pre-launch command failed: {0}")]
+    PreLaunchHookFailed(String),
+    #[error("Post-launch command failed: {0}")]
+    PostLaunchHookFailed(String),
+    #[error("Launch directory is in use by a running emulator: {0}")]
+    LaunchDirectoryInUse(String),
+    #[error("Running launch not found: {0}")]
+    LaunchNotFound(u64),
+    #[error("Emulator failed with status: {0}")]
+    EmulatorFailed(String),
+    #[error("Emulator was stopped")]
+    Stopped,
+    #[error("Emulator was killed after running for {0} seconds")]
+    Timeout(u64),
+    #[error("Invalid emulator definitions: {0}")]
+    EmulatorDefinitionError(String),
+    #[error("Invalid launch plan: {0}")]
+    InvalidLaunchPlan(String),
+    #[error("Invalid template: {0}")]
+    InvalidTemplate(String),
+    #[error("Unknown placeholder: {0}")]
+    UnknownPlaceholder(String),
+    #[error("Emulator executable not available: {0}")]
+    ExecutableNotAvailable(String),
+    #[error("Unknown file set role in arguments: {0}")]
+    UnknownRole(String),
 }
@@ -0,0 +1,302 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use async_process::{Command, Stdio};
+
+use crate::{
+    emulator_detection::{find_executable, is_executable},
+    error::EmulatorRunnerError,
+};
+
+const FLATPAK: &str = "flatpak";
+const WINE: &str = "wine";
+
+/// Emulator executable and the way it's run.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EmulatorExecutable {
+    /// Executable name on the system path or the full path to the executable.
+    Binary(String),
+    /// Flatpak application, run with `flatpak run <app-id>`.
+    Flatpak { app_id: String },
+    /// AppImage, run directly.
+    AppImage(PathBuf),
+    /// Windows executable run with Wine, `WINEPREFIX` is set when a prefix is given.
+    Wine {
+        executable: PathBuf,
+        prefix: Option<PathBuf>,
+    },
+}
+
+impl From<String> for EmulatorExecutable {
+    fn from(executable: String) -> Self {
+        EmulatorExecutable::Binary(executable)
+    }
+}
+
+impl From<&str> for EmulatorExecutable {
+    fn from(executable: &str) -> Self {
+        EmulatorExecutable::Binary(executable.to_string())
+    }
+}
+
+impl Display for EmulatorExecutable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmulatorExecutable::Binary(executable) => write!(f, "{}", executable),
+            EmulatorExecutable::Flatpak { app_id } => write!(f, "{} (Flatpak)", app_id),
+            EmulatorExecutable::AppImage(path) => write!(f, "{} (AppImage)", path.display()),
+            EmulatorExecutable::Wine { executable, .. } => {
+                write!(f, "{} (Wine)", executable.display())
+            }
+        }
+    }
+}
+
+/// Process started for an emulator executable, before the emulator arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutableCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    pub environment: Vec<(String, String)>,
+}
+
+impl EmulatorExecutable {
+    /// Resolves the program to start, the arguments preceding the emulator arguments and the
+    /// environment variables the executable kind needs.
+    pub fn command(&self) -> ExecutableCommand {
+        match self {
+            EmulatorExecutable::Binary(executable) => ExecutableCommand {
+                program: executable.clone(),
+                args: vec![],
+                environment: vec![],
+            },
+            EmulatorExecutable::Flatpak { app_id } => ExecutableCommand {
+                program: FLATPAK.to_string(),
+                args: vec!["run".to_string(), app_id.clone()],
+                environment: vec![],
+            },
+            EmulatorExecutable::AppImage(path) => ExecutableCommand {
+                program: path.to_string_lossy().to_string(),
+                args: vec![],
+                environment: vec![],
+            },
+            EmulatorExecutable::Wine { executable, prefix } => ExecutableCommand {
+                program: WINE.to_string(),
+                args: vec![executable.to_string_lossy().to_string()],
+                environment: prefix
+                    .iter()
+                    .map(|prefix| {
+                        (
+                            "WINEPREFIX".to_string(),
+                            prefix.to_string_lossy().to_string(),
+                        )
+                    })
+                    .collect(),
+            },
+        }
+    }
+
+    /// Checks that the executable can be run: programs are looked up from the search directories
+    /// (see `emulator_detection::default_search_dirs`), Flatpak applications have to be installed,
+    /// and AppImages, Windows executables and Wine prefixes have to exist. Installed Flatpak
+    /// applications are queried with an async `flatpak info`, so the executor isn't blocked.
+    ///
+    /// Returns the path of the program to start in place of `ExecutableCommand::program`, since
+    /// the search directories may have more directories than the system path.
+    ///
+    /// # errors
+    /// * `EmulatorRunnerError::ExecutableNotAvailable`: If the executable can't be run.
+    pub async fn validate(&self, search_dirs: &[PathBuf]) -> Result<PathBuf, EmulatorRunnerError> {
+        match self {
+            EmulatorExecutable::Binary(executable) => resolve_program(executable, search_dirs),
+            EmulatorExecutable::Flatpak { app_id } => {
+                let flatpak = resolve_program(FLATPAK, search_dirs)?;
+                let status = Command::new(&flatpak)
+                    .args(["info", app_id])
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status()
+                    .await
+                    .map_err(|e| {
+                        EmulatorRunnerError::ExecutableNotAvailable(format!(
+                            "Failed to run flatpak: {}",
+                            e
+                        ))
+                    })?;
+                if status.success() {
+                    Ok(flatpak)
+                } else {
+                    Err(EmulatorRunnerError::ExecutableNotAvailable(format!(
+                        "Flatpak application {} is not installed",
+                        app_id
+                    )))
+                }
+            }
+            EmulatorExecutable::AppImage(path) => {
+                if is_executable(path) {
+                    Ok(path.clone())
+                } else {
+                    Err(EmulatorRunnerError::ExecutableNotAvailable(format!(
+                        "AppImage {} not found or not executable",
+                        path.display()
+                    )))
+                }
+            }
+            EmulatorExecutable::Wine { executable, prefix } => {
+                let wine = resolve_program(WINE, search_dirs)?;
+                if !executable.is_file() {
+                    return Err(EmulatorRunnerError::ExecutableNotAvailable(format!(
+                        "Windows executable {} not found",
+                        executable.display()
+                    )));
+                }
+                match prefix {
+                    Some(prefix) if !prefix.is_dir() => {
+                        Err(EmulatorRunnerError::ExecutableNotAvailable(format!(
+                            "Wine prefix {} not found",
+                            prefix.display()
+                        )))
+                    }
+                    _ => Ok(wine),
+                }
+            }
+        }
+    }
+}
+
+/// Resolves a program given either as a path or as a name looked up from the search directories.
+fn resolve_program(program: &str, search_dirs: &[PathBuf]) -> Result<PathBuf, EmulatorRunnerError> {
+    let path = Path::new(program);
+    let resolved = if path.components().count() > 1 {
+        Some(path.to_path_buf()).filter(|path| is_executable(path))
+    } else {
+        find_executable(program, search_dirs)
+    };
+    resolved.ok_or_else(|| {
+        EmulatorRunnerError::ExecutableNotAvailable(format!("{} not found", program))
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn create_stub_executable(dir: &Path, name: &str, script: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[async_std::test]
+    async fn test_binary() {
+        let temp_dir = tempdir().unwrap();
+        let search_dirs = vec![temp_dir.path().to_path_buf()];
+        let path = create_stub_executable(temp_dir.path(), "x64sc", "exit 0");
+
+        let executable = EmulatorExecutable::from("x64sc");
+        assert_eq!(executable.command().program, "x64sc");
+        // program found from the search directories is started with its full path
+        assert_eq!(executable.validate(&search_dirs).await.unwrap(), path);
+
+        let executable = EmulatorExecutable::Binary(path.to_string_lossy().to_string());
+        assert_eq!(executable.validate(&[]).await.unwrap(), path);
+
+        let result = EmulatorExecutable::from("x128")
+            .validate(&search_dirs)
+            .await;
+        assert!(matches!(
+            result,
+            Err(EmulatorRunnerError::ExecutableNotAvailable(_))
+        ));
+    }
+
+    #[async_std::test]
+    async fn test_flatpak() {
+        let temp_dir = tempdir().unwrap();
+        let search_dirs = vec![temp_dir.path().to_path_buf()];
+        let executable = EmulatorExecutable::Flatpak {
+            app_id: "org.libretro.RetroArch".to_string(),
+        };
+        assert_eq!(
+            executable.command(),
+            ExecutableCommand {
+                program: "flatpak".to_string(),
+                args: vec!["run".to_string(), "org.libretro.RetroArch".to_string()],
+                environment: vec![],
+            }
+        );
+
+        // flatpak itself not installed
+        assert!(executable.validate(&search_dirs).await.is_err());
+
+        // stub reports only RetroArch as installed
+        let flatpak = create_stub_executable(
+            temp_dir.path(),
+            "flatpak",
+            "[ \"$1\" = \"info\" ] && [ \"$2\" = \"org.libretro.RetroArch\" ]",
+        );
+        assert_eq!(executable.validate(&search_dirs).await.unwrap(), flatpak);
+        let not_installed = EmulatorExecutable::Flatpak {
+            app_id: "org.DolphinEmu.dolphin-emu".to_string(),
+        };
+        assert!(matches!(
+            not_installed.validate(&search_dirs).await,
+            Err(EmulatorRunnerError::ExecutableNotAvailable(_))
+        ));
+    }
+
+    #[async_std::test]
+    async fn test_app_image() {
+        let temp_dir = tempdir().unwrap();
+        let path = create_stub_executable(temp_dir.path(), "DuckStation.AppImage", "exit 0");
+        let executable = EmulatorExecutable::AppImage(path.clone());
+        assert_eq!(executable.command().program, path.to_string_lossy());
+        assert_eq!(executable.validate(&[]).await.unwrap(), path);
+
+        // not executable
+        let path = temp_dir.path().join("PCSX2.AppImage");
+        std::fs::write(&path, "").unwrap();
+        assert!(EmulatorExecutable::AppImage(path)
+            .validate(&[])
+            .await
+            .is_err());
+    }
+
+    #[async_std::test]
+    async fn test_wine() {
+        let temp_dir = tempdir().unwrap();
+        let search_dirs = vec![temp_dir.path().to_path_buf()];
+        let windows_executable = temp_dir.path().join("WinUAE.exe");
+        std::fs::write(&windows_executable, "").unwrap();
+        let prefix = temp_dir.path().join("prefix");
+
+        let executable = EmulatorExecutable::Wine {
+            executable: windows_executable.clone(),
+            prefix: Some(prefix.clone()),
+        };
+        assert_eq!(
+            executable.command(),
+            ExecutableCommand {
+                program: "wine".to_string(),
+                args: vec![windows_executable.to_string_lossy().to_string()],
+                environment: vec![(
+                    "WINEPREFIX".to_string(),
+                    prefix.to_string_lossy().to_string()
+                )],
+            }
+        );
+
+        // wine is not installed
+        assert!(executable.validate(&search_dirs).await.is_err());
+        let wine = create_stub_executable(temp_dir.path(), "wine", "exit 0");
+        // prefix doesn't exist
+        assert!(executable.validate(&search_dirs).await.is_err());
+        std::fs::create_dir(&prefix).unwrap();
+        assert_eq!(executable.validate(&search_dirs).await.unwrap(), wine);
+    }
+}
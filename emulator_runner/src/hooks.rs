@@ -0,0 +1,41 @@
+use async_process::Command;
+use std::path::Path;
+
+/// Runs a hook command through the system shell in the given working directory with the
+/// given environment variables.
+///
+/// Returns an error message if the command cannot be started or it exits with a failure status.
+pub async fn run_hook_command(
+    hook_command: &str,
+    working_directory: &Path,
+    environment: &[(String, String)],
+) -> Result<(), String> {
+    let mut command = shell_command(hook_command);
+    command
+        .current_dir(working_directory)
+        .envs(environment.iter().cloned());
+
+    let status = command
+        .status()
+        .await
+        .map_err(|e| format!("Failed to run '{}': {}", hook_command, e))?;
+
+    if !status.success() {
+        return Err(format!("'{}' failed with status: {}", hook_command, status));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn shell_command(hook_command: &str) -> Command {
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(hook_command);
+    command
+}
+
+#[cfg(windows)]
+fn shell_command(hook_command: &str) -> Command {
+    let mut command = Command::new("cmd");
+    command.arg("/C").arg(hook_command);
+    command
+}
@@ -0,0 +1,683 @@
+use std::path::{Path, PathBuf};
+
+use file_export::{export_files_zipped_or_non_zipped, FileSetExportModel};
+
+use crate::{
+    arguments::render_argument_template,
+    config_template::{config_path, is_within_launch_dir, RenderedConfig, CONFIG_PLACEHOLDER},
+    error::EmulatorRunnerError,
+    executable::EmulatorExecutable,
+    process_registry::LaunchHandle,
+    run_with_emulator, LaunchMode, LaunchOptions,
+};
+
+/// Whether the role can be used as a placeholder and a subdirectory name: ASCII letters, digits,
+/// `_` and `-`. `config` is reserved, `{config}` is the path of the configuration file.
+pub fn is_valid_role(role: &str) -> bool {
+    !role.is_empty()
+        && role != CONFIG_PLACEHOLDER
+        && role
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Where the files of a file set are exported within the launch directory.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileSetPlacement {
+    /// Files are exported directly into the launch directory.
+    LaunchDirectory,
+    /// Files are exported into a subdirectory named after the role of the file set.
+    Subdirectory,
+}
+
+/// File set taking part in a composite launch.
+#[derive(Debug)]
+pub struct PlannedFileSet {
+    /// Name the file set is referred to in argument templates, e.g. `rom`, `bios` or `patch`.
+    pub role: String,
+    pub placement: FileSetPlacement,
+    /// Export model with the output directory resolved from the placement.
+    pub export_model: FileSetExportModel,
+    /// File passed to the emulator when the file set is referred to, relative to the output
+    /// directory of the file set.
+    pub entry_point: String,
+}
+
+impl PlannedFileSet {
+    /// Path of the entry point relative to the launch directory.
+    pub fn relative_entry_point(&self) -> PathBuf {
+        self.relative_dir().join(&self.entry_point)
+    }
+
+    fn relative_dir(&self) -> PathBuf {
+        match self.placement {
+            FileSetPlacement::LaunchDirectory => PathBuf::new(),
+            FileSetPlacement::Subdirectory => PathBuf::from(&self.role),
+        }
+    }
+
+    /// File names written by the export, relative to the launch directory.
+    fn relative_file_names(&self) -> Vec<String> {
+        let relative_dir = self.relative_dir();
+        let mut file_names = if self.export_model.extract_files {
+            self.export_model
+                .output_mapping
+                .values()
+                .map(|output_file| output_file.output_file_name.clone())
+                .collect::<Vec<_>>()
+        } else {
+            vec![self.export_model.exported_zip_file_name.clone()]
+        };
+        file_names.sort();
+        file_names
+            .iter()
+            .map(|file_name| relative_dir.join(file_name).to_string_lossy().to_string())
+            .collect()
+    }
+}
+
+/// Playlist the primary file set is launched with instead of its entry point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedPlaylist {
+    pub name: String,
+    /// Files of the primary file set in the playlist order.
+    pub file_names: Vec<String>,
+}
+
+/// Launch combining several file sets of a release, e.g. a ROM and a BIOS, a cartridge and a
+/// disk, or a game and a patch.
+///
+/// All file sets are exported under the same launch directory. The first added file set is the
+/// primary one, its entry point is passed to the emulator like in a single file set launch. Other
+/// file sets are passed through the argument template, where `{role}` is replaced with the full
+/// path of the entry point of the file set with that role and `{role.dir}` with the directory the
+/// file set was exported to. `{{` and `}}` are written as literal braces. The template is split
+/// into argv words before the placeholders are replaced, see `arguments::split_arguments`.
+///
+/// Firmware needed by the emulator is exported along with the file sets, but it's not referred to
+/// in the arguments since emulators look up firmware from their own directories.
+///
+/// Configuration file rendered for the release is written along with the file sets and `{config}`
+/// is replaced with its path.
+///
+/// A plan with a single file set is a regular launch, optionally with a playlist, e.g. of the
+/// disks of a multi-disk game.
+#[derive(Debug)]
+pub struct LaunchPlan {
+    pub launch_dir: PathBuf,
+    pub file_sets: Vec<PlannedFileSet>,
+    /// Firmware export models with the output directory resolved from the target path.
+    pub firmware: Vec<FileSetExportModel>,
+    pub config: Option<RenderedConfig>,
+    pub playlist: Option<PlannedPlaylist>,
+}
+
+impl LaunchPlan {
+    pub fn new(launch_dir: PathBuf) -> Self {
+        Self {
+            launch_dir,
+            file_sets: Vec::new(),
+            firmware: Vec::new(),
+            config: None,
+            playlist: None,
+        }
+    }
+
+    /// Launches the primary file set as an m3u playlist of the given files, written into the
+    /// launch directory.
+    pub fn set_playlist(&mut self, name: String, file_names: Vec<String>) {
+        self.playlist = Some(PlannedPlaylist { name, file_names });
+    }
+
+    /// Sets the configuration file written into the launch directory.
+    pub fn set_config(&mut self, config: RenderedConfig) {
+        self.config = Some(config);
+    }
+
+    /// Adds firmware to be exported to the target path, resolved against the launch directory.
+    ///
+    /// # errors
+    /// * `EmulatorRunnerError::InvalidLaunchPlan`: If the target path is not a relative path
+    ///   within the launch directory.
+    pub fn add_firmware(
+        &mut self,
+        mut export_model: FileSetExportModel,
+        target_path: &Path,
+    ) -> Result<(), EmulatorRunnerError> {
+        if !is_within_launch_dir(target_path) {
+            return Err(EmulatorRunnerError::InvalidLaunchPlan(format!(
+                "Firmware target path has to be relative to the launch directory: {}",
+                target_path.display()
+            )));
+        }
+        export_model.output_dir = self.launch_dir.join(target_path);
+        self.firmware.push(export_model);
+        Ok(())
+    }
+
+    /// Adds a file set with the given role to the plan.
+    ///
+    /// Output directory of the export model is replaced according to the placement. Entry point
+    /// defaults to the only file of the file set, non-extracted file sets are always launched as
+    /// the exported zip file.
+    ///
+    /// # errors
+    /// * `EmulatorRunnerError::InvalidLaunchPlan`: If the role is not a valid name or already in
+    ///   use, or the entry point can't be resolved.
+    pub fn add_file_set(
+        &mut self,
+        role: &str,
+        mut export_model: FileSetExportModel,
+        entry_point: Option<String>,
+        placement: FileSetPlacement,
+    ) -> Result<(), EmulatorRunnerError> {
+        if !is_valid_role(role) {
+            return Err(EmulatorRunnerError::InvalidLaunchPlan(format!(
+                "Invalid role name: '{}'",
+                role
+            )));
+        }
+        if self.file_set(role).is_some() {
+            return Err(EmulatorRunnerError::InvalidLaunchPlan(format!(
+                "Role {} is used more than once",
+                role
+            )));
+        }
+
+        let output_file_names = export_model
+            .output_mapping
+            .values()
+            .map(|output_file| output_file.output_file_name.clone())
+            .collect::<Vec<_>>();
+        let entry_point = if !export_model.extract_files {
+            export_model.exported_zip_file_name.clone()
+        } else {
+            match (entry_point, output_file_names.as_slice()) {
+                (Some(entry_point), _) if output_file_names.contains(&entry_point) => entry_point,
+                (Some(entry_point), _) => {
+                    return Err(EmulatorRunnerError::InvalidLaunchPlan(format!(
+                        "Entry point {} is not part of file set {}",
+                        entry_point, role
+                    )))
+                }
+                (None, [file_name]) => file_name.clone(),
+                (None, _) => {
+                    return Err(EmulatorRunnerError::InvalidLaunchPlan(format!(
+                        "Entry point of file set {} has to be selected",
+                        role
+                    )))
+                }
+            }
+        };
+
+        export_model.output_dir = match placement {
+            FileSetPlacement::LaunchDirectory => self.launch_dir.clone(),
+            FileSetPlacement::Subdirectory => self.launch_dir.join(role),
+        };
+        self.file_sets.push(PlannedFileSet {
+            role: role.to_string(),
+            placement,
+            export_model,
+            entry_point,
+        });
+        Ok(())
+    }
+
+    pub fn file_set(&self, role: &str) -> Option<&PlannedFileSet> {
+        self.file_sets.iter().find(|file_set| file_set.role == role)
+    }
+
+    /// Splits the argument template into argv words and replaces the role placeholders in them.
+    ///
+    /// # errors
+    /// * `EmulatorRunnerError::UnknownRole`: If a placeholder refers to a role not in the plan.
+    /// * `EmulatorRunnerError::InvalidLaunchPlan`: If a placeholder or a quote is not closed.
+    pub fn render_arguments(&self, template: &str) -> Result<Vec<String>, EmulatorRunnerError> {
+        render_argument_template(template, |placeholder| {
+            self.resolve_placeholder(placeholder)
+        })
+        .map_err(|e| match e {
+            EmulatorRunnerError::InvalidTemplate(message) => {
+                EmulatorRunnerError::InvalidLaunchPlan(message)
+            }
+            e => e,
+        })
+    }
+
+    fn resolve_placeholder(&self, placeholder: &str) -> Result<String, EmulatorRunnerError> {
+        if placeholder == CONFIG_PLACEHOLDER {
+            return config_path(self.config.as_ref());
+        }
+        let (role, is_dir) = match placeholder.strip_suffix(".dir") {
+            Some(role) => (role, true),
+            None => (placeholder, false),
+        };
+        let file_set = self
+            .file_set(role)
+            .ok_or_else(|| EmulatorRunnerError::UnknownRole(role.to_string()))?;
+        let path = if is_dir {
+            file_set.export_model.output_dir.clone()
+        } else {
+            self.launch_dir.join(file_set.relative_entry_point())
+        };
+        Ok(path.to_string_lossy().to_string())
+    }
+
+    /// Exports the firmware and all the file sets and writes the configuration file into the
+    /// launch directory.
+    pub fn export(&self) -> Result<(), EmulatorRunnerError> {
+        if let Some(config) = &self.config {
+            config.write()?;
+        }
+        for export_model in &self.firmware {
+            std::fs::create_dir_all(&export_model.output_dir).map_err(|e| {
+                EmulatorRunnerError::IoError(format!(
+                    "Failed to create firmware directory {}: {}",
+                    export_model.output_dir.display(),
+                    e
+                ))
+            })?;
+            export_files_zipped_or_non_zipped(export_model).map_err(|e| {
+                EmulatorRunnerError::IoError(format!("Failed to export firmware: {}", e))
+            })?;
+        }
+        for file_set in &self.file_sets {
+            std::fs::create_dir_all(&file_set.export_model.output_dir).map_err(|e| {
+                EmulatorRunnerError::IoError(format!(
+                    "Failed to create directory for file set {}: {}",
+                    file_set.role, e
+                ))
+            })?;
+            export_files_zipped_or_non_zipped(&file_set.export_model).map_err(|e| {
+                EmulatorRunnerError::IoError(format!(
+                    "Failed to export file set {}: {}",
+                    file_set.role, e
+                ))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Resolves the arguments for `run_with_emulator`, relative to the launch directory: the
+    /// file names of all file sets and the entry point of the primary file set, or the playlist
+    /// and its files.
+    ///
+    /// # errors
+    /// * `EmulatorRunnerError::NoFileSelected`: If the plan has no file sets.
+    pub fn launch_files(&self) -> Result<(Vec<String>, LaunchMode), EmulatorRunnerError> {
+        let primary = self
+            .file_sets
+            .first()
+            .ok_or(EmulatorRunnerError::NoFileSelected)?;
+        match &self.playlist {
+            Some(playlist) => {
+                let relative_dir = primary.relative_dir();
+                let file_names = playlist
+                    .file_names
+                    .iter()
+                    .map(|file_name| relative_dir.join(file_name).to_string_lossy().to_string())
+                    .collect();
+                let launch_mode = LaunchMode::Playlist {
+                    playlist_name: playlist.name.clone(),
+                };
+                Ok((file_names, launch_mode))
+            }
+            None => {
+                let file_names = self
+                    .file_sets
+                    .iter()
+                    .flat_map(|file_set| file_set.relative_file_names())
+                    .collect();
+                let launch_mode = LaunchMode::SingleFile {
+                    selected_file_name: primary
+                        .relative_entry_point()
+                        .to_string_lossy()
+                        .to_string(),
+                };
+                Ok((file_names, launch_mode))
+            }
+        }
+    }
+}
+
+/// Exports the file sets of the plan and runs the emulator with the entry point of the primary
+/// file set and the rendered argument template.
+///
+/// # errors
+/// Same as `run_with_emulator`, and
+/// * `EmulatorRunnerError::UnknownRole`: If the argument template refers to a role not in the plan.
+/// * `EmulatorRunnerError::IoError`: If exporting the file sets fails.
+pub async fn run_launch_plan(
+    plan: &LaunchPlan,
+    executable: EmulatorExecutable,
+    argument_template: &str,
+    launch_options: LaunchOptions,
+    launch_handle: Option<LaunchHandle>,
+) -> Result<(), EmulatorRunnerError> {
+    let arguments = plan.render_arguments(argument_template)?;
+    let (file_names, launch_mode) = plan.launch_files()?;
+    plan.export()?;
+    run_with_emulator(
+        executable,
+        arguments,
+        file_names,
+        launch_mode,
+        plan.launch_dir.clone(),
+        launch_options,
+        launch_handle,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use file_export::OutputFile;
+    use std::collections::HashMap;
+    use tempfile::tempdir;
+
+    fn create_export_model(file_names: &[&str], extract_files: bool) -> FileSetExportModel {
+        let output_mapping = file_names
+            .iter()
+            .map(|file_name| {
+                (
+                    format!("archive_{}", file_name),
+                    OutputFile {
+                        output_file_name: file_name.to_string(),
+                        checksum: [0; 20],
+                    },
+                )
+            })
+            .collect::<HashMap<_, _>>();
+        FileSetExportModel {
+            output_mapping,
+            source_file_path: PathBuf::from("/collection/files"),
+            extract_files,
+            exported_zip_file_name: "set.zip".to_string(),
+            output_dir: PathBuf::new(),
+        }
+    }
+
+    fn create_plan(launch_dir: &Path) -> LaunchPlan {
+        let mut plan = LaunchPlan::new(launch_dir.to_path_buf());
+        plan.add_file_set(
+            "rom",
+            create_export_model(&["game.bin"], true),
+            None,
+            FileSetPlacement::LaunchDirectory,
+        )
+        .unwrap();
+        plan.add_file_set(
+            "bios",
+            create_export_model(&["bios.rom", "bios.txt"], true),
+            Some("bios.rom".to_string()),
+            FileSetPlacement::Subdirectory,
+        )
+        .unwrap();
+        plan.add_file_set(
+            "patch",
+            create_export_model(&["patch.ips"], false),
+            None,
+            FileSetPlacement::Subdirectory,
+        )
+        .unwrap();
+        plan
+    }
+
+    #[test]
+    fn test_render_arguments() {
+        let launch_dir = PathBuf::from("/tmp/launch");
+        let mut plan = create_plan(&launch_dir);
+
+        let arguments = plan
+            .render_arguments("-bios {bios} -biosdir {bios.dir} -patch {patch} {{literal}}")
+            .unwrap();
+        assert_eq!(
+            arguments,
+            vec![
+                "-bios",
+                "/tmp/launch/bios/bios.rom",
+                "-biosdir",
+                "/tmp/launch/bios",
+                "-patch",
+                "/tmp/launch/patch/set.zip",
+                "{literal}"
+            ]
+        );
+        assert!(matches!(
+            plan.render_arguments("-cart {cart}"),
+            Err(EmulatorRunnerError::UnknownRole(role)) if role == "cart"
+        ));
+        assert!(matches!(
+            plan.render_arguments("-bios {bios"),
+            Err(EmulatorRunnerError::InvalidLaunchPlan(_))
+        ));
+
+        assert!(matches!(
+            plan.render_arguments("-inipath {config}"),
+            Err(EmulatorRunnerError::InvalidLaunchPlan(_))
+        ));
+        plan.set_config(RenderedConfig::render(&launch_dir, "game.ini", "cheat 1", &[]).unwrap());
+        assert_eq!(
+            plan.render_arguments("-inipath {config}").unwrap(),
+            vec!["-inipath", "/tmp/launch/game.ini"]
+        );
+
+        let (file_names, launch_mode) = plan.launch_files().unwrap();
+        assert_eq!(
+            file_names,
+            vec![
+                "game.bin",
+                "bios/bios.rom",
+                "bios/bios.txt",
+                "patch/set.zip"
+            ]
+        );
+        assert_eq!(
+            launch_mode,
+            LaunchMode::SingleFile {
+                selected_file_name: "game.bin".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_playlist_launch_files() {
+        let mut plan = LaunchPlan::new(PathBuf::from("/tmp/launch"));
+        plan.add_file_set(
+            "disks",
+            create_export_model(&["Game (Disk 1).d64", "Game (Disk 2).d64"], true),
+            Some("Game (Disk 1).d64".to_string()),
+            FileSetPlacement::LaunchDirectory,
+        )
+        .unwrap();
+        plan.set_playlist(
+            "Game".to_string(),
+            vec![
+                "Game (Disk 2).d64".to_string(),
+                "Game (Disk 1).d64".to_string(),
+            ],
+        );
+
+        let (file_names, launch_mode) = plan.launch_files().unwrap();
+        assert_eq!(file_names, vec!["Game (Disk 2).d64", "Game (Disk 1).d64"]);
+        assert_eq!(
+            launch_mode,
+            LaunchMode::Playlist {
+                playlist_name: "Game".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_render_arguments_with_spaces_in_paths() {
+        let plan = create_plan(Path::new("/tmp/my launch"));
+        let arguments = plan
+            .render_arguments("-bios {bios} -patch={patch} -title \"My Game\"")
+            .unwrap();
+        assert_eq!(
+            arguments,
+            vec![
+                "-bios",
+                "/tmp/my launch/bios/bios.rom",
+                "-patch=/tmp/my launch/patch/set.zip",
+                "-title",
+                "My Game"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_add_invalid_file_set() {
+        let mut plan = create_plan(Path::new("/tmp/launch"));
+        let result = plan.add_file_set(
+            "rom",
+            create_export_model(&["other.bin"], true),
+            None,
+            FileSetPlacement::Subdirectory,
+        );
+        assert!(matches!(
+            result,
+            Err(EmulatorRunnerError::InvalidLaunchPlan(_))
+        ));
+
+        // entry point has to be selected from several files
+        let result = plan.add_file_set(
+            "disks",
+            create_export_model(&["disk1.d64", "disk2.d64"], true),
+            None,
+            FileSetPlacement::Subdirectory,
+        );
+        assert!(matches!(
+            result,
+            Err(EmulatorRunnerError::InvalidLaunchPlan(_))
+        ));
+
+        let result = plan.add_file_set(
+            CONFIG_PLACEHOLDER,
+            create_export_model(&["vice.cfg"], true),
+            None,
+            FileSetPlacement::Subdirectory,
+        );
+        assert!(matches!(
+            result,
+            Err(EmulatorRunnerError::InvalidLaunchPlan(_))
+        ));
+
+        let result = plan.add_file_set(
+            "../disks",
+            create_export_model(&["disk1.d64"], true),
+            None,
+            FileSetPlacement::Subdirectory,
+        );
+        assert!(matches!(
+            result,
+            Err(EmulatorRunnerError::InvalidLaunchPlan(_))
+        ));
+    }
+
+    #[test]
+    fn test_add_invalid_firmware() {
+        let mut plan = create_plan(Path::new("/tmp/launch"));
+        for target_path in ["", "/tmp/kickstarts", "../kickstarts", "kickstarts/../.."] {
+            let result = plan.add_firmware(
+                create_export_model(&["kick13.rom"], true),
+                Path::new(target_path),
+            );
+            assert!(
+                matches!(result, Err(EmulatorRunnerError::InvalidLaunchPlan(_))),
+                "{}",
+                target_path
+            );
+        }
+        plan.add_firmware(
+            create_export_model(&["kick13.rom"], true),
+            Path::new("roms/kickstarts"),
+        )
+        .unwrap();
+        assert_eq!(
+            plan.firmware[0].output_dir,
+            Path::new("/tmp/launch/roms/kickstarts")
+        );
+    }
+
+    /// Writes a compressed collection file and returns the export model exporting it.
+    fn create_collection_file(
+        source_dir: &Path,
+        archive_file_name: &str,
+        output_file_name: &str,
+        content: &str,
+    ) -> FileSetExportModel {
+        use sha1::{Digest, Sha1};
+        let compressed = zstd::encode_all(content.as_bytes(), 0).unwrap();
+        std::fs::write(
+            source_dir.join(format!("{}.zst", archive_file_name)),
+            compressed,
+        )
+        .unwrap();
+        let checksum: [u8; 20] = Sha1::digest(content.as_bytes()).into();
+        let mut export_model = create_export_model(&[], true);
+        export_model.source_file_path = source_dir.to_path_buf();
+        export_model.output_mapping.insert(
+            archive_file_name.to_string(),
+            OutputFile {
+                output_file_name: output_file_name.to_string(),
+                checksum,
+            },
+        );
+        export_model
+    }
+
+    #[cfg(unix)]
+    #[async_std::test]
+    async fn test_run_launch_plan() {
+        let temp_dir = tempdir().unwrap();
+        let source_dir = temp_dir.path().join("collection");
+        let launch_dir = temp_dir.path().join("my launch");
+        std::fs::create_dir_all(&source_dir).unwrap();
+
+        let mut plan = LaunchPlan::new(launch_dir.clone());
+        // "sh" as emulator runs the primary entry point as a script, bios path is an argument
+        plan.add_file_set(
+            "rom",
+            create_collection_file(
+                &source_dir,
+                "archive_game",
+                "game.sh",
+                "test $# -eq 3 && test \"$1\" = -bios && test -f \"$2\" && test -d \"$3\"",
+            ),
+            None,
+            FileSetPlacement::LaunchDirectory,
+        )
+        .unwrap();
+        plan.add_file_set(
+            "bios",
+            create_collection_file(&source_dir, "archive_bios", "bios.rom", "bios"),
+            None,
+            FileSetPlacement::Subdirectory,
+        )
+        .unwrap();
+        plan.add_firmware(
+            create_collection_file(&source_dir, "archive_firmware", "kick13.rom", "kickstart"),
+            Path::new("kickstarts"),
+        )
+        .unwrap();
+
+        let result = run_launch_plan(
+            &plan,
+            "sh".into(),
+            "-bios {bios} {bios.dir}",
+            LaunchOptions::default(),
+            None,
+        )
+        .await;
+        assert!(result.is_ok(), "Emulator run failed: {:?}", result);
+        assert!(launch_dir.join("game.sh").exists());
+        assert!(launch_dir.join("bios").join("bios.rom").exists());
+        assert!(launch_dir.join("kickstarts").join("kick13.rom").exists());
+
+        let result =
+            run_launch_plan(&plan, "sh".into(), "{cart}", LaunchOptions::default(), None).await;
+        assert!(matches!(result, Err(EmulatorRunnerError::UnknownRole(_))));
+    }
+}
@@ -1,65 +1,170 @@
 use async_process::Command;
-use std::path::{Path, PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
+use emulator_detection::default_search_dirs;
 use error::EmulatorRunnerError;
+use executable::EmulatorExecutable;
+use hooks::run_hook_command;
+use playlist::write_m3u_playlist;
+use preview::LaunchCommand;
+use process_registry::{wait_for_process, LaunchHandle, ProcessExit};
 
+pub mod arguments;
+pub mod config_template;
+pub mod emulator_definitions;
+pub mod emulator_detection;
 pub mod error;
+pub mod executable;
+mod hooks;
+pub mod launch_plan;
+pub mod playlist;
+pub mod preview;
+pub mod process_registry;
+
+/// Defines what is passed to the emulator as the entry point.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LaunchMode {
+    /// Launch the selected file of the file set.
+    SingleFile { selected_file_name: String },
+    /// Write an m3u playlist of all the files in the file set, in the order of `file_names`,
+    /// and launch the playlist. Used with multi-disk software on emulators supporting disk
+    /// swapping.
+    Playlist { playlist_name: String },
+}
+
+/// Per-emulator settings applied when launching the emulator.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LaunchOptions {
+    /// Environment variables set for the emulator and the hook commands.
+    pub environment: Vec<(String, String)>,
+    /// Working directory for the emulator and the hook commands. Relative path is resolved
+    /// against the source path. Defaults to the source path.
+    pub working_directory: Option<PathBuf>,
+    /// Command run through the system shell before the emulator is started.
+    pub pre_launch_command: Option<String>,
+    /// Command run through the system shell after the emulator has exited, also when the
+    /// emulator exits with an error.
+    pub post_launch_command: Option<String>,
+    /// Emulator is killed when it has been running longer than this.
+    pub timeout: Option<Duration>,
+}
 
 /// Asynchronous function to run an emulator with the given executable, arguments, and file names.
-/// It takes the selected file name and source path to locate the file.
+/// It takes the launch mode and source path to locate the entry point file.
 ///
 /// # arguments
-/// * `executable`: emulator executable name (if it's found on system path) or the full path to the emulator executable.
-/// * `arguments`: The arguments to pass to the emulator.
+/// * `executable`: emulator executable name (if it's found on system path) or the full path to the emulator executable, or a Flatpak application, AppImage or Windows executable run with Wine.
+/// * `arguments`: The arguments to pass to the emulator after the entry point, one argv word each, see `arguments::split_arguments`.
 /// * `file_names`: A vector of file names to be used with emulator to run a certain software release.
-/// * `selected_file_name`: The name of the entry point file of the set of file_names to be executed.
+/// * `launch_mode`: Either the entry point file of the set of file_names to be executed or a playlist to be written of all file_names.
 /// * `source_path`: The path where the files are located.
+/// * `launch_options`: Environment variables, working directory, pre/post launch commands and timeout.
+/// * `launch_handle`: Registration of the launch in a `ProcessRegistry`, allows stopping the emulator. Launch is unregistered when the emulator has exited.
 ///
 /// # returns
-/// * `Result<(), EmulatorRunnerError>`: Returns Ok if the emulator runs successfully, or an error if it fails.
+/// * `Result<(), EmulatorRunnerError>`: Returns Ok if the emulator runs successfully, or an error if it fails or was stopped through the registry.
 ///
 /// # errors
 /// * `EmulatorRunnerError::NoFileSelected`: If no file is selected.
 /// * `EmulatorRunnerError::FileNotFound`: If the selected file is not found.
-/// * `EmulatorRunnerError::IoError`: If there is an IO error while running the emulator.
+/// * `EmulatorRunnerError::ExecutableNotAvailable`: If the emulator executable can't be run, emulator is not started.
+/// * `EmulatorRunnerError::IoError`: If there is an IO error while writing the playlist or running the emulator.
+/// * `EmulatorRunnerError::PreLaunchHookFailed`: If the pre-launch command fails, emulator is not started.
+/// * `EmulatorRunnerError::PostLaunchHookFailed`: If the post-launch command fails after a successful emulator run.
+/// * `EmulatorRunnerError::EmulatorFailed`: If the emulator exits with a non-zero status.
+/// * `EmulatorRunnerError::Stopped`: If the emulator was stopped through the registry.
+/// * `EmulatorRunnerError::Timeout`: If the emulator was killed because of the timeout.
 ///
 pub async fn run_with_emulator(
-    executable: String,
-    arguments: String,
-    file_names: Vec<String>,    // list of files selected for running
-    selected_file_name: String, // entry point file in possible set of files
-    source_path: PathBuf,       // where to find files
+    executable: EmulatorExecutable,
+    arguments: Vec<String>,
+    file_names: Vec<String>, // list of files selected for running
+    launch_mode: LaunchMode, // entry point file in possible set of files or playlist of them
+    source_path: PathBuf,    // where to find files
+    launch_options: LaunchOptions,
+    launch_handle: Option<LaunchHandle>,
 ) -> Result<(), EmulatorRunnerError> {
     if file_names.is_empty() {
         return Err(EmulatorRunnerError::NoFileSelected);
     }
-    let file_path = Path::new(&source_path).join(&selected_file_name);
-    println!("Running emulator with file: {}", file_path.display());
+    let file_path = match launch_mode {
+        LaunchMode::SingleFile { selected_file_name } => {
+            Path::new(&source_path).join(selected_file_name)
+        }
+        LaunchMode::Playlist { playlist_name } => {
+            write_m3u_playlist(&source_path, &playlist_name, &file_names)?
+        }
+    };
     if !file_path.exists() {
         return Err(EmulatorRunnerError::FileNotFound);
     }
 
-    let mut command = Command::new(&executable);
+    let program = executable.validate(&default_search_dirs()).await?;
 
-    command.arg(&file_path).current_dir(source_path);
+    let launch_command = LaunchCommand::new(
+        &executable,
+        &arguments,
+        &file_path,
+        &source_path,
+        &launch_options,
+    );
+    let working_directory = &launch_command.working_directory;
 
-    if !arguments.is_empty() {
-        // TODO: should use command.args() instead and emulator arguments should be split into separate strings
-        command.arg(&arguments);
+    if let Some(pre_launch_command) = &launch_options.pre_launch_command {
+        run_hook_command(
+            pre_launch_command,
+            working_directory,
+            &launch_options.environment,
+        )
+        .await
+        .map_err(EmulatorRunnerError::PreLaunchHookFailed)?;
     }
 
-    let status = command.status().await.map_err(|e| {
-        EmulatorRunnerError::IoError(format!("Failed to get status of emulator: {}", e))
-    })?;
+    // program resolved from the search directories, which may not all be on the system path
+    let mut command = Command::new(&program);
 
-    if !status.success() {
-        return Err(EmulatorRunnerError::IoError(format!(
-            "Emulator failed with status: {}",
-            status
-        )));
-    }
+    command
+        .args(&launch_command.args)
+        .current_dir(working_directory)
+        .envs(launch_command.environment.iter().cloned());
+
+    let emulator_result = match command.spawn() {
+        Ok(mut child) => {
+            match wait_for_process(&mut child, launch_handle.as_ref(), launch_options.timeout).await
+            {
+                Ok(ProcessExit::Exited(status)) if status.success() => Ok(()),
+                Ok(ProcessExit::Exited(status)) => {
+                    Err(EmulatorRunnerError::EmulatorFailed(status.to_string()))
+                }
+                Ok(ProcessExit::Stopped) => Err(EmulatorRunnerError::Stopped),
+                Ok(ProcessExit::TimedOut) => Err(EmulatorRunnerError::Timeout(
+                    launch_options.timeout.unwrap_or_default().as_secs(),
+                )),
+                Err(e) => Err(e),
+            }
+        }
+        Err(e) => Err(EmulatorRunnerError::IoError(format!(
+            "Failed to start emulator: {}",
+            e
+        ))),
+    };
+
+    // post-launch command is run also when emulator failed, e.g. to restore a controller profile
+    let post_launch_result = match &launch_options.post_launch_command {
+        Some(post_launch_command) => run_hook_command(
+            post_launch_command,
+            working_directory,
+            &launch_options.environment,
+        )
+        .await
+        .map_err(EmulatorRunnerError::PostLaunchHookFailed),
+        None => Ok(()),
+    };
 
-    Ok(())
+    emulator_result.and(post_launch_result)
 }
 
 #[cfg(test)]
@@ -74,19 +179,231 @@ mod tests {
         let file_name = "test.d64";
         let file_path = output_path.join(file_name);
         std::fs::write(&file_path, "test data").unwrap();
-        let executable = "echo".to_string();
-        let arguments = "Hello, world!".to_string();
+        let executable = "echo".into();
+        let arguments = vec!["Hello,".to_string(), "world!".to_string()];
         let file_names = vec![file_name.to_string()];
-        let selected_file_name = file_name.to_string();
+        let launch_mode = LaunchMode::SingleFile {
+            selected_file_name: file_name.to_string(),
+        };
         let source_path = output_path.to_path_buf();
         let result = run_with_emulator(
             executable,
             arguments,
             file_names,
-            selected_file_name,
+            launch_mode,
+            source_path,
+            LaunchOptions::default(),
+            None,
+        )
+        .await;
+        assert!(result.is_ok(), "Emulator run failed: {:?}", result);
+    }
+
+    #[async_std::test]
+    async fn test_run_with_emulator_playlist() {
+        let temp_dir = tempdir().unwrap();
+        let output_path = temp_dir.path();
+        let file_names = vec!["disk1.d64".to_string(), "disk2.d64".to_string()];
+        for file_name in &file_names {
+            std::fs::write(output_path.join(file_name), "test data").unwrap();
+        }
+        let launch_mode = LaunchMode::Playlist {
+            playlist_name: "test".to_string(),
+        };
+        let result = run_with_emulator(
+            "echo".into(),
+            vec![],
+            file_names,
+            launch_mode,
+            output_path.to_path_buf(),
+            LaunchOptions::default(),
+            None,
+        )
+        .await;
+        assert!(result.is_ok(), "Emulator run failed: {:?}", result);
+        assert!(output_path.join("test.m3u").exists());
+    }
+
+    #[cfg(unix)]
+    fn write_test_script(dir: &Path, file_name: &str, content: &str) -> Vec<String> {
+        std::fs::write(dir.join(file_name), content).unwrap();
+        vec![file_name.to_string()]
+    }
+
+    #[cfg(unix)]
+    #[async_std::test]
+    async fn test_run_with_emulator_environment_and_working_directory() {
+        let temp_dir = tempdir().unwrap();
+        let source_path = temp_dir.path().to_path_buf();
+        std::fs::create_dir(source_path.join("work")).unwrap();
+        std::fs::write(source_path.join("work").join("marker"), "").unwrap();
+        // "sh" as emulator runs the launched file as a script
+        let file_names = write_test_script(
+            &source_path,
+            "test.sh",
+            "test \"$SCM_TEST\" = \"value\" && test -f marker",
+        );
+        let launch_options = LaunchOptions {
+            environment: vec![("SCM_TEST".to_string(), "value".to_string())],
+            working_directory: Some(PathBuf::from("work")),
+            ..Default::default()
+        };
+        let result = run_with_emulator(
+            "sh".into(),
+            vec![],
+            file_names,
+            LaunchMode::SingleFile {
+                selected_file_name: "test.sh".to_string(),
+            },
             source_path,
+            launch_options,
+            None,
         )
         .await;
         assert!(result.is_ok(), "Emulator run failed: {:?}", result);
     }
+
+    #[cfg(unix)]
+    #[async_std::test]
+    async fn test_run_with_emulator_hooks() {
+        let temp_dir = tempdir().unwrap();
+        let source_path = temp_dir.path().to_path_buf();
+        let file_names = write_test_script(&source_path, "test.sh", "test -f pre_hook_ran");
+        let launch_options = LaunchOptions {
+            pre_launch_command: Some("touch pre_hook_ran".to_string()),
+            post_launch_command: Some("touch post_hook_ran".to_string()),
+            ..Default::default()
+        };
+        let result = run_with_emulator(
+            "sh".into(),
+            vec![],
+            file_names,
+            LaunchMode::SingleFile {
+                selected_file_name: "test.sh".to_string(),
+            },
+            source_path.clone(),
+            launch_options,
+            None,
+        )
+        .await;
+        assert!(result.is_ok(), "Emulator run failed: {:?}", result);
+        assert!(source_path.join("post_hook_ran").exists());
+    }
+
+    #[cfg(unix)]
+    #[async_std::test]
+    async fn test_run_with_emulator_failing_hooks() {
+        let temp_dir = tempdir().unwrap();
+        let source_path = temp_dir.path().to_path_buf();
+        let file_names = write_test_script(&source_path, "test.sh", "touch emulator_ran");
+        let launch_mode = LaunchMode::SingleFile {
+            selected_file_name: "test.sh".to_string(),
+        };
+
+        let result = run_with_emulator(
+            "sh".into(),
+            vec![],
+            file_names.clone(),
+            launch_mode.clone(),
+            source_path.clone(),
+            LaunchOptions {
+                pre_launch_command: Some("exit 1".to_string()),
+                ..Default::default()
+            },
+            None,
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(EmulatorRunnerError::PreLaunchHookFailed(_))
+        ));
+        assert!(!source_path.join("emulator_ran").exists());
+
+        let result = run_with_emulator(
+            "sh".into(),
+            vec![],
+            file_names,
+            launch_mode,
+            source_path.clone(),
+            LaunchOptions {
+                post_launch_command: Some("exit 1".to_string()),
+                ..Default::default()
+            },
+            None,
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(EmulatorRunnerError::PostLaunchHookFailed(_))
+        ));
+        assert!(source_path.join("emulator_ran").exists());
+    }
+
+    #[cfg(unix)]
+    #[async_std::test]
+    async fn test_run_with_emulator_timeout() {
+        let temp_dir = tempdir().unwrap();
+        let source_path = temp_dir.path().to_path_buf();
+        let file_names = write_test_script(&source_path, "test.sh", "sleep 10");
+        let start = std::time::Instant::now();
+        let result = run_with_emulator(
+            "sh".into(),
+            vec![],
+            file_names,
+            LaunchMode::SingleFile {
+                selected_file_name: "test.sh".to_string(),
+            },
+            source_path,
+            LaunchOptions {
+                timeout: Some(Duration::from_millis(200)),
+                ..Default::default()
+            },
+            None,
+        )
+        .await;
+        assert!(matches!(result, Err(EmulatorRunnerError::Timeout(_))));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(unix)]
+    #[async_std::test]
+    async fn test_run_with_emulator_stopped_through_registry() {
+        use process_registry::ProcessRegistry;
+
+        let temp_dir = tempdir().unwrap();
+        let source_path = temp_dir.path().to_path_buf();
+        let file_names = write_test_script(&source_path, "test.sh", "sleep 10");
+        let registry = ProcessRegistry::new();
+        let launch_handle = registry.register_launch(source_path.clone()).unwrap();
+        let launch_id = launch_handle.launch_id();
+
+        let run = async_std::task::spawn(run_with_emulator(
+            "sh".into(),
+            vec![],
+            file_names,
+            LaunchMode::SingleFile {
+                selected_file_name: "test.sh".to_string(),
+            },
+            source_path.clone(),
+            LaunchOptions::default(),
+            Some(launch_handle),
+        ));
+
+        // wait until the emulator has been started
+        while registry.running_launches()[0].process_id.is_none() {
+            async_std::task::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(registry.is_launch_directory_in_use(&source_path));
+        registry.terminate(launch_id).unwrap();
+
+        let result = async_std::future::timeout(Duration::from_secs(5), run)
+            .await
+            .expect("Emulator was not stopped");
+        assert!(
+            matches!(result, Err(EmulatorRunnerError::Stopped)),
+            "{:?}",
+            result
+        );
+        assert!(registry.running_launches().is_empty());
+    }
 }
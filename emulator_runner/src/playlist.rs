@@ -0,0 +1,177 @@
+use std::{
+    cmp::Ordering,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::error::EmulatorRunnerError;
+
+pub const M3U_EXTENSION: &str = "m3u";
+
+/// Extensions of file set archives, stripped from playlist names.
+const ARCHIVE_EXTENSIONS: [&str; 2] = ["zip", "7z"];
+
+/// Writes an m3u playlist of the given files to the output directory.
+///
+/// File names are written relative to the playlist in the order they are given, first file being
+/// the disk the emulator boots from. Emulators supporting disk swapping (e.g. RetroArch cores,
+/// PCSX, FS-UAE) get all the disks from the playlist.
+///
+/// # arguments
+/// * `output_dir`: directory where the files are exported and where the playlist is written.
+/// * `playlist_name`: name of the playlist file, `.m3u` replaces an archive extension or is
+///   appended.
+/// * `file_names`: ordered file names to be written to the playlist.
+///
+/// # returns
+/// * `Result<PathBuf, EmulatorRunnerError>`: path to the written playlist file.
+pub fn write_m3u_playlist(
+    output_dir: &Path,
+    playlist_name: &str,
+    file_names: &[String],
+) -> Result<PathBuf, EmulatorRunnerError> {
+    if file_names.is_empty() {
+        return Err(EmulatorRunnerError::NoFileSelected);
+    }
+    let playlist_path = m3u_playlist_path(output_dir, playlist_name);
+    let mut content = file_names.join("\n");
+    content.push('\n');
+    fs::write(&playlist_path, content).map_err(|e| {
+        EmulatorRunnerError::IoError(format!("Failed to write playlist file: {}", e))
+    })?;
+    Ok(playlist_path)
+}
+
+/// Path of the m3u playlist with the given name in the directory, see `m3u_playlist_file_name`.
+pub(crate) fn m3u_playlist_path(dir: &Path, playlist_name: &str) -> PathBuf {
+    dir.join(m3u_playlist_file_name(playlist_name))
+}
+
+/// File name of the m3u playlist with the given name, `.m3u` replaces an archive extension or is
+/// appended. Path separators and characters not allowed in file names are replaced with `_` and
+/// leading dots are removed, so the playlist is always written in the given directory.
+pub fn m3u_playlist_file_name(playlist_name: &str) -> String {
+    // names may contain dots, e.g. "Game v1.2", only an archive extension is replaced
+    let stem = Path::new(playlist_name)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .filter(|extension| {
+            ARCHIVE_EXTENSIONS
+                .iter()
+                .any(|archive_extension| extension.eq_ignore_ascii_case(archive_extension))
+        })
+        .map_or(playlist_name, |extension| {
+            &playlist_name[..playlist_name.len() - extension.len() - 1]
+        });
+    let stem = stem
+        .chars()
+        .map(|c| {
+            if c.is_control() || matches!(c, '/' | '\\' | '<' | '>' | ':' | '"' | '|' | '?' | '*') {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect::<String>();
+    let stem = stem.trim().trim_start_matches('.');
+    let stem = if stem.is_empty() { "playlist" } else { stem };
+    format!("{}.{}", stem, M3U_EXTENSION)
+}
+
+/// Compares file names so that numbered disks are in natural order, e.g. "Disk 2" comes before
+/// "Disk 10". Comparison of non-numeric parts is case insensitive.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+    loop {
+        match (a_chars.peek(), b_chars.peek()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a_char), Some(b_char)) if a_char.is_ascii_digit() && b_char.is_ascii_digit() => {
+                let a_number = take_number(&mut a_chars);
+                let b_number = take_number(&mut b_chars);
+                match a_number.cmp(&b_number) {
+                    Ordering::Equal => continue,
+                    ordering => return ordering,
+                }
+            }
+            (Some(a_char), Some(b_char)) => {
+                let ordering = a_char
+                    .to_ascii_lowercase()
+                    .cmp(&b_char.to_ascii_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+fn take_number(chars: &mut std::iter::Peekable<std::str::Chars>) -> u64 {
+    let mut number: u64 = 0;
+    while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+        number = number.saturating_mul(10).saturating_add(digit as u64);
+        chars.next();
+    }
+    number
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_write_m3u_playlist() {
+        let temp_dir = tempdir().unwrap();
+        let file_names = vec![
+            "Game (Disk 1).d64".to_string(),
+            "Game (Disk 2).d64".to_string(),
+        ];
+        let playlist_path = write_m3u_playlist(temp_dir.path(), "Game.zip", &file_names).unwrap();
+        assert_eq!(playlist_path, temp_dir.path().join("Game.m3u"));
+        let content = fs::read_to_string(playlist_path).unwrap();
+        assert_eq!(content, "Game (Disk 1).d64\nGame (Disk 2).d64\n");
+    }
+
+    #[test]
+    fn test_write_m3u_playlist_name_with_dots() {
+        let temp_dir = tempdir().unwrap();
+        let file_names = vec!["Game v1.2 (Disk 1).adf".to_string()];
+        let playlist_path = write_m3u_playlist(temp_dir.path(), "Game v1.2", &file_names).unwrap();
+        assert_eq!(playlist_path, temp_dir.path().join("Game v1.2.m3u"));
+        let playlist_path =
+            write_m3u_playlist(temp_dir.path(), "Game v1.2.ZIP", &file_names).unwrap();
+        assert_eq!(playlist_path, temp_dir.path().join("Game v1.2.m3u"));
+    }
+
+    #[test]
+    fn test_m3u_playlist_file_name() {
+        assert_eq!(m3u_playlist_file_name("Game v1.2"), "Game v1.2.m3u");
+        assert_eq!(m3u_playlist_file_name("Game.7z"), "Game.m3u");
+        assert_eq!(m3u_playlist_file_name("../Games/Best"), "_Games_Best.m3u");
+        assert_eq!(m3u_playlist_file_name(".."), "playlist.m3u");
+        assert_eq!(m3u_playlist_file_name("Best: 1/2"), "Best_ 1_2.m3u");
+    }
+
+    #[test]
+    fn test_write_m3u_playlist_without_files() {
+        let temp_dir = tempdir().unwrap();
+        let result = write_m3u_playlist(temp_dir.path(), "Game", &[]);
+        assert!(matches!(result, Err(EmulatorRunnerError::NoFileSelected)));
+    }
+
+    #[test]
+    fn test_natural_cmp() {
+        let mut file_names = vec![
+            "disk 10.adf".to_string(),
+            "Disk 2.adf".to_string(),
+            "disk 1.adf".to_string(),
+        ];
+        file_names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(file_names, vec!["disk 1.adf", "Disk 2.adf", "disk 10.adf"]);
+    }
+}
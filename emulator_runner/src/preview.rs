@@ -0,0 +1,428 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use file_export::{get_export_plan, ExportPlan, FileSetExportModel};
+
+use crate::{
+    error::EmulatorRunnerError, executable::EmulatorExecutable, playlist::m3u_playlist_path,
+    LaunchMode, LaunchOptions,
+};
+
+/// Fully resolved emulator process: program, argv, working directory and environment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    pub working_directory: PathBuf,
+    pub environment: Vec<(String, String)>,
+}
+
+impl LaunchCommand {
+    /// Environment variables needed by the executable kind (e.g. `WINEPREFIX`) come before the
+    /// ones from the launch options, so the launch options can override them.
+    pub fn new(
+        executable: &EmulatorExecutable,
+        arguments: &[String],
+        entry_point_path: &Path,
+        source_path: &Path,
+        launch_options: &LaunchOptions,
+    ) -> Self {
+        let executable_command = executable.command();
+        let mut args = executable_command.args;
+        args.push(entry_point_path.to_string_lossy().to_string());
+        args.extend(arguments.iter().cloned());
+        let working_directory = match &launch_options.working_directory {
+            Some(dir) => source_path.join(dir),
+            None => source_path.to_path_buf(),
+        };
+        let mut environment = executable_command.environment;
+        environment.extend(launch_options.environment.iter().cloned());
+        Self {
+            program: executable_command.program,
+            args,
+            working_directory,
+            environment,
+        }
+    }
+}
+
+impl Display for LaunchCommand {
+    /// Formats the command as a shell-like command line, e.g. `cd /tmp && FOO=bar x64 game.d64`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "cd {} &&",
+            quote(&self.working_directory.to_string_lossy())
+        )?;
+        for (name, value) in &self.environment {
+            write!(f, " {}={}", name, quote(value))?;
+        }
+        write!(f, " {}", quote(&self.program))?;
+        for arg in &self.args {
+            write!(f, " {}", quote(arg))?;
+        }
+        Ok(())
+    }
+}
+
+fn quote(value: &str) -> String {
+    if !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c))
+    {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// Playlist that would be written to launch a multi-disk file set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaylistPreview {
+    pub path: PathBuf,
+    pub file_names: Vec<String>,
+}
+
+/// Everything a launch would do, resolved without exporting files or starting processes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchPreview {
+    pub export_plan: ExportPlan,
+    pub playlist: Option<PlaylistPreview>,
+    pub pre_launch_command: Option<String>,
+    pub launch_command: LaunchCommand,
+    pub post_launch_command: Option<String>,
+}
+
+impl Display for LaunchPreview {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Export to: {}", self.export_plan.output_dir.display())?;
+        for file in &self.export_plan.files {
+            writeln!(
+                f,
+                "  {} -> {}",
+                file.source_file_path.display(),
+                file.output_file_name
+            )?;
+        }
+        if let Some(zip_file_path) = &self.export_plan.zip_file_path {
+            writeln!(f, "  zipped into {}", zip_file_path.display())?;
+        }
+        if let Some(playlist) = &self.playlist {
+            writeln!(f, "Playlist: {}", playlist.path.display())?;
+            for file_name in &playlist.file_names {
+                writeln!(f, "  {}", file_name)?;
+            }
+        }
+        if let Some(pre_launch_command) = &self.pre_launch_command {
+            writeln!(f, "Pre-launch: {}", pre_launch_command)?;
+        }
+        write!(f, "Command: {}", self.launch_command)?;
+        if let Some(post_launch_command) = &self.post_launch_command {
+            write!(f, "\nPost-launch: {}", post_launch_command)?;
+        }
+        Ok(())
+    }
+}
+
+/// Resolves what launching a file set with an emulator would do: the export plan, the playlist,
+/// the final argv, working directory and environment. Nothing is exported, written or spawned.
+///
+/// Takes the same arguments as `run_with_emulator`, with the export model in place of the source
+/// path, since the files are launched from the export output directory.
+///
+/// # errors
+/// * `EmulatorRunnerError::NoFileSelected`: If there are no files to launch.
+/// * `EmulatorRunnerError::FileNotFound`: If the selected file is not part of the export.
+pub fn preview_launch(
+    export_model: &FileSetExportModel,
+    executable: &EmulatorExecutable,
+    arguments: &[String],
+    file_names: &[String],
+    launch_mode: &LaunchMode,
+    launch_options: &LaunchOptions,
+) -> Result<LaunchPreview, EmulatorRunnerError> {
+    if file_names.is_empty() {
+        return Err(EmulatorRunnerError::NoFileSelected);
+    }
+    let export_plan = get_export_plan(export_model);
+    let source_path = &export_plan.output_dir;
+
+    let (entry_point_path, playlist) = match launch_mode {
+        LaunchMode::SingleFile { selected_file_name } => {
+            // non-extracted file set is launched as the exported zip file
+            let is_exported_zip = !export_model.extract_files
+                && *selected_file_name == export_model.exported_zip_file_name;
+            if !is_exported_zip && !file_names.contains(selected_file_name) {
+                return Err(EmulatorRunnerError::FileNotFound);
+            }
+            (source_path.join(selected_file_name), None)
+        }
+        LaunchMode::Playlist { playlist_name } => {
+            let path = m3u_playlist_path(source_path, playlist_name);
+            let playlist = PlaylistPreview {
+                path: path.clone(),
+                file_names: file_names.to_vec(),
+            };
+            (path, Some(playlist))
+        }
+    };
+
+    let launch_command = LaunchCommand::new(
+        executable,
+        arguments,
+        &entry_point_path,
+        source_path,
+        launch_options,
+    );
+
+    Ok(LaunchPreview {
+        export_plan,
+        playlist,
+        pre_launch_command: launch_options.pre_launch_command.clone(),
+        launch_command,
+        post_launch_command: launch_options.post_launch_command.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::playlist::write_m3u_playlist;
+    use file_export::OutputFile;
+    use std::collections::HashMap;
+    use tempfile::tempdir;
+
+    fn create_export_model(output_dir: &Path, file_names: &[String]) -> FileSetExportModel {
+        let output_mapping = file_names
+            .iter()
+            .map(|file_name| {
+                (
+                    format!("archive_{}", file_name),
+                    OutputFile {
+                        output_file_name: file_name.clone(),
+                        checksum: [0; 20],
+                    },
+                )
+            })
+            .collect::<HashMap<_, _>>();
+        FileSetExportModel {
+            output_mapping,
+            source_file_path: PathBuf::from("/collection/files"),
+            extract_files: true,
+            exported_zip_file_name: "game.zip".to_string(),
+            output_dir: output_dir.to_path_buf(),
+        }
+    }
+
+    #[test]
+    fn test_preview_launch() {
+        let temp_dir = tempdir().unwrap();
+        let output_dir = temp_dir.path().join("launch");
+        let file_names = vec!["game.d64".to_string()];
+        let export_model = create_export_model(&output_dir, &file_names);
+        let launch_options = LaunchOptions {
+            environment: vec![("SDL_VIDEODRIVER".to_string(), "x11".to_string())],
+            working_directory: Some(PathBuf::from("work")),
+            pre_launch_command: Some("echo pre".to_string()),
+            post_launch_command: None,
+            timeout: None,
+        };
+
+        let preview = preview_launch(
+            &export_model,
+            &"x64".into(),
+            &["-autostart".to_string()],
+            &file_names,
+            &LaunchMode::SingleFile {
+                selected_file_name: "game.d64".to_string(),
+            },
+            &launch_options,
+        )
+        .unwrap();
+
+        assert_eq!(preview.export_plan.files.len(), 1);
+        assert_eq!(preview.playlist, None);
+        assert_eq!(preview.pre_launch_command, Some("echo pre".to_string()));
+        assert_eq!(
+            preview.launch_command,
+            LaunchCommand {
+                program: "x64".to_string(),
+                args: vec![
+                    output_dir.join("game.d64").to_string_lossy().to_string(),
+                    "-autostart".to_string()
+                ],
+                working_directory: output_dir.join("work"),
+                environment: launch_options.environment.clone(),
+            }
+        );
+        // dry-run doesn't create anything
+        assert!(!output_dir.exists());
+    }
+
+    #[test]
+    fn test_preview_launch_playlist() {
+        let temp_dir = tempdir().unwrap();
+        let output_dir = temp_dir.path().to_path_buf();
+        let file_names = vec!["disk1.adf".to_string(), "disk2.adf".to_string()];
+        let export_model = create_export_model(&output_dir, &file_names);
+
+        let preview = preview_launch(
+            &export_model,
+            &"fs-uae".into(),
+            &[],
+            &file_names,
+            &LaunchMode::Playlist {
+                playlist_name: "game".to_string(),
+            },
+            &LaunchOptions::default(),
+        )
+        .unwrap();
+
+        let playlist_path = output_dir.join("game.m3u");
+        assert_eq!(
+            preview.playlist,
+            Some(PlaylistPreview {
+                path: playlist_path.clone(),
+                file_names: file_names.clone(),
+            })
+        );
+        assert_eq!(
+            preview.launch_command.args,
+            vec![playlist_path.to_string_lossy().to_string()]
+        );
+        assert!(!playlist_path.exists());
+    }
+
+    #[test]
+    fn test_preview_launch_playlist_dotted_name() {
+        let temp_dir = tempdir().unwrap();
+        let output_dir = temp_dir.path().to_path_buf();
+        let file_names = vec!["disk1.adf".to_string(), "disk2.adf".to_string()];
+        let export_model = create_export_model(&output_dir, &file_names);
+
+        let preview = preview_launch(
+            &export_model,
+            &"fs-uae".into(),
+            &[],
+            &file_names,
+            &LaunchMode::Playlist {
+                playlist_name: "Game v1.2".to_string(),
+            },
+            &LaunchOptions::default(),
+        )
+        .unwrap();
+
+        // same path as the playlist written by the launch
+        assert_eq!(
+            preview.playlist.unwrap().path,
+            output_dir.join("Game v1.2.m3u")
+        );
+        assert_eq!(
+            write_m3u_playlist(&output_dir, "Game v1.2", &file_names).unwrap(),
+            output_dir.join("Game v1.2.m3u")
+        );
+    }
+
+    #[test]
+    fn test_preview_launch_unknown_file() {
+        let temp_dir = tempdir().unwrap();
+        let file_names = vec!["game.d64".to_string()];
+        let export_model = create_export_model(temp_dir.path(), &file_names);
+        let result = preview_launch(
+            &export_model,
+            &"x64".into(),
+            &[],
+            &file_names,
+            &LaunchMode::SingleFile {
+                selected_file_name: "other.d64".to_string(),
+            },
+            &LaunchOptions::default(),
+        );
+        assert!(matches!(result, Err(EmulatorRunnerError::FileNotFound)));
+    }
+
+    #[test]
+    fn test_preview_launch_zipped() {
+        let temp_dir = tempdir().unwrap();
+        let file_names = vec!["game.d64".to_string()];
+        let mut export_model = create_export_model(temp_dir.path(), &file_names);
+        export_model.extract_files = false;
+        let preview = preview_launch(
+            &export_model,
+            &"x64".into(),
+            &[],
+            &file_names,
+            &LaunchMode::SingleFile {
+                selected_file_name: "game.zip".to_string(),
+            },
+            &LaunchOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            preview.export_plan.zip_file_path,
+            Some(temp_dir.path().join("game.zip"))
+        );
+    }
+
+    #[test]
+    fn test_preview_launch_wine() {
+        let temp_dir = tempdir().unwrap();
+        let file_names = vec!["game.adf".to_string()];
+        let export_model = create_export_model(temp_dir.path(), &file_names);
+        let executable = EmulatorExecutable::Wine {
+            executable: PathBuf::from("/emulators/WinUAE.exe"),
+            prefix: Some(PathBuf::from("/wine/winuae")),
+        };
+        let launch_options = LaunchOptions {
+            environment: vec![("WINEDEBUG".to_string(), "-all".to_string())],
+            ..Default::default()
+        };
+        let preview = preview_launch(
+            &export_model,
+            &executable,
+            &[],
+            &file_names,
+            &LaunchMode::SingleFile {
+                selected_file_name: "game.adf".to_string(),
+            },
+            &launch_options,
+        )
+        .unwrap();
+        assert_eq!(preview.launch_command.program, "wine");
+        assert_eq!(
+            preview.launch_command.args,
+            vec![
+                "/emulators/WinUAE.exe".to_string(),
+                temp_dir
+                    .path()
+                    .join("game.adf")
+                    .to_string_lossy()
+                    .to_string()
+            ]
+        );
+        assert_eq!(
+            preview.launch_command.environment,
+            vec![
+                ("WINEPREFIX".to_string(), "/wine/winuae".to_string()),
+                ("WINEDEBUG".to_string(), "-all".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_launch_command_display() {
+        let launch_command = LaunchCommand {
+            program: "x64".to_string(),
+            args: vec!["/tmp/My Game.d64".to_string(), "-autostart".to_string()],
+            working_directory: PathBuf::from("/tmp"),
+            environment: vec![("FOO".to_string(), "it's".to_string())],
+        };
+        assert_eq!(
+            launch_command.to_string(),
+            "cd /tmp && FOO='it'\\''s' x64 '/tmp/My Game.d64' -autostart"
+        );
+    }
+}
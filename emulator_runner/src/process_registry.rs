@@ -0,0 +1,312 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::ExitStatus,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use async_process::Child;
+
+use crate::error::EmulatorRunnerError;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub type LaunchId = u64;
+
+/// How a running emulator is asked to stop.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StopRequest {
+    /// Ask the emulator to exit (SIGTERM on unix), lets the emulator e.g. flush save files.
+    Terminate,
+    /// Kill the emulator process immediately.
+    Kill,
+}
+
+/// Information about a registered launch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunningLaunch {
+    pub launch_id: LaunchId,
+    /// Directory where the files are exported for the launch.
+    pub launch_directory: PathBuf,
+    /// Process id of the emulator, set once the emulator has been started.
+    pub process_id: Option<u32>,
+}
+
+#[derive(Debug)]
+struct LaunchEntry {
+    launch: RunningLaunch,
+    stop_request: Option<StopRequest>,
+}
+
+#[derive(Debug, Default)]
+struct RegistryState {
+    next_launch_id: LaunchId,
+    launches: HashMap<LaunchId, LaunchEntry>,
+}
+
+/// Keeps track of running emulator launches.
+///
+/// A launch is registered before the files are exported to the launch directory, the registry
+/// refuses to register another launch to a directory that is still in use. The returned
+/// `LaunchHandle` is given to `run_with_emulator`, and the launch is unregistered when the handle
+/// is dropped.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessRegistry {
+    state: Arc<Mutex<RegistryState>>,
+}
+
+impl ProcessRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, RegistryState> {
+        // state is kept consistent on every update, so it's safe to use even if poisoned
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Registers a new launch using the given launch directory.
+    ///
+    /// # errors
+    /// * `EmulatorRunnerError::LaunchDirectoryInUse`: If a running launch uses the same directory.
+    pub fn register_launch(
+        &self,
+        launch_directory: PathBuf,
+    ) -> Result<LaunchHandle, EmulatorRunnerError> {
+        let mut state = self.lock();
+        if state
+            .launches
+            .values()
+            .any(|entry| entry.launch.launch_directory == launch_directory)
+        {
+            return Err(EmulatorRunnerError::LaunchDirectoryInUse(
+                launch_directory.display().to_string(),
+            ));
+        }
+        state.next_launch_id += 1;
+        let launch_id = state.next_launch_id;
+        state.launches.insert(
+            launch_id,
+            LaunchEntry {
+                launch: RunningLaunch {
+                    launch_id,
+                    launch_directory,
+                    process_id: None,
+                },
+                stop_request: None,
+            },
+        );
+        Ok(LaunchHandle {
+            launch_id,
+            registry: self.clone(),
+        })
+    }
+
+    pub fn running_launches(&self) -> Vec<RunningLaunch> {
+        let mut launches = self
+            .lock()
+            .launches
+            .values()
+            .map(|entry| entry.launch.clone())
+            .collect::<Vec<_>>();
+        launches.sort_by_key(|launch| launch.launch_id);
+        launches
+    }
+
+    pub fn is_launch_directory_in_use(&self, launch_directory: &Path) -> bool {
+        self.lock()
+            .launches
+            .values()
+            .any(|entry| entry.launch.launch_directory == launch_directory)
+    }
+
+    /// Asks the emulator of the given launch to exit.
+    pub fn terminate(&self, launch_id: LaunchId) -> Result<(), EmulatorRunnerError> {
+        self.request_stop(launch_id, StopRequest::Terminate)
+    }
+
+    /// Kills the emulator of the given launch.
+    pub fn kill(&self, launch_id: LaunchId) -> Result<(), EmulatorRunnerError> {
+        self.request_stop(launch_id, StopRequest::Kill)
+    }
+
+    fn request_stop(
+        &self,
+        launch_id: LaunchId,
+        stop_request: StopRequest,
+    ) -> Result<(), EmulatorRunnerError> {
+        let mut state = self.lock();
+        let entry = state
+            .launches
+            .get_mut(&launch_id)
+            .ok_or(EmulatorRunnerError::LaunchNotFound(launch_id))?;
+        entry.stop_request = Some(stop_request);
+        Ok(())
+    }
+
+    fn set_process_id(&self, launch_id: LaunchId, process_id: u32) {
+        if let Some(entry) = self.lock().launches.get_mut(&launch_id) {
+            entry.launch.process_id = Some(process_id);
+        }
+    }
+
+    fn take_stop_request(&self, launch_id: LaunchId) -> Option<StopRequest> {
+        self.lock()
+            .launches
+            .get_mut(&launch_id)
+            .and_then(|entry| entry.stop_request.take())
+    }
+
+    fn unregister(&self, launch_id: LaunchId) {
+        self.lock().launches.remove(&launch_id);
+    }
+}
+
+/// Registration of a single launch, unregisters the launch when dropped.
+#[derive(Debug)]
+pub struct LaunchHandle {
+    launch_id: LaunchId,
+    registry: ProcessRegistry,
+}
+
+impl LaunchHandle {
+    pub fn launch_id(&self) -> LaunchId {
+        self.launch_id
+    }
+}
+
+impl Drop for LaunchHandle {
+    fn drop(&mut self) {
+        self.registry.unregister(self.launch_id);
+    }
+}
+
+/// How the wait for the emulator process ended.
+#[derive(Debug)]
+pub(crate) enum ProcessExit {
+    Exited(ExitStatus),
+    Stopped,
+    TimedOut,
+}
+
+/// Waits until the emulator process exits, the launch is stopped through the registry or the
+/// timeout is reached. The process is killed when the timeout is reached.
+pub(crate) async fn wait_for_process(
+    child: &mut Child,
+    launch_handle: Option<&LaunchHandle>,
+    timeout: Option<Duration>,
+) -> Result<ProcessExit, EmulatorRunnerError> {
+    if let Some(launch_handle) = launch_handle {
+        launch_handle
+            .registry
+            .set_process_id(launch_handle.launch_id, child.id());
+    }
+    let started = Instant::now();
+    let mut stopped = false;
+    let mut timed_out = false;
+    loop {
+        let status = child.try_status().map_err(|e| {
+            EmulatorRunnerError::IoError(format!("Failed to get status of emulator: {}", e))
+        })?;
+        if let Some(status) = status {
+            return Ok(match (stopped, timed_out) {
+                (_, true) => ProcessExit::TimedOut,
+                (true, _) => ProcessExit::Stopped,
+                _ => ProcessExit::Exited(status),
+            });
+        }
+
+        let stop_request = launch_handle.and_then(|launch_handle| {
+            launch_handle
+                .registry
+                .take_stop_request(launch_handle.launch_id)
+        });
+        if let Some(stop_request) = stop_request {
+            stopped = true;
+            stop_process(child, stop_request).await?;
+        } else if !timed_out && timeout.is_some_and(|timeout| started.elapsed() >= timeout) {
+            timed_out = true;
+            stop_process(child, StopRequest::Kill).await?;
+        }
+
+        async_std::task::sleep(POLL_INTERVAL).await;
+    }
+}
+
+async fn stop_process(
+    child: &mut Child,
+    stop_request: StopRequest,
+) -> Result<(), EmulatorRunnerError> {
+    match stop_request {
+        #[cfg(unix)]
+        StopRequest::Terminate => {
+            async_process::Command::new("kill")
+                .arg("-TERM")
+                .arg(child.id().to_string())
+                .status()
+                .await
+                .map_err(|e| {
+                    EmulatorRunnerError::IoError(format!("Failed to terminate emulator: {}", e))
+                })?;
+            Ok(())
+        }
+        // no graceful termination available, emulator is killed instead
+        #[cfg(not(unix))]
+        StopRequest::Terminate => kill_process(child),
+        StopRequest::Kill => kill_process(child),
+    }
+}
+
+fn kill_process(child: &mut Child) -> Result<(), EmulatorRunnerError> {
+    child
+        .kill()
+        .map_err(|e| EmulatorRunnerError::IoError(format!("Failed to kill emulator: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_launch() {
+        let registry = ProcessRegistry::new();
+        let launch_directory = PathBuf::from("/tmp/launch/1");
+
+        let launch_handle = registry.register_launch(launch_directory.clone()).unwrap();
+        assert!(registry.is_launch_directory_in_use(&launch_directory));
+
+        let result = registry.register_launch(launch_directory.clone());
+        assert!(matches!(
+            result,
+            Err(EmulatorRunnerError::LaunchDirectoryInUse(_))
+        ));
+
+        let other_handle = registry
+            .register_launch(PathBuf::from("/tmp/launch/2"))
+            .unwrap();
+        assert_eq!(registry.running_launches().len(), 2);
+
+        drop(launch_handle);
+        assert!(!registry.is_launch_directory_in_use(&launch_directory));
+        assert_eq!(
+            registry.running_launches(),
+            vec![RunningLaunch {
+                launch_id: other_handle.launch_id(),
+                launch_directory: PathBuf::from("/tmp/launch/2"),
+                process_id: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_stop_unknown_launch() {
+        let registry = ProcessRegistry::new();
+        assert!(matches!(
+            registry.kill(42),
+            Err(EmulatorRunnerError::LaunchNotFound(42))
+        ));
+    }
+}
@@ -30,6 +30,7 @@ pub struct OutputFile {
     pub checksum: Sha1Checksum,
 }
 
+#[derive(Debug)]
 pub struct FileSetExportModel {
     pub output_mapping: HashMap<String, OutputFile>,
     pub source_file_path: PathBuf,
@@ -38,13 +39,118 @@ pub struct FileSetExportModel {
     pub output_dir: PathBuf,
 }
 
+/// A single file to be written when a file set is exported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedExportFile {
+    pub source_file_path: PathBuf,
+    pub output_file_name: String,
+}
+
+/// Describes what exporting a file set would write, without touching the file system.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportPlan {
+    pub output_dir: PathBuf,
+    /// Files in the order of output file names.
+    pub files: Vec<PlannedExportFile>,
+    /// Path of the zip archive the files are written into, when files are not extracted.
+    pub zip_file_path: Option<PathBuf>,
+}
+
+/// Resolves the export plan for the given export model. Nothing is exported.
+pub fn get_export_plan(export_model: &FileSetExportModel) -> ExportPlan {
+    let mut files = export_model
+        .output_mapping
+        .iter()
+        .map(|(archive_file_name, output_file)| PlannedExportFile {
+            source_file_path: export_model
+                .source_file_path
+                .join(archive_file_name)
+                .with_extension("zst"),
+            output_file_name: output_file.output_file_name.clone(),
+        })
+        .collect::<Vec<_>>();
+    files.sort_by(|a, b| a.output_file_name.cmp(&b.output_file_name));
+
+    let zip_file_path = if export_model.extract_files {
+        None
+    } else {
+        Some(
+            export_model
+                .output_dir
+                .join(&export_model.exported_zip_file_name),
+        )
+    };
+
+    ExportPlan {
+        output_dir: export_model.output_dir.clone(),
+        files,
+        zip_file_path,
+    }
+}
+
+/// Outcome of verifying a single collection file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileVerificationStatus {
+    Ok,
+    Missing,
+    ChecksumMismatch,
+    /// File exists but couldn't be decompressed.
+    Unreadable(String),
+}
+
+/// Collection file of a file set checked against its SHA1 checksum.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileVerification {
+    pub source_file_path: PathBuf,
+    pub output_file_name: String,
+    pub status: FileVerificationStatus,
+}
+
+impl FileVerification {
+    pub fn is_ok(&self) -> bool {
+        self.status == FileVerificationStatus::Ok
+    }
+}
+
+/// Checks that the files of the export model are found in the collection and decompress to
+/// their SHA1 checksums. Nothing is exported, results are in the order of output file names.
+pub fn verify_files(export_model: &FileSetExportModel) -> Vec<FileVerification> {
+    let mut verifications = export_model
+        .output_mapping
+        .iter()
+        .map(|(archive_file_name, output_file)| {
+            let source_file_path = export_model
+                .source_file_path
+                .join(archive_file_name)
+                .with_extension("zst");
+            let status = if !source_file_path.is_file() {
+                FileVerificationStatus::Missing
+            } else {
+                match zstd_file_checksum(&source_file_path) {
+                    Ok(checksum) if checksum == output_file.checksum => FileVerificationStatus::Ok,
+                    Ok(_) => FileVerificationStatus::ChecksumMismatch,
+                    Err(e) => FileVerificationStatus::Unreadable(e.to_string()),
+                }
+            };
+            FileVerification {
+                source_file_path,
+                output_file_name: output_file.output_file_name.clone(),
+                status,
+            }
+        })
+        .collect::<Vec<_>>();
+    verifications.sort_by(|a, b| a.output_file_name.cmp(&b.output_file_name));
+    verifications
+}
+
+/// Writes the files as they are when `extract_files` is set, otherwise into a zip archive.
 pub fn export_files_zipped_or_non_zipped(
     export_model: &FileSetExportModel,
 ) -> Result<(), FileExportError> {
     if export_model.extract_files {
-        export_files_zipped(export_model)
-    } else {
         export_files(export_model)
+    } else {
+        export_files_zipped(export_model)
     }
 }
 
@@ -167,10 +273,6 @@ fn decompress_zstd_file(
     let file = File::open(input_path)?;
     let mut zstd_reader = zstd::Decoder::new(file)?;
     if let Some(parent) = output_path.parent() {
-        println!(
-            "Creating parent directory: {}",
-            parent.to_str().unwrap_or("Invalid path")
-        );
         std::fs::create_dir_all(parent)?;
     }
     let mut output_file = File::create(output_path)?;
@@ -188,6 +290,14 @@ fn decompress_zstd_to_writer(
     Ok(())
 }
 
+fn zstd_file_checksum(input_path: &Path) -> Result<Sha1Checksum, Box<dyn std::error::Error>> {
+    let file = File::open(input_path)?;
+    let mut zstd_reader = zstd::Decoder::new(file)?;
+    let mut hasher = Sha1::new();
+    std::io::copy(&mut zstd_reader, &mut hasher)?;
+    Ok(hasher.finalize().into())
+}
+
 fn check_file_checksum(
     file_path: &Path,
     expected_checksum: &Sha1Checksum,
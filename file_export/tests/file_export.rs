@@ -5,7 +5,10 @@ use std::{
 };
 
 use core_types::Sha1Checksum;
-use file_export::{export_files, export_files_zipped, FileSetExportModel, OutputFile};
+use file_export::{
+    export_files, export_files_zipped, export_files_zipped_or_non_zipped, get_export_plan,
+    verify_files, FileSetExportModel, FileVerificationStatus, OutputFile,
+};
 use tempfile::tempdir;
 use utils::test_utils::get_sha1_and_size;
 
@@ -78,6 +81,134 @@ fn test_export_files_zipped() {
     // Note: The temporary directory will be automatically deleted when it goes out of scope
 }
 
+#[test]
+fn test_export_files_zipped_or_non_zipped() {
+    let temp_dir = tempdir().unwrap();
+    let input_dir = temp_dir.path().join(TEST_INPUT_FOLDER);
+    let output_dir = temp_dir.path().join(TEST_OUTPUT_FOLDER);
+    fs::create_dir_all(&input_dir).unwrap();
+    fs::create_dir_all(&output_dir).unwrap();
+    create_sample_compressed_file(&input_dir, TEST_FILE_NAME);
+
+    let mut export_model = FileSetExportModel {
+        output_mapping: prepare_file_mappings(),
+        source_file_path: input_dir,
+        extract_files: true,
+        exported_zip_file_name: "exported_files.zip".to_string(),
+        output_dir: output_dir.clone(),
+    };
+
+    // extracted files are written as they are, without a zip archive
+    export_files_zipped_or_non_zipped(&export_model).unwrap();
+    assert_eq!(
+        fs::read_to_string(output_dir.join(TEST_OUTPUT_FILE_NAME)).unwrap(),
+        TEST_FILE_CONTENT
+    );
+    assert!(!output_dir.join("exported_files.zip").exists());
+
+    fs::remove_file(output_dir.join(TEST_OUTPUT_FILE_NAME)).unwrap();
+    export_model.extract_files = false;
+    export_files_zipped_or_non_zipped(&export_model).unwrap();
+    assert!(!output_dir.join(TEST_OUTPUT_FILE_NAME).exists());
+    let mut zip_reader =
+        zip::ZipArchive::new(File::open(output_dir.join("exported_files.zip")).unwrap()).unwrap();
+    let mut zip_content = String::new();
+    zip_reader
+        .by_name(TEST_OUTPUT_FILE_NAME)
+        .unwrap()
+        .read_to_string(&mut zip_content)
+        .unwrap();
+    assert_eq!(zip_content, TEST_FILE_CONTENT);
+}
+
+#[test]
+fn test_get_export_plan() {
+    let temp_dir = tempdir().unwrap();
+    let input_dir = temp_dir.path().join(TEST_INPUT_FOLDER);
+    let output_dir = temp_dir.path().join(TEST_OUTPUT_FOLDER);
+
+    let mut export_model = FileSetExportModel {
+        output_mapping: prepare_file_mappings(),
+        source_file_path: input_dir.clone(),
+        extract_files: true,
+        exported_zip_file_name: "exported_files.zip".to_string(),
+        output_dir: output_dir.clone(),
+    };
+
+    let export_plan = get_export_plan(&export_model);
+    assert_eq!(export_plan.output_dir, output_dir);
+    assert_eq!(export_plan.zip_file_path, None);
+    assert_eq!(export_plan.files.len(), 1);
+    assert_eq!(
+        export_plan.files[0].source_file_path,
+        input_dir.join(format!("{}.zst", TEST_FILE_NAME))
+    );
+    assert_eq!(export_plan.files[0].output_file_name, TEST_OUTPUT_FILE_NAME);
+
+    export_model.extract_files = false;
+    let export_plan = get_export_plan(&export_model);
+    assert_eq!(
+        export_plan.zip_file_path,
+        Some(output_dir.join("exported_files.zip"))
+    );
+    // nothing is written
+    assert!(!output_dir.exists());
+}
+
+#[test]
+fn test_verify_files() {
+    let temp_dir = tempdir().unwrap();
+    let input_dir = temp_dir.path().join(TEST_INPUT_FOLDER);
+    fs::create_dir_all(&input_dir).unwrap();
+    create_sample_compressed_file(&input_dir, TEST_FILE_NAME);
+
+    let mut output_mapping = prepare_file_mappings();
+    output_mapping.insert(
+        "missing_file".to_string(),
+        OutputFile {
+            output_file_name: "a_missing_file".to_string(),
+            checksum: [0; 20],
+        },
+    );
+    let export_model = FileSetExportModel {
+        output_mapping,
+        source_file_path: input_dir.clone(),
+        extract_files: true,
+        exported_zip_file_name: "exported_files.zip".to_string(),
+        output_dir: temp_dir.path().join(TEST_OUTPUT_FOLDER),
+    };
+
+    let verifications = verify_files(&export_model);
+    assert_eq!(verifications.len(), 2);
+    assert_eq!(verifications[0].output_file_name, "a_missing_file");
+    assert_eq!(verifications[0].status, FileVerificationStatus::Missing);
+    assert_eq!(verifications[1].output_file_name, TEST_OUTPUT_FILE_NAME);
+    assert!(verifications[1].is_ok());
+
+    // corrupted file in the collection
+    let mut encoder = zstd::Encoder::new(
+        File::create(input_dir.join(format!("{}.zst", TEST_FILE_NAME))).unwrap(),
+        0,
+    )
+    .unwrap();
+    write!(encoder, "Hello, corrupted world!").unwrap();
+    encoder.finish().unwrap();
+    assert_eq!(
+        verify_files(&export_model)[1].status,
+        FileVerificationStatus::ChecksumMismatch
+    );
+
+    fs::write(
+        input_dir.join(format!("{}.zst", TEST_FILE_NAME)),
+        "not zstd",
+    )
+    .unwrap();
+    assert!(matches!(
+        verify_files(&export_model)[1].status,
+        FileVerificationStatus::Unreadable(_)
+    ));
+}
+
 fn create_sample_compressed_file(
     input_dir: &std::path::Path,
     file_name: &str,
@@ -44,9 +44,11 @@ impl Display for FileImportError {
 
 pub fn get_compression_level(file_type: &FileType) -> CompressionLevel {
     match file_type {
-        FileType::Rom | FileType::DiskImage | FileType::TapeImage | FileType::MemorySnapshot => {
-            CompressionLevel::Good
-        }
+        FileType::Rom
+        | FileType::DiskImage
+        | FileType::TapeImage
+        | FileType::MemorySnapshot
+        | FileType::Firmware => CompressionLevel::Good,
         FileType::Screenshot
         | FileType::Manual
         | FileType::CoverScan
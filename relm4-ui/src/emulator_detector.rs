@@ -0,0 +1,192 @@
+use std::sync::Arc;
+
+use database::{database_error::Error as DatabaseError, repository_manager::RepositoryManager};
+use emulator_runner::emulator_detection::default_search_dirs;
+use relm4::{
+    Component, ComponentParts, ComponentSender,
+    gtk::{
+        self,
+        prelude::{ButtonExt, GtkWindowExt, OrientableExt, WidgetExt},
+    },
+    typed_view::list::TypedListView,
+};
+use service::{
+    error::Error as ServiceError,
+    view_model_service::ViewModelService,
+    view_models::{EmulatorListModel, EmulatorProposal},
+};
+
+use crate::list_item::ListItem;
+
+#[derive(Debug)]
+pub enum EmulatorDetectorMsg {
+    DetectEmulators,
+    AddClicked,
+}
+
+#[derive(Debug)]
+pub enum EmulatorDetectorOutputMsg {
+    EmulatorAdded(EmulatorListModel),
+}
+
+#[derive(Debug)]
+pub enum EmulatorDetectorCommandMsg {
+    EmulatorsDetected(Result<Vec<EmulatorProposal>, ServiceError>),
+    EmulatorAdded(Result<EmulatorListModel, DatabaseError>),
+}
+
+pub struct EmulatorDetectorInit {
+    pub view_model_service: Arc<ViewModelService>,
+    pub repository_manager: Arc<RepositoryManager>,
+}
+
+#[derive(Debug)]
+pub struct EmulatorDetectorModel {
+    view_model_service: Arc<ViewModelService>,
+    repository_manager: Arc<RepositoryManager>,
+    proposals: Vec<EmulatorProposal>,
+    list_view_wrapper: TypedListView<ListItem, gtk::SingleSelection>,
+}
+
+#[relm4::component(pub)]
+impl Component for EmulatorDetectorModel {
+    type Input = EmulatorDetectorMsg;
+    type Output = EmulatorDetectorOutputMsg;
+    type CommandOutput = EmulatorDetectorCommandMsg;
+    type Init = EmulatorDetectorInit;
+
+    view! {
+        #[root]
+        gtk::Window {
+            set_default_width: 800,
+            set_default_height: 600,
+            set_title: Some("Detected Emulators"),
+            gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+
+                gtk::Label {
+                    set_label: "Installed emulators supporting systems in the collection",
+                },
+
+                gtk::ScrolledWindow {
+                    set_vexpand: true,
+                    #[local_ref]
+                    proposals_list_view -> gtk::ListView {}
+                },
+
+                gtk::Button {
+                    set_label: "Detect Again",
+                    connect_clicked => EmulatorDetectorMsg::DetectEmulators,
+                },
+
+                gtk::Button {
+                    set_label: "Add Emulator",
+                    connect_clicked => EmulatorDetectorMsg::AddClicked,
+                    #[watch]
+                    set_sensitive: !model.proposals.is_empty(),
+                },
+            }
+        }
+    }
+
+    fn init(
+        init_model: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = EmulatorDetectorModel {
+            view_model_service: init_model.view_model_service,
+            repository_manager: init_model.repository_manager,
+            proposals: Vec::new(),
+            list_view_wrapper: TypedListView::new(),
+        };
+
+        let proposals_list_view = &model.list_view_wrapper.view;
+        let widgets = view_output!();
+
+        sender.input(EmulatorDetectorMsg::DetectEmulators);
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>, _: &Self::Root) {
+        match msg {
+            EmulatorDetectorMsg::DetectEmulators => {
+                println!("Detecting emulators...");
+                let view_model_service = Arc::clone(&self.view_model_service);
+                sender.oneshot_command(async move {
+                    let result = view_model_service
+                        .get_emulator_proposals(&default_search_dirs())
+                        .await;
+                    EmulatorDetectorCommandMsg::EmulatorsDetected(result)
+                });
+            }
+            EmulatorDetectorMsg::AddClicked => {
+                let selected = self.list_view_wrapper.selection_model.selected();
+                if let Some(proposal) = self.proposals.get(selected as usize).cloned() {
+                    println!("Adding detected emulator: {}", proposal);
+                    let repository_manager = Arc::clone(&self.repository_manager);
+                    sender.oneshot_command(async move {
+                        let result = repository_manager
+                            .get_emulator_repository()
+                            .add_emulator_with_systems(
+                                proposal.name.clone(),
+                                proposal.executable,
+                                proposal.extract_files,
+                                proposal.systems,
+                                Default::default(),
+                            )
+                            .await
+                            .map(|id| EmulatorListModel {
+                                id,
+                                name: proposal.name,
+                            });
+                        EmulatorDetectorCommandMsg::EmulatorAdded(result)
+                    });
+                } else {
+                    eprintln!("No emulator found at selected index {}", selected);
+                }
+            }
+        }
+    }
+
+    fn update_cmd(
+        &mut self,
+        message: Self::CommandOutput,
+        sender: ComponentSender<Self>,
+        _: &Self::Root,
+    ) {
+        match message {
+            EmulatorDetectorCommandMsg::EmulatorsDetected(Ok(proposals)) => {
+                println!("Detected {} emulators", proposals.len());
+                self.list_view_wrapper.clear();
+                // proposals are identified by their index
+                let list_items = proposals
+                    .iter()
+                    .enumerate()
+                    .map(|(index, proposal)| ListItem {
+                        id: index as i64,
+                        name: proposal.to_string(),
+                    });
+                self.list_view_wrapper.extend_from_iter(list_items);
+                self.proposals = proposals;
+            }
+            EmulatorDetectorCommandMsg::EmulatorsDetected(Err(error)) => {
+                eprintln!("Error detecting emulators: {:?}", error);
+                // TODO: show error to user
+            }
+            EmulatorDetectorCommandMsg::EmulatorAdded(Ok(emulator_list_model)) => {
+                println!("Added detected emulator: {}", emulator_list_model.name);
+                if let Err(error) = sender.output(EmulatorDetectorOutputMsg::EmulatorAdded(
+                    emulator_list_model,
+                )) {
+                    eprintln!("Sending message failed: {:?}", error);
+                }
+                sender.input(EmulatorDetectorMsg::DetectEmulators);
+            }
+            EmulatorDetectorCommandMsg::EmulatorAdded(Err(error)) => {
+                eprintln!("Error adding emulator: {:?}", error);
+                // TODO: show error to user
+            }
+        }
+    }
+}
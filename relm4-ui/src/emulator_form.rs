@@ -1,7 +1,11 @@
 use std::{collections::HashMap, sync::Arc};
 
 use database::{
-    database_error::Error, models::EmulatorSystemUpdateModel, repository_manager::RepositoryManager,
+    database_error::Error,
+    models::{
+        EmulatorLaunchSettings, EmulatorSystemUpdateModel, EnvironmentVariable, ExecutableKind,
+    },
+    repository_manager::RepositoryManager,
 };
 use relm4::{
     Component, ComponentController, ComponentParts, ComponentSender, Controller, FactorySender,
@@ -26,6 +30,13 @@ use crate::{
     system_selector::{SystemSelectInit, SystemSelectModel, SystemSelectOutputMsg},
 };
 
+const EXECUTABLE_KINDS: [ExecutableKind; 4] = [
+    ExecutableKind::Binary,
+    ExecutableKind::Flatpak,
+    ExecutableKind::AppImage,
+    ExecutableKind::Wine,
+];
+
 #[derive(Debug)]
 pub struct CommandLineArgument {
     value: String,
@@ -83,6 +94,8 @@ impl FactoryComponent for CommandLineArgument {
 #[derive(Debug)]
 pub enum EmulatorFormMsg {
     ExecutableChanged(String),
+    ExecutableKindSelected { index: u32 },
+    WinePrefixChanged(String),
     NameChanged(String),
     ExtractFilesToggled,
     SystemSelected(SystemListModel),
@@ -90,6 +103,12 @@ pub enum EmulatorFormMsg {
     OpenSystemSelector,
     AddCommandLineArgument(String),
     DeleteCommandLineArgument(DynamicIndex),
+    WorkingDirectoryChanged(String),
+    PreLaunchCommandChanged(String),
+    PostLaunchCommandChanged(String),
+    LaunchTimeoutChanged(String),
+    AddEnvironmentVariable(String),
+    ClearEnvironmentVariables,
     Submit,
 }
 
@@ -121,6 +140,7 @@ pub struct EmulatorFormModel {
     pub command_line_arguments: FactoryVecDeque<CommandLineArgument>,
     pub currently_selected_system: Option<SystemListModel>,
     pub system_arguments: HashMap<i64, Vec<String>>,
+    pub launch_settings: EmulatorLaunchSettings,
 }
 
 #[relm4::component(pub)]
@@ -159,7 +179,7 @@ impl Component for EmulatorFormModel {
                 },
                 gtk::Entry {
                     set_text: &model.executable,
-                    set_placeholder_text: Some("Emulator executable"),
+                    set_placeholder_text: Some("Emulator executable, Flatpak application id or path to AppImage or Windows executable"),
                     connect_activate[sender] => move |entry| {
                         let buffer = entry.buffer();
                         sender.input(
@@ -168,12 +188,110 @@ impl Component for EmulatorFormModel {
                     },
                 },
 
+                #[local_ref]
+                executable_kind_dropdown -> gtk::DropDown {
+                    connect_selected_notify[sender] => move |dropdown| {
+                        sender.input(EmulatorFormMsg::ExecutableKindSelected {
+                            index: dropdown.selected(),
+                        });
+                    },
+                },
+
+                gtk::Label {
+                    set_label: "Wine prefix",
+                },
+                gtk::Entry {
+                    #[watch]
+                    set_sensitive: model.launch_settings.executable_kind == ExecutableKind::Wine,
+                    set_placeholder_text: Some("Defaults to the Wine default prefix"),
+                    connect_activate[sender] => move |entry| {
+                        let buffer = entry.buffer();
+                        sender.input(EmulatorFormMsg::WinePrefixChanged(buffer.text().into()));
+                    },
+                },
+
                 gtk::CheckButton {
                     set_label: Some("Extract files"),
                     set_active: model.extract_files,
                     connect_toggled => EmulatorFormMsg::ExtractFilesToggled
                 },
 
+                gtk::Label {
+                    set_label: "Working directory",
+                },
+                gtk::Entry {
+                    set_placeholder_text: Some("Defaults to the launch directory"),
+                    connect_activate[sender] => move |entry| {
+                        let buffer = entry.buffer();
+                        sender.input(
+                            EmulatorFormMsg::WorkingDirectoryChanged(buffer.text().into()),
+                        );
+                    },
+                },
+
+                gtk::Label {
+                    set_label: "Pre-launch command",
+                },
+                gtk::Entry {
+                    set_placeholder_text: Some("Command run before the emulator starts"),
+                    connect_activate[sender] => move |entry| {
+                        let buffer = entry.buffer();
+                        sender.input(
+                            EmulatorFormMsg::PreLaunchCommandChanged(buffer.text().into()),
+                        );
+                    },
+                },
+
+                gtk::Label {
+                    set_label: "Post-launch command",
+                },
+                gtk::Entry {
+                    set_placeholder_text: Some("Command run after the emulator exits"),
+                    connect_activate[sender] => move |entry| {
+                        let buffer = entry.buffer();
+                        sender.input(
+                            EmulatorFormMsg::PostLaunchCommandChanged(buffer.text().into()),
+                        );
+                    },
+                },
+
+                gtk::Label {
+                    set_label: "Launch timeout (seconds)",
+                },
+                gtk::Entry {
+                    set_placeholder_text: Some("Emulator is killed after the timeout"),
+                    connect_activate[sender] => move |entry| {
+                        let buffer = entry.buffer();
+                        sender.input(EmulatorFormMsg::LaunchTimeoutChanged(buffer.text().into()));
+                    },
+                },
+
+                gtk::Label {
+                    set_label: "Add environment variable",
+                },
+                gtk::Entry {
+                    set_placeholder_text: Some("NAME=value"),
+                    connect_activate[sender] => move |entry| {
+                        let buffer = entry.buffer();
+                        sender.input(EmulatorFormMsg::AddEnvironmentVariable(buffer.text().into()));
+                        buffer.delete_text(0, None);
+                    },
+                },
+                gtk::Label {
+                    #[watch]
+                    set_label: &model
+                        .launch_settings
+                        .environment_variables
+                        .iter()
+                        .map(|v| format!("{}={}", v.name, v.value))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                },
+                gtk::Button {
+                    set_label: "Clear environment variables",
+                    connect_clicked => EmulatorFormMsg::ClearEnvironmentVariables,
+                },
+
                 #[local_ref]
                 selected_systems_list_view -> gtk::ListView { },
 
@@ -293,6 +411,7 @@ impl Component for EmulatorFormModel {
                 let executable = self.executable.clone();
                 let name = self.name.clone();
                 let extract_files = self.extract_files;
+                let launch_settings = self.launch_settings.clone();
 
                 let systems = self
                     .selected_systems
@@ -314,7 +433,13 @@ impl Component for EmulatorFormModel {
                 sender.oneshot_command(async move {
                     let res = repository_manager
                         .get_emulator_repository()
-                        .add_emulator_with_systems(name, executable, extract_files, systems)
+                        .add_emulator_with_systems(
+                            name,
+                            executable,
+                            extract_files,
+                            systems,
+                            launch_settings,
+                        )
                         .await;
                     EmulatorFormCommandMsg::EmulatorSubmitted(res)
                 });
@@ -322,6 +447,59 @@ impl Component for EmulatorFormModel {
             EmulatorFormMsg::NameChanged(name) => {
                 self.name = name;
             }
+            EmulatorFormMsg::ExecutableKindSelected { index } => {
+                if let Some(kind) = EXECUTABLE_KINDS.get(index as usize) {
+                    self.launch_settings.executable_kind = *kind;
+                }
+            }
+            EmulatorFormMsg::WinePrefixChanged(wine_prefix) => {
+                self.launch_settings.wine_prefix =
+                    Some(wine_prefix).filter(|prefix| !prefix.is_empty());
+            }
+            EmulatorFormMsg::WorkingDirectoryChanged(working_directory) => {
+                self.launch_settings.working_directory =
+                    Some(working_directory).filter(|dir| !dir.is_empty());
+            }
+            EmulatorFormMsg::PreLaunchCommandChanged(command) => {
+                self.launch_settings.pre_launch_command =
+                    Some(command).filter(|command| !command.is_empty());
+            }
+            EmulatorFormMsg::PostLaunchCommandChanged(command) => {
+                self.launch_settings.post_launch_command =
+                    Some(command).filter(|command| !command.is_empty());
+            }
+            EmulatorFormMsg::LaunchTimeoutChanged(timeout) => {
+                let timeout = timeout.trim();
+                if timeout.is_empty() {
+                    self.launch_settings.launch_timeout_seconds = None;
+                } else {
+                    match timeout.parse::<i64>() {
+                        Ok(seconds) if seconds > 0 => {
+                            self.launch_settings.launch_timeout_seconds = Some(seconds);
+                        }
+                        _ => eprintln!("Invalid launch timeout: {}", timeout),
+                    }
+                }
+            }
+            EmulatorFormMsg::AddEnvironmentVariable(variable) => {
+                if let Some((name, value)) = variable.split_once('=') {
+                    let name = name.trim().to_string();
+                    self.launch_settings
+                        .environment_variables
+                        .retain(|v| v.name != name);
+                    self.launch_settings
+                        .environment_variables
+                        .push(EnvironmentVariable {
+                            name,
+                            value: value.to_string(),
+                        });
+                } else {
+                    eprintln!("Invalid environment variable: {}", variable);
+                }
+            }
+            EmulatorFormMsg::ClearEnvironmentVariables => {
+                self.launch_settings.environment_variables.clear();
+            }
             _ => {}
         }
     }
@@ -401,8 +579,15 @@ impl Component for EmulatorFormModel {
             currently_selected_system: None,
             system_arguments: HashMap::<i64, Vec<String>>::new(),
             name: String::new(),
+            launch_settings: EmulatorLaunchSettings::default(),
         };
 
+        let executable_kind_names = EXECUTABLE_KINDS.map(|kind| kind.to_string());
+        let executable_kind_names: Vec<&str> =
+            executable_kind_names.iter().map(|s| s.as_str()).collect();
+        let executable_kind_dropdown = gtk::DropDown::from_strings(&executable_kind_names);
+        executable_kind_dropdown.set_selected(0);
+
         let selected_systems_list_view = &model.selected_systems_list_view_wrapper.view;
         let command_line_argument_list_box = model.command_line_arguments.widget();
         let widgets = view_output!();
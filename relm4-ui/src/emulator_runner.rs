@@ -1,32 +1,51 @@
-use std::{collections::HashMap, sync::Arc};
+use std::sync::Arc;
 
 use crate::{
+    emulator_detector::{EmulatorDetectorInit, EmulatorDetectorModel, EmulatorDetectorOutputMsg},
     emulator_form::{EmulatorFormInit, EmulatorFormModel, EmulatorFormOutputMsg},
+    file_selector::{FileSelectInit, FileSelectModel, FileSelectOutputMsg},
     list_item::ListItem,
-    utils::{prepare_fileset_for_export, resolve_file_type_path},
+    utils::resolve_file_type_path,
 };
 use core_types::Sha1Checksum;
 use database::{
-    models::{FileSetFileInfo, System},
+    database_error::DatabaseError,
+    models::{CompatibilityStatus, EmulatorConfigTemplate, FileSetFileInfo, System},
     repository_manager::RepositoryManager,
 };
-use emulator_runner::{error::EmulatorRunnerError, run_with_emulator};
-use file_export::{export_files, export_files_zipped, export_files_zipped_or_non_zipped};
+use emulator_runner::{
+    error::EmulatorRunnerError,
+    process_registry::{LaunchId, ProcessRegistry},
+};
+use file_export::{export_files, export_files_zipped};
 use relm4::{
     Component, ComponentController, ComponentParts, ComponentSender, Controller,
     gtk::{
         self,
         glib::clone,
-        prelude::{ButtonExt, GtkWindowExt, OrientableExt, WidgetExt},
+        prelude::{
+            ButtonExt, CheckButtonExt, EditableExt, EntryBufferExtManual, EntryExt, GtkWindowExt,
+            OrientableExt, TextBufferExt, TextViewExt, WidgetExt,
+        },
     },
     typed_view::list::TypedListView,
 };
 use service::{
     error::Error as ServiceError,
+    release_launch::{PreparedReleaseLaunch, ReleaseLaunchRequest},
     view_model_service::ViewModelService,
-    view_models::{EmulatorListModel, EmulatorViewModel, FileSetViewModel, Settings},
+    view_models::{
+        EmulatorFirmwareViewModel, EmulatorListModel, EmulatorViewModel, FileSetListModel,
+        FileSetViewModel, RankedEmulatorViewModel, ReleaseLaunchSelection, Settings,
+    },
 };
 
+const COMPATIBILITY_STATUSES: [CompatibilityStatus; 3] = [
+    CompatibilityStatus::Works,
+    CompatibilityStatus::MinorIssues,
+    CompatibilityStatus::Broken,
+];
+
 #[derive(Debug)]
 pub enum EmulatorRunnerMsg {
     FetchEmulators { system_id: i64 },
@@ -36,24 +55,64 @@ pub enum EmulatorRunnerMsg {
     EmulatorSelected { index: u32 },
     SystemSelected { index: u32 },
 
+    PlaylistToggled,
+    MoveFileUp,
+    MoveFileDown,
+    SetEntryPoint,
     OpenEmulatorForm,
+    OpenEmulatorDetector,
     AddEmulator(EmulatorListModel),
 
+    SetDefaultEmulator,
+    SetReleaseEmulator,
+
+    FetchFirmware,
+    FirmwareTargetPathChanged(String),
+    OpenFirmwareSelector,
+    AddFirmware(FileSetListModel),
+
+    FetchConfigTemplate,
+    SaveConfigTemplate { for_release: bool },
+
+    CompatibilityStatusSelected { index: u32 },
+    SaveCompatibility,
+    RecordLaunchResultToggled,
+
     RunEmulator,
+    StopEmulator,
+    KillEmulator,
 }
 
 #[derive(Debug)]
 pub enum EmulatorRunnerCommandMsg {
-    EmulatorsFetched(Result<Vec<EmulatorViewModel>, ServiceError>),
+    EmulatorsFetched(Result<Vec<RankedEmulatorViewModel>, ServiceError>),
+    /// Launch prepared for the current selections, `run` when it was prepared for running.
+    LaunchPrepared {
+        launch: Result<PreparedReleaseLaunch, ServiceError>,
+        run: bool,
+    },
     FinishedRunningEmulator(Result<(), EmulatorRunnerError>),
+    EmulatorPreferenceSaved(Result<(), DatabaseError>),
+    FileOrderSaved(Result<(), DatabaseError>),
+    FirmwareFetched(Result<Vec<EmulatorFirmwareViewModel>, ServiceError>),
+    FirmwareSaved(Result<i64, DatabaseError>),
+    ConfigTemplateFetched(Result<Option<EmulatorConfigTemplate>, DatabaseError>),
+    ConfigTemplateSaved(Result<i64, DatabaseError>),
+    CompatibilitySaved(Result<i64, DatabaseError>),
 }
 
 pub struct EmulatorRunnerInit {
     pub view_model_service: Arc<ViewModelService>,
     pub repository_manager: Arc<RepositoryManager>,
     pub settings: Arc<Settings>,
+    pub process_registry: Arc<ProcessRegistry>,
+    pub release_id: i64,
     pub systems: Vec<System>,
     pub file_set: FileSetViewModel,
+    /// Preselected system, file and emulator.
+    pub launch_selection: ReleaseLaunchSelection,
+    /// Run the emulator right away with the preselected choices.
+    pub auto_run: bool,
 }
 
 #[derive(Debug)]
@@ -61,6 +120,7 @@ pub struct EmulatorRunnerModel {
     // services
     view_model_service: Arc<ViewModelService>,
     repository_manager: Arc<RepositoryManager>,
+    process_registry: Arc<ProcessRegistry>,
 
     // list views
     file_list_view_wrapper: TypedListView<ListItem, gtk::SingleSelection>,
@@ -69,6 +129,8 @@ pub struct EmulatorRunnerModel {
 
     // controllers
     emulator_form: Option<Controller<EmulatorFormModel>>,
+    emulator_detector: Option<Controller<EmulatorDetectorModel>>,
+    firmware_selector: Option<Controller<FileSelectModel>>,
 
     // data
     emulators: Vec<EmulatorViewModel>,
@@ -80,6 +142,208 @@ pub struct EmulatorRunnerModel {
     selected_file: Option<FileSetFileInfo>,
     selected_system: Option<System>,
     selected_emulator: Option<EmulatorViewModel>,
+    /// Firmware of the selected emulator and system.
+    firmware: Vec<EmulatorFirmwareViewModel>,
+    firmware_target_path: String,
+    config_file_name_buffer: gtk::EntryBuffer,
+    config_content_buffer: gtk::TextBuffer,
+    compatibility_status: CompatibilityStatus,
+    compatibility_notes_buffer: gtk::EntryBuffer,
+    /// Launch result is recorded in the compatibility of the release with the emulator.
+    record_launch_result: bool,
+    launch_as_playlist: bool,
+    launch_preview: String,
+    running_launch_id: Option<LaunchId>,
+    release_id: i64,
+    preferred_emulator_id: Option<i64>,
+    auto_run: bool,
+}
+
+impl EmulatorRunnerModel {
+    fn missing_firmware(&self) -> Vec<&EmulatorFirmwareViewModel> {
+        self.firmware
+            .iter()
+            .filter(|firmware| firmware.required && firmware.is_missing())
+            .collect()
+    }
+
+    fn selected_emulator_system_id(&self) -> Option<i64> {
+        let emulator = self.selected_emulator.as_ref()?;
+        let system = self.selected_system.as_ref()?;
+        emulator
+            .systems
+            .iter()
+            .find(|s| s.system_id == system.id)
+            .map(|s| s.id)
+    }
+
+    /// Launch request for the current selections, the rest is resolved by the service the
+    /// same way as for launches outside this dialog.
+    fn launch_request(&self) -> Option<ReleaseLaunchRequest> {
+        Some(ReleaseLaunchRequest {
+            release_id: self.release_id,
+            system_id: Some(self.selected_system.as_ref()?.id),
+            emulator_id: Some(self.selected_emulator.as_ref()?.id),
+            file_set_id: Some(self.file_set.id),
+            file_name: Some(self.selected_file.as_ref()?.file_name.clone()),
+            playlist: self.launch_as_playlist,
+            file_set_roles: None,
+        })
+    }
+
+    /// Prepares the launch for the current selections, the preview is updated and the emulator
+    /// run, when `run` is set, once the launch has been prepared.
+    fn prepare_launch(&self, run: bool, sender: &ComponentSender<Self>) {
+        let Some(request) = self.launch_request() else {
+            return;
+        };
+        let view_model_service = Arc::clone(&self.view_model_service);
+        sender.oneshot_command(async move {
+            let launch = view_model_service.prepare_release_launch(&request).await;
+            EmulatorRunnerCommandMsg::LaunchPrepared { launch, run }
+        });
+    }
+
+    fn update_file_list(&mut self) {
+        let selected_file_info_id = self.selected_file.as_ref().map(|f| f.file_info_id);
+        self.file_list_view_wrapper.clear();
+        self.file_list_view_wrapper
+            .extend_from_iter(file_list_items(&self.file_set.files));
+        let index = self
+            .file_set
+            .files
+            .iter()
+            .position(|f| Some(f.file_info_id) == selected_file_info_id)
+            .unwrap_or(0);
+        self.file_list_view_wrapper
+            .selection_model
+            .set_selected(index as u32);
+    }
+
+    /// Moves the selected file by the given offset and saves the new file order.
+    fn move_selected_file(&mut self, offset: isize, sender: &ComponentSender<Self>) {
+        let Some(selected_file) = &self.selected_file else {
+            return;
+        };
+        let files = &mut self.file_set.files;
+        let Some(index) = files
+            .iter()
+            .position(|f| f.file_info_id == selected_file.file_info_id)
+        else {
+            return;
+        };
+        let Some(new_index) = index
+            .checked_add_signed(offset)
+            .filter(|new_index| *new_index < files.len())
+        else {
+            return;
+        };
+        files.swap(index, new_index);
+        for (ordinal, file) in files.iter_mut().enumerate() {
+            file.ordinal = ordinal as i64;
+        }
+        let file_info_ids = files.iter().map(|f| f.file_info_id).collect::<Vec<_>>();
+        self.update_file_list();
+
+        let repository_manager = Arc::clone(&self.repository_manager);
+        let file_set_id = self.file_set.id;
+        sender.oneshot_command(async move {
+            let res = repository_manager
+                .get_file_set_repository()
+                .reorder_file_set_files(file_set_id, &file_info_ids)
+                .await;
+            EmulatorRunnerCommandMsg::FileOrderSaved(res)
+        });
+    }
+
+    fn update_launch_preview(&mut self, sender: &ComponentSender<Self>) {
+        if self.launch_request().is_none() {
+            self.launch_preview.clear();
+        }
+        self.prepare_launch(false, sender);
+    }
+
+    fn show_launch_preview(&mut self, launch: &Result<PreparedReleaseLaunch, ServiceError>) {
+        self.launch_preview = match launch {
+            Ok(launch) => match launch.preview() {
+                Ok(preview) => {
+                    let mut text = preview.to_string();
+                    for file_set in launch.plan.file_sets.iter().skip(1) {
+                        text.push_str(&format!(
+                            "\nRole {}: {}",
+                            file_set.role,
+                            file_set.relative_entry_point().display()
+                        ));
+                    }
+                    if let Some(config) = &launch.plan.config {
+                        text.push_str(&format!(
+                            "\nConfig: {}\n{}",
+                            config.path.display(),
+                            config.content
+                        ));
+                    }
+                    text
+                }
+                Err(e) => format!("Unable to preview launch: {}", e),
+            },
+            // e.g. missing required firmware
+            Err(e) => format!("Unable to launch: {}", e),
+        };
+        for firmware in &self.firmware {
+            self.launch_preview
+                .push_str(&format!("\nFirmware: {}", firmware));
+        }
+    }
+
+    fn run_launch(&mut self, launch: PreparedReleaseLaunch, sender: &ComponentSender<Self>) {
+        let launch = match launch.register(&self.process_registry) {
+            Ok(launch) => launch,
+            Err(e) => {
+                eprintln!("Unable to launch emulator: {}", e);
+                return;
+            }
+        };
+        self.running_launch_id = Some(launch.launch_id());
+        let record_launch_result = self.record_launch_result;
+        let view_model_service = Arc::clone(&self.view_model_service);
+        sender.oneshot_command(async move {
+            let (release_id, emulator_id, system_id) = (
+                launch.launch.release_id,
+                launch.launch.emulator_id,
+                launch.launch.system_id,
+            );
+            let res = launch.run().await;
+            if let Err(e) = view_model_service
+                .record_launch(release_id, emulator_id, system_id, &res)
+                .await
+            {
+                eprintln!("Error recording launch: {}", e);
+            }
+            if record_launch_result {
+                if let Err(e) = view_model_service
+                    .record_launch_result(release_id, emulator_id, system_id, res.clone())
+                    .await
+                {
+                    eprintln!("Error recording launch result: {}", e);
+                }
+            }
+            EmulatorRunnerCommandMsg::FinishedRunningEmulator(res)
+        });
+    }
+}
+
+fn file_list_items(files: &[FileSetFileInfo]) -> Vec<ListItem> {
+    files
+        .iter()
+        .map(|file| ListItem {
+            id: file.file_info_id,
+            name: if file.is_entry_point {
+                format!("{} (entry point)", file.file_name)
+            } else {
+                file.file_name.clone()
+            },
+        })
+        .collect()
 }
 
 #[relm4::component(pub)]
@@ -97,23 +361,203 @@ impl Component for EmulatorRunnerModel {
                 #[local_ref]
                 file_list_view -> gtk::ListView,
 
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+
+                    gtk::Button {
+                        set_label: "Move up",
+                        connect_clicked => EmulatorRunnerMsg::MoveFileUp,
+                        #[watch]
+                        set_sensitive: model.selected_file.is_some(),
+                    },
+
+                    gtk::Button {
+                        set_label: "Move down",
+                        connect_clicked => EmulatorRunnerMsg::MoveFileDown,
+                        #[watch]
+                        set_sensitive: model.selected_file.is_some(),
+                    },
+
+                    gtk::Button {
+                        set_label: "Set as entry point",
+                        connect_clicked => EmulatorRunnerMsg::SetEntryPoint,
+                        #[watch]
+                        set_sensitive: model.selected_file.is_some(),
+                    },
+                },
+
                 #[local_ref]
                 system_list_view -> gtk::ListView,
 
                 #[local_ref]
                 emulator_list_view -> gtk::ListView,
 
+                gtk::CheckButton {
+                    set_label: Some("Launch all files as playlist"),
+                    set_active: model.launch_as_playlist,
+                    #[watch]
+                    set_sensitive: model.file_set.files.len() > 1
+                        && model.selected_emulator.as_ref().is_some_and(|e| e.extract_files),
+                    connect_toggled => EmulatorRunnerMsg::PlaylistToggled,
+                },
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+
+                    gtk::Button {
+                        set_label: "Set as default for system",
+                        connect_clicked => EmulatorRunnerMsg::SetDefaultEmulator,
+                        #[watch]
+                        set_sensitive: model.selected_emulator.is_some()
+                            && model.selected_system.is_some(),
+                    },
+
+                    gtk::Button {
+                        set_label: "Always use for this release",
+                        connect_clicked => EmulatorRunnerMsg::SetReleaseEmulator,
+                        #[watch]
+                        set_sensitive: model.selected_emulator.is_some(),
+                    },
+                },
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+
+                    gtk::Entry {
+                        set_placeholder_text: Some("Firmware target directory"),
+                        connect_changed[sender] => move |entry| {
+                            sender.input(EmulatorRunnerMsg::FirmwareTargetPathChanged(
+                                entry.text().into(),
+                            ));
+                        },
+                    },
+
+                    gtk::Button {
+                        set_label: "Add firmware",
+                        connect_clicked => EmulatorRunnerMsg::OpenFirmwareSelector,
+                        #[watch]
+                        set_sensitive: model.selected_emulator.is_some()
+                            && model.selected_system.is_some()
+                            && !model.firmware_target_path.is_empty(),
+                    },
+                },
+
+                gtk::Label {
+                    set_label: "Emulator configuration, passed with {config} in the arguments",
+                },
+
+                gtk::Entry {
+                    set_buffer: &model.config_file_name_buffer,
+                    set_placeholder_text: Some("Configuration file name"),
+                },
+
+                gtk::ScrolledWindow {
+                    set_min_content_height: 120,
+
+                    gtk::TextView {
+                        set_buffer: Some(&model.config_content_buffer),
+                        set_monospace: true,
+                    },
+                },
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+
+                    gtk::Button {
+                        set_label: "Save configuration for this release",
+                        connect_clicked => EmulatorRunnerMsg::SaveConfigTemplate { for_release: true },
+                        #[watch]
+                        set_sensitive: model.selected_emulator.is_some()
+                            && model.selected_system.is_some(),
+                    },
+
+                    gtk::Button {
+                        set_label: "Save as default for emulator and system",
+                        connect_clicked => EmulatorRunnerMsg::SaveConfigTemplate { for_release: false },
+                        #[watch]
+                        set_sensitive: model.selected_emulator.is_some()
+                            && model.selected_system.is_some(),
+                    },
+                },
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+
+                    #[local_ref]
+                    compatibility_status_dropdown -> gtk::DropDown {
+                        connect_selected_notify[sender] => move |dropdown| {
+                            sender.input(EmulatorRunnerMsg::CompatibilityStatusSelected {
+                                index: dropdown.selected(),
+                            });
+                        },
+                    },
+
+                    gtk::Entry {
+                        set_buffer: &model.compatibility_notes_buffer,
+                        set_placeholder_text: Some("Compatibility notes"),
+                        set_hexpand: true,
+                    },
+
+                    gtk::Button {
+                        set_label: "Record compatibility",
+                        connect_clicked => EmulatorRunnerMsg::SaveCompatibility,
+                        #[watch]
+                        set_sensitive: model.selected_emulator.is_some()
+                            && model.selected_system.is_some(),
+                    },
+                },
+
+                gtk::CheckButton {
+                    set_label: Some("Record launch result in compatibility"),
+                    set_active: model.record_launch_result,
+                    connect_toggled => EmulatorRunnerMsg::RecordLaunchResultToggled,
+                },
+
+                gtk::Label {
+                    set_selectable: true,
+                    set_wrap: true,
+                    set_xalign: 0.0,
+                    #[watch]
+                    set_label: &model.launch_preview,
+                },
+
                 gtk::Button {
                     set_label: "Add emulator",
                     connect_clicked => EmulatorRunnerMsg::OpenEmulatorForm,
 
                 },
 
+                gtk::Button {
+                    set_label: "Detect installed emulators",
+                    connect_clicked => EmulatorRunnerMsg::OpenEmulatorDetector,
+                },
+
                 gtk::Button {
                     set_label: "Run Emulator",
                     connect_clicked => EmulatorRunnerMsg::RunEmulator,
                     #[watch]
-                    set_sensitive: model.selected_emulator.is_some() && model.selected_file.is_some(),
+                    set_sensitive: model.selected_emulator.is_some()
+                        && model.selected_file.is_some()
+                        && model.running_launch_id.is_none()
+                        && model.missing_firmware().is_empty(),
+                },
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+
+                    gtk::Button {
+                        set_label: "Stop Emulator",
+                        connect_clicked => EmulatorRunnerMsg::StopEmulator,
+                        #[watch]
+                        set_sensitive: model.running_launch_id.is_some(),
+                    },
+
+                    gtk::Button {
+                        set_label: "Kill Emulator",
+                        connect_clicked => EmulatorRunnerMsg::KillEmulator,
+                        #[watch]
+                        set_sensitive: model.running_launch_id.is_some(),
+                    },
                 },
             }
         }
@@ -127,17 +571,7 @@ impl Component for EmulatorRunnerModel {
         let mut file_list_view_wrapper = TypedListView::<ListItem, gtk::SingleSelection>::new();
         let emulator_list_view_wrapper = TypedListView::<ListItem, gtk::SingleSelection>::new();
 
-        let file_list_items = init
-            .file_set
-            .files
-            .iter()
-            .map(|file| ListItem {
-                id: file.file_info_id,
-                name: file.file_name.clone(),
-            })
-            .collect::<Vec<_>>();
-
-        file_list_view_wrapper.extend_from_iter(file_list_items);
+        file_list_view_wrapper.extend_from_iter(file_list_items(&init.file_set.files));
 
         let mut system_list_view_wrapper = TypedListView::<ListItem, gtk::SingleSelection>::new();
 
@@ -151,9 +585,38 @@ impl Component for EmulatorRunnerModel {
             .collect::<Vec<_>>();
         system_list_view_wrapper.extend_from_iter(system_list_items);
 
+        // preselect the resolved system and file, falling back to the entry point of the file set,
+        // selection is changed before the notify handlers are connected and the messages are
+        // sent below
+        let selection = &init.launch_selection;
+        let system_index = init
+            .systems
+            .iter()
+            .position(|system| Some(system.id) == selection.system_id)
+            .unwrap_or(0) as u32;
+        let file_index = init
+            .file_set
+            .files
+            .iter()
+            .position(|file| Some(file.file_info_id) == selection.file_info_id)
+            .or_else(|| {
+                init.file_set
+                    .files
+                    .iter()
+                    .position(|file| file.is_entry_point)
+            })
+            .unwrap_or(0) as u32;
+        system_list_view_wrapper
+            .selection_model
+            .set_selected(system_index);
+        file_list_view_wrapper
+            .selection_model
+            .set_selected(file_index);
+
         let model = EmulatorRunnerModel {
             view_model_service: init.view_model_service,
             repository_manager: init.repository_manager,
+            process_registry: init.process_registry,
 
             systems: init.systems,
             emulators: Vec::new(),
@@ -167,7 +630,22 @@ impl Component for EmulatorRunnerModel {
             selected_file: None,
             selected_emulator: None,
             emulator_form: None,
+            emulator_detector: None,
+            firmware_selector: None,
             selected_system: None,
+            firmware: Vec::new(),
+            firmware_target_path: String::new(),
+            config_file_name_buffer: gtk::EntryBuffer::default(),
+            config_content_buffer: gtk::TextBuffer::default(),
+            compatibility_status: CompatibilityStatus::Works,
+            compatibility_notes_buffer: gtk::EntryBuffer::default(),
+            record_launch_result: false,
+            launch_as_playlist: false,
+            launch_preview: String::new(),
+            running_launch_id: None,
+            release_id: init.release_id,
+            preferred_emulator_id: init.launch_selection.emulator_id,
+            auto_run: init.auto_run,
         };
 
         let file_list_view = &model.file_list_view_wrapper.view;
@@ -210,71 +688,72 @@ impl Component for EmulatorRunnerModel {
                 }
             ));
 
+        let compatibility_status_names = COMPATIBILITY_STATUSES.map(|status| status.to_string());
+        let compatibility_status_names: Vec<&str> = compatibility_status_names
+            .iter()
+            .map(|s| s.as_str())
+            .collect();
+        let compatibility_status_dropdown =
+            gtk::DropDown::from_strings(&compatibility_status_names);
+        compatibility_status_dropdown.set_selected(0);
+
         let widgets = view_output!();
-        sender.input(EmulatorRunnerMsg::SystemSelected { index: 0 });
-        sender.input(EmulatorRunnerMsg::FileSelected { index: 0 });
+        sender.input(EmulatorRunnerMsg::SystemSelected {
+            index: system_index,
+        });
+        sender.input(EmulatorRunnerMsg::FileSelected { index: file_index });
         ComponentParts { model, widgets }
     }
 
     fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>, root: &Self::Root) {
         match msg {
             EmulatorRunnerMsg::RunEmulator => {
-                if let (Some(emulator), Some(selected_file), Some(system)) = (
-                    self.selected_emulator.clone(),
-                    self.selected_file.clone(),
-                    self.selected_system.clone(),
-                ) {
-                    let emulator_system =
-                        emulator.systems.iter().find(|s| s.system_id == system.id);
-
-                    if let Some(emulator_system) = emulator_system {
-                        let temp_dir = std::env::temp_dir();
-                        let export_model = prepare_fileset_for_export(
-                            &self.file_set,
-                            &self.settings.collection_root_dir,
-                            temp_dir.as_path(),
-                            emulator.extract_files,
-                        );
-                        let files_in_fileset = self
-                            .file_set
-                            .files
-                            .iter()
-                            .map(|f| f.file_name.clone())
-                            .collect::<Vec<_>>();
-
-                        let extract_files = emulator.extract_files;
-                        let starting_file = if extract_files {
-                            selected_file.file_name.clone()
-                        } else {
-                            export_model.exported_zip_file_name.clone()
-                        };
-
-                        let executable = emulator.executable.clone();
-                        let arguments = emulator_system.arguments.clone();
-
-                        sender.oneshot_command(async move {
-                            let res = match export_files_zipped_or_non_zipped(&export_model) {
-                                Ok(()) => {
-                                    run_with_emulator(
-                                        executable,
-                                        arguments,
-                                        files_in_fileset,
-                                        starting_file,
-                                        temp_dir,
-                                    )
-                                    .await
-                                }
-                                Err(e) => Err(EmulatorRunnerError::IoError(format!(
-                                    "Failed to export files: {}",
-                                    e
-                                ))),
-                            };
-                            EmulatorRunnerCommandMsg::FinishedRunningEmulator(res)
-                        });
-                    }
-                } else {
-                    // Handle the case where no emulator or file is selected
+                if self.launch_request().is_none() {
                     eprintln!("No emulator or file selected");
+                    return;
+                }
+                self.prepare_launch(true, &sender);
+            }
+            EmulatorRunnerMsg::SetDefaultEmulator => {
+                if let (Some(emulator), Some(system)) =
+                    (&self.selected_emulator, &self.selected_system)
+                {
+                    let repository_manager = Arc::clone(&self.repository_manager);
+                    let (system_id, emulator_id) = (system.id, emulator.id);
+                    sender.oneshot_command(async move {
+                        let res = repository_manager
+                            .get_emulator_repository()
+                            .set_default_emulator_for_system(system_id, Some(emulator_id))
+                            .await;
+                        EmulatorRunnerCommandMsg::EmulatorPreferenceSaved(res)
+                    });
+                }
+            }
+            EmulatorRunnerMsg::SetReleaseEmulator => {
+                if let Some(emulator) = &self.selected_emulator {
+                    let repository_manager = Arc::clone(&self.repository_manager);
+                    let (release_id, emulator_id) = (self.release_id, emulator.id);
+                    sender.oneshot_command(async move {
+                        let res = repository_manager
+                            .get_emulator_repository()
+                            .set_release_emulator(release_id, Some(emulator_id))
+                            .await;
+                        EmulatorRunnerCommandMsg::EmulatorPreferenceSaved(res)
+                    });
+                }
+            }
+            EmulatorRunnerMsg::StopEmulator => {
+                if let Some(launch_id) = self.running_launch_id {
+                    if let Err(e) = self.process_registry.terminate(launch_id) {
+                        eprintln!("Error stopping emulator: {}", e);
+                    }
+                }
+            }
+            EmulatorRunnerMsg::KillEmulator => {
+                if let Some(launch_id) = self.running_launch_id {
+                    if let Err(e) = self.process_registry.kill(launch_id) {
+                        eprintln!("Error killing emulator: {}", e);
+                    }
                 }
             }
             EmulatorRunnerMsg::FileSelected { index } => {
@@ -285,6 +764,7 @@ impl Component for EmulatorRunnerModel {
                     let file_info = self.file_set.files.iter().find(|f| f.file_info_id == id);
                     self.selected_file = file_info.cloned();
                 }
+                self.update_launch_preview(&sender);
             }
             EmulatorRunnerMsg::EmulatorSelected { index } => {
                 println!("Emulator selected at index: {}", index);
@@ -294,6 +774,8 @@ impl Component for EmulatorRunnerModel {
                     let emulator = self.emulators.iter().find(|e| e.id == id);
                     self.selected_emulator = emulator.cloned();
                 }
+                self.update_launch_preview(&sender);
+                sender.input(EmulatorRunnerMsg::FetchFirmware);
             }
             EmulatorRunnerMsg::SystemSelected { index } => {
                 println!("System selected at index: {}", index);
@@ -305,6 +787,191 @@ impl Component for EmulatorRunnerModel {
                     sender.input(EmulatorRunnerMsg::FetchEmulators { system_id: id });
                 }
             }
+            EmulatorRunnerMsg::PlaylistToggled => {
+                self.launch_as_playlist = !self.launch_as_playlist;
+                self.update_launch_preview(&sender);
+            }
+            EmulatorRunnerMsg::MoveFileUp => {
+                self.move_selected_file(-1, &sender);
+            }
+            EmulatorRunnerMsg::MoveFileDown => {
+                self.move_selected_file(1, &sender);
+            }
+            EmulatorRunnerMsg::SetEntryPoint => {
+                if let Some(selected_file) = &self.selected_file {
+                    let file_info_id = selected_file.file_info_id;
+                    for file in self.file_set.files.iter_mut() {
+                        file.is_entry_point = file.file_info_id == file_info_id;
+                    }
+                    self.update_file_list();
+
+                    let repository_manager = Arc::clone(&self.repository_manager);
+                    let file_set_id = self.file_set.id;
+                    sender.oneshot_command(async move {
+                        let res = repository_manager
+                            .get_file_set_repository()
+                            .set_file_set_entry_point(file_set_id, Some(file_info_id))
+                            .await;
+                        EmulatorRunnerCommandMsg::FileOrderSaved(res)
+                    });
+                }
+            }
+            EmulatorRunnerMsg::FetchFirmware => {
+                if let (Some(emulator), Some(system)) =
+                    (&self.selected_emulator, &self.selected_system)
+                {
+                    let view_model_service = Arc::clone(&self.view_model_service);
+                    let (emulator_id, system_id) = (emulator.id, system.id);
+                    sender.oneshot_command(async move {
+                        let res = view_model_service
+                            .get_emulator_firmware(emulator_id, system_id)
+                            .await;
+                        EmulatorRunnerCommandMsg::FirmwareFetched(res)
+                    });
+                } else {
+                    self.firmware.clear();
+                    self.update_launch_preview(&sender);
+                    sender.input(EmulatorRunnerMsg::FetchConfigTemplate);
+                }
+            }
+            EmulatorRunnerMsg::FirmwareTargetPathChanged(target_path) => {
+                self.firmware_target_path = target_path.trim().to_string();
+            }
+            EmulatorRunnerMsg::OpenFirmwareSelector => {
+                if let Some(system) = &self.selected_system {
+                    let init_model = FileSelectInit {
+                        view_model_service: Arc::clone(&self.view_model_service),
+                        repository_manager: Arc::clone(&self.repository_manager),
+                        settings: Arc::clone(&self.settings),
+                        selected_system_ids: vec![system.id],
+                        selected_file_set_ids: self
+                            .firmware
+                            .iter()
+                            .map(|firmware| firmware.file_set.id)
+                            .collect(),
+                    };
+                    let firmware_selector = FileSelectModel::builder()
+                        .transient_for(root)
+                        .launch(init_model)
+                        .forward(sender.input_sender(), |msg| match msg {
+                            FileSelectOutputMsg::FileSetSelected(file_set_list_model) => {
+                                EmulatorRunnerMsg::AddFirmware(file_set_list_model)
+                            }
+                        });
+                    self.firmware_selector = Some(firmware_selector);
+                    self.firmware_selector
+                        .as_ref()
+                        .expect("Firmware selector should be initialized")
+                        .widget()
+                        .present();
+                }
+            }
+            EmulatorRunnerMsg::AddFirmware(file_set_list_model) => {
+                let emulator_system = self.selected_emulator.as_ref().and_then(|emulator| {
+                    emulator
+                        .systems
+                        .iter()
+                        .find(|s| Some(s.system_id) == self.selected_system.as_ref().map(|s| s.id))
+                });
+                if let Some(emulator_system) = emulator_system {
+                    let repository_manager = Arc::clone(&self.repository_manager);
+                    let emulator_system_id = emulator_system.id;
+                    let target_path = self.firmware_target_path.clone();
+                    sender.oneshot_command(async move {
+                        let res = repository_manager
+                            .get_emulator_repository()
+                            .add_emulator_system_firmware(
+                                emulator_system_id,
+                                file_set_list_model.id,
+                                target_path,
+                                true,
+                            )
+                            .await;
+                        EmulatorRunnerCommandMsg::FirmwareSaved(res)
+                    });
+                }
+            }
+            EmulatorRunnerMsg::FetchConfigTemplate => {
+                if let Some(emulator_system_id) = self.selected_emulator_system_id() {
+                    let repository_manager = Arc::clone(&self.repository_manager);
+                    let release_id = self.release_id;
+                    sender.oneshot_command(async move {
+                        let res = repository_manager
+                            .get_emulator_repository()
+                            .get_emulator_config_template(emulator_system_id, release_id)
+                            .await;
+                        EmulatorRunnerCommandMsg::ConfigTemplateFetched(res)
+                    });
+                } else {
+                    self.update_launch_preview(&sender);
+                }
+            }
+            EmulatorRunnerMsg::SaveConfigTemplate { for_release } => {
+                let file_name = self.config_file_name_buffer.text().trim().to_string();
+                let content = self
+                    .config_content_buffer
+                    .text(
+                        &self.config_content_buffer.start_iter(),
+                        &self.config_content_buffer.end_iter(),
+                        false,
+                    )
+                    .to_string();
+                if file_name.is_empty() {
+                    eprintln!("Configuration file name is missing");
+                    // TODO: show error to user
+                    return;
+                }
+                if let Some(emulator_system_id) = self.selected_emulator_system_id() {
+                    let repository_manager = Arc::clone(&self.repository_manager);
+                    let release_id = for_release.then_some(self.release_id);
+                    sender.oneshot_command(async move {
+                        let res = repository_manager
+                            .get_emulator_repository()
+                            .set_emulator_config_template(
+                                emulator_system_id,
+                                release_id,
+                                file_name,
+                                content,
+                            )
+                            .await;
+                        EmulatorRunnerCommandMsg::ConfigTemplateSaved(res)
+                    });
+                }
+            }
+            EmulatorRunnerMsg::CompatibilityStatusSelected { index } => {
+                if let Some(status) = COMPATIBILITY_STATUSES.get(index as usize) {
+                    self.compatibility_status = *status;
+                }
+            }
+            EmulatorRunnerMsg::SaveCompatibility => {
+                if let (Some(emulator), Some(system)) =
+                    (&self.selected_emulator, &self.selected_system)
+                {
+                    let repository_manager = Arc::clone(&self.repository_manager);
+                    let (release_id, emulator_id, system_id) =
+                        (self.release_id, emulator.id, system.id);
+                    let status = self.compatibility_status;
+                    let notes = Some(self.compatibility_notes_buffer.text().trim().to_string())
+                        .filter(|notes| !notes.is_empty());
+                    sender.oneshot_command(async move {
+                        let res = repository_manager
+                            .get_compatibility_repository()
+                            .set_compatibility(
+                                release_id,
+                                emulator_id,
+                                system_id,
+                                status,
+                                notes,
+                                None,
+                            )
+                            .await;
+                        EmulatorRunnerCommandMsg::CompatibilitySaved(res)
+                    });
+                }
+            }
+            EmulatorRunnerMsg::RecordLaunchResultToggled => {
+                self.record_launch_result = !self.record_launch_result;
+            }
             EmulatorRunnerMsg::OpenEmulatorForm => {
                 println!("Open Emulator Form");
                 let init_model = EmulatorFormInit {
@@ -327,6 +994,27 @@ impl Component for EmulatorRunnerModel {
                     .widget()
                     .present();
             }
+            EmulatorRunnerMsg::OpenEmulatorDetector => {
+                let init_model = EmulatorDetectorInit {
+                    view_model_service: Arc::clone(&self.view_model_service),
+                    repository_manager: Arc::clone(&self.repository_manager),
+                };
+                let emulator_detector = EmulatorDetectorModel::builder()
+                    .transient_for(root)
+                    .launch(init_model)
+                    .forward(sender.input_sender(), |msg| match msg {
+                        EmulatorDetectorOutputMsg::EmulatorAdded(emulator_list_model) => {
+                            EmulatorRunnerMsg::AddEmulator(emulator_list_model)
+                        }
+                    });
+
+                self.emulator_detector = Some(emulator_detector);
+                self.emulator_detector
+                    .as_ref()
+                    .expect("Emulator detector should be initialized")
+                    .widget()
+                    .present();
+            }
             EmulatorRunnerMsg::AddEmulator(_emulator_list_model) => {
                 if let Some(system) = &self.selected_system {
                     sender.input(EmulatorRunnerMsg::FetchEmulators {
@@ -337,9 +1025,10 @@ impl Component for EmulatorRunnerModel {
             EmulatorRunnerMsg::FetchEmulators { system_id } => {
                 println!("Fetching emulators for systems: {:?}", system_id);
                 let view_model_service = Arc::clone(&self.view_model_service);
+                let release_id = self.release_id;
                 sender.oneshot_command(async move {
                     let emulators_result = view_model_service
-                        .get_emulator_view_models_for_systems(&[system_id])
+                        .get_ranked_emulators_for_release(release_id, system_id)
                         .await;
                     EmulatorRunnerCommandMsg::EmulatorsFetched(emulators_result)
                 });
@@ -351,33 +1040,140 @@ impl Component for EmulatorRunnerModel {
     fn update_cmd(
         &mut self,
         message: Self::CommandOutput,
-        _sender: ComponentSender<Self>,
+        sender: ComponentSender<Self>,
         root: &Self::Root,
     ) {
         match message {
-            EmulatorRunnerCommandMsg::EmulatorsFetched(Ok(emulator_view_models)) => {
-                println!("Emulators fetched successfully: {:?}", emulator_view_models);
-                let emulator_list_items = emulator_view_models
+            EmulatorRunnerCommandMsg::EmulatorsFetched(Ok(ranked_emulators)) => {
+                println!("Emulators fetched successfully: {:?}", ranked_emulators);
+                let emulator_list_items = ranked_emulators
                     .iter()
-                    .map(|emulator| ListItem {
-                        id: emulator.id,
-                        name: emulator.name.clone(),
+                    .map(|ranked| ListItem {
+                        id: ranked.emulator.id,
+                        name: ranked.to_string(),
                     })
                     .collect::<Vec<_>>();
-                self.emulators = emulator_view_models;
+                self.emulators = ranked_emulators
+                    .into_iter()
+                    .map(|ranked| ranked.emulator)
+                    .collect();
+                self.emulator_list_view_wrapper.clear();
                 self.emulator_list_view_wrapper
                     .extend_from_iter(emulator_list_items);
+
+                let preferred_index = self
+                    .emulators
+                    .iter()
+                    .position(|emulator| Some(emulator.id) == self.preferred_emulator_id);
+                if let Some(index) = preferred_index {
+                    self.emulator_list_view_wrapper
+                        .selection_model
+                        .set_selected(index as u32);
+                    self.selected_emulator = self.emulators.get(index).cloned();
+                }
+                self.update_launch_preview(&sender);
+
+                // auto run continues once the firmware of the emulator has been checked
+                if preferred_index.is_none() || self.selected_file.is_none() {
+                    self.auto_run = false;
+                }
+                sender.input(EmulatorRunnerMsg::FetchFirmware);
             }
             EmulatorRunnerCommandMsg::EmulatorsFetched(Err(error)) => {
                 eprintln!("Error fetching emulators: {:?}", error);
                 // TODO: Handle error appropriately, e.g., show a dialog or log the error
             }
+            EmulatorRunnerCommandMsg::LaunchPrepared { launch, run } => {
+                self.show_launch_preview(&launch);
+                match launch {
+                    Ok(launch) if run => self.run_launch(launch, &sender),
+                    Err(error) if run => {
+                        eprintln!("Unable to launch emulator: {}", error);
+                        // TODO: show error to user
+                    }
+                    _ => {}
+                }
+            }
             EmulatorRunnerCommandMsg::FinishedRunningEmulator(Ok(())) => {
                 println!("Emulator ran successfully");
+                self.running_launch_id = None;
                 root.close();
             }
+            EmulatorRunnerCommandMsg::EmulatorPreferenceSaved(Ok(())) => {
+                println!("Emulator preference saved");
+            }
+            EmulatorRunnerCommandMsg::EmulatorPreferenceSaved(Err(error)) => {
+                eprintln!("Error saving emulator preference: {:?}", error);
+                // TODO: show error to user
+            }
+            EmulatorRunnerCommandMsg::FirmwareFetched(Ok(firmware)) => {
+                self.firmware = firmware;
+                self.update_launch_preview(&sender);
+                sender.input(EmulatorRunnerMsg::FetchConfigTemplate);
+            }
+            EmulatorRunnerCommandMsg::FirmwareFetched(Err(error)) => {
+                eprintln!("Error fetching firmware: {:?}", error);
+                // TODO: show error to user
+            }
+            EmulatorRunnerCommandMsg::FirmwareSaved(Ok(id)) => {
+                println!("Firmware added with id {}", id);
+                sender.input(EmulatorRunnerMsg::FetchFirmware);
+            }
+            EmulatorRunnerCommandMsg::FirmwareSaved(Err(error)) => {
+                eprintln!("Error adding firmware: {:?}", error);
+                // TODO: show error to user
+            }
+            EmulatorRunnerCommandMsg::ConfigTemplateFetched(Ok(config_template)) => {
+                let (file_name, content) = config_template
+                    .as_ref()
+                    .map(|template| (template.file_name.as_str(), template.content.as_str()))
+                    .unwrap_or_default();
+                self.config_file_name_buffer.set_text(file_name);
+                self.config_content_buffer.set_text(content);
+                self.update_launch_preview(&sender);
+                if self.auto_run {
+                    self.auto_run = false;
+                    sender.input(EmulatorRunnerMsg::RunEmulator);
+                }
+            }
+            EmulatorRunnerCommandMsg::ConfigTemplateFetched(Err(error)) => {
+                eprintln!("Error fetching emulator configuration: {:?}", error);
+                self.auto_run = false;
+                // TODO: show error to user
+            }
+            EmulatorRunnerCommandMsg::ConfigTemplateSaved(Ok(id)) => {
+                println!("Emulator configuration saved with id {}", id);
+                sender.input(EmulatorRunnerMsg::FetchConfigTemplate);
+            }
+            EmulatorRunnerCommandMsg::ConfigTemplateSaved(Err(error)) => {
+                eprintln!("Error saving emulator configuration: {:?}", error);
+                // TODO: show error to user
+            }
+            EmulatorRunnerCommandMsg::CompatibilitySaved(Ok(id)) => {
+                println!("Compatibility recorded with id {}", id);
+            }
+            EmulatorRunnerCommandMsg::CompatibilitySaved(Err(error)) => {
+                eprintln!("Error recording compatibility: {:?}", error);
+                // TODO: show error to user
+            }
+            EmulatorRunnerCommandMsg::FileOrderSaved(Ok(())) => {
+                println!("File order saved");
+                // playlist order and entry point are read from the saved file set
+                self.update_launch_preview(&sender);
+            }
+            EmulatorRunnerCommandMsg::FileOrderSaved(Err(error)) => {
+                eprintln!("Error saving file order: {:?}", error);
+                // TODO: show error to user
+            }
+            EmulatorRunnerCommandMsg::FinishedRunningEmulator(Err(
+                EmulatorRunnerError::Stopped,
+            )) => {
+                println!("Emulator was stopped");
+                self.running_launch_id = None;
+            }
             EmulatorRunnerCommandMsg::FinishedRunningEmulator(Err(error)) => {
                 eprintln!("Error running emulator: {:?}", error);
+                self.running_launch_id = None;
             }
         }
     }
@@ -0,0 +1,411 @@
+use std::sync::Arc;
+
+use database::{database_error::DatabaseError, repository_manager::RepositoryManager};
+use relm4::{
+    Component, ComponentController, ComponentParts, ComponentSender, Controller, RelmWidgetExt,
+    gtk::{
+        self,
+        glib::clone,
+        prelude::{
+            ButtonExt, EntryBufferExtManual, EntryExt, GtkWindowExt, OrientableExt, WidgetExt,
+        },
+    },
+    typed_view::list::TypedListView,
+};
+use service::{
+    error::Error as ServiceError,
+    view_model_service::ViewModelService,
+    view_models::{FranchiseListModel, FranchiseViewModel, SoftwareTitleListModel},
+};
+
+use crate::{
+    list_item::ListItem,
+    software_title_selector::{
+        SoftwareTitleSelectInit, SoftwareTitleSelectModel, SoftwareTitleSelectOutputMsg,
+    },
+};
+
+#[derive(Debug)]
+pub enum FranchisesMsg {
+    FetchFranchises,
+    FranchiseSelected { index: u32 },
+    AddFranchise { name: String },
+    DeleteFranchise,
+    OpenSoftwareTitleSelector,
+    SoftwareTitleSelected(SoftwareTitleListModel),
+    RemoveSoftwareTitle,
+}
+
+#[derive(Debug)]
+pub enum CommandMsg {
+    FranchisesFetched(Result<Vec<FranchiseListModel>, ServiceError>),
+    FranchiseFetched(Result<FranchiseViewModel, ServiceError>),
+    FranchiseAdded(Result<i64, DatabaseError>),
+    FranchiseDeleted(Result<(), DatabaseError>),
+    SoftwareTitleFranchiseSet(Result<(), DatabaseError>),
+}
+
+pub struct FranchisesInit {
+    pub view_model_service: Arc<ViewModelService>,
+    pub repository_manager: Arc<RepositoryManager>,
+}
+
+/// Franchises with their software titles and the releases of the titles on all systems.
+#[derive(Debug)]
+pub struct FranchisesModel {
+    view_model_service: Arc<ViewModelService>,
+    repository_manager: Arc<RepositoryManager>,
+    franchises: Vec<FranchiseListModel>,
+    franchises_list_view_wrapper: TypedListView<ListItem, gtk::SingleSelection>,
+    software_titles_list_view_wrapper: TypedListView<ListItem, gtk::SingleSelection>,
+    selected_franchise: Option<FranchiseListModel>,
+    franchise: Option<FranchiseViewModel>,
+    software_title_selector: Option<Controller<SoftwareTitleSelectModel>>,
+}
+
+impl FranchisesModel {
+    fn releases_text(&self) -> String {
+        self.franchise
+            .as_ref()
+            .map(|franchise| {
+                franchise
+                    .releases
+                    .iter()
+                    .map(|release| release.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .unwrap_or_default()
+    }
+
+    fn fetch_franchise(&self, sender: &ComponentSender<Self>) {
+        if let Some(franchise) = &self.selected_franchise {
+            let view_model_service = Arc::clone(&self.view_model_service);
+            let franchise_id = franchise.id;
+            sender.oneshot_command(async move {
+                let result = view_model_service
+                    .get_franchise_view_model(franchise_id)
+                    .await;
+                CommandMsg::FranchiseFetched(result)
+            });
+        }
+    }
+
+    fn set_software_title_franchise(
+        &self,
+        sender: &ComponentSender<Self>,
+        software_title_id: i64,
+        franchise_id: Option<i64>,
+    ) {
+        let repository_manager = Arc::clone(&self.repository_manager);
+        sender.oneshot_command(async move {
+            let result = repository_manager
+                .get_software_title_repository()
+                .set_software_title_franchise(software_title_id, franchise_id)
+                .await;
+            CommandMsg::SoftwareTitleFranchiseSet(result)
+        });
+    }
+}
+
+#[relm4::component(pub)]
+impl Component for FranchisesModel {
+    type Input = FranchisesMsg;
+    type Output = ();
+    type CommandOutput = CommandMsg;
+    type Init = FranchisesInit;
+
+    view! {
+        #[root]
+        gtk::Window {
+            set_default_width: 800,
+            set_default_height: 600,
+            set_title: Some("Franchises"),
+
+            gtk::Box {
+                set_orientation: gtk::Orientation::Horizontal,
+                set_spacing: 10,
+                set_margin_all: 10,
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+                    set_spacing: 5,
+
+                    gtk::Entry {
+                        set_placeholder_text: Some("Add new franchise"),
+                        connect_activate[sender] => move |entry| {
+                            let buffer = entry.buffer();
+                            sender.input(FranchisesMsg::AddFranchise {
+                                name: buffer.text().into(),
+                            });
+                            buffer.delete_text(0, None);
+                        }
+                    },
+
+                    gtk::ScrolledWindow {
+                        set_vexpand: true,
+                        #[local_ref]
+                        franchises_list_view -> gtk::ListView {}
+                    },
+
+                    gtk::Button {
+                        set_label: "Delete franchise",
+                        #[watch]
+                        set_sensitive: model.selected_franchise.as_ref().is_some_and(|franchise| franchise.can_delete),
+                        connect_clicked => FranchisesMsg::DeleteFranchise,
+                    },
+                },
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+                    set_spacing: 5,
+                    set_hexpand: true,
+
+                    gtk::Label {
+                        set_label: "Software titles",
+                    },
+
+                    gtk::ScrolledWindow {
+                        set_vexpand: true,
+                        #[local_ref]
+                        software_titles_list_view -> gtk::ListView {}
+                    },
+
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Horizontal,
+                        set_spacing: 5,
+
+                        gtk::Button {
+                            set_label: "Add software title",
+                            #[watch]
+                            set_sensitive: model.franchise.is_some(),
+                            connect_clicked => FranchisesMsg::OpenSoftwareTitleSelector,
+                        },
+
+                        gtk::Button {
+                            set_label: "Remove software title",
+                            #[watch]
+                            set_sensitive: model.franchise.as_ref().is_some_and(|franchise| !franchise.software_titles.is_empty()),
+                            connect_clicked => FranchisesMsg::RemoveSoftwareTitle,
+                        },
+                    },
+
+                    gtk::Label {
+                        set_label: "Releases",
+                    },
+
+                    gtk::ScrolledWindow {
+                        set_vexpand: true,
+                        gtk::Label {
+                            set_xalign: 0.0,
+                            set_yalign: 0.0,
+                            set_selectable: true,
+                            #[watch]
+                            set_label: &model.releases_text(),
+                        },
+                    },
+                },
+            }
+        }
+    }
+
+    fn init(
+        init_model: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = FranchisesModel {
+            view_model_service: init_model.view_model_service,
+            repository_manager: init_model.repository_manager,
+            franchises: Vec::new(),
+            franchises_list_view_wrapper: TypedListView::new(),
+            software_titles_list_view_wrapper: TypedListView::new(),
+            selected_franchise: None,
+            franchise: None,
+            software_title_selector: None,
+        };
+
+        let franchises_list_view = &model.franchises_list_view_wrapper.view;
+        let software_titles_list_view = &model.software_titles_list_view_wrapper.view;
+        let widgets = view_output!();
+
+        model
+            .franchises_list_view_wrapper
+            .selection_model
+            .connect_selected_notify(clone!(
+                #[strong]
+                sender,
+                move |selection| {
+                    sender.input(FranchisesMsg::FranchiseSelected {
+                        index: selection.selected(),
+                    });
+                }
+            ));
+
+        sender.input(FranchisesMsg::FetchFranchises);
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>, root: &Self::Root) {
+        match msg {
+            FranchisesMsg::FetchFranchises => {
+                let view_model_service = Arc::clone(&self.view_model_service);
+                sender.oneshot_command(async move {
+                    let result = view_model_service.get_franchise_list_models().await;
+                    CommandMsg::FranchisesFetched(result)
+                });
+            }
+            FranchisesMsg::FranchiseSelected { index } => {
+                let selected_id = self
+                    .franchises_list_view_wrapper
+                    .get(index)
+                    .map(|item| item.borrow().id);
+                self.selected_franchise = selected_id.and_then(|id| {
+                    self.franchises
+                        .iter()
+                        .find(|franchise| franchise.id == id)
+                        .cloned()
+                });
+                self.franchise = None;
+                self.software_titles_list_view_wrapper.clear();
+                self.fetch_franchise(&sender);
+            }
+            FranchisesMsg::AddFranchise { name } => {
+                let name = name.trim().to_string();
+                if name.is_empty() {
+                    return;
+                }
+                let repository_manager = Arc::clone(&self.repository_manager);
+                sender.oneshot_command(async move {
+                    let result = repository_manager
+                        .get_franchise_repository()
+                        .add_franchise(&name)
+                        .await;
+                    CommandMsg::FranchiseAdded(result)
+                });
+            }
+            FranchisesMsg::DeleteFranchise => {
+                if let Some(franchise) = &self.selected_franchise {
+                    let repository_manager = Arc::clone(&self.repository_manager);
+                    let franchise_id = franchise.id;
+                    sender.oneshot_command(async move {
+                        let result = repository_manager
+                            .get_franchise_repository()
+                            .delete_franchise(franchise_id)
+                            .await;
+                        CommandMsg::FranchiseDeleted(result)
+                    });
+                }
+            }
+            FranchisesMsg::OpenSoftwareTitleSelector => {
+                let software_title_selector = SoftwareTitleSelectModel::builder()
+                    .transient_for(root)
+                    .launch(SoftwareTitleSelectInit {
+                        view_model_service: Arc::clone(&self.view_model_service),
+                        repository_manager: Arc::clone(&self.repository_manager),
+                        selected_software_title_ids: self
+                            .franchise
+                            .as_ref()
+                            .map(|franchise| {
+                                franchise.software_titles.iter().map(|st| st.id).collect()
+                            })
+                            .unwrap_or_default(),
+                    })
+                    .forward(sender.input_sender(), |msg| match msg {
+                        SoftwareTitleSelectOutputMsg::SoftwareTitleSelected(software_title) => {
+                            FranchisesMsg::SoftwareTitleSelected(software_title)
+                        }
+                    });
+                self.software_title_selector = Some(software_title_selector);
+                self.software_title_selector
+                    .as_ref()
+                    .expect("Software title selector should be set")
+                    .widget()
+                    .present();
+            }
+            FranchisesMsg::SoftwareTitleSelected(software_title) => {
+                if let Some(franchise) = &self.franchise {
+                    self.set_software_title_franchise(
+                        &sender,
+                        software_title.id,
+                        Some(franchise.id),
+                    );
+                }
+            }
+            FranchisesMsg::RemoveSoftwareTitle => {
+                let selected = self
+                    .software_titles_list_view_wrapper
+                    .selection_model
+                    .selected();
+                if let Some(software_title) = self.software_titles_list_view_wrapper.get(selected) {
+                    let software_title_id = software_title.borrow().id;
+                    self.set_software_title_franchise(&sender, software_title_id, None);
+                }
+            }
+        }
+    }
+
+    fn update_cmd(
+        &mut self,
+        message: Self::CommandOutput,
+        sender: ComponentSender<Self>,
+        _: &Self::Root,
+    ) {
+        match message {
+            CommandMsg::FranchisesFetched(Ok(franchises)) => {
+                self.selected_franchise = None;
+                self.franchise = None;
+                self.software_titles_list_view_wrapper.clear();
+                self.franchises_list_view_wrapper.clear();
+                self.franchises_list_view_wrapper
+                    .extend_from_iter(franchises.iter().map(|franchise| ListItem {
+                        name: franchise.name.clone(),
+                        id: franchise.id,
+                    }));
+                self.franchises = franchises;
+            }
+            CommandMsg::FranchisesFetched(Err(e)) => {
+                eprintln!("Error fetching franchises: {:?}", e);
+                // TODO: show error to user
+            }
+            CommandMsg::FranchiseFetched(Ok(franchise)) => {
+                self.software_titles_list_view_wrapper.clear();
+                self.software_titles_list_view_wrapper.extend_from_iter(
+                    franchise
+                        .software_titles
+                        .iter()
+                        .map(|software_title| ListItem {
+                            name: software_title.name.clone(),
+                            id: software_title.id,
+                        }),
+                );
+                if let Some(selected_franchise) = self.selected_franchise.as_mut() {
+                    selected_franchise.can_delete = franchise.software_titles.is_empty();
+                }
+                self.franchise = Some(franchise);
+            }
+            CommandMsg::FranchiseFetched(Err(e)) => {
+                eprintln!("Error fetching franchise: {:?}", e);
+                // TODO: show error to user
+            }
+            CommandMsg::FranchiseAdded(Ok(_)) | CommandMsg::FranchiseDeleted(Ok(_)) => {
+                sender.input(FranchisesMsg::FetchFranchises);
+            }
+            CommandMsg::FranchiseAdded(Err(e)) => {
+                eprintln!("Error adding franchise: {:?}", e);
+                // TODO: show error to user
+            }
+            CommandMsg::FranchiseDeleted(Err(e)) => {
+                eprintln!("Error deleting franchise: {:?}", e);
+                // TODO: show error to user
+            }
+            CommandMsg::SoftwareTitleFranchiseSet(Ok(())) => {
+                self.fetch_franchise(&sender);
+            }
+            CommandMsg::SoftwareTitleFranchiseSet(Err(e)) => {
+                eprintln!("Error setting software title franchise: {:?}", e);
+                // TODO: show error to user
+            }
+        }
+    }
+}
@@ -1,8 +1,10 @@
+mod emulator_detector;
 mod emulator_form;
 mod emulator_runner;
 mod file_importer;
 mod file_selector;
 mod file_set_form;
+mod franchises;
 mod list_item;
 mod release;
 mod release_form;
@@ -12,7 +14,10 @@ mod system_selector;
 mod utils;
 use std::sync::Arc;
 
+// local emulator_runner module shadows the crate name
+use ::emulator_runner::process_registry::ProcessRegistry;
 use database::{get_db_pool, repository_manager::RepositoryManager};
+use franchises::{FranchisesInit, FranchisesModel};
 use list_item::ListItem;
 use releases::{ReleasesInit, ReleasesModel, ReleasesMsg};
 use relm4::{
@@ -22,8 +27,8 @@ use relm4::{
     typed_view::list::TypedListView,
 };
 use service::{
-    view_model_service::ViewModelService,
-    view_models::{Settings, SoftwareTitleListModel},
+    view_model_service::{SoftwareTitleFilter, ViewModelService},
+    view_models::{CollectionListModel, Settings, SoftwareTitleListModel},
 };
 
 #[derive(Debug)]
@@ -31,14 +36,27 @@ struct InitResult {
     repository_manager: Arc<RepositoryManager>,
     view_model_service: Arc<ViewModelService>,
     software_titles: Vec<SoftwareTitleListModel>,
+    collections: Vec<CollectionListModel>,
     settings: Settings,
 }
 
 #[derive(Debug)]
 enum AppMsg {
     Initialize,
-    SoftwareTitleSelected { index: u32 },
-    AddSoftwareTitle { name: String },
+    SoftwareTitleSelected {
+        index: u32,
+    },
+    AddSoftwareTitle {
+        name: String,
+    },
+    SearchSoftwareTitles {
+        query: String,
+    },
+    /// Index 0 browses the software titles, the rest browse the collections.
+    BrowseSelected {
+        index: u32,
+    },
+    OpenFranchises,
     Dummy,
 }
 
@@ -46,15 +64,21 @@ enum AppMsg {
 enum CommandMsg {
     InitializationDone(InitResult),
     SoftwareTitleAdded(ListItem),
+    SoftwareTitlesFetched(Vec<SoftwareTitleListModel>),
 }
 
 struct AppModel {
     software_titles: Vec<SoftwareTitleListModel>,
+    software_title_search: String,
+    collections: Vec<CollectionListModel>,
+    collection_names: gtk::StringList,
+    software_titles_box: gtk::Box,
     repository_manager: OnceCell<Arc<RepositoryManager>>,
     view_model_service: OnceCell<Arc<ViewModelService>>,
     list_view_wrapper: TypedListView<ListItem, gtk::SingleSelection>,
     releases_view: gtk::Box,
     releases: OnceCell<Controller<ReleasesModel>>,
+    franchises: Option<Controller<FranchisesModel>>,
 }
 
 struct AppWidgets {
@@ -111,8 +135,26 @@ impl Component for AppModel {
         main_layout_hbox.append(&left_vbox);
         main_layout_hbox.append(&right_vbox);
 
-        let title_label = gtk::Label::builder().label("Software Titles").build();
-        left_vbox.append(&title_label);
+        let collection_names = gtk::StringList::new(&["Software Titles"]);
+        let browse_dropdown = gtk::DropDown::builder().model(&collection_names).build();
+        browse_dropdown.connect_selected_notify(clone!(
+            #[strong]
+            sender,
+            move |dropdown| {
+                sender.input(AppMsg::BrowseSelected {
+                    index: dropdown.selected(),
+                });
+            }
+        ));
+        left_vbox.append(&browse_dropdown);
+
+        // hidden when a collection is browsed
+        let software_titles_box = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(10)
+            .vexpand(true)
+            .build();
+        left_vbox.append(&software_titles_box);
 
         let add_new_software_title_entry = gtk::Entry::builder()
             .placeholder_text("Add new software title")
@@ -130,7 +172,24 @@ impl Component for AppModel {
             }
         ));
 
-        left_vbox.append(&add_new_software_title_entry);
+        software_titles_box.append(&add_new_software_title_entry);
+
+        // matches aliases of the titles as well
+        let software_title_search_entry = gtk::SearchEntry::builder()
+            .placeholder_text("Search software titles")
+            .build();
+
+        software_title_search_entry.connect_search_changed(clone!(
+            #[strong]
+            sender,
+            move |entry| {
+                sender.input(AppMsg::SearchSoftwareTitles {
+                    query: entry.text().into(),
+                });
+            }
+        ));
+
+        software_titles_box.append(&software_title_search_entry);
 
         let software_titles_list_container = gtk::ScrolledWindow::builder().vexpand(true).build();
 
@@ -149,7 +208,17 @@ impl Component for AppModel {
 
         software_titles_list_container.set_child(Some(software_titles_view));
 
-        left_vbox.append(&software_titles_list_container);
+        software_titles_box.append(&software_titles_list_container);
+
+        let franchises_button = gtk::Button::builder().label("Franchises").build();
+        franchises_button.connect_clicked(clone!(
+            #[strong]
+            sender,
+            move |_| {
+                sender.input(AppMsg::OpenFranchises);
+            }
+        ));
+        left_vbox.append(&franchises_button);
 
         root.set_child(Some(&main_layout_hbox));
 
@@ -157,11 +226,16 @@ impl Component for AppModel {
 
         let model = AppModel {
             software_titles: vec![],
+            software_title_search: String::new(),
+            collections: vec![],
+            collection_names,
+            software_titles_box,
             repository_manager: OnceCell::new(),
             view_model_service: OnceCell::new(),
             list_view_wrapper,
             releases_view: right_vbox,
             releases: OnceCell::new(),
+            franchises: None,
         };
 
         sender.input(AppMsg::Initialize);
@@ -169,7 +243,7 @@ impl Component for AppModel {
         ComponentParts { model, widgets }
     }
 
-    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>, _: &Self::Root) {
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>, root: &Self::Root) {
         match msg {
             AppMsg::Initialize => {
                 sender.oneshot_command(async {
@@ -181,6 +255,10 @@ impl Component for AppModel {
                         .get_software_title_list_models()
                         .await
                         .expect("Fetching software titles failed");
+                    let collections = view_model_service
+                        .get_collection_list_models()
+                        .await
+                        .expect("Fetching collections failed");
                     let settings = view_model_service
                         .get_settings()
                         .await
@@ -189,6 +267,7 @@ impl Component for AppModel {
                         repository_manager,
                         view_model_service,
                         software_titles,
+                        collections,
                         settings,
                     })
                 });
@@ -225,6 +304,62 @@ impl Component for AppModel {
                     }
                 ));
             }
+            AppMsg::SearchSoftwareTitles { query } => {
+                let Some(view_model_service) = self.view_model_service.get() else {
+                    return;
+                };
+                self.software_title_search = query.trim().to_string();
+                let query = self.software_title_search.clone();
+
+                sender.oneshot_command(clone!(
+                    #[strong]
+                    view_model_service,
+                    async move {
+                        let software_titles = if query.is_empty() {
+                            view_model_service.get_software_title_list_models().await
+                        } else {
+                            view_model_service
+                                .search_software_title_list_models(&SoftwareTitleFilter {
+                                    search: Some(query),
+                                    ..Default::default()
+                                })
+                                .await
+                        }
+                        .expect("Fetching software titles failed");
+
+                        CommandMsg::SoftwareTitlesFetched(software_titles)
+                    }
+                ));
+            }
+            AppMsg::BrowseSelected { index } => {
+                let Some(releases) = self.releases.get() else {
+                    return;
+                };
+                let collection = index
+                    .checked_sub(1)
+                    .and_then(|index| self.collections.get(index as usize));
+                self.software_titles_box.set_visible(collection.is_none());
+                releases.emit(ReleasesMsg::CollectionSelected {
+                    id: collection.map(|collection| collection.id),
+                    is_smart: collection.is_some_and(|collection| collection.is_smart),
+                });
+            }
+            AppMsg::OpenFranchises => {
+                let (Some(view_model_service), Some(repository_manager)) =
+                    (self.view_model_service.get(), self.repository_manager.get())
+                else {
+                    return;
+                };
+                let franchises = FranchisesModel::builder()
+                    .transient_for(root)
+                    .launch(FranchisesInit {
+                        view_model_service: Arc::clone(view_model_service),
+                        repository_manager: Arc::clone(repository_manager),
+                    })
+                    .detach();
+                franchises.widget().present();
+                self.franchises = Some(franchises);
+            }
             AppMsg::Dummy => {
                 println!("Dummy message received");
             }
@@ -253,11 +388,21 @@ impl Component for AppModel {
                     id: title.id,
                 });
                 self.list_view_wrapper.extend_from_iter(list_items);
+                self.collections = init_result.collections;
+                for collection in &self.collections {
+                    if collection.is_smart {
+                        self.collection_names
+                            .append(&format!("{} (smart)", collection.name));
+                    } else {
+                        self.collection_names.append(&collection.name);
+                    }
+                }
 
                 let releases_init = ReleasesInit {
                     view_model_service,
                     repository_manager,
                     settings: Arc::new(init_result.settings),
+                    process_registry: Arc::new(ProcessRegistry::new()),
                 };
 
                 let releases = ReleasesModel::builder().launch(releases_init).forward(
@@ -272,12 +417,20 @@ impl Component for AppModel {
                     .expect("ReleasesModel already initialized");
             }
             CommandMsg::SoftwareTitleAdded(item) => {
-                self.software_titles.push(SoftwareTitleListModel {
-                    id: item.id,
-                    name: item.name.clone(),
-                    can_delete: false,
+                println!("Added software title: {}", item.name);
+                // fetch the titles again to keep them ordered by sort name
+                sender.input(AppMsg::SearchSoftwareTitles {
+                    query: self.software_title_search.clone(),
                 });
-                self.list_view_wrapper.append(item);
+            }
+            CommandMsg::SoftwareTitlesFetched(software_titles) => {
+                self.software_titles = software_titles;
+                self.list_view_wrapper.clear();
+                let list_items = self.software_titles.iter().map(|title| ListItem {
+                    name: title.name.clone(),
+                    id: title.id,
+                });
+                self.list_view_wrapper.extend_from_iter(list_items);
             }
         }
     }
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
-use database::repository_manager::RepositoryManager;
+use database::{models::ReleaseMetadata, repository_manager::RepositoryManager};
+use emulator_runner::process_registry::ProcessRegistry;
 use relm4::{
     Component, ComponentController, ComponentParts, ComponentSender, Controller,
     gtk::{
@@ -12,7 +13,9 @@ use relm4::{
 use service::{
     error::Error,
     view_model_service::ViewModelService,
-    view_models::{FileSetViewModel, ReleaseListModel, ReleaseViewModel, Settings},
+    view_models::{
+        FileSetViewModel, ReleaseLaunchSelection, ReleaseListModel, ReleaseViewModel, Settings,
+    },
 };
 
 use crate::{
@@ -26,9 +29,11 @@ pub struct ReleaseModel {
     view_model_service: Arc<ViewModelService>,
     repository_manager: Arc<RepositoryManager>,
     settings: Arc<Settings>,
+    process_registry: Arc<ProcessRegistry>,
 
     selected_release: Option<ReleaseViewModel>,
     selected_release_system_names: String,
+    selected_release_details: String,
     file_set_list_view_wrapper: TypedListView<ListItem, gtk::SingleSelection>,
     selected_file_set: Option<FileSetViewModel>,
     emulator_runner: Option<Controller<EmulatorRunnerModel>>,
@@ -40,6 +45,7 @@ pub struct ReleaseInitModel {
     pub view_model_service: Arc<ViewModelService>,
     pub repository_manager: Arc<RepositoryManager>,
     pub settings: Arc<Settings>,
+    pub process_registry: Arc<ProcessRegistry>,
 }
 
 #[derive(Debug)]
@@ -47,6 +53,7 @@ pub enum ReleaseMsg {
     ReleaseSelected { id: i64 },
     FetchRelease { id: i64 },
     StartEmulatorRunner,
+    Play,
     StartEditRelease,
     UpdateRelease(ReleaseListModel),
 }
@@ -54,6 +61,28 @@ pub enum ReleaseMsg {
 #[derive(Debug)]
 pub enum ReleaseCommandMsg {
     FetchedRelease(Result<ReleaseViewModel, Error>),
+    LaunchSelectionResolved {
+        selection: Result<ReleaseLaunchSelection, Error>,
+        auto_run: bool,
+    },
+}
+
+impl ReleaseModel {
+    fn resolve_launch_selection(&self, sender: &ComponentSender<Self>, auto_run: bool) {
+        if let Some(release) = &self.selected_release {
+            let view_model_service = Arc::clone(&self.view_model_service);
+            let release_id = release.id;
+            sender.oneshot_command(async move {
+                let selection = view_model_service
+                    .get_release_launch_selection(release_id)
+                    .await;
+                ReleaseCommandMsg::LaunchSelectionResolved {
+                    selection,
+                    auto_run,
+                }
+            });
+        }
+    }
 }
 
 #[relm4::component(pub)]
@@ -75,10 +104,20 @@ impl Component for ReleaseModel {
                 set_label: model.selected_release_system_names.as_str(),
 
             },
+            gtk::Label {
+                #[watch]
+                set_label: model.selected_release_details.as_str(),
+            },
 
             #[local_ref]
             file_set_list_view -> gtk::ListView { },
 
+            gtk::Button {
+                set_label: "Play",
+                #[watch]
+                set_sensitive: model.selected_release.is_some(),
+                connect_clicked => ReleaseMsg::Play,
+            },
             gtk::Button {
                 set_label: "Run with Emulator",
                 #[watch]
@@ -101,9 +140,11 @@ impl Component for ReleaseModel {
             view_model_service: init_model.view_model_service,
             repository_manager: init_model.repository_manager,
             settings: init_model.settings,
+            process_registry: init_model.process_registry,
 
             selected_release: None,
             selected_release_system_names: String::new(),
+            selected_release_details: String::new(),
             file_set_list_view_wrapper: TypedListView::new(),
             selected_file_set: None,
             emulator_runner: None,
@@ -130,30 +171,13 @@ impl Component for ReleaseModel {
                 });
             }
             ReleaseMsg::StartEmulatorRunner => {
-                if let (Some(file_set), Some(release)) =
-                    (&self.selected_file_set, &self.selected_release)
-                {
-                    println!("Starting emulator runner with file set: {:?}", file_set);
-                    let init_model = EmulatorRunnerInit {
-                        view_model_service: Arc::clone(&self.view_model_service),
-                        repository_manager: Arc::clone(&self.repository_manager),
-                        settings: Arc::clone(&self.settings),
-                        file_set: file_set.clone(),
-                        systems: release.systems.clone(),
-                    };
-                    let emulator_runner = EmulatorRunnerModel::builder()
-                        .transient_for(root)
-                        .launch(init_model)
-                        .detach();
-
-                    self.emulator_runner = Some(emulator_runner);
-                    self.emulator_runner
-                        .as_ref()
-                        .expect("Emulator runner should be set already")
-                        .widget()
-                        .present();
+                if self.selected_file_set.is_some() {
+                    self.resolve_launch_selection(&sender, false);
                 }
             }
+            ReleaseMsg::Play => {
+                self.resolve_launch_selection(&sender, true);
+            }
             ReleaseMsg::UpdateRelease(release_list_model) => {
                 println!("Updating release with model: {:?}", release_list_model);
                 // TODO
@@ -193,7 +217,7 @@ impl Component for ReleaseModel {
         &mut self,
         message: Self::CommandOutput,
         _sender: ComponentSender<Self>,
-        _: &Self::Root,
+        root: &Self::Root,
     ) {
         match message {
             ReleaseCommandMsg::FetchedRelease(Ok(release)) => {
@@ -207,6 +231,10 @@ impl Component for ReleaseModel {
                         .join(", ")
                 });
                 self.selected_release_system_names = system_names;
+                self.selected_release_details = self
+                    .selected_release
+                    .as_ref()
+                    .map_or(String::new(), |r| release_details(&r.metadata));
                 self.file_set_list_view_wrapper.clear();
                 self.file_set_list_view_wrapper.extend_from_iter(
                     self.selected_release.as_ref().map_or(vec![], |r| {
@@ -239,6 +267,78 @@ impl Component for ReleaseModel {
                 eprintln!("Error fetching release: {:?}", err);
                 // TODO: show error to user
             }
+            ReleaseCommandMsg::LaunchSelectionResolved {
+                selection: Ok(selection),
+                auto_run,
+            } => {
+                println!("Launch selection resolved: {:?}", selection);
+                // Play uses the resolved file set, otherwise the file set selected in the list
+                let resolved_file_set = self.selected_release.as_ref().and_then(|release| {
+                    release
+                        .file_sets
+                        .iter()
+                        .find(|fs| auto_run && Some(fs.id) == selection.file_set_id)
+                });
+                let file_set = resolved_file_set
+                    .or(self.selected_file_set.as_ref())
+                    .cloned();
+                if let (Some(file_set), Some(release)) = (file_set, &self.selected_release) {
+                    let auto_run = auto_run && selection.is_complete();
+                    let init_model = EmulatorRunnerInit {
+                        view_model_service: Arc::clone(&self.view_model_service),
+                        repository_manager: Arc::clone(&self.repository_manager),
+                        settings: Arc::clone(&self.settings),
+                        process_registry: Arc::clone(&self.process_registry),
+                        release_id: release.id,
+                        file_set,
+                        systems: release.systems.clone(),
+                        launch_selection: selection,
+                        auto_run,
+                    };
+                    let emulator_runner = EmulatorRunnerModel::builder()
+                        .transient_for(root)
+                        .launch(init_model)
+                        .detach();
+
+                    self.emulator_runner = Some(emulator_runner);
+                    self.emulator_runner
+                        .as_ref()
+                        .expect("Emulator runner should be set already")
+                        .widget()
+                        .present();
+                } else {
+                    eprintln!("No file set to launch");
+                }
+            }
+            ReleaseCommandMsg::LaunchSelectionResolved {
+                selection: Err(err),
+                ..
+            } => {
+                eprintln!("Error resolving launch selection: {:?}", err);
+                // TODO: show error to user
+            }
         }
     }
 }
+
+fn release_details(metadata: &ReleaseMetadata) -> String {
+    [
+        (
+            "Released",
+            metadata.release_date.map(|date| date.to_string()),
+        ),
+        ("Regions", Some(metadata.regions.join(", "))),
+        ("Languages", Some(metadata.languages.join(", "))),
+        ("Version", metadata.version.clone()),
+        ("Product code", metadata.product_code.clone()),
+        ("Barcode", metadata.barcode.clone()),
+    ]
+    .into_iter()
+    .filter_map(|(label, value)| {
+        value
+            .filter(|value| !value.is_empty())
+            .map(|value| format!("{}: {}", label, value))
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
@@ -1,11 +1,18 @@
 use std::sync::Arc;
 
-use database::{database_error::Error, repository_manager::RepositoryManager};
+use database::{
+    database_error::Error,
+    models::{PartialDate, ReleaseMetadata},
+    repository_manager::RepositoryManager,
+};
 use relm4::{
     Component, ComponentController, ComponentParts, ComponentSender, Controller,
     gtk::{
         self,
-        prelude::{ButtonExt, GtkWindowExt, OrientableExt, WidgetExt},
+        prelude::{
+            ButtonExt, EditableExt, EntryBufferExtManual, EntryExt, GtkWindowExt, OrientableExt,
+            WidgetExt,
+        },
     },
     typed_view::list::TypedListView,
 };
@@ -35,6 +42,12 @@ pub enum ReleaseFormMsg {
     SoftwareTitleSelected(SoftwareTitleListModel),
     StartSaveRelease,
     OpenSoftwareTitleSelector,
+    ReleaseDateChanged(String),
+    RegionsChanged(String),
+    LanguagesChanged(String),
+    VersionChanged(String),
+    ProductCodeChanged(String),
+    BarcodeChanged(String),
 }
 
 #[derive(Debug)]
@@ -62,6 +75,7 @@ pub struct ReleaseFormModel {
     selected_systems_list_view_wrapper: TypedListView<ListItem, gtk::SingleSelection>,
     selected_file_sets_list_view_wrapper: TypedListView<ListItem, gtk::SingleSelection>,
     release: Option<ReleaseViewModel>,
+    metadata: ReleaseMetadata,
 }
 
 pub struct ReleaseFormInit {
@@ -124,6 +138,81 @@ impl Component for ReleaseFormModel {
                 },
 
 
+                gtk::Label {
+                    set_label: "Release date",
+                },
+                gtk::Entry {
+                    set_text: &model
+                        .metadata
+                        .release_date
+                        .map(|date| date.to_string())
+                        .unwrap_or_default(),
+                    set_placeholder_text: Some("YYYY, YYYY-MM or YYYY-MM-DD"),
+                    connect_activate[sender] => move |entry| {
+                        let buffer = entry.buffer();
+                        sender.input(ReleaseFormMsg::ReleaseDateChanged(buffer.text().into()));
+                    },
+                },
+
+                gtk::Label {
+                    set_label: "Regions",
+                },
+                gtk::Entry {
+                    set_text: &model.metadata.regions.join(", "),
+                    set_placeholder_text: Some("Comma separated, e.g. Europe, USA"),
+                    connect_activate[sender] => move |entry| {
+                        let buffer = entry.buffer();
+                        sender.input(ReleaseFormMsg::RegionsChanged(buffer.text().into()));
+                    },
+                },
+
+                gtk::Label {
+                    set_label: "Languages",
+                },
+                gtk::Entry {
+                    set_text: &model.metadata.languages.join(", "),
+                    set_placeholder_text: Some("Comma separated, e.g. en, de"),
+                    connect_activate[sender] => move |entry| {
+                        let buffer = entry.buffer();
+                        sender.input(ReleaseFormMsg::LanguagesChanged(buffer.text().into()));
+                    },
+                },
+
+                gtk::Label {
+                    set_label: "Version",
+                },
+                gtk::Entry {
+                    set_text: model.metadata.version.as_deref().unwrap_or_default(),
+                    set_placeholder_text: Some("Version or revision, e.g. Rev A"),
+                    connect_activate[sender] => move |entry| {
+                        let buffer = entry.buffer();
+                        sender.input(ReleaseFormMsg::VersionChanged(buffer.text().into()));
+                    },
+                },
+
+                gtk::Label {
+                    set_label: "Product code",
+                },
+                gtk::Entry {
+                    set_text: model.metadata.product_code.as_deref().unwrap_or_default(),
+                    set_placeholder_text: Some("Product code or serial"),
+                    connect_activate[sender] => move |entry| {
+                        let buffer = entry.buffer();
+                        sender.input(ReleaseFormMsg::ProductCodeChanged(buffer.text().into()));
+                    },
+                },
+
+                gtk::Label {
+                    set_label: "Barcode",
+                },
+                gtk::Entry {
+                    set_text: model.metadata.barcode.as_deref().unwrap_or_default(),
+                    connect_activate[sender] => move |entry| {
+                        let buffer = entry.buffer();
+                        sender.input(ReleaseFormMsg::BarcodeChanged(buffer.text().into()));
+                    },
+                },
+
                 gtk::Button {
                     set_label: "Submit Release",
                     connect_clicked => ReleaseFormMsg::StartSaveRelease,
@@ -163,11 +252,7 @@ impl Component for ReleaseFormModel {
             selected_software_titles = release
                 .software_titles
                 .iter()
-                .map(|st| SoftwareTitleListModel {
-                    id: st.id,
-                    name: st.name.clone(),
-                    can_delete: false,
-                })
+                .map(SoftwareTitleListModel::from)
                 .collect();
         }
 
@@ -205,6 +290,11 @@ impl Component for ReleaseFormModel {
             }),
         );
 
+        let metadata = init_model
+            .release
+            .as_ref()
+            .map(|release| release.metadata.clone())
+            .unwrap_or_default();
         let model = ReleaseFormModel {
             view_model_service: init_model.view_model_service,
             repository_manager: init_model.repository_manager,
@@ -219,6 +309,7 @@ impl Component for ReleaseFormModel {
             selected_systems,
             selected_file_sets,
             selected_software_titles,
+            metadata,
         };
 
         let selected_systems_list_view = &model.selected_systems_list_view_wrapper.view;
@@ -327,6 +418,32 @@ impl Component for ReleaseFormModel {
                     });
                 self.selected_software_titles.push(software_title);
             }
+            ReleaseFormMsg::ReleaseDateChanged(release_date) => {
+                let release_date = release_date.trim();
+                if release_date.is_empty() {
+                    self.metadata.release_date = None;
+                } else {
+                    match release_date.parse::<PartialDate>() {
+                        Ok(date) => self.metadata.release_date = Some(date),
+                        Err(err) => eprintln!("{}", err),
+                    }
+                }
+            }
+            ReleaseFormMsg::RegionsChanged(regions) => {
+                self.metadata.regions = split_list(&regions);
+            }
+            ReleaseFormMsg::LanguagesChanged(languages) => {
+                self.metadata.languages = split_list(&languages);
+            }
+            ReleaseFormMsg::VersionChanged(version) => {
+                self.metadata.version = non_empty(version);
+            }
+            ReleaseFormMsg::ProductCodeChanged(product_code) => {
+                self.metadata.product_code = non_empty(product_code);
+            }
+            ReleaseFormMsg::BarcodeChanged(barcode) => {
+                self.metadata.barcode = non_empty(barcode);
+            }
             ReleaseFormMsg::StartSaveRelease => {
                 println!("Starting to save release with selected systems and file sets");
                 let repository_manager = Arc::clone(&self.repository_manager);
@@ -358,6 +475,7 @@ impl Component for ReleaseFormModel {
                         .collect();
 
                     let release_id = self.release.as_ref().map(|r| r.id);
+                    let metadata = self.metadata.clone();
 
                     sender.oneshot_command(async move {
                         let res = match release_id {
@@ -387,6 +505,14 @@ impl Component for ReleaseFormModel {
                                     .await
                             }
                         };
+                        let res = match res {
+                            Ok(id) => repository_manager
+                                .get_release_repository()
+                                .update_release_metadata(id, &metadata)
+                                .await
+                                .map(|_| id),
+                            Err(err) => Err(err),
+                        };
                         CommandMsg::ReleaseCreatedOrUpdated(res)
                     });
                 }
@@ -433,3 +559,22 @@ impl Component for ReleaseFormModel {
         }
     }
 }
+
+/// Splits a comma separated list, leaving out empty and repeated entries.
+fn split_list(value: &str) -> Vec<String> {
+    let mut items: Vec<String> = vec![];
+    for item in value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+    {
+        if !items.iter().any(|existing| existing == item) {
+            items.push(item.to_string());
+        }
+    }
+    items
+}
+
+fn non_empty(value: String) -> Option<String> {
+    Some(value.trim().to_string()).filter(|value| !value.is_empty())
+}
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use database::repository_manager::RepositoryManager;
+use emulator_runner::process_registry::ProcessRegistry;
 use relm4::{
     Component, ComponentController, ComponentParts, ComponentSender, Controller, RelmWidgetExt,
     gtk::{self, prelude::*},
@@ -20,16 +21,32 @@ use crate::{
 
 #[derive(Debug)]
 pub enum ReleasesMsg {
-    SoftwareTitleSelected { id: i64 },
-    ReleaseSelected { index: u32 },
+    SoftwareTitleSelected {
+        id: i64,
+    },
+    /// Shows the releases of the collection, or of the selected software title when `None`.
+    CollectionSelected {
+        id: Option<i64>,
+        /// Releases of a smart collection can't be reordered.
+        is_smart: bool,
+    },
+    ReleaseSelected {
+        index: u32,
+    },
     StartAddRelease,
     AddRelease(ReleaseListModel),
     FetchReleases,
+    ToggleFavorite,
+    /// Moves the selected release up (negative) or down in the collection.
+    MoveInCollection {
+        offset: i32,
+    },
 }
 
 #[derive(Debug)]
 pub enum CommandMsg {
     FetchedReleases(Result<Vec<ReleaseListModel>, Error>),
+    CollectionChanged(Result<(), Error>),
 }
 
 #[derive(Debug)]
@@ -40,6 +57,9 @@ pub struct ReleasesModel {
     form_window: Option<Controller<ReleaseFormModel>>,
     releases_list_view_wrapper: TypedListView<ListItem, gtk::SingleSelection>,
     selected_software_title_id: Option<i64>,
+    selected_collection_id: Option<i64>,
+    is_smart_collection: bool,
+    selected_release_id: Option<i64>,
 
     release: Controller<ReleaseModel>,
 }
@@ -48,6 +68,7 @@ pub struct ReleasesInit {
     pub view_model_service: Arc<ViewModelService>,
     pub repository_manager: Arc<RepositoryManager>,
     pub settings: Arc<Settings>,
+    pub process_registry: Arc<ProcessRegistry>,
 }
 
 #[relm4::component(pub)]
@@ -79,9 +100,35 @@ impl Component for ReleasesModel {
 
                 gtk::Button {
                     set_label: "Add Release",
+                    #[watch]
+                    set_visible: model.selected_collection_id.is_none(),
                     connect_clicked => ReleasesMsg::StartAddRelease,
                 },
 
+                gtk::Button {
+                    set_label: "Toggle Favorite",
+                    #[watch]
+                    set_sensitive: model.selected_release_id.is_some(),
+                    connect_clicked => ReleasesMsg::ToggleFavorite,
+                },
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 5,
+                    #[watch]
+                    set_visible: model.selected_collection_id.is_some() && !model.is_smart_collection,
+
+                    gtk::Button {
+                        set_label: "Move Up",
+                        connect_clicked => ReleasesMsg::MoveInCollection { offset: -1 },
+                    },
+
+                    gtk::Button {
+                        set_label: "Move Down",
+                        connect_clicked => ReleasesMsg::MoveInCollection { offset: 1 },
+                    },
+                },
+
             },
 
             gtk::Box {
@@ -99,6 +146,7 @@ impl Component for ReleasesModel {
             view_model_service: Arc::clone(&init_model.view_model_service),
             repository_manager: Arc::clone(&init_model.repository_manager),
             settings: Arc::clone(&init_model.settings),
+            process_registry: init_model.process_registry,
         };
         let release_model = ReleaseModel::builder().launch(release_init_model).detach();
 
@@ -110,6 +158,9 @@ impl Component for ReleasesModel {
             releases_list_view_wrapper: TypedListView::new(),
             release: release_model,
             selected_software_title_id: None,
+            selected_collection_id: None,
+            is_smart_collection: false,
+            selected_release_id: None,
         };
         let releases_list_view = &model.releases_list_view_wrapper.view;
         let widgets = view_output!();
@@ -123,8 +174,22 @@ impl Component for ReleasesModel {
                 self.selected_software_title_id = Some(id);
                 sender.input(ReleasesMsg::FetchReleases);
             }
+            ReleasesMsg::CollectionSelected { id, is_smart } => {
+                self.selected_collection_id = id;
+                self.is_smart_collection = is_smart;
+                self.releases_list_view_wrapper.clear();
+                sender.input(ReleasesMsg::FetchReleases);
+            }
             ReleasesMsg::FetchReleases => {
-                if let Some(software_title_id) = self.selected_software_title_id {
+                if let Some(collection_id) = self.selected_collection_id {
+                    let view_model_service = Arc::clone(&self.view_model_service);
+                    sender.oneshot_command(async move {
+                        let releases_result = view_model_service
+                            .get_collection_release_list_models(collection_id)
+                            .await;
+                        CommandMsg::FetchedReleases(releases_result)
+                    });
+                } else if let Some(software_title_id) = self.selected_software_title_id {
                     println!(
                         "Fetching releases for software title ID: {}",
                         software_title_id
@@ -134,8 +199,8 @@ impl Component for ReleasesModel {
                     sender.oneshot_command(async move {
                         let releases_result = view_model_service
                             .get_release_list_models(ReleaseFilter {
-                                software_title_id: Some(software_title_id),
-                                system_id: None,
+                                software_title_ids: vec![software_title_id],
+                                ..Default::default()
                             })
                             .await;
                         CommandMsg::FetchedReleases(releases_result)
@@ -151,6 +216,7 @@ impl Component for ReleasesModel {
                 if let Some(item) = selected {
                     println!("Selected item: {:?}", item);
                     let selected_id = item.borrow().id;
+                    self.selected_release_id = Some(selected_id);
                     self.release
                         .sender()
                         .emit(ReleaseMsg::ReleaseSelected { id: selected_id });
@@ -195,6 +261,37 @@ impl Component for ReleasesModel {
                     return;
                 }
             }
+            ReleasesMsg::ToggleFavorite => {
+                let Some(release_id) = self.selected_release_id else {
+                    return;
+                };
+                let view_model_service = Arc::clone(&self.view_model_service);
+                sender.oneshot_command(async move {
+                    let result = view_model_service
+                        .toggle_favorite(release_id)
+                        .await
+                        .map(|_| ());
+                    CommandMsg::CollectionChanged(result)
+                });
+            }
+            ReleasesMsg::MoveInCollection { offset } => {
+                let (Some(collection_id), Some(release_id)) =
+                    (self.selected_collection_id, self.selected_release_id)
+                else {
+                    return;
+                };
+                let index = self.releases_list_view_wrapper.selection_model.selected();
+                let Some(position) = index.checked_add_signed(offset) else {
+                    return;
+                };
+                let view_model_service = Arc::clone(&self.view_model_service);
+                sender.oneshot_command(async move {
+                    let result = view_model_service
+                        .move_release_in_collection(collection_id, release_id, position as usize)
+                        .await;
+                    CommandMsg::CollectionChanged(result)
+                });
+            }
             ReleasesMsg::AddRelease(release_list_model) => {
                 println!("Release added: {:?}", release_list_model);
                 self.releases_list_view_wrapper.append(ListItem {
@@ -233,6 +330,16 @@ impl Component for ReleasesModel {
                     }
                 }
             }
+            CommandMsg::CollectionChanged(result) => match result {
+                // the favorites or the order of the shown collection may have changed
+                Ok(()) if self.selected_collection_id.is_some() => {
+                    sender.input(ReleasesMsg::FetchReleases);
+                }
+                Ok(()) => {}
+                Err(err) => {
+                    eprintln!("Error updating collection: {:?}", err);
+                }
+            },
         }
     }
 }
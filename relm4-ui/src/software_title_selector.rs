@@ -1,6 +1,9 @@
 use std::sync::Arc;
 
-use database::{database_error::Error as DatabaseError, repository_manager::RepositoryManager};
+use database::{
+    database_error::Error as DatabaseError, models::SoftwareTitle,
+    repository_manager::RepositoryManager,
+};
 use relm4::{
     Component, ComponentParts, ComponentSender,
     gtk::{
@@ -141,6 +144,7 @@ impl Component for SoftwareTitleSelectModel {
                             let software_title_list_model = SoftwareTitleListModel {
                                 id,
                                 name: name.clone(),
+                                sort_name: SoftwareTitle::default_sort_name(&name),
                                 can_delete: true, // OK to delete since this was just added
                             };
                             CommandMsg::SoftwareTitleAdded(software_title_list_model)
@@ -161,6 +165,7 @@ impl Component for SoftwareTitleSelectModel {
                         SoftwareTitleListModel {
                             id: software_title.id,
                             name: software_title.name.clone(),
+                            sort_name: SoftwareTitle::default_sort_name(&software_title.name),
                             can_delete: false, // TODO
                         },
                     ));
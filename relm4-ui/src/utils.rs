@@ -1,12 +1,10 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::HashSet,
     path::{Path, PathBuf},
 };
 
-use core_types::{FileType, Sha1Checksum};
-use file_export::{FileSetExportModel, OutputFile};
+use core_types::FileType;
 use file_import::FileImportModel;
-use service::view_models::FileSetViewModel;
 
 use crate::file_importer::FileImporter;
 
@@ -16,44 +14,6 @@ pub fn resolve_file_type_path(root_path: &Path, file_type: &FileType) -> PathBuf
     path
 }
 
-pub fn prepare_fileset_for_export(
-    file_set: &FileSetViewModel,
-    collection_root_dir: &Path,
-    temp_dir: &Path,
-    extract_files: bool,
-) -> FileSetExportModel {
-    let source_file_path = resolve_file_type_path(collection_root_dir, &file_set.file_type.into());
-
-    let output_mapping = file_set
-        .files
-        .iter()
-        .map(|f| {
-            let checksum: Sha1Checksum = f
-                .sha1_checksum
-                .clone()
-                .try_into()
-                .expect("Failed to convert to Sha1Checksum");
-            (
-                f.archive_file_name.clone(),
-                OutputFile {
-                    output_file_name: f.file_name.clone(),
-                    checksum,
-                },
-            )
-        })
-        .collect::<HashMap<String, OutputFile>>();
-
-    let exported_zip_file_name = file_set.file_set_name.clone();
-
-    FileSetExportModel {
-        output_mapping,
-        source_file_path,
-        output_dir: temp_dir.to_path_buf(),
-        extract_files,
-        exported_zip_file_name,
-    }
-}
-
 pub fn prepare_file_import(
     file_path: &Path,
     file_type: FileType,
@@ -0,0 +1,382 @@
+use std::path::PathBuf;
+
+use clap::{ArgGroup, Args, Parser, Subcommand};
+use core_types::FileType;
+use database::models::{
+    CompanyRole, FileSetLaunchRole, PartialDate, ReleaseCriterion, ReleaseSortKey,
+};
+use strum::IntoEnumIterator;
+
+/// Manage the software collection from the command line.
+#[derive(Debug, Parser)]
+#[command(name = "scm", version, about)]
+pub struct Cli {
+    /// Print the output as JSON.
+    #[arg(long, global = true)]
+    pub json: bool,
+    /// Database file, defaults to the database of the application.
+    #[arg(long, global = true, value_name = "PATH")]
+    pub database: Option<PathBuf>,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// List and add systems.
+    #[command(subcommand)]
+    Systems(SystemsCommand),
+    /// List and add software titles.
+    #[command(subcommand)]
+    Titles(TitlesCommand),
+    /// List and add releases.
+    #[command(subcommand)]
+    Releases(ReleasesCommand),
+    /// List and add companies.
+    #[command(subcommand)]
+    Companies(CompaniesCommand),
+    /// List, add and manage tags.
+    #[command(subcommand)]
+    Tags(TagsCommand),
+    /// Manage collections of releases and export them as playlists.
+    #[command(subcommand)]
+    Collections(CollectionsCommand),
+    /// List emulators.
+    #[command(subcommand)]
+    Emulators(EmulatorsCommand),
+    /// List, import and export file sets.
+    #[command(subcommand)]
+    FileSets(FileSetsCommand),
+    /// Search software titles, releases and file names, best matches first.
+    Search {
+        /// Words the names should have words starting with, case and diacritics are ignored.
+        query: String,
+        #[arg(long, default_value_t = 50)]
+        limit: i64,
+    },
+    /// Verify that the collection files are found and match their checksums.
+    Verify {
+        /// Verify only this file set.
+        #[arg(long)]
+        file_set: Option<i64>,
+    },
+    /// Launch a release with an emulator.
+    Launch(LaunchArgs),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SystemsCommand {
+    List,
+    Add { name: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TitlesCommand {
+    List {
+        /// Only titles whose name, sort name or an alias contains this.
+        #[arg(long)]
+        search: Option<String>,
+        /// Only titles tagged with this tag or its subtags.
+        #[arg(long)]
+        tag: Option<String>,
+    },
+    Add {
+        name: String,
+        /// Name the title is sorted by, e.g. "Legend of Zelda, The".
+        #[arg(long)]
+        sort_name: Option<String>,
+    },
+    /// Add another name for a title, e.g. a regional name.
+    Alias {
+        id: i64,
+        name: String,
+        #[arg(long)]
+        language: Option<String>,
+        #[arg(long)]
+        region: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ReleasesCommand {
+    /// List releases, filters given more than once match any of their values.
+    List {
+        /// Only releases for this system.
+        #[arg(long = "system")]
+        systems: Vec<i64>,
+        /// Only releases of this software title.
+        #[arg(long = "title")]
+        titles: Vec<i64>,
+        /// Only releases with a file set of this type, e.g. rom or disk_image.
+        #[arg(long = "type", value_parser = parse_file_type)]
+        file_types: Vec<FileType>,
+        /// Only releases named like this pattern ignoring case, `*` matches any characters and
+        /// `?` a single character.
+        #[arg(long = "name")]
+        name_patterns: Vec<String>,
+        /// Only releases for this region.
+        #[arg(long)]
+        region: Option<String>,
+        /// Only releases in this language.
+        #[arg(long)]
+        language: Option<String>,
+        /// Only releases dated within or after YYYY[-MM[-DD]].
+        #[arg(long, value_parser = parse_date)]
+        released_from: Option<PartialDate>,
+        /// Only releases dated within or before YYYY[-MM[-DD]].
+        #[arg(long, value_parser = parse_date)]
+        released_to: Option<PartialDate>,
+        /// Only releases the company is credited for.
+        #[arg(long)]
+        company: Option<i64>,
+        /// Role of the company: developer, publisher or distributor.
+        #[arg(long, requires = "company", value_parser = parse_company_role)]
+        role: Option<CompanyRole>,
+        /// Only releases tagged with this tag or its subtags, directly or via their titles or
+        /// file sets.
+        #[arg(long)]
+        tag: Option<String>,
+        /// Sort by name, date or id.
+        #[arg(long, default_value = "name", value_parser = parse_sort_key)]
+        sort: ReleaseSortKey,
+        #[arg(long)]
+        desc: bool,
+        /// List the releases after this release in the sort order, to page from the last
+        /// listed release.
+        #[arg(long)]
+        after: Option<i64>,
+        #[arg(long)]
+        limit: Option<i64>,
+        #[arg(long)]
+        offset: Option<i64>,
+    },
+    /// Search releases with a query, e.g. `system:"Commodore 64" type:disk year:<1990
+    /// tag:shmup -has:manual`. Words match release, title, alias and file names, the keys are
+    /// system, type, year, tag, region, language and has, and a leading - negates a term.
+    Search { query: String },
+    Add {
+        name: String,
+        #[arg(long = "system")]
+        systems: Vec<i64>,
+        #[arg(long = "title")]
+        titles: Vec<i64>,
+        #[arg(long = "file-set")]
+        file_sets: Vec<i64>,
+    },
+    /// List the launch roles of the file sets of a release.
+    Roles { id: i64 },
+    /// Set the role a file set is launched with along with other file sets of the release, e.g.
+    /// "bios" for "{bios}" in the emulator arguments. The role is cleared when not given.
+    SetRole {
+        id: i64,
+        file_set: i64,
+        role: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CompaniesCommand {
+    List,
+    Add { name: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TagsCommand {
+    List,
+    /// Tag a title, release or file set. Subtags are separated with a slash, e.g. "genre/shmup".
+    #[command(group(ArgGroup::new("target").required(true)))]
+    Add {
+        name: String,
+        #[arg(long, group = "target")]
+        title: Option<i64>,
+        #[arg(long, group = "target")]
+        release: Option<i64>,
+        #[arg(long, group = "target")]
+        file_set: Option<i64>,
+    },
+    /// Rename a tag, its subtags are renamed along.
+    Rename {
+        id: i64,
+        name: String,
+    },
+    /// Move everything tagged with the source tag to the target tag and delete the source tag.
+    Merge {
+        source: i64,
+        target: i64,
+    },
+    /// Delete a tag and its subtags.
+    Delete {
+        id: i64,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CollectionsCommand {
+    List,
+    Add {
+        name: String,
+    },
+    /// Add a smart collection of the releases matching all the criteria, systems, regions and
+    /// languages match any of the given ones.
+    AddSmart {
+        name: String,
+        /// Criterion as key=value, one of system=<id>, file-type=<type>, no-file-type=<type>,
+        /// region=<region>, language=<language>, released-from=<date>, released-to=<date>,
+        /// tag=<tag>, no-tag=<tag>, name=<text> or played-within=<days>.
+        #[arg(long = "criterion", required = true, value_parser = parse_criterion)]
+        criteria: Vec<ReleaseCriterion>,
+    },
+    /// List the criteria of a smart collection.
+    Criteria {
+        id: i64,
+    },
+    /// Replace the criteria of a smart collection.
+    SetCriteria {
+        id: i64,
+        #[arg(long = "criterion", required = true, value_parser = parse_criterion)]
+        criteria: Vec<ReleaseCriterion>,
+    },
+    Rename {
+        id: i64,
+        name: String,
+    },
+    /// Delete a collection, the releases in it are kept.
+    Delete {
+        id: i64,
+    },
+    /// List the releases of a collection in the order of the collection.
+    Releases {
+        id: i64,
+    },
+    /// Add a release to the end of a collection.
+    AddRelease {
+        id: i64,
+        release: i64,
+    },
+    RemoveRelease {
+        id: i64,
+        release: i64,
+    },
+    /// Move a release to a zero based position in a collection.
+    Move {
+        id: i64,
+        release: i64,
+        position: usize,
+    },
+    /// Add a release to the favorites, or remove it if it's a favorite already.
+    Favorite {
+        release: i64,
+    },
+    /// Export the releases of a collection as zip archives with an m3u playlist.
+    Export {
+        id: i64,
+        output_dir: PathBuf,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum EmulatorsCommand {
+    List,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum FileSetsCommand {
+    List,
+    /// Import a file, or files from a zip archive, into a new file set.
+    Import {
+        path: PathBuf,
+        /// File type, e.g. rom, disk_image or tape_image.
+        #[arg(long = "type", value_parser = parse_file_type)]
+        file_type: FileType,
+        #[arg(long = "system", required = true)]
+        systems: Vec<i64>,
+        /// File set name, defaults to the file name.
+        #[arg(long)]
+        name: Option<String>,
+        /// File to import from a zip archive, all files are imported by default.
+        #[arg(long = "file")]
+        files: Vec<String>,
+    },
+    /// Export the files of a file set to a directory.
+    Export {
+        id: i64,
+        output_dir: PathBuf,
+        /// Write the files into a zip archive instead of extracting them.
+        #[arg(long)]
+        zip: bool,
+    },
+}
+
+/// Choices override what is resolved for the release, see
+/// `ViewModelService::get_release_launch_selection`.
+#[derive(Debug, Args)]
+pub struct LaunchArgs {
+    pub release_id: i64,
+    #[arg(long)]
+    pub system: Option<i64>,
+    #[arg(long)]
+    pub emulator: Option<i64>,
+    #[arg(long)]
+    pub file_set: Option<i64>,
+    /// File of the file set the emulator is started with.
+    #[arg(long)]
+    pub file: Option<String>,
+    /// Launch an m3u playlist of all the files in the file set.
+    #[arg(long)]
+    pub playlist: bool,
+    /// Record the launch result in the release compatibility.
+    #[arg(long)]
+    pub record: bool,
+    /// Launch the file set with this id along with the role, as ROLE=FILE_SET_ID, instead of
+    /// the roles saved for the release.
+    #[arg(long = "role", value_parser = parse_launch_role)]
+    pub roles: Vec<FileSetLaunchRole>,
+}
+
+fn parse_file_type(value: &str) -> Result<FileType, String> {
+    FileType::iter()
+        .find(|file_type| file_type.dir_name() == value)
+        .ok_or_else(|| {
+            let file_types = FileType::iter()
+                .map(|file_type| file_type.dir_name())
+                .collect::<Vec<_>>();
+            format!("expected one of: {}", file_types.join(", "))
+        })
+}
+
+fn parse_date(value: &str) -> Result<PartialDate, String> {
+    value
+        .parse()
+        .map_err(|err: database::database_error::Error| err.to_string())
+}
+
+fn parse_criterion(value: &str) -> Result<ReleaseCriterion, String> {
+    value
+        .parse()
+        .map_err(|err: database::database_error::Error| err.to_string())
+}
+
+fn parse_company_role(value: &str) -> Result<CompanyRole, String> {
+    value
+        .parse()
+        .map_err(|err: database::database_error::Error| err.to_string())
+}
+
+fn parse_launch_role(value: &str) -> Result<FileSetLaunchRole, String> {
+    let (role, file_set_id) = value
+        .split_once('=')
+        .ok_or_else(|| "expected ROLE=FILE_SET_ID".to_string())?;
+    let file_set_id = file_set_id
+        .parse()
+        .map_err(|err| format!("invalid file set id: {}", err))?;
+    Ok(FileSetLaunchRole {
+        file_set_id,
+        role: role.to_string(),
+    })
+}
+
+fn parse_sort_key(value: &str) -> Result<ReleaseSortKey, String> {
+    value
+        .parse()
+        .map_err(|err: database::database_error::Error| err.to_string())
+}
@@ -0,0 +1,824 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use core_types::FileType;
+use database::{
+    models::{ReleaseCriterion, SoftwareTitle, TagTarget},
+    repository_manager::RepositoryManager,
+};
+use emulator_runner::process_registry::ProcessRegistry;
+use file_export::{verify_files, FileVerificationStatus};
+use service::{
+    file_set_operations::FileSetImportRequest,
+    release_launch::{file_set_export_model, ReleaseLaunchRequest},
+    view_model_service::{ReleaseFilter, SoftwareTitleFilter, ViewModelService},
+};
+
+use crate::{
+    cli::LaunchArgs,
+    error::Error,
+    output::{
+        CollectionOutput, CompanyOutput, Created, CriterionOutput, EmulatorOutput, ExportOutput,
+        FavoriteOutput, FileSetImportOutput, FileSetOutput, LaunchOutput, LaunchRoleOutput,
+        PlaylistOutput, ReleaseOutput, SearchResultOutput, SoftwareTitleOutput, SystemOutput,
+        TagOutput, VerificationOutput,
+    },
+};
+
+pub struct Context {
+    pub repository_manager: Arc<RepositoryManager>,
+    pub view_model_service: ViewModelService,
+    pub collection_root_dir: PathBuf,
+    /// Launches started by the command, a launch directory in use isn't exported to again.
+    pub process_registry: ProcessRegistry,
+}
+
+impl Context {
+    pub async fn new(repository_manager: Arc<RepositoryManager>) -> Result<Self, Error> {
+        let view_model_service = ViewModelService::new(Arc::clone(&repository_manager));
+        let settings = view_model_service.get_settings().await?;
+        Ok(Self {
+            repository_manager,
+            view_model_service,
+            collection_root_dir: settings.collection_root_dir,
+            process_registry: ProcessRegistry::new(),
+        })
+    }
+}
+
+pub async fn list_systems(context: &Context) -> Result<Vec<SystemOutput>, Error> {
+    let systems = context.view_model_service.get_system_list_models().await?;
+    Ok(systems
+        .into_iter()
+        .map(|system| SystemOutput {
+            id: system.id,
+            name: system.name,
+            can_delete: system.can_delete,
+        })
+        .collect())
+}
+
+pub async fn add_system(context: &Context, name: String) -> Result<Created, Error> {
+    let id = context
+        .repository_manager
+        .get_system_repository()
+        .add_system(&name)
+        .await?;
+    Ok(Created { id })
+}
+
+pub async fn list_software_titles(
+    context: &Context,
+    filter: SoftwareTitleFilter,
+) -> Result<Vec<SoftwareTitleOutput>, Error> {
+    let software_titles = if filter.search.is_none() && filter.tag.is_none() {
+        context
+            .view_model_service
+            .get_software_title_list_models()
+            .await?
+    } else {
+        context
+            .view_model_service
+            .search_software_title_list_models(&filter)
+            .await?
+    };
+    Ok(software_titles
+        .into_iter()
+        .map(|software_title| SoftwareTitleOutput {
+            id: software_title.id,
+            name: software_title.name,
+            sort_name: software_title.sort_name,
+            can_delete: software_title.can_delete,
+        })
+        .collect())
+}
+
+pub async fn add_software_title(
+    context: &Context,
+    name: String,
+    sort_name: Option<String>,
+) -> Result<Created, Error> {
+    let software_title_repository = context.repository_manager.get_software_title_repository();
+    let id = software_title_repository
+        .add_software_title(&name, None)
+        .await?;
+    if sort_name.is_some() {
+        software_title_repository
+            .update_software_title(&SoftwareTitle {
+                id,
+                name,
+                franchise_id: None,
+                sort_name,
+            })
+            .await?;
+    }
+    Ok(Created { id })
+}
+
+pub async fn add_software_title_alias(
+    context: &Context,
+    software_title_id: i64,
+    name: &str,
+    language: Option<&str>,
+    region: Option<&str>,
+) -> Result<Created, Error> {
+    let id = context
+        .repository_manager
+        .get_software_title_repository()
+        .add_alias(software_title_id, name, language, region)
+        .await?;
+    Ok(Created { id })
+}
+
+pub async fn list_releases(
+    context: &Context,
+    filter: ReleaseFilter,
+) -> Result<Vec<ReleaseOutput>, Error> {
+    let releases = context
+        .view_model_service
+        .get_release_list_models(filter)
+        .await?;
+    Ok(releases.into_iter().map(ReleaseOutput::from).collect())
+}
+
+pub async fn search_releases(context: &Context, query: &str) -> Result<Vec<ReleaseOutput>, Error> {
+    let releases = context
+        .view_model_service
+        .search_release_list_models(query)
+        .await?;
+    Ok(releases.into_iter().map(ReleaseOutput::from).collect())
+}
+
+pub async fn add_release(
+    context: &Context,
+    name: String,
+    system_ids: Vec<i64>,
+    software_title_ids: Vec<i64>,
+    file_set_ids: Vec<i64>,
+) -> Result<Created, Error> {
+    let id = context
+        .repository_manager
+        .get_release_repository()
+        .add_release_full(name, software_title_ids, file_set_ids, system_ids)
+        .await?;
+    Ok(Created { id })
+}
+
+pub async fn list_companies(context: &Context) -> Result<Vec<CompanyOutput>, Error> {
+    let companies = context.view_model_service.get_company_list_models().await?;
+    Ok(companies
+        .into_iter()
+        .map(|company| CompanyOutput {
+            id: company.id,
+            name: company.name,
+            can_delete: company.can_delete,
+        })
+        .collect())
+}
+
+pub async fn add_company(context: &Context, name: String) -> Result<Created, Error> {
+    let id = context
+        .repository_manager
+        .get_company_repository()
+        .add_company(&name)
+        .await?;
+    Ok(Created { id })
+}
+
+pub async fn list_tags(context: &Context) -> Result<Vec<TagOutput>, Error> {
+    let tags = context
+        .repository_manager
+        .get_tag_repository()
+        .get_all_tags()
+        .await?;
+    Ok(tags.into_iter().map(TagOutput::from).collect())
+}
+
+pub async fn add_tag(context: &Context, target: TagTarget, name: &str) -> Result<Created, Error> {
+    let id = context
+        .repository_manager
+        .get_tag_repository()
+        .add_tag(target, name)
+        .await?;
+    Ok(Created { id })
+}
+
+pub async fn rename_tag(context: &Context, id: i64, name: &str) -> Result<TagOutput, Error> {
+    let tag_repository = context.repository_manager.get_tag_repository();
+    tag_repository.rename_tag(id, name).await?;
+    Ok(tag_repository.get_tag(id).await?.into())
+}
+
+/// Returns the tag the source tag was merged to.
+pub async fn merge_tags(context: &Context, source: i64, target: i64) -> Result<TagOutput, Error> {
+    let tag_repository = context.repository_manager.get_tag_repository();
+    tag_repository.merge_tags(source, target).await?;
+    Ok(tag_repository.get_tag(target).await?.into())
+}
+
+/// Returns the deleted tag.
+pub async fn delete_tag(context: &Context, id: i64) -> Result<TagOutput, Error> {
+    let tag_repository = context.repository_manager.get_tag_repository();
+    let tag = tag_repository.get_tag(id).await?;
+    tag_repository.delete_tag(id).await?;
+    Ok(tag.into())
+}
+
+pub async fn list_collections(context: &Context) -> Result<Vec<CollectionOutput>, Error> {
+    let collections = context
+        .view_model_service
+        .get_collection_list_models()
+        .await?;
+    Ok(collections
+        .into_iter()
+        .map(|collection| CollectionOutput {
+            id: collection.id,
+            name: collection.name,
+            is_favorites: collection.is_favorites,
+            is_smart: collection.is_smart,
+            release_count: collection.release_count,
+        })
+        .collect())
+}
+
+pub async fn add_collection(context: &Context, name: &str) -> Result<Created, Error> {
+    let id = context.view_model_service.add_collection(name).await?;
+    Ok(Created { id })
+}
+
+pub async fn add_smart_collection(
+    context: &Context,
+    name: &str,
+    criteria: &[ReleaseCriterion],
+) -> Result<Created, Error> {
+    let id = context
+        .view_model_service
+        .add_smart_collection(name, criteria)
+        .await?;
+    Ok(Created { id })
+}
+
+pub async fn list_collection_criteria(
+    context: &Context,
+    id: i64,
+) -> Result<Vec<CriterionOutput>, Error> {
+    let criteria = context
+        .view_model_service
+        .get_collection_criteria(id)
+        .await?;
+    Ok(criteria
+        .iter()
+        .map(|criterion| CriterionOutput {
+            criterion: criterion.to_string(),
+        })
+        .collect())
+}
+
+/// Returns the criteria after the change.
+pub async fn set_collection_criteria(
+    context: &Context,
+    id: i64,
+    criteria: &[ReleaseCriterion],
+) -> Result<Vec<CriterionOutput>, Error> {
+    context
+        .view_model_service
+        .set_collection_criteria(id, criteria)
+        .await?;
+    list_collection_criteria(context, id).await
+}
+
+/// Returns the collections after the change.
+pub async fn rename_collection(
+    context: &Context,
+    id: i64,
+    name: &str,
+) -> Result<Vec<CollectionOutput>, Error> {
+    context
+        .view_model_service
+        .rename_collection(id, name)
+        .await?;
+    list_collections(context).await
+}
+
+/// Returns the collections after the change.
+pub async fn delete_collection(context: &Context, id: i64) -> Result<Vec<CollectionOutput>, Error> {
+    context.view_model_service.delete_collection(id).await?;
+    list_collections(context).await
+}
+
+pub async fn list_collection_releases(
+    context: &Context,
+    id: i64,
+) -> Result<Vec<ReleaseOutput>, Error> {
+    let releases = context
+        .view_model_service
+        .get_collection_release_list_models(id)
+        .await?;
+    Ok(releases.into_iter().map(ReleaseOutput::from).collect())
+}
+
+/// Returns the releases of the collection after the change.
+pub async fn add_release_to_collection(
+    context: &Context,
+    id: i64,
+    release_id: i64,
+) -> Result<Vec<ReleaseOutput>, Error> {
+    context
+        .view_model_service
+        .add_release_to_collection(id, release_id)
+        .await?;
+    list_collection_releases(context, id).await
+}
+
+/// Returns the releases of the collection after the change.
+pub async fn remove_release_from_collection(
+    context: &Context,
+    id: i64,
+    release_id: i64,
+) -> Result<Vec<ReleaseOutput>, Error> {
+    context
+        .view_model_service
+        .remove_release_from_collection(id, release_id)
+        .await?;
+    list_collection_releases(context, id).await
+}
+
+/// Returns the releases of the collection after the change.
+pub async fn move_release_in_collection(
+    context: &Context,
+    id: i64,
+    release_id: i64,
+    position: usize,
+) -> Result<Vec<ReleaseOutput>, Error> {
+    context
+        .view_model_service
+        .move_release_in_collection(id, release_id, position)
+        .await?;
+    list_collection_releases(context, id).await
+}
+
+pub async fn toggle_favorite(context: &Context, release_id: i64) -> Result<FavoriteOutput, Error> {
+    let is_favorite = context
+        .view_model_service
+        .toggle_favorite(release_id)
+        .await?;
+    Ok(FavoriteOutput {
+        release_id,
+        is_favorite,
+    })
+}
+
+pub async fn export_collection(
+    context: &Context,
+    id: i64,
+    output_dir: &Path,
+) -> Result<PlaylistOutput, Error> {
+    let playlist = context
+        .view_model_service
+        .export_collection_playlist(id, &context.collection_root_dir, output_dir)
+        .await?;
+    Ok(PlaylistOutput {
+        playlist: playlist.path.to_string_lossy().to_string(),
+        releases: playlist.releases,
+        skipped_releases: playlist.skipped_releases,
+    })
+}
+
+pub async fn list_emulators(context: &Context) -> Result<Vec<EmulatorOutput>, Error> {
+    let emulators = context
+        .view_model_service
+        .get_emulator_list_models()
+        .await?;
+    Ok(emulators
+        .into_iter()
+        .map(|emulator| EmulatorOutput {
+            id: emulator.id,
+            name: emulator.name,
+        })
+        .collect())
+}
+
+pub async fn list_file_sets(context: &Context) -> Result<Vec<FileSetOutput>, Error> {
+    let file_sets = context
+        .view_model_service
+        .get_all_file_set_list_models()
+        .await?;
+    Ok(file_sets
+        .into_iter()
+        .map(|file_set| FileSetOutput {
+            id: file_set.id,
+            name: file_set.file_set_name,
+            file_type: file_set.file_type.to_string(),
+        })
+        .collect())
+}
+
+/// Imports the file, or the selected files of a zip archive, into the collection and adds them
+/// as a new file set. Files already in the collection are not imported again.
+pub async fn import_file_set(
+    context: &Context,
+    path: &Path,
+    file_type: FileType,
+    system_ids: Vec<i64>,
+    name: Option<String>,
+    file_names: Vec<String>,
+) -> Result<FileSetImportOutput, Error> {
+    let file_set = context
+        .view_model_service
+        .import_file_set(
+            &FileSetImportRequest {
+                path: path.to_path_buf(),
+                file_type,
+                system_ids,
+                name,
+                file_names,
+            },
+            &context.collection_root_dir,
+        )
+        .await?;
+    Ok(FileSetImportOutput {
+        id: file_set.id,
+        name: file_set.name,
+        imported_files: file_set.imported_files,
+        existing_files: file_set.existing_files,
+    })
+}
+
+pub async fn export_file_set(
+    context: &Context,
+    file_set_id: i64,
+    output_dir: &Path,
+    zip: bool,
+) -> Result<ExportOutput, Error> {
+    let export_plan = context
+        .view_model_service
+        .export_file_set(file_set_id, &context.collection_root_dir, output_dir, zip)
+        .await?;
+    Ok(ExportOutput {
+        output_dir: export_plan.output_dir.to_string_lossy().to_string(),
+        files: export_plan
+            .files
+            .into_iter()
+            .map(|file| file.output_file_name)
+            .collect(),
+        zip_file: export_plan
+            .zip_file_path
+            .map(|path| path.to_string_lossy().to_string()),
+    })
+}
+
+pub async fn search(
+    context: &Context,
+    query: &str,
+    limit: i64,
+) -> Result<Vec<SearchResultOutput>, Error> {
+    let results = context.view_model_service.search(query, limit).await?;
+    Ok(results
+        .into_iter()
+        .map(|result| SearchResultOutput {
+            kind: result.kind.to_string(),
+            id: result.id,
+            name: result.name,
+            matched_name: result.matched_name,
+        })
+        .collect())
+}
+
+/// Verifies the files of the file set, or of all file sets, against the collection.
+pub async fn verify(
+    context: &Context,
+    file_set_id: Option<i64>,
+) -> Result<Vec<VerificationOutput>, Error> {
+    let file_set_ids = match file_set_id {
+        Some(file_set_id) => vec![file_set_id],
+        None => context
+            .view_model_service
+            .get_all_file_set_list_models()
+            .await?
+            .into_iter()
+            .map(|file_set| file_set.id)
+            .collect(),
+    };
+
+    let mut verifications = vec![];
+    for file_set_id in file_set_ids {
+        let file_set = context
+            .view_model_service
+            .get_file_set_view_model(file_set_id)
+            .await?;
+        // nothing is exported, the output directory is not used
+        let export_model =
+            file_set_export_model(&file_set, &context.collection_root_dir, Path::new(""), true)?;
+        verifications.extend(verify_files(&export_model).into_iter().map(|verification| {
+            let (status, message) = match verification.status {
+                FileVerificationStatus::Ok => ("ok", None),
+                FileVerificationStatus::Missing => ("missing", None),
+                FileVerificationStatus::ChecksumMismatch => ("checksum_mismatch", None),
+                FileVerificationStatus::Unreadable(message) => ("unreadable", Some(message)),
+            };
+            VerificationOutput {
+                file_set_id: file_set.id,
+                file_set_name: file_set.file_set_name.clone(),
+                file_name: verification.output_file_name,
+                source_file: verification.source_file_path.to_string_lossy().to_string(),
+                status: status.to_string(),
+                message,
+            }
+        }));
+    }
+    Ok(verifications)
+}
+
+pub async fn list_release_roles(
+    context: &Context,
+    release_id: i64,
+) -> Result<Vec<LaunchRoleOutput>, Error> {
+    let roles = context
+        .view_model_service
+        .get_file_set_launch_roles(release_id)
+        .await?;
+    Ok(roles
+        .into_iter()
+        .map(|role| LaunchRoleOutput {
+            file_set_id: role.file_set_id,
+            role: role.role,
+        })
+        .collect())
+}
+
+/// Returns the launch roles of the release after setting the role.
+pub async fn set_release_role(
+    context: &Context,
+    release_id: i64,
+    file_set_id: i64,
+    role: Option<&str>,
+) -> Result<Vec<LaunchRoleOutput>, Error> {
+    context
+        .view_model_service
+        .set_file_set_launch_role(release_id, file_set_id, role)
+        .await?;
+    list_release_roles(context, release_id).await
+}
+
+/// Exports the file set of the release with the emulator firmware and configuration to the
+/// launch directory and runs the emulator. Returns once the emulator has exited.
+pub async fn launch(context: &Context, args: &LaunchArgs) -> Result<LaunchOutput, Error> {
+    let view_model_service = &context.view_model_service;
+    let launch = view_model_service
+        .prepare_release_launch(&ReleaseLaunchRequest {
+            release_id: args.release_id,
+            system_id: args.system,
+            emulator_id: args.emulator,
+            file_set_id: args.file_set,
+            file_name: args.file.clone(),
+            playlist: args.playlist,
+            file_set_roles: (!args.roles.is_empty()).then(|| args.roles.clone()),
+        })
+        .await?;
+    let output = LaunchOutput {
+        release_id: launch.release_id,
+        system: launch.system_name.clone(),
+        emulator: launch.emulator_name.clone(),
+        file_set: launch.file_set_name.clone(),
+        file: launch.launched_file.clone(),
+    };
+    let (release_id, emulator_id, system_id) =
+        (launch.release_id, launch.emulator_id, launch.system_id);
+
+    let result = launch.run(&context.process_registry).await;
+    view_model_service
+        .record_launch(release_id, emulator_id, system_id, &result)
+        .await?;
+    if args.record {
+        view_model_service
+            .record_launch_result(release_id, emulator_id, system_id, result.clone())
+            .await?;
+    }
+    result?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use database::setup_test_db;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    async fn create_context(collection_root_dir: &Path) -> Context {
+        let pool = Arc::new(setup_test_db().await);
+        let repository_manager = Arc::new(RepositoryManager::new(pool));
+        Context {
+            view_model_service: ViewModelService::new(Arc::clone(&repository_manager)),
+            repository_manager,
+            collection_root_dir: collection_root_dir.to_path_buf(),
+            process_registry: ProcessRegistry::new(),
+        }
+    }
+
+    #[async_std::test]
+    async fn test_launch_directory_in_use() {
+        let temp_dir = tempdir().unwrap();
+        let context = create_context(&temp_dir.path().join("collection")).await;
+        let file_path = temp_dir.path().join("game.d64");
+        std::fs::write(&file_path, "disk image").unwrap();
+        let system = add_system(&context, "Commodore 64".to_string())
+            .await
+            .unwrap();
+        let file_set = import_file_set(
+            &context,
+            &file_path,
+            FileType::DiskImage,
+            vec![system.id],
+            None,
+            vec![],
+        )
+        .await
+        .unwrap();
+        let release = add_release(
+            &context,
+            "Game".to_string(),
+            vec![system.id],
+            vec![],
+            vec![file_set.id],
+        )
+        .await
+        .unwrap();
+        context
+            .repository_manager
+            .get_emulator_repository()
+            .add_emulator_with_systems(
+                "VICE".to_string(),
+                "x64sc".to_string(),
+                true,
+                vec![database::models::EmulatorSystemUpdateModel {
+                    id: None,
+                    system_id: system.id,
+                    arguments: String::new(),
+                }],
+                Default::default(),
+            )
+            .await
+            .unwrap();
+        let args = LaunchArgs {
+            release_id: release.id,
+            system: None,
+            emulator: None,
+            file_set: None,
+            file: None,
+            playlist: false,
+            record: false,
+            roles: vec![],
+        };
+
+        // e.g. the emulator of an earlier launch is still running
+        let running_launch = context
+            .view_model_service
+            .prepare_release_launch(&ReleaseLaunchRequest {
+                release_id: release.id,
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .register(&context.process_registry)
+            .unwrap();
+        let result = launch(&context, &args).await;
+        assert!(
+            matches!(&result, Err(Error::Emulator(message)) if message.contains("in use")),
+            "{:?}",
+            result.err()
+        );
+        drop(running_launch);
+    }
+
+    #[async_std::test]
+    async fn test_import_export_and_verify() {
+        let temp_dir = tempdir().unwrap();
+        let context = create_context(&temp_dir.path().join("collection")).await;
+        let file_path = temp_dir.path().join("game.d64");
+        std::fs::write(&file_path, "disk image").unwrap();
+
+        let system = add_system(&context, "Commodore 64".to_string())
+            .await
+            .unwrap();
+        let software_title = add_software_title(&context, "Game".to_string(), None)
+            .await
+            .unwrap();
+        let file_set = import_file_set(
+            &context,
+            &file_path,
+            FileType::DiskImage,
+            vec![system.id],
+            None,
+            vec![],
+        )
+        .await
+        .unwrap();
+        assert_eq!(file_set.name, "game.d64");
+        assert_eq!(file_set.imported_files, vec!["game.d64".to_string()]);
+        assert!(file_set.existing_files.is_empty());
+
+        // importing the same file again reuses the file in the collection
+        let second_file_set = import_file_set(
+            &context,
+            &file_path,
+            FileType::DiskImage,
+            vec![system.id],
+            Some("Game (alt)".to_string()),
+            vec![],
+        )
+        .await
+        .unwrap();
+        assert!(second_file_set.imported_files.is_empty());
+        assert_eq!(second_file_set.existing_files, vec!["game.d64".to_string()]);
+
+        let release = add_release(
+            &context,
+            "Game".to_string(),
+            vec![system.id],
+            vec![software_title.id],
+            vec![file_set.id],
+        )
+        .await
+        .unwrap();
+        let releases = list_releases(
+            &context,
+            ReleaseFilter {
+                system_ids: vec![system.id],
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(releases.len(), 1);
+        assert_eq!(releases[0].id, release.id);
+        assert_eq!(releases[0].systems, vec!["Commodore 64".to_string()]);
+
+        let output_dir = temp_dir.path().join("export");
+        let export = export_file_set(&context, file_set.id, &output_dir, false)
+            .await
+            .unwrap();
+        assert_eq!(export.files, vec!["game.d64".to_string()]);
+        assert_eq!(export.zip_file, None);
+        assert_eq!(
+            std::fs::read_to_string(output_dir.join("game.d64")).unwrap(),
+            "disk image"
+        );
+
+        let verifications = verify(&context, None).await.unwrap();
+        assert_eq!(verifications.len(), 2);
+        assert!(verifications.iter().all(|v| v.is_ok()));
+
+        // both file sets refer to the same collection file
+        std::fs::remove_file(&verifications[0].source_file).unwrap();
+        let verifications = verify(&context, Some(file_set.id)).await.unwrap();
+        assert_eq!(verifications.len(), 1);
+        assert_eq!(verifications[0].status, "missing");
+    }
+
+    #[async_std::test]
+    async fn test_import_selected_files_from_zip() {
+        use std::io::Write;
+        use zip::write::FileOptions;
+
+        let temp_dir = tempdir().unwrap();
+        let context = create_context(&temp_dir.path().join("collection")).await;
+        let zip_path = temp_dir.path().join("game.zip");
+        let mut zip_writer = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        for (file_name, content) in [("disk1.d64", "disk 1"), ("disk2.d64", "disk 2")] {
+            zip_writer
+                .start_file(file_name, FileOptions::<()>::default())
+                .unwrap();
+            zip_writer.write_all(content.as_bytes()).unwrap();
+        }
+        zip_writer.finish().unwrap();
+        let system = add_system(&context, "Commodore 64".to_string())
+            .await
+            .unwrap();
+
+        let file_set = import_file_set(
+            &context,
+            &zip_path,
+            FileType::DiskImage,
+            vec![system.id],
+            None,
+            vec!["disk2.d64".to_string()],
+        )
+        .await
+        .unwrap();
+        assert_eq!(file_set.imported_files, vec!["disk2.d64".to_string()]);
+
+        let result = import_file_set(
+            &context,
+            &zip_path,
+            FileType::DiskImage,
+            vec![system.id],
+            None,
+            vec!["disk3.d64".to_string()],
+        )
+        .await;
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt::{Display, Formatter, Result};
+
+use database::database_error::{DatabaseError, Error as DatabaseQueryError};
+use emulator_runner::error::EmulatorRunnerError;
+use file_export::FileExportError;
+use file_import::FileImportError;
+
+#[derive(Debug, Clone)]
+pub enum Error {
+    Database(String),
+    NotFound(String),
+    InvalidArgument(String),
+    Import(String),
+    Export(String),
+    Emulator(String),
+    Output(String),
+    /// Number of files that failed verification.
+    VerificationFailed(usize),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Error::Database(message) => write!(f, "Database error: {}", message),
+            Error::NotFound(message) => write!(f, "Not found: {}", message),
+            Error::InvalidArgument(message) => write!(f, "Invalid argument: {}", message),
+            Error::Import(message) => write!(f, "Import error: {}", message),
+            Error::Export(message) => write!(f, "Export error: {}", message),
+            Error::Emulator(message) => write!(f, "Emulator error: {}", message),
+            Error::Output(message) => write!(f, "Output error: {}", message),
+            Error::VerificationFailed(count) => write!(f, "{} files failed verification", count),
+        }
+    }
+}
+
+impl From<service::error::Error> for Error {
+    fn from(err: service::error::Error) -> Self {
+        match err {
+            service::error::Error::DbError(message) => Error::Database(message),
+            service::error::Error::EmulatorError(message) => Error::Emulator(message),
+            service::error::Error::InvalidSelection(message) => Error::InvalidArgument(message),
+            service::error::Error::InvalidQuery(message) => Error::InvalidArgument(message),
+            service::error::Error::NotFound(message) => Error::NotFound(message),
+            service::error::Error::ImportError(message) => Error::Import(message),
+            service::error::Error::ExportError(message) => Error::Export(message),
+        }
+    }
+}
+
+impl From<DatabaseError> for Error {
+    fn from(err: DatabaseError) -> Self {
+        Error::Database(err.to_string())
+    }
+}
+
+impl From<DatabaseQueryError> for Error {
+    fn from(err: DatabaseQueryError) -> Self {
+        Error::Database(err.to_string())
+    }
+}
+
+impl From<FileImportError> for Error {
+    fn from(err: FileImportError) -> Self {
+        Error::Import(err.to_string())
+    }
+}
+
+impl From<FileExportError> for Error {
+    fn from(err: FileExportError) -> Self {
+        Error::Export(err.to_string())
+    }
+}
+
+impl From<EmulatorRunnerError> for Error {
+    fn from(err: EmulatorRunnerError) -> Self {
+        Error::Emulator(err.to_string())
+    }
+}
@@ -0,0 +1,261 @@
+mod cli;
+mod commands;
+mod error;
+mod output;
+
+use std::sync::Arc;
+
+use clap::Parser;
+use cli::{
+    Cli, CollectionsCommand, Command, CompaniesCommand, EmulatorsCommand, FileSetsCommand,
+    ReleasesCommand, SystemsCommand, TagsCommand, TitlesCommand,
+};
+use commands::Context;
+use database::{
+    get_db_pool, get_db_pool_with_path,
+    models::{FileType, TagTarget},
+    repository_manager::RepositoryManager,
+};
+use error::Error;
+use output::print;
+use service::view_model_service::{ReleaseFilter, SoftwareTitleFilter};
+
+#[async_std::main]
+async fn main() {
+    let cli = Cli::parse();
+    let json = cli.json;
+    if let Err(err) = run(cli).await {
+        if json {
+            eprintln!("{}", serde_json::json!({ "error": err.to_string() }));
+        } else {
+            eprintln!("{}", err);
+        }
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Error> {
+    let pool = match &cli.database {
+        Some(path) => get_db_pool_with_path(path).await,
+        None => get_db_pool().await,
+    }
+    .map_err(|err| Error::Database(err.to_string()))?;
+    let context = Context::new(Arc::new(RepositoryManager::new(pool))).await?;
+    let json = cli.json;
+
+    match cli.command {
+        Command::Systems(SystemsCommand::List) => {
+            print(&commands::list_systems(&context).await?, json)
+        }
+        Command::Systems(SystemsCommand::Add { name }) => {
+            print(&commands::add_system(&context, name).await?, json)
+        }
+        Command::Titles(TitlesCommand::List { search, tag }) => print(
+            &commands::list_software_titles(&context, SoftwareTitleFilter { search, tag }).await?,
+            json,
+        ),
+        Command::Titles(TitlesCommand::Add { name, sort_name }) => print(
+            &commands::add_software_title(&context, name, sort_name).await?,
+            json,
+        ),
+        Command::Titles(TitlesCommand::Alias {
+            id,
+            name,
+            language,
+            region,
+        }) => print(
+            &commands::add_software_title_alias(
+                &context,
+                id,
+                &name,
+                language.as_deref(),
+                region.as_deref(),
+            )
+            .await?,
+            json,
+        ),
+        Command::Releases(ReleasesCommand::List {
+            systems,
+            titles,
+            file_types,
+            name_patterns,
+            region,
+            language,
+            released_from,
+            released_to,
+            company,
+            role,
+            tag,
+            sort,
+            desc,
+            after,
+            limit,
+            offset,
+        }) => print(
+            &commands::list_releases(
+                &context,
+                ReleaseFilter {
+                    system_ids: systems,
+                    software_title_ids: titles,
+                    file_types: file_types.into_iter().map(FileType::from).collect(),
+                    name_patterns,
+                    region,
+                    language,
+                    released_from,
+                    released_to,
+                    company_id: company,
+                    company_role: role,
+                    tag,
+                    sort_key: sort,
+                    descending: desc,
+                    after_release_id: after,
+                    limit,
+                    offset,
+                },
+            )
+            .await?,
+            json,
+        ),
+        Command::Releases(ReleasesCommand::Search { query }) => {
+            print(&commands::search_releases(&context, &query).await?, json)
+        }
+        Command::Releases(ReleasesCommand::Add {
+            name,
+            systems,
+            titles,
+            file_sets,
+        }) => print(
+            &commands::add_release(&context, name, systems, titles, file_sets).await?,
+            json,
+        ),
+        Command::Releases(ReleasesCommand::Roles { id }) => {
+            print(&commands::list_release_roles(&context, id).await?, json)
+        }
+        Command::Releases(ReleasesCommand::SetRole { id, file_set, role }) => print(
+            &commands::set_release_role(&context, id, file_set, role.as_deref()).await?,
+            json,
+        ),
+        Command::Companies(CompaniesCommand::List) => {
+            print(&commands::list_companies(&context).await?, json)
+        }
+        Command::Companies(CompaniesCommand::Add { name }) => {
+            print(&commands::add_company(&context, name).await?, json)
+        }
+        Command::Tags(TagsCommand::List) => print(&commands::list_tags(&context).await?, json),
+        Command::Tags(TagsCommand::Add {
+            name,
+            title,
+            release,
+            file_set,
+        }) => {
+            let target = match (title, release, file_set) {
+                (Some(id), _, _) => TagTarget::SoftwareTitle(id),
+                (_, Some(id), _) => TagTarget::Release(id),
+                (_, _, Some(id)) => TagTarget::FileSet(id),
+                _ => {
+                    return Err(Error::InvalidArgument(
+                        "A title, release or file set is required".to_string(),
+                    ))
+                }
+            };
+            print(&commands::add_tag(&context, target, &name).await?, json)
+        }
+        Command::Tags(TagsCommand::Rename { id, name }) => {
+            print(&commands::rename_tag(&context, id, &name).await?, json)
+        }
+        Command::Tags(TagsCommand::Merge { source, target }) => {
+            print(&commands::merge_tags(&context, source, target).await?, json)
+        }
+        Command::Tags(TagsCommand::Delete { id }) => {
+            print(&commands::delete_tag(&context, id).await?, json)
+        }
+        Command::Collections(CollectionsCommand::List) => {
+            print(&commands::list_collections(&context).await?, json)
+        }
+        Command::Collections(CollectionsCommand::Add { name }) => {
+            print(&commands::add_collection(&context, &name).await?, json)
+        }
+        Command::Collections(CollectionsCommand::AddSmart { name, criteria }) => print(
+            &commands::add_smart_collection(&context, &name, &criteria).await?,
+            json,
+        ),
+        Command::Collections(CollectionsCommand::Criteria { id }) => print(
+            &commands::list_collection_criteria(&context, id).await?,
+            json,
+        ),
+        Command::Collections(CollectionsCommand::SetCriteria { id, criteria }) => print(
+            &commands::set_collection_criteria(&context, id, &criteria).await?,
+            json,
+        ),
+        Command::Collections(CollectionsCommand::Rename { id, name }) => print(
+            &commands::rename_collection(&context, id, &name).await?,
+            json,
+        ),
+        Command::Collections(CollectionsCommand::Delete { id }) => {
+            print(&commands::delete_collection(&context, id).await?, json)
+        }
+        Command::Collections(CollectionsCommand::Releases { id }) => print(
+            &commands::list_collection_releases(&context, id).await?,
+            json,
+        ),
+        Command::Collections(CollectionsCommand::AddRelease { id, release }) => print(
+            &commands::add_release_to_collection(&context, id, release).await?,
+            json,
+        ),
+        Command::Collections(CollectionsCommand::RemoveRelease { id, release }) => print(
+            &commands::remove_release_from_collection(&context, id, release).await?,
+            json,
+        ),
+        Command::Collections(CollectionsCommand::Move {
+            id,
+            release,
+            position,
+        }) => print(
+            &commands::move_release_in_collection(&context, id, release, position).await?,
+            json,
+        ),
+        Command::Collections(CollectionsCommand::Favorite { release }) => {
+            print(&commands::toggle_favorite(&context, release).await?, json)
+        }
+        Command::Collections(CollectionsCommand::Export { id, output_dir }) => print(
+            &commands::export_collection(&context, id, &output_dir).await?,
+            json,
+        ),
+        Command::Emulators(EmulatorsCommand::List) => {
+            print(&commands::list_emulators(&context).await?, json)
+        }
+        Command::FileSets(FileSetsCommand::List) => {
+            print(&commands::list_file_sets(&context).await?, json)
+        }
+        Command::FileSets(FileSetsCommand::Import {
+            path,
+            file_type,
+            systems,
+            name,
+            files,
+        }) => print(
+            &commands::import_file_set(&context, &path, file_type, systems, name, files).await?,
+            json,
+        ),
+        Command::FileSets(FileSetsCommand::Export {
+            id,
+            output_dir,
+            zip,
+        }) => print(
+            &commands::export_file_set(&context, id, &output_dir, zip).await?,
+            json,
+        ),
+        Command::Search { query, limit } => {
+            print(&commands::search(&context, &query, limit).await?, json)
+        }
+        Command::Verify { file_set } => {
+            let verifications = commands::verify(&context, file_set).await?;
+            print(&verifications, json)?;
+            match verifications.iter().filter(|v| !v.is_ok()).count() {
+                0 => Ok(()),
+                failed => Err(Error::VerificationFailed(failed)),
+            }
+        }
+        Command::Launch(args) => print(&commands::launch(&context, &args).await?, json),
+    }
+}
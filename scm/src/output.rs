@@ -0,0 +1,336 @@
+use database::models::Tag;
+use serde::Serialize;
+use service::view_models::ReleaseListModel;
+
+use crate::error::Error;
+
+/// Command output, printed as text lines or as JSON for scripting.
+pub trait Render: Serialize {
+    fn render_text(&self) -> String;
+}
+
+impl<T: Render> Render for Vec<T> {
+    fn render_text(&self) -> String {
+        self.iter()
+            .map(|item| item.render_text())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+pub fn print<T: Render>(output: &T, json: bool) -> Result<(), Error> {
+    if json {
+        let json =
+            serde_json::to_string_pretty(output).map_err(|err| Error::Output(err.to_string()))?;
+        println!("{}", json);
+    } else {
+        let text = output.render_text();
+        if !text.is_empty() {
+            println!("{}", text);
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Created {
+    pub id: i64,
+}
+
+impl Render for Created {
+    fn render_text(&self) -> String {
+        self.id.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SystemOutput {
+    pub id: i64,
+    pub name: String,
+    pub can_delete: bool,
+}
+
+impl Render for SystemOutput {
+    fn render_text(&self) -> String {
+        format!("{}\t{}", self.id, self.name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SoftwareTitleOutput {
+    pub id: i64,
+    pub name: String,
+    pub sort_name: String,
+    pub can_delete: bool,
+}
+
+impl Render for SoftwareTitleOutput {
+    fn render_text(&self) -> String {
+        format!("{}\t{}", self.id, self.name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReleaseOutput {
+    pub id: i64,
+    pub name: String,
+    pub systems: Vec<String>,
+    pub file_types: Vec<String>,
+}
+
+impl From<ReleaseListModel> for ReleaseOutput {
+    fn from(release: ReleaseListModel) -> Self {
+        ReleaseOutput {
+            id: release.id,
+            name: release.name,
+            systems: release.system_names,
+            file_types: release.file_types,
+        }
+    }
+}
+
+impl Render for ReleaseOutput {
+    fn render_text(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}",
+            self.id,
+            self.name,
+            self.systems.join(", "),
+            self.file_types.join(", ")
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CompanyOutput {
+    pub id: i64,
+    pub name: String,
+    pub can_delete: bool,
+}
+
+impl Render for CompanyOutput {
+    fn render_text(&self) -> String {
+        format!("{}\t{}", self.id, self.name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TagOutput {
+    pub id: i64,
+    pub name: String,
+}
+
+impl From<Tag> for TagOutput {
+    fn from(tag: Tag) -> Self {
+        TagOutput {
+            id: tag.id,
+            name: tag.name,
+        }
+    }
+}
+
+impl Render for TagOutput {
+    fn render_text(&self) -> String {
+        format!("{}\t{}", self.id, self.name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CollectionOutput {
+    pub id: i64,
+    pub name: String,
+    pub is_favorites: bool,
+    pub is_smart: bool,
+    pub release_count: usize,
+}
+
+impl Render for CollectionOutput {
+    fn render_text(&self) -> String {
+        let smart = if self.is_smart { "\tsmart" } else { "" };
+        format!(
+            "{}\t{}\t{}{}",
+            self.id, self.name, self.release_count, smart
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CriterionOutput {
+    pub criterion: String,
+}
+
+impl Render for CriterionOutput {
+    fn render_text(&self) -> String {
+        self.criterion.clone()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FavoriteOutput {
+    pub release_id: i64,
+    pub is_favorite: bool,
+}
+
+impl Render for FavoriteOutput {
+    fn render_text(&self) -> String {
+        format!("{}\t{}", self.release_id, self.is_favorite)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlaylistOutput {
+    pub playlist: String,
+    pub releases: Vec<String>,
+    /// Releases left out because they have no file set.
+    pub skipped_releases: Vec<String>,
+}
+
+impl Render for PlaylistOutput {
+    fn render_text(&self) -> String {
+        self.playlist.clone()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchResultOutput {
+    pub kind: String,
+    pub id: i64,
+    pub name: String,
+    pub matched_name: String,
+}
+
+impl Render for SearchResultOutput {
+    fn render_text(&self) -> String {
+        if self.matched_name == self.name {
+            format!("{}\t{}\t{}", self.kind, self.id, self.name)
+        } else {
+            format!(
+                "{}\t{}\t{}\t{}",
+                self.kind, self.id, self.name, self.matched_name
+            )
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EmulatorOutput {
+    pub id: i64,
+    pub name: String,
+}
+
+impl Render for EmulatorOutput {
+    fn render_text(&self) -> String {
+        format!("{}\t{}", self.id, self.name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileSetOutput {
+    pub id: i64,
+    pub name: String,
+    pub file_type: String,
+}
+
+impl Render for FileSetOutput {
+    fn render_text(&self) -> String {
+        format!("{}\t{}\t{}", self.id, self.name, self.file_type)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileSetImportOutput {
+    pub id: i64,
+    pub name: String,
+    /// Files written to the collection.
+    pub imported_files: Vec<String>,
+    /// Files already in the collection, added to the file set as they are.
+    pub existing_files: Vec<String>,
+}
+
+impl Render for FileSetImportOutput {
+    fn render_text(&self) -> String {
+        format!(
+            "{}\t{}\t{} imported, {} existing",
+            self.id,
+            self.name,
+            self.imported_files.len(),
+            self.existing_files.len()
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportOutput {
+    pub output_dir: String,
+    pub files: Vec<String>,
+    pub zip_file: Option<String>,
+}
+
+impl Render for ExportOutput {
+    fn render_text(&self) -> String {
+        match &self.zip_file {
+            Some(zip_file) => zip_file.clone(),
+            None => self.files.join("\n"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VerificationOutput {
+    pub file_set_id: i64,
+    pub file_set_name: String,
+    pub file_name: String,
+    pub source_file: String,
+    /// One of `ok`, `missing`, `checksum_mismatch` or `unreadable`.
+    pub status: String,
+    pub message: Option<String>,
+}
+
+impl VerificationOutput {
+    pub fn is_ok(&self) -> bool {
+        self.status == "ok"
+    }
+}
+
+impl Render for VerificationOutput {
+    fn render_text(&self) -> String {
+        let mut text = format!(
+            "{}\t{}\t{}\t{}",
+            self.file_set_id, self.file_set_name, self.file_name, self.status
+        );
+        if let Some(message) = &self.message {
+            text.push_str(&format!("\t{}", message));
+        }
+        text
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LaunchRoleOutput {
+    pub file_set_id: i64,
+    pub role: String,
+}
+
+impl Render for LaunchRoleOutput {
+    fn render_text(&self) -> String {
+        format!("{}\t{}", self.file_set_id, self.role)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LaunchOutput {
+    pub release_id: i64,
+    pub system: String,
+    pub emulator: String,
+    pub file_set: String,
+    pub file: String,
+}
+
+impl Render for LaunchOutput {
+    fn render_text(&self) -> String {
+        format!(
+            "{} on {} with {}: {}",
+            self.file_set, self.system, self.emulator, self.file
+        )
+    }
+}
@@ -0,0 +1,956 @@
+use std::{path::PathBuf, sync::Arc};
+
+use core_types::FileType;
+use database::models::{CompanyRole, FileSetLaunchRole, PartialDate, ReleaseSortKey};
+use emulator_runner::{error::EmulatorRunnerError, process_registry::ProcessRegistry};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use service::{
+    error::Error,
+    file_set_operations::FileSetImportRequest,
+    release_launch::ReleaseLaunchRequest,
+    view_model_service::{ReleaseFilter, SoftwareTitleFilter, ViewModelService},
+};
+use strum::IntoEnumIterator;
+use tide::{utils::After, Body, Request, Response, StatusCode};
+
+use crate::{
+    auth::TokenAuth,
+    jobs::{Job, JobKind, JobStatus, Jobs},
+    models::{
+        ExportRequest, ExportResponse, FileSetDetailsResponse, FileSetResponse, ImportRequest,
+        ImportResponse, LaunchRequest, LaunchResponse, RankedEmulatorResponse,
+        ReleaseDetailsResponse, ReleaseResponse, SearchResultResponse, SoftwareTitleResponse,
+        SystemResponse,
+    },
+    openapi,
+};
+
+#[derive(Clone)]
+pub struct State {
+    pub view_model_service: Arc<ViewModelService>,
+    pub collection_root_dir: PathBuf,
+    pub jobs: Arc<Jobs>,
+    /// Launches of the launch jobs, a launch directory in use isn't exported to again.
+    pub process_registry: Arc<ProcessRegistry>,
+}
+
+impl State {
+    pub fn new(view_model_service: Arc<ViewModelService>, collection_root_dir: PathBuf) -> Self {
+        Self {
+            view_model_service,
+            collection_root_dir,
+            jobs: Arc::new(Jobs::default()),
+            process_registry: Arc::new(ProcessRegistry::new()),
+        }
+    }
+}
+
+/// The API is served under `/api` and requires the token, the OpenAPI description at
+/// `/openapi.json` doesn't.
+pub fn app(state: State, token: String) -> tide::Server<()> {
+    let mut api = tide::with_state(state);
+    api.with(TokenAuth::new(token));
+    api.with(After(error_body));
+    api.at("/search").get(search);
+    api.at("/systems").get(list_systems);
+    api.at("/software-titles").get(list_software_titles);
+    api.at("/releases").get(list_releases);
+    api.at("/releases/:id").get(get_release);
+    api.at("/releases/:id/emulators")
+        .get(list_release_emulators);
+    api.at("/releases/:id/launch").post(launch_release);
+    api.at("/file-sets").get(list_file_sets);
+    api.at("/file-sets/import").post(import_file_set);
+    api.at("/file-sets/:id").get(get_file_set);
+    api.at("/file-sets/:id/export").post(export_file_set);
+    api.at("/jobs").get(list_jobs);
+    api.at("/jobs/:id").get(get_job);
+    api.at("/jobs/:id/stop").post(stop_job);
+
+    let mut app = tide::new();
+    app.at("/openapi.json")
+        .get(|_| async { json_response(StatusCode::Ok, &openapi::description()) });
+    app.at("/api").nest(api);
+    app
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SoftwareTitleQuery {
+    search: Option<String>,
+    tag: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ReleaseQuery {
+    /// Lists are comma separated, releases match any of their values.
+    system_id: Option<String>,
+    software_title_id: Option<String>,
+    file_type: Option<String>,
+    name: Option<String>,
+    region: Option<String>,
+    language: Option<String>,
+    released_from: Option<String>,
+    released_to: Option<String>,
+    company_id: Option<i64>,
+    company_role: Option<String>,
+    tag: Option<String>,
+    sort: Option<String>,
+    descending: bool,
+    after: Option<i64>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchQuery {
+    q: String,
+    #[serde(default = "default_search_limit")]
+    limit: i64,
+}
+
+fn default_search_limit() -> i64 {
+    50
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct EmulatorQuery {
+    system_id: Option<i64>,
+}
+
+async fn search(request: Request<State>) -> tide::Result {
+    let query: SearchQuery = request.query()?;
+    let results = request
+        .state()
+        .view_model_service
+        .search(&query.q, query.limit)
+        .await
+        .map_err(service_error)?;
+    json_response(
+        StatusCode::Ok,
+        &to_responses::<_, SearchResultResponse>(results),
+    )
+}
+
+async fn list_systems(request: Request<State>) -> tide::Result {
+    let systems = request
+        .state()
+        .view_model_service
+        .get_system_list_models()
+        .await
+        .map_err(service_error)?;
+    json_response(StatusCode::Ok, &to_responses::<_, SystemResponse>(systems))
+}
+
+async fn list_software_titles(request: Request<State>) -> tide::Result {
+    let query: SoftwareTitleQuery = request.query()?;
+    let view_model_service = &request.state().view_model_service;
+    let software_titles = if query.search.is_none() && query.tag.is_none() {
+        view_model_service.get_software_title_list_models().await
+    } else {
+        view_model_service
+            .search_software_title_list_models(&SoftwareTitleFilter {
+                search: query.search,
+                tag: query.tag,
+            })
+            .await
+    }
+    .map_err(service_error)?;
+    json_response(
+        StatusCode::Ok,
+        &to_responses::<_, SoftwareTitleResponse>(software_titles),
+    )
+}
+
+async fn list_releases(request: Request<State>) -> tide::Result {
+    let query: ReleaseQuery = request.query()?;
+    let releases = request
+        .state()
+        .view_model_service
+        .get_release_list_models(ReleaseFilter {
+            system_ids: id_list_param(query.system_id)?,
+            software_title_ids: id_list_param(query.software_title_id)?,
+            file_types: file_types_param(query.file_type)?,
+            name_patterns: query.name.into_iter().collect(),
+            region: query.region,
+            language: query.language,
+            released_from: date_param(query.released_from)?,
+            released_to: date_param(query.released_to)?,
+            company_id: query.company_id,
+            company_role: company_role_param(query.company_role)?,
+            tag: query.tag,
+            sort_key: sort_key_param(query.sort)?,
+            descending: query.descending,
+            after_release_id: query.after,
+            limit: query.limit,
+            offset: query.offset,
+        })
+        .await
+        .map_err(service_error)?;
+    json_response(
+        StatusCode::Ok,
+        &to_responses::<_, ReleaseResponse>(releases),
+    )
+}
+
+async fn get_release(request: Request<State>) -> tide::Result {
+    let release = request
+        .state()
+        .view_model_service
+        .get_release_view_model(id_param(&request)?)
+        .await
+        .map_err(service_error)?;
+    json_response(StatusCode::Ok, &ReleaseDetailsResponse::from(release))
+}
+
+/// Emulators ranked by compatibility with the release, on the first system of the release when
+/// the system isn't given.
+async fn list_release_emulators(request: Request<State>) -> tide::Result {
+    let release_id = id_param(&request)?;
+    let query: EmulatorQuery = request.query()?;
+    let view_model_service = &request.state().view_model_service;
+    let release = view_model_service
+        .get_release_view_model(release_id)
+        .await
+        .map_err(service_error)?;
+    let system_id = match query.system_id {
+        Some(system_id) => system_id,
+        None => match release.systems.first() {
+            Some(system) => system.id,
+            None => return json_response(StatusCode::Ok, &Vec::<RankedEmulatorResponse>::new()),
+        },
+    };
+    let emulators = view_model_service
+        .get_ranked_emulators_for_release(release_id, system_id)
+        .await
+        .map_err(service_error)?;
+    json_response(
+        StatusCode::Ok,
+        &to_responses::<_, RankedEmulatorResponse>(emulators),
+    )
+}
+
+/// Choices are resolved and the launch is registered before responding, the emulator runs as
+/// a job.
+async fn launch_release(mut request: Request<State>) -> tide::Result {
+    let launch_request: LaunchRequest = request.body_json().await?;
+    let release_id = id_param(&request)?;
+    let state = request.state().clone();
+    let launch = state
+        .view_model_service
+        .prepare_release_launch(&ReleaseLaunchRequest {
+            release_id,
+            system_id: launch_request.system_id,
+            emulator_id: launch_request.emulator_id,
+            file_set_id: launch_request.file_set_id,
+            file_name: launch_request.file_name,
+            playlist: launch_request.playlist,
+            file_set_roles: launch_request
+                .roles
+                .map(|roles| roles.into_iter().map(FileSetLaunchRole::from).collect()),
+        })
+        .await
+        .map_err(service_error)?;
+    let launch = launch
+        .register(&state.process_registry)
+        .map_err(emulator_error)?;
+    let response = LaunchResponse::from(&launch.launch);
+    let description = format!(
+        "Launch {} with {} on {}",
+        response.file, response.emulator, response.system
+    );
+    let (emulator_id, system_id) = (launch.launch.emulator_id, launch.launch.system_id);
+    let launch_id = launch.launch_id();
+    let view_model_service = Arc::clone(&state.view_model_service);
+    let job = state.jobs.spawn_launch(description, launch_id, async move {
+        let result = launch.run().await;
+        view_model_service
+            .record_launch(release_id, emulator_id, system_id, &result)
+            .await
+            .map_err(|err| err.to_string())?;
+        if launch_request.record {
+            view_model_service
+                .record_launch_result(release_id, emulator_id, system_id, result.clone())
+                .await
+                .map_err(|err| err.to_string())?;
+        }
+        result.map_err(|err| err.to_string())?;
+        Ok(json!(response))
+    });
+    json_response(StatusCode::Accepted, &job)
+}
+
+async fn list_file_sets(request: Request<State>) -> tide::Result {
+    let file_sets = request
+        .state()
+        .view_model_service
+        .get_all_file_set_list_models()
+        .await
+        .map_err(service_error)?;
+    json_response(
+        StatusCode::Ok,
+        &to_responses::<_, FileSetResponse>(file_sets),
+    )
+}
+
+async fn get_file_set(request: Request<State>) -> tide::Result {
+    let file_set = request
+        .state()
+        .view_model_service
+        .get_file_set_view_model(id_param(&request)?)
+        .await
+        .map_err(service_error)?;
+    json_response(StatusCode::Ok, &FileSetDetailsResponse::from(file_set))
+}
+
+async fn import_file_set(mut request: Request<State>) -> tide::Result {
+    let import_request: ImportRequest = request.body_json().await?;
+    let file_type = FileType::iter()
+        .find(|file_type| file_type.dir_name() == import_request.file_type)
+        .ok_or_else(|| {
+            tide::Error::from_str(
+                StatusCode::BadRequest,
+                format!("Unknown file type {}", import_request.file_type),
+            )
+        })?;
+    if import_request.system_ids.is_empty() {
+        return Err(tide::Error::from_str(
+            StatusCode::BadRequest,
+            "At least one system is required",
+        ));
+    }
+    let state = request.state().clone();
+    let description = format!("Import {}", import_request.path.display());
+    let job = state.jobs.spawn(JobKind::Import, description, async move {
+        let file_set = state
+            .view_model_service
+            .import_file_set(
+                &FileSetImportRequest {
+                    path: import_request.path,
+                    file_type,
+                    system_ids: import_request.system_ids,
+                    name: import_request.name,
+                    file_names: import_request.file_names,
+                },
+                &state.collection_root_dir,
+            )
+            .await
+            .map_err(|err| err.to_string())?;
+        Ok(json!(ImportResponse::from(file_set)))
+    });
+    json_response(StatusCode::Accepted, &job)
+}
+
+async fn export_file_set(mut request: Request<State>) -> tide::Result {
+    let export_request: ExportRequest = request.body_json().await?;
+    let file_set_id = id_param(&request)?;
+    let state = request.state().clone();
+    let file_set = state
+        .view_model_service
+        .get_file_set_view_model(file_set_id)
+        .await
+        .map_err(service_error)?;
+    let description = format!(
+        "Export {} to {}",
+        file_set.file_set_name,
+        export_request.output_dir.display()
+    );
+    let job = state.jobs.spawn(JobKind::Export, description, async move {
+        let export_plan = state
+            .view_model_service
+            .export_file_set(
+                file_set_id,
+                &state.collection_root_dir,
+                &export_request.output_dir,
+                export_request.zip,
+            )
+            .await
+            .map_err(|err| err.to_string())?;
+        Ok(json!(ExportResponse {
+            output_dir: export_plan.output_dir.to_string_lossy().to_string(),
+            files: export_plan
+                .files
+                .into_iter()
+                .map(|file| file.output_file_name)
+                .collect(),
+            zip_file: export_plan
+                .zip_file_path
+                .map(|path| path.to_string_lossy().to_string()),
+        }))
+    });
+    json_response(StatusCode::Accepted, &job)
+}
+
+async fn list_jobs(request: Request<State>) -> tide::Result {
+    json_response(StatusCode::Ok, &request.state().jobs.list())
+}
+
+async fn get_job(request: Request<State>) -> tide::Result {
+    let job = job_param(&request)?;
+    json_response(StatusCode::Ok, &job)
+}
+
+/// Asks the emulator of a running launch job to exit, the job fails once it has.
+async fn stop_job(request: Request<State>) -> tide::Result {
+    let job = job_param(&request)?;
+    let launch_id = job
+        .launch_id
+        .filter(|_| job.status == JobStatus::Running)
+        .ok_or_else(|| {
+            tide::Error::from_str(
+                StatusCode::Conflict,
+                format!("Job {} isn't a running launch", job.id),
+            )
+        })?;
+    request
+        .state()
+        .process_registry
+        .terminate(launch_id)
+        .map_err(emulator_error)?;
+    json_response(StatusCode::Accepted, &job)
+}
+
+fn job_param(request: &Request<State>) -> tide::Result<Job> {
+    let id = id_param(request)?;
+    u64::try_from(id)
+        .ok()
+        .and_then(|id| request.state().jobs.get(id))
+        .ok_or_else(|| tide::Error::from_str(StatusCode::NotFound, format!("Job {}", id)))
+}
+
+fn to_responses<T, R: From<T>>(items: Vec<T>) -> Vec<R> {
+    items.into_iter().map(R::from).collect()
+}
+
+fn json_response<T: Serialize>(status: StatusCode, body: &T) -> tide::Result {
+    Ok(Response::builder(status)
+        .body(Body::from_json(body)?)
+        .build())
+}
+
+fn id_param(request: &Request<State>) -> tide::Result<i64> {
+    let id = request.param("id")?;
+    id.parse()
+        .map_err(|_| tide::Error::from_str(StatusCode::BadRequest, format!("Invalid id {}", id)))
+}
+
+fn date_param(value: Option<String>) -> tide::Result<Option<PartialDate>> {
+    value
+        .map(|value| value.parse::<PartialDate>())
+        .transpose()
+        .map_err(|err| tide::Error::from_str(StatusCode::BadRequest, err.to_string()))
+}
+
+fn company_role_param(value: Option<String>) -> tide::Result<Option<CompanyRole>> {
+    value
+        .map(|value| value.parse::<CompanyRole>())
+        .transpose()
+        .map_err(|err| tide::Error::from_str(StatusCode::BadRequest, err.to_string()))
+}
+
+fn id_list_param(value: Option<String>) -> tide::Result<Vec<i64>> {
+    list_param(value)
+        .into_iter()
+        .map(|id| {
+            id.parse().map_err(|_| {
+                tide::Error::from_str(StatusCode::BadRequest, format!("Invalid id {}", id))
+            })
+        })
+        .collect()
+}
+
+fn file_types_param(value: Option<String>) -> tide::Result<Vec<database::models::FileType>> {
+    list_param(value)
+        .into_iter()
+        .map(|name| {
+            FileType::iter()
+                .find(|file_type| file_type.dir_name() == name)
+                .map(database::models::FileType::from)
+                .ok_or_else(|| {
+                    tide::Error::from_str(
+                        StatusCode::BadRequest,
+                        format!("Unknown file type {}", name),
+                    )
+                })
+        })
+        .collect()
+}
+
+fn list_param(value: Option<String>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn sort_key_param(value: Option<String>) -> tide::Result<ReleaseSortKey> {
+    value
+        .map(|value| value.parse::<ReleaseSortKey>())
+        .transpose()
+        .map(Option::unwrap_or_default)
+        .map_err(|err| tide::Error::from_str(StatusCode::BadRequest, err.to_string()))
+}
+
+fn service_error(err: Error) -> tide::Error {
+    let status = match err {
+        Error::NotFound(_) => StatusCode::NotFound,
+        Error::InvalidSelection(_) | Error::InvalidQuery(_) => StatusCode::BadRequest,
+        _ => StatusCode::InternalServerError,
+    };
+    tide::Error::from_str(status, err.to_string())
+}
+
+fn emulator_error(err: EmulatorRunnerError) -> tide::Error {
+    let status = match err {
+        EmulatorRunnerError::LaunchDirectoryInUse(_) | EmulatorRunnerError::LaunchNotFound(_) => {
+            StatusCode::Conflict
+        }
+        _ => StatusCode::InternalServerError,
+    };
+    tide::Error::from_str(status, err.to_string())
+}
+
+/// Errors are returned as `{"error": "<message>"}`.
+async fn error_body(mut response: Response) -> tide::Result {
+    if let Some(err) = response.error() {
+        let body = json!({ "error": err.to_string() });
+        response.set_body(body);
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use database::{
+        models::{ReleaseMetadata, TagTarget},
+        repository_manager::RepositoryManager,
+        setup_test_db,
+    };
+    use serde::de::DeserializeOwned;
+    use tempfile::{tempdir, TempDir};
+    use tide::http::{Method, Url};
+
+    use crate::models::ReleaseCompanyResponse;
+
+    use super::*;
+
+    const TOKEN: &str = "secret";
+
+    struct TestServer {
+        app: tide::Server<()>,
+        state: State,
+        repository_manager: Arc<RepositoryManager>,
+        temp_dir: TempDir,
+    }
+
+    impl TestServer {
+        async fn new() -> Self {
+            let temp_dir = tempdir().unwrap();
+            let pool = Arc::new(setup_test_db().await);
+            let repository_manager = Arc::new(RepositoryManager::new(pool));
+            let state = State::new(
+                Arc::new(ViewModelService::new(Arc::clone(&repository_manager))),
+                temp_dir.path().join("collection"),
+            );
+            Self {
+                app: app(state.clone(), TOKEN.to_string()),
+                state,
+                repository_manager,
+                temp_dir,
+            }
+        }
+
+        async fn request(
+            &self,
+            method: Method,
+            path: &str,
+            token: Option<&str>,
+            body: Option<serde_json::Value>,
+        ) -> tide::http::Response {
+            let url = Url::parse(&format!("http://localhost{}", path)).unwrap();
+            let mut request = tide::http::Request::new(method, url);
+            if let Some(token) = token {
+                request.insert_header("Authorization", format!("Bearer {}", token));
+            }
+            if let Some(body) = body {
+                request.set_body(body);
+            }
+            self.app.respond(request).await.unwrap()
+        }
+
+        async fn get<T: DeserializeOwned>(&self, path: &str) -> T {
+            let mut response = self.request(Method::Get, path, Some(TOKEN), None).await;
+            assert_eq!(response.status(), StatusCode::Ok);
+            response.body_json().await.unwrap()
+        }
+
+        async fn post_job(&self, path: &str, body: serde_json::Value) -> Job {
+            let mut response = self
+                .request(Method::Post, path, Some(TOKEN), Some(body))
+                .await;
+            assert_eq!(response.status(), StatusCode::Accepted);
+            let job: Job = response.body_json().await.unwrap();
+            for _ in 0..500 {
+                let job: Job = self.get(&format!("/api/jobs/{}", job.id)).await;
+                if job.status != JobStatus::Running {
+                    return job;
+                }
+                async_std::task::sleep(Duration::from_millis(10)).await;
+            }
+            panic!("Job {} didn't finish", job.id);
+        }
+    }
+
+    #[async_std::test]
+    async fn test_token_auth() {
+        let server = TestServer::new().await;
+        let response = server
+            .request(Method::Get, "/api/systems", None, None)
+            .await;
+        assert_eq!(response.status(), StatusCode::Unauthorized);
+        let response = server
+            .request(Method::Get, "/api/systems", Some("wrong"), None)
+            .await;
+        assert_eq!(response.status(), StatusCode::Unauthorized);
+        let response = server
+            .request(Method::Get, "/api/systems", Some(TOKEN), None)
+            .await;
+        assert_eq!(response.status(), StatusCode::Ok);
+
+        let mut response = server
+            .request(Method::Get, "/openapi.json", None, None)
+            .await;
+        assert_eq!(response.status(), StatusCode::Ok);
+        let description: serde_json::Value = response.body_json().await.unwrap();
+        assert!(description["paths"]["/api/releases/{id}/launch"]["post"].is_object());
+    }
+
+    #[async_std::test]
+    async fn test_read_endpoints() {
+        let server = TestServer::new().await;
+        let repository_manager = &server.repository_manager;
+        let system_id = repository_manager
+            .get_system_repository()
+            .add_system(&"Commodore 64".to_string())
+            .await
+            .unwrap();
+        let software_title_id = repository_manager
+            .get_software_title_repository()
+            .add_software_title(&"Game".to_string(), None)
+            .await
+            .unwrap();
+        let release_id = repository_manager
+            .get_release_repository()
+            .add_release_full(
+                "Game".to_string(),
+                vec![software_title_id],
+                vec![],
+                vec![system_id],
+            )
+            .await
+            .unwrap();
+
+        let systems: Vec<SystemResponse> = server.get("/api/systems").await;
+        assert_eq!(
+            systems,
+            vec![SystemResponse {
+                id: system_id,
+                name: "Commodore 64".to_string()
+            }]
+        );
+        let software_titles: Vec<SoftwareTitleResponse> = server.get("/api/software-titles").await;
+        assert_eq!(software_titles.len(), 1);
+        repository_manager
+            .get_software_title_repository()
+            .add_alias(software_title_id, "Spiel", Some("de"), None)
+            .await
+            .unwrap();
+        let software_titles: Vec<SoftwareTitleResponse> =
+            server.get("/api/software-titles?search=spiel").await;
+        assert_eq!(software_titles.len(), 1);
+        let software_titles: Vec<SoftwareTitleResponse> =
+            server.get("/api/software-titles?search=other").await;
+        assert!(software_titles.is_empty());
+        let results: Vec<SearchResultResponse> = server.get("/api/search?q=spiel").await;
+        assert_eq!(
+            results,
+            vec![SearchResultResponse {
+                kind: "software_title".to_string(),
+                id: software_title_id,
+                name: "Game".to_string(),
+                matched_name: "Spiel".to_string(),
+            }]
+        );
+
+        repository_manager
+            .get_release_repository()
+            .update_release_metadata(
+                release_id,
+                &ReleaseMetadata {
+                    release_date: Some("1987-06".parse().unwrap()),
+                    regions: vec!["Europe".to_string()],
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let company_repository = repository_manager.get_company_repository();
+        let company_id = company_repository.add_company("Ocean").await.unwrap();
+        company_repository
+            .add_company_to_release(release_id, company_id, CompanyRole::Publisher)
+            .await
+            .unwrap();
+
+        let release: ReleaseDetailsResponse =
+            server.get(&format!("/api/releases/{}", release_id)).await;
+        assert_eq!(release.name, "Game");
+        assert_eq!(release.release_date, Some("1987-06".to_string()));
+        assert_eq!(release.regions, vec!["Europe".to_string()]);
+        assert_eq!(release.software_titles[0].id, software_title_id);
+        assert_eq!(
+            release.companies,
+            vec![ReleaseCompanyResponse {
+                id: company_id,
+                name: "Ocean".to_string(),
+                role: "publisher".to_string()
+            }]
+        );
+        assert!(release.tags.is_empty());
+
+        repository_manager
+            .get_tag_repository()
+            .add_tag(TagTarget::SoftwareTitle(software_title_id), "genre/action")
+            .await
+            .unwrap();
+        let software_titles: Vec<SoftwareTitleResponse> =
+            server.get("/api/software-titles?tag=genre").await;
+        assert_eq!(software_titles.len(), 1);
+        let software_titles: Vec<SoftwareTitleResponse> = server
+            .get("/api/software-titles?search=game&tag=genre/puzzle")
+            .await;
+        assert!(software_titles.is_empty());
+
+        let emulators: Vec<RankedEmulatorResponse> = server
+            .get(&format!("/api/releases/{}/emulators", release_id))
+            .await;
+        assert!(emulators.is_empty());
+
+        // the release without file sets is listed too
+        let releases: Vec<ReleaseResponse> = server
+            .get(&format!(
+                "/api/releases?system_id={},{}&sort=date&descending=true&limit=1",
+                system_id,
+                system_id + 1
+            ))
+            .await;
+        assert_eq!(releases.len(), 1);
+        assert_eq!(releases[0].systems, vec!["Commodore 64".to_string()]);
+        assert!(releases[0].file_types.is_empty());
+        let releases: Vec<ReleaseResponse> =
+            server.get("/api/releases?file_type=rom,disk_image").await;
+        assert!(releases.is_empty());
+        let response = server
+            .request(Method::Get, "/api/releases?sort=size", Some(TOKEN), None)
+            .await;
+        assert_eq!(response.status(), StatusCode::BadRequest);
+
+        let response = server
+            .request(
+                Method::Get,
+                "/api/releases?released_from=1987-13",
+                Some(TOKEN),
+                None,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::BadRequest);
+        let response = server
+            .request(
+                Method::Get,
+                &format!("/api/releases?company_id={}&company_role=owner", company_id),
+                Some(TOKEN),
+                None,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::BadRequest);
+
+        let mut response = server
+            .request(Method::Get, "/api/releases/999", Some(TOKEN), None)
+            .await;
+        assert_eq!(response.status(), StatusCode::NotFound);
+        let error: serde_json::Value = response.body_json().await.unwrap();
+        assert_eq!(error["error"], "Not found: Release 999");
+        let response = server
+            .request(Method::Get, "/api/file-sets/abc", Some(TOKEN), None)
+            .await;
+        assert_eq!(response.status(), StatusCode::BadRequest);
+
+        // the release has no file set to launch
+        let response = server
+            .request(
+                Method::Post,
+                &format!("/api/releases/{}/launch", release_id),
+                Some(TOKEN),
+                Some(json!({})),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::BadRequest);
+    }
+
+    #[async_std::test]
+    async fn test_launch_directory_in_use_and_stop() {
+        let server = TestServer::new().await;
+        let repository_manager = &server.repository_manager;
+        let system_id = repository_manager
+            .get_system_repository()
+            .add_system(&"Commodore 64".to_string())
+            .await
+            .unwrap();
+        let file_path = server.temp_dir.path().join("game.d64");
+        std::fs::write(&file_path, "disk image").unwrap();
+        let job = server
+            .post_job(
+                "/api/file-sets/import",
+                json!({ "path": file_path, "file_type": "disk_image", "system_ids": [system_id] }),
+            )
+            .await;
+        let JobStatus::Finished { result } = job.status else {
+            panic!("Import failed: {:?}", job.status);
+        };
+        let imported: ImportResponse = serde_json::from_value(result).unwrap();
+        let release_id = repository_manager
+            .get_release_repository()
+            .add_release_full(
+                "Game".to_string(),
+                vec![],
+                vec![imported.id],
+                vec![system_id],
+            )
+            .await
+            .unwrap();
+        repository_manager
+            .get_emulator_repository()
+            .add_emulator_with_systems(
+                "VICE".to_string(),
+                "x64sc".to_string(),
+                true,
+                vec![database::models::EmulatorSystemUpdateModel {
+                    id: None,
+                    system_id,
+                    arguments: String::new(),
+                }],
+                Default::default(),
+            )
+            .await
+            .unwrap();
+
+        // e.g. the emulator of an earlier launch is still running
+        let state = &server.state;
+        let running_launch = state
+            .view_model_service
+            .prepare_release_launch(&ReleaseLaunchRequest {
+                release_id,
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .register(&state.process_registry)
+            .unwrap();
+        let mut response = server
+            .request(
+                Method::Post,
+                &format!("/api/releases/{}/launch", release_id),
+                Some(TOKEN),
+                Some(json!({})),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::Conflict);
+        let error: serde_json::Value = response.body_json().await.unwrap();
+        assert!(error["error"].as_str().unwrap().contains("in use"));
+        drop(running_launch);
+        assert_eq!(server.get::<Vec<Job>>("/api/jobs").await.len(), 1);
+
+        // only running launch jobs can be stopped
+        let response = server
+            .request(
+                Method::Post,
+                &format!("/api/jobs/{}/stop", job.id),
+                Some(TOKEN),
+                None,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::Conflict);
+        let response = server
+            .request(Method::Post, "/api/jobs/999/stop", Some(TOKEN), None)
+            .await;
+        assert_eq!(response.status(), StatusCode::NotFound);
+    }
+
+    #[async_std::test]
+    async fn test_import_and_export_jobs() {
+        let server = TestServer::new().await;
+        let system_id = server
+            .repository_manager
+            .get_system_repository()
+            .add_system(&"Commodore 64".to_string())
+            .await
+            .unwrap();
+        let file_path = server.temp_dir.path().join("game.d64");
+        std::fs::write(&file_path, "disk image").unwrap();
+
+        let response = server
+            .request(
+                Method::Post,
+                "/api/file-sets/import",
+                Some(TOKEN),
+                Some(
+                    json!({ "path": file_path, "file_type": "floppy", "system_ids": [system_id] }),
+                ),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::BadRequest);
+
+        let job = server
+            .post_job(
+                "/api/file-sets/import",
+                json!({ "path": file_path, "file_type": "disk_image", "system_ids": [system_id] }),
+            )
+            .await;
+        assert_eq!(job.kind, JobKind::Import);
+        let JobStatus::Finished { result } = job.status else {
+            panic!("Import failed: {:?}", job.status);
+        };
+        let imported: ImportResponse = serde_json::from_value(result).unwrap();
+        assert_eq!(imported.imported_files, vec!["game.d64".to_string()]);
+
+        let file_sets: Vec<FileSetResponse> = server.get("/api/file-sets").await;
+        assert_eq!(file_sets.len(), 1);
+        let file_set: FileSetDetailsResponse =
+            server.get(&format!("/api/file-sets/{}", imported.id)).await;
+        assert_eq!(file_set.files[0].name, "game.d64");
+        assert_eq!(file_set.files[0].size, 10);
+
+        let output_dir = server.temp_dir.path().join("export");
+        let job = server
+            .post_job(
+                &format!("/api/file-sets/{}/export", imported.id),
+                json!({ "output_dir": output_dir }),
+            )
+            .await;
+        assert!(matches!(job.status, JobStatus::Finished { .. }));
+        assert_eq!(
+            std::fs::read_to_string(output_dir.join("game.d64")).unwrap(),
+            "disk image"
+        );
+
+        let jobs: Vec<Job> = server.get("/api/jobs").await;
+        assert_eq!(jobs.len(), 2);
+    }
+}
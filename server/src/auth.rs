@@ -0,0 +1,39 @@
+use serde_json::json;
+use tide::{Middleware, Next, Request, Response, StatusCode};
+
+/// Requires the requests to have the `Authorization: Bearer <token>` header.
+#[derive(Debug, Clone)]
+pub struct TokenAuth {
+    token: String,
+}
+
+impl TokenAuth {
+    pub fn new(token: String) -> Self {
+        Self { token }
+    }
+
+    fn is_authorized<State>(&self, request: &Request<State>) -> bool {
+        request
+            .header("Authorization")
+            .and_then(|values| values.as_str().strip_prefix("Bearer "))
+            .is_some_and(|token| constant_time_eq(token.as_bytes(), self.token.as_bytes()))
+    }
+}
+
+#[tide::utils::async_trait]
+impl<State: Clone + Send + Sync + 'static> Middleware<State> for TokenAuth {
+    async fn handle(&self, request: Request<State>, next: Next<'_, State>) -> tide::Result {
+        if !self.is_authorized(&request) {
+            return Ok(Response::builder(StatusCode::Unauthorized)
+                .header("WWW-Authenticate", "Bearer")
+                .body(json!({ "error": "Missing or invalid token" }))
+                .build());
+        }
+        Ok(next.run(request).await)
+    }
+}
+
+/// Compares the tokens without returning early on the first differing byte.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
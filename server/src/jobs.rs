@@ -0,0 +1,186 @@
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use emulator_runner::process_registry::LaunchId;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Finished and failed jobs kept for polling, the oldest are dropped first.
+pub const MAX_FINISHED_JOBS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    Launch,
+    Import,
+    Export,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Finished { result: Value },
+    Failed { error: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Job {
+    pub id: u64,
+    pub kind: JobKind,
+    pub description: String,
+    #[serde(flatten)]
+    pub status: JobStatus,
+    /// Registered launch of a launch job, used for stopping it.
+    #[serde(skip)]
+    pub launch_id: Option<LaunchId>,
+}
+
+/// Launches, imports and exports run in the background, their status is kept here for the
+/// clients to poll.
+#[derive(Debug, Default)]
+pub struct Jobs {
+    jobs: Mutex<JobList>,
+}
+
+#[derive(Debug, Default)]
+struct JobList {
+    jobs: Vec<Job>,
+    last_id: u64,
+}
+
+impl Jobs {
+    /// Starts the job and returns it in the running state.
+    pub fn spawn<F>(self: &Arc<Self>, kind: JobKind, description: String, task: F) -> Job
+    where
+        F: Future<Output = Result<Value, String>> + Send + 'static,
+    {
+        self.spawn_job(kind, description, None, task)
+    }
+
+    /// Starts the job of a registered launch, the launch can be stopped through the job.
+    pub fn spawn_launch<F>(
+        self: &Arc<Self>,
+        description: String,
+        launch_id: LaunchId,
+        task: F,
+    ) -> Job
+    where
+        F: Future<Output = Result<Value, String>> + Send + 'static,
+    {
+        self.spawn_job(JobKind::Launch, description, Some(launch_id), task)
+    }
+
+    fn spawn_job<F>(
+        self: &Arc<Self>,
+        kind: JobKind,
+        description: String,
+        launch_id: Option<LaunchId>,
+        task: F,
+    ) -> Job
+    where
+        F: Future<Output = Result<Value, String>> + Send + 'static,
+    {
+        let job = {
+            let mut jobs = self.jobs.lock().unwrap();
+            jobs.last_id += 1;
+            let job = Job {
+                id: jobs.last_id,
+                kind,
+                description,
+                status: JobStatus::Running,
+                launch_id,
+            };
+            jobs.jobs.push(job.clone());
+            job
+        };
+        let jobs = Arc::clone(self);
+        let id = job.id;
+        async_std::task::spawn(async move {
+            let status = match task.await {
+                Ok(result) => JobStatus::Finished { result },
+                Err(error) => JobStatus::Failed { error },
+            };
+            let mut jobs = jobs.jobs.lock().unwrap();
+            if let Some(job) = jobs.jobs.iter_mut().find(|job| job.id == id) {
+                job.status = status;
+            }
+            jobs.prune_finished();
+        });
+        job
+    }
+
+    pub fn get(&self, id: u64) -> Option<Job> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .jobs
+            .iter()
+            .find(|job| job.id == id)
+            .cloned()
+    }
+
+    pub fn list(&self) -> Vec<Job> {
+        self.jobs.lock().unwrap().jobs.clone()
+    }
+}
+
+impl JobList {
+    /// Running jobs are always kept, of the others only the latest `MAX_FINISHED_JOBS`.
+    fn prune_finished(&mut self) {
+        let finished = self
+            .jobs
+            .iter()
+            .filter(|job| job.status != JobStatus::Running)
+            .count();
+        let mut excess = finished.saturating_sub(MAX_FINISHED_JOBS);
+        self.jobs.retain(|job| {
+            if excess > 0 && job.status != JobStatus::Running {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[async_std::test]
+    async fn test_finished_jobs_are_pruned() {
+        let jobs = Arc::new(Jobs::default());
+        let (sender, receiver) = async_std::channel::bounded::<()>(1);
+        let running = jobs.spawn(JobKind::Launch, "Launch".to_string(), async move {
+            receiver.recv().await.ok();
+            Ok(Value::Null)
+        });
+        for index in 0..MAX_FINISHED_JOBS + 5 {
+            jobs.spawn(JobKind::Import, format!("Import {}", index), async {
+                Ok(Value::Null)
+            });
+        }
+        for _ in 0..500 {
+            if jobs.list().len() == MAX_FINISHED_JOBS + 1 {
+                break;
+            }
+            async_std::task::sleep(Duration::from_millis(10)).await;
+        }
+        let list = jobs.list();
+        assert_eq!(list.len(), MAX_FINISHED_JOBS + 1);
+        assert_eq!(list[0], running);
+        assert!(jobs.get(2).is_none());
+        // ids aren't reused after pruning
+        let job = jobs.spawn(JobKind::Export, "Export".to_string(), async {
+            Ok(Value::Null)
+        });
+        assert_eq!(job.id, MAX_FINISHED_JOBS as u64 + 7);
+        sender.send(()).await.unwrap();
+    }
+}
@@ -0,0 +1,64 @@
+mod api;
+mod auth;
+mod jobs;
+mod models;
+mod openapi;
+
+use std::{path::PathBuf, sync::Arc};
+
+use api::State;
+use clap::Parser;
+use database::{get_db_pool, get_db_pool_with_path, repository_manager::RepositoryManager};
+use service::view_model_service::ViewModelService;
+
+/// Serve the collection on localhost for external frontends and scripts.
+#[derive(Debug, Parser)]
+#[command(name = "server", version, about)]
+struct Args {
+    /// Port to listen on, only connections from localhost are accepted.
+    #[arg(long, default_value_t = 8765)]
+    port: u16,
+    /// Token the clients send in the `Authorization: Bearer <token>` header.
+    #[arg(long, env = "SCM_SERVER_TOKEN", hide_env_values = true)]
+    token: String,
+    /// Database file, defaults to the database of the application.
+    #[arg(long, value_name = "PATH")]
+    database: Option<PathBuf>,
+}
+
+#[async_std::main]
+async fn main() -> std::io::Result<()> {
+    let args = Args::parse();
+    if args.token.is_empty() {
+        eprintln!("Token must not be empty");
+        std::process::exit(1);
+    }
+    let pool = match &args.database {
+        Some(path) => get_db_pool_with_path(path).await,
+        None => get_db_pool().await,
+    };
+    let pool = match pool {
+        Ok(pool) => pool,
+        Err(err) => {
+            eprintln!("Failed to open the database: {}", err);
+            std::process::exit(1);
+        }
+    };
+    let view_model_service = Arc::new(ViewModelService::new(Arc::new(RepositoryManager::new(
+        pool,
+    ))));
+    let settings = match view_model_service.get_settings().await {
+        Ok(settings) => settings,
+        Err(err) => {
+            eprintln!("Failed to read the settings: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    let app = api::app(
+        State::new(view_model_service, settings.collection_root_dir),
+        args.token,
+    );
+    eprintln!("Listening on http://127.0.0.1:{}", args.port);
+    app.listen(("127.0.0.1", args.port)).await
+}
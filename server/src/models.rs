@@ -0,0 +1,378 @@
+use std::path::PathBuf;
+
+use database::models::{
+    CompanyRole, CompatibilityStatus, FileSetFileInfo, FileSetLaunchRole, ReleaseCompany,
+    ReleaseEmulatorCompatibility, SearchResult, SearchResultKind, SoftwareTitle, System,
+};
+use serde::{Deserialize, Serialize};
+use service::{
+    file_set_operations::ImportedFileSet,
+    release_launch::PreparedReleaseLaunch,
+    view_models::{
+        FileSetListModel, FileSetViewModel, RankedEmulatorViewModel, ReleaseListModel,
+        ReleaseViewModel, SoftwareTitleListModel, SystemListModel,
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SystemResponse {
+    pub id: i64,
+    pub name: String,
+}
+
+impl From<SystemListModel> for SystemResponse {
+    fn from(system: SystemListModel) -> Self {
+        SystemResponse {
+            id: system.id,
+            name: system.name,
+        }
+    }
+}
+
+impl From<System> for SystemResponse {
+    fn from(system: System) -> Self {
+        SystemResponse {
+            id: system.id,
+            name: system.name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SoftwareTitleResponse {
+    pub id: i64,
+    pub name: String,
+}
+
+impl From<SoftwareTitleListModel> for SoftwareTitleResponse {
+    fn from(software_title: SoftwareTitleListModel) -> Self {
+        SoftwareTitleResponse {
+            id: software_title.id,
+            name: software_title.name,
+        }
+    }
+}
+
+impl From<SoftwareTitle> for SoftwareTitleResponse {
+    fn from(software_title: SoftwareTitle) -> Self {
+        SoftwareTitleResponse {
+            id: software_title.id,
+            name: software_title.name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReleaseResponse {
+    pub id: i64,
+    pub name: String,
+    pub systems: Vec<String>,
+    pub file_types: Vec<String>,
+}
+
+impl From<ReleaseListModel> for ReleaseResponse {
+    fn from(release: ReleaseListModel) -> Self {
+        ReleaseResponse {
+            id: release.id,
+            name: release.name,
+            systems: release.system_names,
+            file_types: release.file_types,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReleaseDetailsResponse {
+    pub id: i64,
+    pub name: String,
+    pub systems: Vec<SystemResponse>,
+    pub software_titles: Vec<SoftwareTitleResponse>,
+    pub file_sets: Vec<FileSetDetailsResponse>,
+    /// `YYYY`, `YYYY-MM` or `YYYY-MM-DD`.
+    pub release_date: Option<String>,
+    pub regions: Vec<String>,
+    pub languages: Vec<String>,
+    pub version: Option<String>,
+    pub product_code: Option<String>,
+    pub barcode: Option<String>,
+    pub companies: Vec<ReleaseCompanyResponse>,
+    pub tags: Vec<String>,
+}
+
+impl From<ReleaseViewModel> for ReleaseDetailsResponse {
+    fn from(release: ReleaseViewModel) -> Self {
+        ReleaseDetailsResponse {
+            id: release.id,
+            name: release.name,
+            systems: release.systems.into_iter().map(Into::into).collect(),
+            software_titles: release
+                .software_titles
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            file_sets: release.file_sets.into_iter().map(Into::into).collect(),
+            release_date: release.metadata.release_date.map(|date| date.to_string()),
+            regions: release.metadata.regions,
+            languages: release.metadata.languages,
+            version: release.metadata.version,
+            product_code: release.metadata.product_code,
+            barcode: release.metadata.barcode,
+            companies: release.companies.into_iter().map(Into::into).collect(),
+            tags: release.tags.into_iter().map(|tag| tag.name).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReleaseCompanyResponse {
+    pub id: i64,
+    pub name: String,
+    /// One of `developer`, `publisher` or `distributor`.
+    pub role: String,
+}
+
+impl From<ReleaseCompany> for ReleaseCompanyResponse {
+    fn from(release_company: ReleaseCompany) -> Self {
+        ReleaseCompanyResponse {
+            id: release_company.company.id,
+            name: release_company.company.name,
+            role: match release_company.role {
+                CompanyRole::Developer => "developer",
+                CompanyRole::Publisher => "publisher",
+                CompanyRole::Distributor => "distributor",
+            }
+            .to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileSetResponse {
+    pub id: i64,
+    pub name: String,
+    pub file_type: String,
+}
+
+impl From<FileSetListModel> for FileSetResponse {
+    fn from(file_set: FileSetListModel) -> Self {
+        FileSetResponse {
+            id: file_set.id,
+            name: file_set.file_set_name,
+            file_type: file_set.file_type.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileSetDetailsResponse {
+    pub id: i64,
+    pub name: String,
+    pub file_type: String,
+    pub files: Vec<FileResponse>,
+}
+
+impl From<FileSetViewModel> for FileSetDetailsResponse {
+    fn from(file_set: FileSetViewModel) -> Self {
+        FileSetDetailsResponse {
+            id: file_set.id,
+            name: file_set.file_set_name,
+            file_type: file_set.file_type.to_string(),
+            files: file_set.files.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileResponse {
+    pub name: String,
+    /// SHA-1 checksum as a hex string.
+    pub sha1_checksum: String,
+    pub size: i64,
+    pub ordinal: i64,
+    pub is_entry_point: bool,
+}
+
+impl From<FileSetFileInfo> for FileResponse {
+    fn from(file: FileSetFileInfo) -> Self {
+        FileResponse {
+            name: file.file_name,
+            sha1_checksum: file
+                .sha1_checksum
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect(),
+            size: file.file_size,
+            ordinal: file.ordinal,
+            is_entry_point: file.is_entry_point,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RankedEmulatorResponse {
+    pub id: i64,
+    pub name: String,
+    pub compatibility: Option<CompatibilityResponse>,
+}
+
+impl From<RankedEmulatorViewModel> for RankedEmulatorResponse {
+    fn from(ranked_emulator: RankedEmulatorViewModel) -> Self {
+        RankedEmulatorResponse {
+            id: ranked_emulator.emulator.id,
+            name: ranked_emulator.emulator.name,
+            compatibility: ranked_emulator.compatibility.map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompatibilityResponse {
+    /// One of `works`, `minor_issues` or `broken`.
+    pub status: String,
+    pub notes: Option<String>,
+    pub tested_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchResultResponse {
+    /// One of `software_title`, `release` or `file_set`.
+    pub kind: String,
+    pub id: i64,
+    pub name: String,
+    /// Name the search matched, e.g. an alias of a software title or a file of a file set.
+    pub matched_name: String,
+}
+
+impl From<SearchResult> for SearchResultResponse {
+    fn from(result: SearchResult) -> Self {
+        SearchResultResponse {
+            kind: match result.kind {
+                SearchResultKind::SoftwareTitle => "software_title",
+                SearchResultKind::Release => "release",
+                SearchResultKind::FileSet => "file_set",
+            }
+            .to_string(),
+            id: result.id,
+            name: result.name,
+            matched_name: result.matched_name,
+        }
+    }
+}
+
+impl From<ReleaseEmulatorCompatibility> for CompatibilityResponse {
+    fn from(compatibility: ReleaseEmulatorCompatibility) -> Self {
+        CompatibilityResponse {
+            status: match compatibility.status {
+                CompatibilityStatus::Works => "works",
+                CompatibilityStatus::MinorIssues => "minor_issues",
+                CompatibilityStatus::Broken => "broken",
+            }
+            .to_string(),
+            notes: compatibility.notes,
+            tested_at: compatibility.tested_at,
+        }
+    }
+}
+
+/// Choices override what is resolved for the release.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchRequest {
+    pub system_id: Option<i64>,
+    pub emulator_id: Option<i64>,
+    pub file_set_id: Option<i64>,
+    pub file_name: Option<String>,
+    pub playlist: bool,
+    /// Record the launch result in the release compatibility.
+    pub record: bool,
+    /// File sets launched along with the file set by their role, the roles saved for the
+    /// release when not given.
+    pub roles: Option<Vec<FileSetRoleRequest>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileSetRoleRequest {
+    pub file_set_id: i64,
+    /// Name the emulator arguments refer to the file set with, e.g. `bios` for `{bios}`.
+    pub role: String,
+}
+
+impl From<FileSetRoleRequest> for FileSetLaunchRole {
+    fn from(request: FileSetRoleRequest) -> Self {
+        FileSetLaunchRole {
+            file_set_id: request.file_set_id,
+            role: request.role,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LaunchResponse {
+    pub release_id: i64,
+    pub system: String,
+    pub emulator: String,
+    pub file_set: String,
+    pub file: String,
+}
+
+impl From<&PreparedReleaseLaunch> for LaunchResponse {
+    fn from(launch: &PreparedReleaseLaunch) -> Self {
+        LaunchResponse {
+            release_id: launch.release_id,
+            system: launch.system_name.clone(),
+            emulator: launch.emulator_name.clone(),
+            file_set: launch.file_set_name.clone(),
+            file: launch.launched_file.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportRequest {
+    /// File, or zip archive, on the machine the server runs on.
+    pub path: PathBuf,
+    /// File type directory name, e.g. `rom`, `disk_image` or `tape_image`.
+    pub file_type: String,
+    pub system_ids: Vec<i64>,
+    /// File set name, defaults to the file name.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Files to import from a zip archive, all files are imported when empty.
+    #[serde(default)]
+    pub file_names: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportResponse {
+    pub id: i64,
+    pub name: String,
+    pub imported_files: Vec<String>,
+    pub existing_files: Vec<String>,
+}
+
+impl From<ImportedFileSet> for ImportResponse {
+    fn from(file_set: ImportedFileSet) -> Self {
+        ImportResponse {
+            id: file_set.id,
+            name: file_set.name,
+            imported_files: file_set.imported_files,
+            existing_files: file_set.existing_files,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportRequest {
+    /// Directory on the machine the server runs on.
+    pub output_dir: PathBuf,
+    /// Write the files into a zip archive instead of extracting them.
+    #[serde(default)]
+    pub zip: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportResponse {
+    pub output_dir: String,
+    pub files: Vec<String>,
+    pub zip_file: Option<String>,
+}
@@ -0,0 +1,379 @@
+use serde_json::{json, Value};
+
+/// OpenAPI 3.0 description of the API, kept in sync with the routes in `api::app`.
+pub fn description() -> Value {
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Software Collection Manager API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Local API for querying the collection and launching, importing and exporting from it. Launches, imports and exports run as jobs that are polled from /api/jobs/{id}."
+        },
+        "servers": [{ "url": "http://127.0.0.1:8765" }],
+        "security": [{ "bearerAuth": [] }],
+        "paths": {
+            "/api/systems": {
+                "get": operation("listSystems", "List systems", vec![], None, array_of("System"))
+            },
+            "/api/search": {
+                "get": operation(
+                    "search",
+                    "Search software titles and their aliases, releases and the file names of file sets for names with words starting with the words of the query, regardless of case and diacritics, best matches first",
+                    vec![
+                        json!({ "name": "q", "in": "query", "required": true, "schema": string() }),
+                        query_param("limit")
+                    ],
+                    None,
+                    array_of("SearchResult")
+                )
+            },
+            "/api/software-titles": {
+                "get": operation(
+                    "listSoftwareTitles",
+                    "List software titles ordered by sort name, optionally only the titles whose name, sort name or an alias contains the search and the titles tagged with the tag or its subtags",
+                    vec![string_query_param("search"), string_query_param("tag")],
+                    None,
+                    array_of("SoftwareTitle")
+                )
+            },
+            "/api/releases": {
+                "get": operation(
+                    "listReleases",
+                    "List releases, optionally filtered by systems, software titles, file types, name pattern, region, language, release date, company and tag, sorted and paged",
+                    vec![
+                        string_query_param("system_id"),
+                        string_query_param("software_title_id"),
+                        string_query_param("file_type"),
+                        string_query_param("name"),
+                        string_query_param("region"),
+                        string_query_param("language"),
+                        string_query_param("released_from"),
+                        string_query_param("released_to"),
+                        query_param("company_id"),
+                        string_query_param("company_role"),
+                        string_query_param("tag"),
+                        string_query_param("sort"),
+                        boolean_query_param("descending"),
+                        query_param("after"),
+                        query_param("limit"),
+                        query_param("offset")
+                    ],
+                    None,
+                    array_of("Release")
+                )
+            },
+            "/api/releases/{id}": {
+                "get": operation("getRelease", "Get release with its file sets", vec![id_param()], None, schema("ReleaseDetails"))
+            },
+            "/api/releases/{id}/emulators": {
+                "get": operation(
+                    "listReleaseEmulators",
+                    "List emulators ranked by compatibility with the release, on the first system of the release by default",
+                    vec![id_param(), query_param("system_id")],
+                    None,
+                    array_of("RankedEmulator")
+                )
+            },
+            "/api/releases/{id}/launch": {
+                "post": conflict_operation(
+                    job_operation("launchRelease", "Launch the release, choices not given are resolved for the release", vec![id_param()], "LaunchRequest"),
+                    "The launch directory is in use by a running launch"
+                )
+            },
+            "/api/file-sets": {
+                "get": operation("listFileSets", "List file sets", vec![], None, array_of("FileSet"))
+            },
+            "/api/file-sets/import": {
+                "post": job_operation("importFileSet", "Import a file, or files from a zip archive, into a new file set", vec![], "ImportRequest")
+            },
+            "/api/file-sets/{id}": {
+                "get": operation("getFileSet", "Get file set with its files", vec![id_param()], None, schema("FileSetDetails"))
+            },
+            "/api/file-sets/{id}/export": {
+                "post": job_operation("exportFileSet", "Export the files of the file set to a directory", vec![id_param()], "ExportRequest")
+            },
+            "/api/jobs": {
+                "get": operation("listJobs", "List jobs", vec![], None, array_of("Job"))
+            },
+            "/api/jobs/{id}": {
+                "get": operation("getJob", "Get job status", vec![id_param()], None, schema("Job"))
+            },
+            "/api/jobs/{id}/stop": {
+                "post": conflict_operation(
+                    accepted(operation("stopJob", "Ask the emulator of a running launch job to exit", vec![id_param()], None, schema("Job"))),
+                    "The job isn't a running launch"
+                )
+            }
+        },
+        "components": {
+            "securitySchemes": {
+                "bearerAuth": { "type": "http", "scheme": "bearer" }
+            },
+            "schemas": {
+                "System": object(json!({ "id": integer(), "name": string() }), &["id", "name"]),
+                "SoftwareTitle": object(json!({ "id": integer(), "name": string() }), &["id", "name"]),
+                "SearchResult": object(
+                    json!({
+                        "kind": { "type": "string", "enum": ["software_title", "release", "file_set"] },
+                        "id": integer(),
+                        "name": string(),
+                        "matched_name": string()
+                    }),
+                    &["kind", "id", "name", "matched_name"]
+                ),
+                "Release": object(
+                    json!({
+                        "id": integer(),
+                        "name": string(),
+                        "systems": { "type": "array", "items": string() },
+                        "file_types": { "type": "array", "items": string() }
+                    }),
+                    &["id", "name", "systems", "file_types"]
+                ),
+                "ReleaseDetails": object(
+                    json!({
+                        "id": integer(),
+                        "name": string(),
+                        "systems": array_of("System"),
+                        "software_titles": array_of("SoftwareTitle"),
+                        "file_sets": array_of("FileSetDetails"),
+                        "release_date": {
+                            "type": "string",
+                            "nullable": true,
+                            "description": "YYYY, YYYY-MM or YYYY-MM-DD"
+                        },
+                        "regions": { "type": "array", "items": string() },
+                        "languages": { "type": "array", "items": string() },
+                        "version": { "type": "string", "nullable": true },
+                        "product_code": { "type": "string", "nullable": true },
+                        "barcode": { "type": "string", "nullable": true },
+                        "companies": array_of("ReleaseCompany"),
+                        "tags": {
+                            "type": "array",
+                            "items": string(),
+                            "description": "Tags of the release, subtags are separated with a slash"
+                        }
+                    }),
+                    &["id", "name", "systems", "software_titles", "file_sets", "regions", "languages", "companies", "tags"]
+                ),
+                "ReleaseCompany": object(
+                    json!({
+                        "id": integer(),
+                        "name": string(),
+                        "role": { "type": "string", "enum": ["developer", "publisher", "distributor"] }
+                    }),
+                    &["id", "name", "role"]
+                ),
+                "FileSet": object(
+                    json!({ "id": integer(), "name": string(), "file_type": string() }),
+                    &["id", "name", "file_type"]
+                ),
+                "FileSetDetails": object(
+                    json!({
+                        "id": integer(),
+                        "name": string(),
+                        "file_type": string(),
+                        "files": array_of("File")
+                    }),
+                    &["id", "name", "file_type", "files"]
+                ),
+                "File": object(
+                    json!({
+                        "name": string(),
+                        "sha1_checksum": string(),
+                        "size": integer(),
+                        "ordinal": integer(),
+                        "is_entry_point": boolean()
+                    }),
+                    &["name", "sha1_checksum", "size", "ordinal", "is_entry_point"]
+                ),
+                "RankedEmulator": object(
+                    json!({
+                        "id": integer(),
+                        "name": string(),
+                        "compatibility": {
+                            "nullable": true,
+                            "allOf": [schema("Compatibility")]
+                        }
+                    }),
+                    &["id", "name"]
+                ),
+                "Compatibility": object(
+                    json!({
+                        "status": { "type": "string", "enum": ["works", "minor_issues", "broken"] },
+                        "notes": { "type": "string", "nullable": true },
+                        "tested_at": { "type": "string", "format": "date" }
+                    }),
+                    &["status", "tested_at"]
+                ),
+                "LaunchRequest": object(
+                    json!({
+                        "system_id": integer(),
+                        "emulator_id": integer(),
+                        "file_set_id": integer(),
+                        "file_name": string(),
+                        "playlist": boolean(),
+                        "record": boolean(),
+                        "roles": {
+                            "type": "array",
+                            "description": "File sets launched along with the file set, the roles saved for the release when not given",
+                            "items": schema("FileSetRole")
+                        }
+                    }),
+                    &[]
+                ),
+                "FileSetRole": object(
+                    json!({
+                        "file_set_id": integer(),
+                        "role": {
+                            "type": "string",
+                            "description": "Name the emulator arguments refer to the file set with, e.g. bios for {bios}"
+                        }
+                    }),
+                    &["file_set_id", "role"]
+                ),
+                "ImportRequest": object(
+                    json!({
+                        "path": string(),
+                        "file_type": {
+                            "type": "string",
+                            "description": "File type directory name, e.g. rom, disk_image or tape_image"
+                        },
+                        "system_ids": { "type": "array", "items": integer() },
+                        "name": string(),
+                        "file_names": { "type": "array", "items": string() }
+                    }),
+                    &["path", "file_type", "system_ids"]
+                ),
+                "ExportRequest": object(
+                    json!({ "output_dir": string(), "zip": boolean() }),
+                    &["output_dir"]
+                ),
+                "Job": object(
+                    json!({
+                        "id": integer(),
+                        "kind": { "type": "string", "enum": ["launch", "import", "export"] },
+                        "description": string(),
+                        "status": { "type": "string", "enum": ["running", "finished", "failed"] },
+                        "result": { "type": "object", "description": "Launch, import or export result when finished" },
+                        "error": { "type": "string", "description": "Error message when failed" }
+                    }),
+                    &["id", "kind", "description", "status"]
+                ),
+                "Error": object(json!({ "error": string() }), &["error"])
+            }
+        }
+    })
+}
+
+fn operation(
+    operation_id: &str,
+    summary: &str,
+    parameters: Vec<Value>,
+    request_body: Option<&str>,
+    response_schema: Value,
+) -> Value {
+    let mut operation = json!({
+        "operationId": operation_id,
+        "summary": summary,
+        "parameters": parameters,
+        "responses": {
+            "200": json_content("OK", response_schema),
+            "400": error_response("Invalid request"),
+            "401": error_response("Missing or invalid token"),
+            "404": error_response("Not found")
+        }
+    });
+    if let Some(request_body) = request_body {
+        operation["requestBody"] = json!({
+            "required": true,
+            "content": { "application/json": { "schema": schema(request_body) } }
+        });
+    }
+    operation
+}
+
+fn job_operation(
+    operation_id: &str,
+    summary: &str,
+    parameters: Vec<Value>,
+    request_body: &str,
+) -> Value {
+    accepted(operation(
+        operation_id,
+        summary,
+        parameters,
+        Some(request_body),
+        schema("Job"),
+    ))
+}
+
+fn accepted(mut operation: Value) -> Value {
+    let responses = operation["responses"].as_object_mut().unwrap();
+    let accepted = responses.remove("200").unwrap();
+    responses.insert("202".to_string(), accepted);
+    operation
+}
+
+fn conflict_operation(mut operation: Value, description: &str) -> Value {
+    operation["responses"]["409"] = error_response(description);
+    operation
+}
+
+fn json_content(description: &str, schema: Value) -> Value {
+    json!({
+        "description": description,
+        "content": { "application/json": { "schema": schema } }
+    })
+}
+
+fn error_response(description: &str) -> Value {
+    json_content(description, schema("Error"))
+}
+
+fn id_param() -> Value {
+    json!({ "name": "id", "in": "path", "required": true, "schema": integer() })
+}
+
+fn query_param(name: &str) -> Value {
+    json!({ "name": name, "in": "query", "required": false, "schema": integer() })
+}
+
+/// Date parameters are `YYYY`, `YYYY-MM` or `YYYY-MM-DD`, company roles `developer`,
+/// `publisher` or `distributor` and release sort keys `name`, `date` or `id`. Lists of ids and
+/// file types are comma separated.
+fn string_query_param(name: &str) -> Value {
+    json!({ "name": name, "in": "query", "required": false, "schema": string() })
+}
+
+fn boolean_query_param(name: &str) -> Value {
+    json!({ "name": name, "in": "query", "required": false, "schema": { "type": "boolean" } })
+}
+
+fn schema(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+fn array_of(name: &str) -> Value {
+    json!({ "type": "array", "items": schema(name) })
+}
+
+fn object(properties: Value, required: &[&str]) -> Value {
+    let mut object = json!({ "type": "object", "properties": properties });
+    if !required.is_empty() {
+        object["required"] = json!(required);
+    }
+    object
+}
+
+fn integer() -> Value {
+    json!({ "type": "integer", "format": "int64" })
+}
+
+fn string() -> Value {
+    json!({ "type": "string" })
+}
+
+fn boolean() -> Value {
+    json!({ "type": "boolean" })
+}
@@ -0,0 +1,558 @@
+use std::path::{Path, PathBuf};
+
+use database::{
+    database_error::DatabaseError,
+    models::{Collection, ReleaseCriterion},
+};
+use emulator_runner::playlist::m3u_playlist_file_name;
+use file_export::FileSetExportModel;
+
+use crate::{
+    error::Error,
+    release_launch::file_set_export_model,
+    view_model_service::ViewModelService,
+    view_models::{CollectionListModel, ReleaseListModel},
+};
+
+/// Playlist written for a collection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectionPlaylist {
+    pub path: PathBuf,
+    /// Releases in the playlist, in the order of the collection.
+    pub releases: Vec<String>,
+    /// Releases left out because they have no file set.
+    pub skipped_releases: Vec<String>,
+}
+
+impl ViewModelService {
+    /// Favorites first, other collections ordered by name.
+    pub async fn get_collection_list_models(&self) -> Result<Vec<CollectionListModel>, Error> {
+        let collection_repository = self.repository_manager.get_collection_repository();
+        let collections = collection_repository
+            .get_all_collections()
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+
+        let mut list_models = vec![];
+        for collection in collections {
+            let release_count = collection_repository
+                .get_collection_release_count(collection.id)
+                .await
+                .map_err(|err| Error::DbError(err.to_string()))?
+                as usize;
+            list_models.push(CollectionListModel {
+                id: collection.id,
+                name: collection.name,
+                is_favorites: collection.is_favorites,
+                is_smart: collection.is_smart,
+                release_count,
+            });
+        }
+        Ok(list_models)
+    }
+
+    /// Releases of the collection in the order of the collection, releases of a smart collection
+    /// are ordered by name.
+    pub async fn get_collection_release_list_models(
+        &self,
+        collection_id: i64,
+    ) -> Result<Vec<ReleaseListModel>, Error> {
+        self.get_collection(collection_id).await?;
+        let releases = self
+            .repository_manager
+            .get_collection_repository()
+            .get_collection_releases(collection_id)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+        self.to_release_list_models(releases).await
+    }
+
+    pub async fn add_collection(&self, name: &str) -> Result<i64, Error> {
+        self.repository_manager
+            .get_collection_repository()
+            .add_collection(name)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))
+    }
+
+    pub async fn add_smart_collection(
+        &self,
+        name: &str,
+        criteria: &[ReleaseCriterion],
+    ) -> Result<i64, Error> {
+        self.repository_manager
+            .get_collection_repository()
+            .add_smart_collection(name, criteria)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))
+    }
+
+    pub async fn get_collection_criteria(
+        &self,
+        collection_id: i64,
+    ) -> Result<Vec<ReleaseCriterion>, Error> {
+        self.get_collection(collection_id).await?;
+        self.repository_manager
+            .get_collection_repository()
+            .get_collection_criteria(collection_id)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))
+    }
+
+    pub async fn set_collection_criteria(
+        &self,
+        collection_id: i64,
+        criteria: &[ReleaseCriterion],
+    ) -> Result<(), Error> {
+        if !self.get_collection(collection_id).await?.is_smart {
+            return Err(Error::InvalidSelection(format!(
+                "Collection {} is not a smart collection",
+                collection_id
+            )));
+        }
+        self.repository_manager
+            .get_collection_repository()
+            .set_collection_criteria(collection_id, criteria)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))
+    }
+
+    pub async fn rename_collection(&self, collection_id: i64, name: &str) -> Result<(), Error> {
+        self.check_not_favorites(collection_id).await?;
+        self.repository_manager
+            .get_collection_repository()
+            .rename_collection(collection_id, name)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))
+    }
+
+    pub async fn delete_collection(&self, collection_id: i64) -> Result<(), Error> {
+        self.check_not_favorites(collection_id).await?;
+        self.repository_manager
+            .get_collection_repository()
+            .delete_collection(collection_id)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))
+    }
+
+    pub async fn add_release_to_collection(
+        &self,
+        collection_id: i64,
+        release_id: i64,
+    ) -> Result<(), Error> {
+        self.check_not_smart(collection_id).await?;
+        self.repository_manager
+            .get_release_repository()
+            .get_release(release_id)
+            .await
+            .map_err(|err| not_found_or_db_error(err, format!("Release {}", release_id)))?;
+        self.repository_manager
+            .get_collection_repository()
+            .add_release_to_collection(collection_id, release_id)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))
+    }
+
+    pub async fn remove_release_from_collection(
+        &self,
+        collection_id: i64,
+        release_id: i64,
+    ) -> Result<(), Error> {
+        self.check_not_smart(collection_id).await?;
+        self.repository_manager
+            .get_collection_repository()
+            .remove_release_from_collection(collection_id, release_id)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))
+    }
+
+    /// Moves the release to the zero based position in the collection.
+    pub async fn move_release_in_collection(
+        &self,
+        collection_id: i64,
+        release_id: i64,
+        position: usize,
+    ) -> Result<(), Error> {
+        self.check_not_smart(collection_id).await?;
+        self.repository_manager
+            .get_collection_repository()
+            .move_release_in_collection(collection_id, release_id, position)
+            .await
+            .map_err(|err| {
+                not_found_or_db_error(
+                    err,
+                    format!("Release {} in collection {}", release_id, collection_id),
+                )
+            })
+    }
+
+    /// Adds the release to the favorites, or removes it if it's a favorite already. Returns
+    /// whether the release is a favorite.
+    pub async fn toggle_favorite(&self, release_id: i64) -> Result<bool, Error> {
+        let collection_repository = self.repository_manager.get_collection_repository();
+        let favorites = collection_repository
+            .get_favorites_collection()
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+        let is_favorite = collection_repository
+            .get_collections_by_release(release_id)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?
+            .iter()
+            .any(|collection| collection.is_favorites);
+        if is_favorite {
+            self.remove_release_from_collection(favorites.id, release_id)
+                .await?;
+        } else {
+            self.add_release_to_collection(favorites.id, release_id)
+                .await?;
+        }
+        Ok(!is_favorite)
+    }
+
+    /// Exports the first file set of each release in the collection as a zip archive to the
+    /// output directory and writes an extended m3u playlist of the archives named after the
+    /// collection, entries are titled with the release names. Nothing is exported when two
+    /// releases would be exported as the same archive.
+    pub async fn export_collection_playlist(
+        &self,
+        collection_id: i64,
+        collection_root_dir: &Path,
+        output_dir: &Path,
+    ) -> Result<CollectionPlaylist, Error> {
+        let collection = self.get_collection(collection_id).await?;
+        let releases = self
+            .repository_manager
+            .get_collection_repository()
+            .get_collection_releases(collection_id)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+
+        // archives of all releases are checked before exporting, releases exporting the same
+        // archive would overwrite each other
+        let mut exports: Vec<(String, FileSetExportModel)> = vec![];
+        let mut skipped_releases = vec![];
+        for release in releases {
+            let release = self.get_release_view_model(release.id).await?;
+            let Some(file_set) = release.file_sets.first() else {
+                skipped_releases.push(release.name);
+                continue;
+            };
+            let mut export_model =
+                file_set_export_model(file_set, collection_root_dir, output_dir, false)?;
+            // frontends pick the emulator core by the file extension
+            if !export_model
+                .exported_zip_file_name
+                .to_lowercase()
+                .ends_with(".zip")
+            {
+                export_model.exported_zip_file_name.push_str(".zip");
+            }
+            if let Some((other_release, _)) = exports.iter().find(|(_, other)| {
+                other
+                    .exported_zip_file_name
+                    .eq_ignore_ascii_case(&export_model.exported_zip_file_name)
+            }) {
+                return Err(Error::InvalidSelection(format!(
+                    "Releases {} and {} are both exported as {}",
+                    other_release, release.name, export_model.exported_zip_file_name
+                )));
+            }
+            exports.push((release.name, export_model));
+        }
+        std::fs::create_dir_all(output_dir).map_err(|err| {
+            Error::ExportError(format!("Failed creating {}: {}", output_dir.display(), err))
+        })?;
+
+        let mut content = String::from("#EXTM3U\n");
+        let mut exported_releases = vec![];
+        for (release_name, export_model) in exports {
+            file_export::export_files_zipped_or_non_zipped(&export_model)
+                .map_err(|err| Error::ExportError(err.to_string()))?;
+            content.push_str(&format!(
+                "#EXTINF:-1,{}\n{}\n",
+                release_name, export_model.exported_zip_file_name
+            ));
+            exported_releases.push(release_name);
+        }
+
+        let path = output_dir.join(m3u_playlist_file_name(&collection.name));
+        std::fs::write(&path, content).map_err(|err| {
+            Error::ExportError(format!("Failed writing {}: {}", path.display(), err))
+        })?;
+        Ok(CollectionPlaylist {
+            path,
+            releases: exported_releases,
+            skipped_releases,
+        })
+    }
+
+    async fn get_collection(&self, collection_id: i64) -> Result<Collection, Error> {
+        self.repository_manager
+            .get_collection_repository()
+            .get_collection(collection_id)
+            .await
+            .map_err(|err| not_found_or_db_error(err, format!("Collection {}", collection_id)))
+    }
+
+    async fn check_not_favorites(&self, collection_id: i64) -> Result<(), Error> {
+        if self.get_collection(collection_id).await?.is_favorites {
+            return Err(Error::InvalidSelection(
+                "Favorites collection can't be renamed or deleted".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn check_not_smart(&self, collection_id: i64) -> Result<(), Error> {
+        if self.get_collection(collection_id).await?.is_smart {
+            return Err(Error::InvalidSelection(
+                "Releases of a smart collection are picked by its criteria".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn not_found_or_db_error(err: DatabaseError, entity: String) -> Error {
+    if err.is_not_found() {
+        Error::NotFound(entity)
+    } else {
+        Error::DbError(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use database::{repository_manager::RepositoryManager, setup_test_db};
+    use tempfile::tempdir;
+
+    use crate::file_set_operations::FileSetImportRequest;
+
+    use super::*;
+
+    #[async_std::test]
+    async fn test_collections_and_playlist_export() {
+        let temp_dir = tempdir().unwrap();
+        let collection_root_dir = temp_dir.path().join("collection");
+        let pool = Arc::new(setup_test_db().await);
+        let repository_manager = Arc::new(RepositoryManager::new(pool));
+        let view_model_service = ViewModelService::new(Arc::clone(&repository_manager));
+        let system_id = repository_manager
+            .get_system_repository()
+            .add_system(&"Commodore 64".to_string())
+            .await
+            .unwrap();
+
+        let release_repository = repository_manager.get_release_repository();
+        let mut release_ids = vec![];
+        let mut file_set_ids = vec![];
+        for name in ["Boulder Dash", "Elite"] {
+            let file_path = temp_dir.path().join(format!("{}.d64", name));
+            std::fs::write(&file_path, name).unwrap();
+            let file_set = view_model_service
+                .import_file_set(
+                    &FileSetImportRequest {
+                        path: file_path,
+                        file_type: core_types::FileType::DiskImage,
+                        system_ids: vec![system_id],
+                        name: Some(name.to_string()),
+                        file_names: vec![],
+                    },
+                    &collection_root_dir,
+                )
+                .await
+                .unwrap();
+            let software_title_id = repository_manager
+                .get_software_title_repository()
+                .add_software_title(&name.to_string(), None)
+                .await
+                .unwrap();
+            let release_id = release_repository
+                .add_release_full(
+                    name.to_string(),
+                    vec![software_title_id],
+                    vec![file_set.id],
+                    vec![system_id],
+                )
+                .await
+                .unwrap();
+            release_ids.push(release_id);
+            file_set_ids.push(file_set.id);
+        }
+        let incomplete_release_id = release_repository.add_release("Sentinel").await.unwrap();
+
+        let collection_id = view_model_service.add_collection("Classics").await.unwrap();
+        for release_id in [release_ids[0], incomplete_release_id, release_ids[1]] {
+            view_model_service
+                .add_release_to_collection(collection_id, release_id)
+                .await
+                .unwrap();
+        }
+        view_model_service
+            .move_release_in_collection(collection_id, release_ids[1], 0)
+            .await
+            .unwrap();
+        assert!(matches!(
+            view_model_service
+                .add_release_to_collection(collection_id, 999)
+                .await,
+            Err(Error::NotFound(_))
+        ));
+
+        let releases = view_model_service
+            .get_collection_release_list_models(collection_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            releases
+                .iter()
+                .map(|release| release.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Elite", "Boulder Dash", "Sentinel"]
+        );
+        assert_eq!(releases[0].system_names, vec!["Commodore 64"]);
+
+        assert!(view_model_service
+            .toggle_favorite(release_ids[0])
+            .await
+            .unwrap());
+        let collections = view_model_service
+            .get_collection_list_models()
+            .await
+            .unwrap();
+        assert_eq!(
+            collections
+                .iter()
+                .map(|collection| (collection.name.as_str(), collection.release_count))
+                .collect::<Vec<_>>(),
+            vec![("Favorites", 1), ("Classics", 3)]
+        );
+        assert!(matches!(
+            view_model_service
+                .delete_collection(collections[0].id)
+                .await,
+            Err(Error::InvalidSelection(_))
+        ));
+        assert!(!view_model_service
+            .toggle_favorite(release_ids[0])
+            .await
+            .unwrap());
+
+        let output_dir = temp_dir.path().join("playlists");
+        let playlist = view_model_service
+            .export_collection_playlist(collection_id, &collection_root_dir, &output_dir)
+            .await
+            .unwrap();
+        assert_eq!(playlist.path, output_dir.join("Classics.m3u"));
+        assert_eq!(playlist.releases, vec!["Elite", "Boulder Dash"]);
+        assert_eq!(playlist.skipped_releases, vec!["Sentinel"]);
+        assert_eq!(
+            std::fs::read_to_string(&playlist.path).unwrap(),
+            "#EXTM3U\n#EXTINF:-1,Elite\nElite.zip\n#EXTINF:-1,Boulder Dash\nBoulder Dash.zip\n"
+        );
+        assert!(output_dir.join("Elite.zip").exists());
+
+        // playlist name is the collection name as a file name in the output directory
+        view_model_service
+            .rename_collection(collection_id, "Best of 1.5/../2")
+            .await
+            .unwrap();
+        let playlist = view_model_service
+            .export_collection_playlist(collection_id, &collection_root_dir, &output_dir)
+            .await
+            .unwrap();
+        assert_eq!(playlist.path, output_dir.join("Best of 1.5_.._2.m3u"));
+
+        // releases exported as the same archive would overwrite each other
+        let copy_release_id = release_repository
+            .add_release_full(
+                "Elite (copy)".to_string(),
+                vec![],
+                vec![file_set_ids[1]],
+                vec![],
+            )
+            .await
+            .unwrap();
+        view_model_service
+            .add_release_to_collection(collection_id, copy_release_id)
+            .await
+            .unwrap();
+        let copy_output_dir = temp_dir.path().join("copies");
+        assert!(matches!(
+            view_model_service
+                .export_collection_playlist(collection_id, &collection_root_dir, &copy_output_dir)
+                .await,
+            Err(Error::InvalidSelection(_))
+        ));
+        assert!(!copy_output_dir.exists());
+        view_model_service
+            .remove_release_from_collection(collection_id, copy_release_id)
+            .await
+            .unwrap();
+
+        let smart_collection_id = view_model_service
+            .add_smart_collection("Disks", &[ReleaseCriterion::System(system_id)])
+            .await
+            .unwrap();
+        let releases = view_model_service
+            .get_collection_release_list_models(smart_collection_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            releases
+                .iter()
+                .map(|release| release.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Boulder Dash", "Elite"]
+        );
+        assert!(matches!(
+            view_model_service
+                .add_release_to_collection(smart_collection_id, incomplete_release_id)
+                .await,
+            Err(Error::InvalidSelection(_))
+        ));
+        let criteria = vec![
+            ReleaseCriterion::System(system_id),
+            ReleaseCriterion::NameContains("elite".to_string()),
+        ];
+        view_model_service
+            .set_collection_criteria(smart_collection_id, &criteria)
+            .await
+            .unwrap();
+        assert_eq!(
+            view_model_service
+                .get_collection_criteria(smart_collection_id)
+                .await
+                .unwrap(),
+            criteria
+        );
+        assert!(matches!(
+            view_model_service
+                .set_collection_criteria(collection_id, &criteria)
+                .await,
+            Err(Error::InvalidSelection(_))
+        ));
+        let collections = view_model_service
+            .get_collection_list_models()
+            .await
+            .unwrap();
+        assert_eq!(
+            collections
+                .iter()
+                .map(|collection| (
+                    collection.name.as_str(),
+                    collection.is_smart,
+                    collection.release_count
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Favorites", false, 0),
+                ("Best of 1.5/../2", false, 3),
+                ("Disks", true, 1)
+            ]
+        );
+    }
+}
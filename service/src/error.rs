@@ -3,12 +3,26 @@ use std::fmt::{Display, Formatter, Result};
 #[derive(Debug, Clone)]
 pub enum Error {
     DbError(String),
+    EmulatorError(String),
+    /// Launch choices that couldn't be resolved or don't belong together.
+    InvalidSelection(String),
+    /// Search query with a syntax error, the message tells where.
+    InvalidQuery(String),
+    NotFound(String),
+    ImportError(String),
+    ExportError(String),
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         match self {
             Error::DbError(message) => write!(f, "Database error: {}", message),
+            Error::EmulatorError(message) => write!(f, "Emulator error: {}", message),
+            Error::InvalidSelection(message) => write!(f, "Invalid selection: {}", message),
+            Error::InvalidQuery(message) => write!(f, "Invalid query: {}", message),
+            Error::NotFound(message) => write!(f, "Not found: {}", message),
+            Error::ImportError(message) => write!(f, "Import error: {}", message),
+            Error::ExportError(message) => write!(f, "Export error: {}", message),
         }
     }
 }
@@ -0,0 +1,241 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use core_types::{FileType, ImportedFile, Sha1Checksum};
+use emulator_runner::playlist::natural_cmp;
+use file_export::{export_files_zipped_or_non_zipped, get_export_plan, ExportPlan};
+use file_import::FileImportModel;
+use utils::file_util;
+
+use crate::{
+    error::Error, release_launch::file_set_export_model, view_model_service::ViewModelService,
+};
+
+/// File, or files from a zip archive, to import into a new file set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileSetImportRequest {
+    pub path: PathBuf,
+    pub file_type: FileType,
+    pub system_ids: Vec<i64>,
+    /// File set name, defaults to the file name.
+    pub name: Option<String>,
+    /// Files to import from a zip archive, all files are imported when empty.
+    pub file_names: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedFileSet {
+    pub id: i64,
+    pub name: String,
+    /// Files written to the collection.
+    pub imported_files: Vec<String>,
+    /// Files already in the collection, added to the file set as they are.
+    pub existing_files: Vec<String>,
+}
+
+impl ViewModelService {
+    /// Imports the files into the collection and adds them to a new file set. Files already in
+    /// the collection are found by checksum and not imported again.
+    ///
+    /// # Errors
+    ///
+    /// * `Error::NotFound`: If a selected file is not in the zip archive.
+    /// * `Error::InvalidSelection`: If there are no files to import.
+    /// * `Error::ImportError`: If the files can't be read or written to the collection.
+    pub async fn import_file_set(
+        &self,
+        request: &FileSetImportRequest,
+        collection_root_dir: &Path,
+    ) -> Result<ImportedFileSet, Error> {
+        let path = &request.path;
+        let is_zip_file = file_util::is_zip_file(path).map_err(|err| {
+            Error::ImportError(format!("Failed reading {}: {}", path.display(), err))
+        })?;
+        let mut read_files = if is_zip_file {
+            file_import::read_zip_contents_with_checksums(path.clone())
+        } else {
+            file_import::read_file_checksum(path.clone())
+        }
+        .map_err(|err| Error::ImportError(err.to_string()))?;
+        if !request.file_names.is_empty() {
+            if let Some(file_name) = request
+                .file_names
+                .iter()
+                .find(|file_name| !read_files.values().any(|f| &f.file_name == *file_name))
+            {
+                return Err(Error::NotFound(format!(
+                    "{} in {}",
+                    file_name,
+                    path.display()
+                )));
+            }
+            read_files.retain(|_, read_file| request.file_names.contains(&read_file.file_name));
+        }
+        if read_files.is_empty() {
+            return Err(Error::InvalidSelection(format!(
+                "No files to import in {}",
+                path.display()
+            )));
+        }
+
+        let existing_files = self
+            .repository_manager
+            .get_file_info_repository()
+            .get_file_infos_by_sha1_checksums(read_files.keys().cloned().collect())
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?
+            .into_iter()
+            .filter_map(|file_info| {
+                let checksum: Sha1Checksum = file_info.sha1_checksum.clone().try_into().ok()?;
+                let read_file = read_files.get(&checksum)?;
+                Some((
+                    checksum,
+                    ImportedFile {
+                        original_file_name: read_file.file_name.clone(),
+                        archive_file_name: file_info.archive_file_name,
+                        sha1_checksum: checksum,
+                        file_size: file_info.file_size,
+                    },
+                ))
+            })
+            .collect::<HashMap<Sha1Checksum, ImportedFile>>();
+
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "unknown_file".to_string());
+        let new_files = read_files
+            .iter()
+            .filter(|(checksum, _)| !existing_files.contains_key(*checksum))
+            .map(|(_, read_file)| read_file.file_name.clone())
+            .collect::<Vec<_>>();
+        let imported_files = if new_files.is_empty() {
+            HashMap::new()
+        } else {
+            file_import::import(&FileImportModel {
+                file_path: path.clone(),
+                output_dir: collection_root_dir.join(request.file_type.dir_name()),
+                file_name: file_name.clone(),
+                file_type: request.file_type,
+                file_name_filter: new_files.into_iter().collect(),
+                is_zip_file,
+            })
+            .map_err(|err| Error::ImportError(err.to_string()))?
+        };
+
+        let mut imported_file_names = imported_files
+            .values()
+            .map(|file| file.original_file_name.clone())
+            .collect::<Vec<_>>();
+        imported_file_names.sort_by(|a, b| natural_cmp(a, b));
+        let mut existing_file_names = existing_files
+            .values()
+            .map(|file| file.original_file_name.clone())
+            .collect::<Vec<_>>();
+        existing_file_names.sort_by(|a, b| natural_cmp(a, b));
+
+        let file_set_name = request.name.clone().unwrap_or(file_name);
+        let files_in_file_set = imported_files
+            .into_values()
+            .chain(existing_files.into_values())
+            .collect();
+        let id = self
+            .repository_manager
+            .get_file_set_repository()
+            .add_file_set(
+                file_set_name.clone(),
+                request.file_type.into(),
+                files_in_file_set,
+                &request.system_ids,
+            )
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+
+        Ok(ImportedFileSet {
+            id,
+            name: file_set_name,
+            imported_files: imported_file_names,
+            existing_files: existing_file_names,
+        })
+    }
+
+    /// Exports the files of the file set to the output directory, extracted or written into a
+    /// zip archive.
+    pub async fn export_file_set(
+        &self,
+        file_set_id: i64,
+        collection_root_dir: &Path,
+        output_dir: &Path,
+        zip: bool,
+    ) -> Result<ExportPlan, Error> {
+        let file_set = self.get_file_set_view_model(file_set_id).await?;
+        let export_model = file_set_export_model(&file_set, collection_root_dir, output_dir, !zip)?;
+        std::fs::create_dir_all(output_dir).map_err(|err| {
+            Error::ExportError(format!("Failed creating {}: {}", output_dir.display(), err))
+        })?;
+        export_files_zipped_or_non_zipped(&export_model)
+            .map_err(|err| Error::ExportError(err.to_string()))?;
+        Ok(get_export_plan(&export_model))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use database::{repository_manager::RepositoryManager, setup_test_db};
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[async_std::test]
+    async fn test_import_and_export_file_set() {
+        let temp_dir = tempdir().unwrap();
+        let collection_root_dir = temp_dir.path().join("collection");
+        let pool = Arc::new(setup_test_db().await);
+        let repository_manager = Arc::new(RepositoryManager::new(pool));
+        let view_model_service = ViewModelService::new(Arc::clone(&repository_manager));
+        let system_id = repository_manager
+            .get_system_repository()
+            .add_system(&"Commodore 64".to_string())
+            .await
+            .unwrap();
+        let file_path = temp_dir.path().join("game.d64");
+        std::fs::write(&file_path, "disk image").unwrap();
+
+        let request = FileSetImportRequest {
+            path: file_path.clone(),
+            file_type: FileType::DiskImage,
+            system_ids: vec![system_id],
+            name: Some("Game".to_string()),
+            file_names: vec![],
+        };
+        let file_set = view_model_service
+            .import_file_set(&request, &collection_root_dir)
+            .await
+            .unwrap();
+        assert_eq!(file_set.name, "Game");
+        assert_eq!(file_set.imported_files, vec!["game.d64".to_string()]);
+
+        let output_dir = temp_dir.path().join("export");
+        let export_plan = view_model_service
+            .export_file_set(file_set.id, &collection_root_dir, &output_dir, true)
+            .await
+            .unwrap();
+        let zip_file_path = export_plan.zip_file_path.unwrap();
+        assert!(zip_file_path.exists());
+
+        let result = view_model_service
+            .import_file_set(
+                &FileSetImportRequest {
+                    file_names: vec!["other.d64".to_string()],
+                    ..request
+                },
+                &collection_root_dir,
+            )
+            .await;
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+}
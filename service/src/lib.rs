@@ -1,3 +1,6 @@
+pub mod collection_operations;
 pub mod error;
+pub mod file_set_operations;
+pub mod release_launch;
 pub mod view_model_service;
 pub mod view_models;
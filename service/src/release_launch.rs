@@ -0,0 +1,741 @@
+use std::{collections::HashMap, path::Path};
+
+use core_types::Sha1Checksum;
+use database::models::FileSetLaunchRole;
+use emulator_runner::{
+    config_template::RenderedConfig,
+    error::EmulatorRunnerError,
+    executable::EmulatorExecutable,
+    launch_plan::{is_valid_role, run_launch_plan, FileSetPlacement, LaunchPlan},
+    playlist::natural_cmp,
+    preview::{preview_launch, LaunchPreview},
+    process_registry::{LaunchHandle, LaunchId, ProcessRegistry},
+    LaunchOptions,
+};
+use file_export::{FileSetExportModel, OutputFile};
+
+use crate::{error::Error, view_model_service::ViewModelService, view_models::FileSetViewModel};
+
+/// Release to launch. Choices left `None` are resolved with
+/// `ViewModelService::get_release_launch_selection`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReleaseLaunchRequest {
+    pub release_id: i64,
+    pub system_id: Option<i64>,
+    pub emulator_id: Option<i64>,
+    pub file_set_id: Option<i64>,
+    /// File of the file set the emulator is started with.
+    pub file_name: Option<String>,
+    /// Launch an m3u playlist of all the files in the file set.
+    pub playlist: bool,
+    /// File sets launched along with the file set by their role, the roles saved for the release
+    /// when `None`.
+    pub file_set_roles: Option<Vec<FileSetLaunchRole>>,
+}
+
+/// Role of the launched file set when it has no saved role, e.g. `{game}` in arguments.
+pub const DEFAULT_LAUNCH_ROLE: &str = "game";
+
+/// Release launch resolved from a `ReleaseLaunchRequest`, nothing has been written yet.
+#[derive(Debug)]
+pub struct PreparedReleaseLaunch {
+    pub release_id: i64,
+    pub system_id: i64,
+    pub system_name: String,
+    pub emulator_id: i64,
+    pub emulator_name: String,
+    pub file_set_name: String,
+    /// File or playlist the emulator is started with.
+    pub launched_file: String,
+    pub executable: EmulatorExecutable,
+    /// Argument template of the emulator for the system.
+    pub argument_template: String,
+    /// Emulator arguments after the entry point rendered from the template, one argv word each.
+    pub arguments: Vec<String>,
+    pub launch_options: LaunchOptions,
+    /// The launched file set, file sets with a role, firmware and configuration.
+    pub plan: LaunchPlan,
+}
+
+impl PreparedReleaseLaunch {
+    /// Exports the file sets and the firmware to the launch directory and writes the emulator
+    /// configuration.
+    pub fn export(&self) -> Result<(), EmulatorRunnerError> {
+        self.plan.export()
+    }
+
+    /// Resolves what running the launch would do without exporting or running anything, see
+    /// `emulator_runner::preview::preview_launch`. The export plan is the one of the launched
+    /// file set.
+    pub fn preview(&self) -> Result<LaunchPreview, EmulatorRunnerError> {
+        let launched_file_set = self
+            .plan
+            .file_sets
+            .first()
+            .ok_or(EmulatorRunnerError::NoFileSelected)?;
+        let (file_names, launch_mode) = self.plan.launch_files()?;
+        preview_launch(
+            &launched_file_set.export_model,
+            &self.executable,
+            &self.arguments,
+            &file_names,
+            &launch_mode,
+            &self.launch_options,
+        )
+    }
+
+    /// Registers the launch directory in the registry, the directory is in use until the
+    /// registered launch has finished or is dropped.
+    ///
+    /// # errors
+    /// * `EmulatorRunnerError::LaunchDirectoryInUse`: If a running launch uses the launch
+    ///   directory.
+    pub fn register(
+        self,
+        process_registry: &ProcessRegistry,
+    ) -> Result<RegisteredReleaseLaunch, EmulatorRunnerError> {
+        let launch_handle = process_registry.register_launch(self.plan.launch_dir.clone())?;
+        Ok(RegisteredReleaseLaunch {
+            launch: self,
+            launch_handle,
+        })
+    }
+
+    /// Registers the launch, exports the files and runs the emulator, see `register` and
+    /// `RegisteredReleaseLaunch::run`.
+    pub async fn run(self, process_registry: &ProcessRegistry) -> Result<(), EmulatorRunnerError> {
+        self.register(process_registry)?.run().await
+    }
+}
+
+/// Release launch registered in a `ProcessRegistry`, the launch can be stopped through the
+/// registry with its launch id once the emulator is running.
+#[derive(Debug)]
+pub struct RegisteredReleaseLaunch {
+    pub launch: PreparedReleaseLaunch,
+    launch_handle: LaunchHandle,
+}
+
+impl RegisteredReleaseLaunch {
+    pub fn launch_id(&self) -> LaunchId {
+        self.launch_handle.launch_id()
+    }
+
+    /// Exports the files and runs the emulator, see
+    /// `emulator_runner::launch_plan::run_launch_plan`. The launch is unregistered when the
+    /// emulator has exited.
+    pub async fn run(self) -> Result<(), EmulatorRunnerError> {
+        let launch = self.launch;
+        run_launch_plan(
+            &launch.plan,
+            launch.executable,
+            &launch.argument_template,
+            launch.launch_options,
+            Some(self.launch_handle),
+        )
+        .await
+    }
+}
+
+impl ViewModelService {
+    /// Resolves the system, file set, entry file and emulator for launching the release and
+    /// plans exporting the file set, the file sets with a role, the emulator firmware and
+    /// configuration to the launch directory.
+    ///
+    /// The launched file set is exported directly into the launch directory and the file sets
+    /// with a role into subdirectories named after the role, see `LaunchPlan`.
+    ///
+    /// # errors
+    /// * `Error::InvalidSelection`: If a choice couldn't be resolved or doesn't belong to the
+    ///   release, a role is invalid or used twice, or required firmware is missing.
+    /// * `Error::EmulatorError`: If the emulator configuration or arguments can't be rendered.
+    pub async fn prepare_release_launch(
+        &self,
+        request: &ReleaseLaunchRequest,
+    ) -> Result<PreparedReleaseLaunch, Error> {
+        let selection = self
+            .get_release_launch_selection(request.release_id)
+            .await?;
+        let release = self.get_release_view_model(request.release_id).await?;
+        let settings = self.get_settings().await?;
+
+        let system_id = request
+            .system_id
+            .or(selection.system_id)
+            .ok_or_else(|| Error::InvalidSelection("System couldn't be resolved".to_string()))?;
+        let system = release
+            .systems
+            .iter()
+            .find(|system| system.id == system_id)
+            .ok_or_else(|| {
+                Error::InvalidSelection(format!("System {} is not in the release", system_id))
+            })?;
+        let file_set_id = request
+            .file_set_id
+            .or(selection.file_set_id)
+            .ok_or_else(|| Error::InvalidSelection("File set couldn't be resolved".to_string()))?;
+        let file_set = release
+            .file_sets
+            .iter()
+            .find(|file_set| file_set.id == file_set_id)
+            .ok_or_else(|| {
+                Error::InvalidSelection(format!("File set {} is not in the release", file_set_id))
+            })?;
+        let emulator_id = request
+            .emulator_id
+            .or(selection.emulator_id)
+            .ok_or_else(|| Error::InvalidSelection("Emulator couldn't be resolved".to_string()))?;
+        let emulator = self.get_emulator_view_model(emulator_id).await?;
+        let emulator_system = emulator
+            .systems
+            .iter()
+            .find(|emulator_system| emulator_system.system_id == system_id)
+            .ok_or_else(|| {
+                Error::InvalidSelection(format!(
+                    "{} is not configured for {}",
+                    emulator.name, system.name
+                ))
+            })?;
+
+        let firmware = self.get_emulator_firmware(emulator_id, system_id).await?;
+        let missing_firmware = firmware
+            .iter()
+            .filter(|firmware| firmware.required && firmware.is_missing())
+            .map(|firmware| firmware.file_set.file_set_name.clone())
+            .collect::<Vec<_>>();
+        if !missing_firmware.is_empty() {
+            return Err(Error::InvalidSelection(format!(
+                "Missing required firmware: {}",
+                missing_firmware.join(", ")
+            )));
+        }
+
+        // each file set has its own launch directory, so that a running launch isn't overwritten
+        let launch_dir = std::env::temp_dir()
+            .join("software_collection_manager")
+            .join(file_set.id.to_string());
+        let mut plan = LaunchPlan::new(launch_dir.clone());
+        let export_model = file_set_export_model(
+            file_set,
+            &settings.collection_root_dir,
+            &launch_dir,
+            emulator.extract_files,
+        )?;
+
+        let roles = match &request.file_set_roles {
+            Some(roles) => roles.clone(),
+            None => self.get_file_set_launch_roles(release.id).await?,
+        };
+        let role = roles
+            .iter()
+            .find(|role| role.file_set_id == file_set.id)
+            .map_or(DEFAULT_LAUNCH_ROLE, |role| role.role.as_str());
+        let invalid_role = |err: EmulatorRunnerError| Error::InvalidSelection(err.to_string());
+
+        let (entry_point, launched_file) = if emulator.extract_files && request.playlist {
+            // files are in the persisted order, names only break ties
+            let mut files = file_set.files.clone();
+            files.sort_by(|a, b| {
+                a.ordinal
+                    .cmp(&b.ordinal)
+                    .then_with(|| natural_cmp(&a.file_name, &b.file_name))
+            });
+            let file_names = files
+                .into_iter()
+                .map(|file| file.file_name)
+                .collect::<Vec<_>>();
+            let entry_point = file_names.first().cloned();
+            plan.set_playlist(file_set.file_set_name.clone(), file_names);
+            (entry_point, file_set.file_set_name.clone())
+        } else if emulator.extract_files {
+            let selected_file = match &request.file_name {
+                Some(file_name) => file_set
+                    .files
+                    .iter()
+                    .find(|file| &file.file_name == file_name),
+                None => file_set
+                    .files
+                    .iter()
+                    .find(|file| Some(file.file_info_id) == selection.file_info_id)
+                    .or_else(|| file_set.files.iter().find(|file| file.is_entry_point))
+                    .or(match file_set.files.as_slice() {
+                        [file] => Some(file),
+                        _ => None,
+                    }),
+            };
+            let selected_file = selected_file
+                .ok_or_else(|| Error::InvalidSelection("File couldn't be resolved".to_string()))?;
+            (
+                Some(selected_file.file_name.clone()),
+                selected_file.file_name.clone(),
+            )
+        } else {
+            (None, export_model.exported_zip_file_name.clone())
+        };
+        plan.add_file_set(
+            role,
+            export_model,
+            entry_point,
+            FileSetPlacement::LaunchDirectory,
+        )
+        .map_err(invalid_role)?;
+
+        for role in roles.iter().filter(|role| role.file_set_id != file_set.id) {
+            let role_file_set = release
+                .file_sets
+                .iter()
+                .find(|file_set| file_set.id == role.file_set_id)
+                .ok_or_else(|| {
+                    Error::InvalidSelection(format!(
+                        "File set {} of role {} is not in the release",
+                        role.file_set_id, role.role
+                    ))
+                })?;
+            let entry_point = role_file_set
+                .files
+                .iter()
+                .find(|file| file.is_entry_point)
+                .map(|file| file.file_name.clone());
+            plan.add_file_set(
+                &role.role,
+                file_set_export_model(
+                    role_file_set,
+                    &settings.collection_root_dir,
+                    &launch_dir,
+                    emulator.extract_files,
+                )?,
+                entry_point,
+                FileSetPlacement::Subdirectory,
+            )
+            .map_err(invalid_role)?;
+        }
+
+        // firmware is exported to its target path, relative to the launch directory
+        for firmware in firmware.iter().filter(|firmware| !firmware.is_missing()) {
+            plan.add_firmware(
+                file_set_export_model(
+                    &firmware.file_set,
+                    &settings.collection_root_dir,
+                    &launch_dir,
+                    true,
+                )?,
+                Path::new(&firmware.target_path),
+            )
+            .map_err(|err| Error::EmulatorError(err.to_string()))?;
+        }
+
+        // configuration is rendered into the launch directory and passed with {config}
+        if let Some(config) = self
+            .get_emulator_config_template(emulator_system.id, release.id)
+            .await?
+            .map(|template| {
+                let variables = [
+                    ("system", system.name.clone()),
+                    ("file_set", file_set.file_set_name.clone()),
+                    ("launch_dir", launch_dir.to_string_lossy().to_string()),
+                ];
+                RenderedConfig::render(
+                    &launch_dir,
+                    &template.file_name,
+                    &template.content,
+                    &variables,
+                )
+            })
+            .transpose()
+            .map_err(|err| Error::EmulatorError(err.to_string()))?
+        {
+            plan.set_config(config);
+        }
+        let arguments = plan
+            .render_arguments(&emulator_system.arguments)
+            .map_err(|err| Error::EmulatorError(err.to_string()))?;
+
+        Ok(PreparedReleaseLaunch {
+            release_id: release.id,
+            system_id,
+            system_name: system.name.clone(),
+            emulator_id,
+            emulator_name: emulator.name.clone(),
+            file_set_name: file_set.file_set_name.clone(),
+            launched_file,
+            executable: emulator.emulator_executable(),
+            argument_template: emulator_system.arguments.clone(),
+            arguments,
+            launch_options: emulator.launch_options(),
+            plan,
+        })
+    }
+
+    /// Launch roles saved for the file sets of the release.
+    pub async fn get_file_set_launch_roles(
+        &self,
+        release_id: i64,
+    ) -> Result<Vec<FileSetLaunchRole>, Error> {
+        Ok(self
+            .repository_manager
+            .get_release_repository()
+            .get_file_set_launch_roles(release_id)
+            .await?)
+    }
+
+    /// Saves the role of a file set of the release used when the release is launched, `None`
+    /// clears the role.
+    ///
+    /// # errors
+    /// * `Error::InvalidSelection`: If the role is not a valid name, see
+    ///   `emulator_runner::launch_plan::is_valid_role`.
+    /// * `Error::DbError`: If the file set is not in the release or another file set of the
+    ///   release has the role.
+    pub async fn set_file_set_launch_role(
+        &self,
+        release_id: i64,
+        file_set_id: i64,
+        role: Option<&str>,
+    ) -> Result<(), Error> {
+        if let Some(role) = role.filter(|role| !is_valid_role(role)) {
+            return Err(Error::InvalidSelection(format!(
+                "Invalid role name: '{}'",
+                role
+            )));
+        }
+        self.repository_manager
+            .get_release_repository()
+            .set_file_set_launch_role(release_id, file_set_id, role)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Export model for exporting the files of the file set from the collection to the output
+/// directory.
+pub fn file_set_export_model(
+    file_set: &FileSetViewModel,
+    collection_root_dir: &Path,
+    output_dir: &Path,
+    extract_files: bool,
+) -> Result<FileSetExportModel, Error> {
+    let output_mapping = file_set
+        .files
+        .iter()
+        .map(|file| {
+            let checksum: Sha1Checksum = file.sha1_checksum.clone().try_into().map_err(|_| {
+                Error::DbError(format!("Invalid checksum for file {}", file.file_name))
+            })?;
+            Ok((
+                file.archive_file_name.clone(),
+                OutputFile {
+                    output_file_name: file.file_name.clone(),
+                    checksum,
+                },
+            ))
+        })
+        .collect::<Result<HashMap<String, OutputFile>, Error>>()?;
+
+    Ok(FileSetExportModel {
+        output_mapping,
+        source_file_path: collection_root_dir.join(file_set.file_type.dir_name()),
+        output_dir: output_dir.to_path_buf(),
+        extract_files,
+        exported_zip_file_name: file_set.file_set_name.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use core_types::ImportedFile;
+    use database::{
+        models::{EmulatorLaunchSettings, EmulatorSystemUpdateModel, FileType},
+        repository_manager::RepositoryManager,
+        setup_test_db,
+    };
+
+    use super::*;
+
+    #[async_std::test]
+    async fn test_prepare_release_launch() {
+        let pool = Arc::new(setup_test_db().await);
+        let repository_manager = Arc::new(RepositoryManager::new(pool));
+        let view_model_service = ViewModelService::new(repository_manager.clone());
+        let system_id = repository_manager
+            .get_system_repository()
+            .add_system(&"Commodore 64".to_string())
+            .await
+            .unwrap();
+        let files = ["Game (Disk 1).d64", "Game (Disk 2).d64"]
+            .iter()
+            .enumerate()
+            .map(|(i, file_name)| ImportedFile {
+                original_file_name: file_name.to_string(),
+                archive_file_name: format!("archive_{}", i),
+                sha1_checksum: [i as u8; 20],
+                file_size: 174848,
+            })
+            .collect::<Vec<_>>();
+        let file_set_id = repository_manager
+            .get_file_set_repository()
+            .add_file_set("Game".to_string(), FileType::DiskImage, files, &[system_id])
+            .await
+            .unwrap();
+        let release_id = repository_manager
+            .get_release_repository()
+            .add_release_full(
+                "Game".to_string(),
+                vec![],
+                vec![file_set_id],
+                vec![system_id],
+            )
+            .await
+            .unwrap();
+        let emulator_id = repository_manager
+            .get_emulator_repository()
+            .add_emulator_with_systems(
+                "VICE".to_string(),
+                "x64sc".to_string(),
+                true,
+                vec![EmulatorSystemUpdateModel {
+                    id: None,
+                    system_id,
+                    arguments: "-config {config}".to_string(),
+                }],
+                EmulatorLaunchSettings::default(),
+            )
+            .await
+            .unwrap();
+
+        // the release has two disks and no entry point
+        let request = ReleaseLaunchRequest {
+            release_id,
+            ..Default::default()
+        };
+        assert!(matches!(
+            view_model_service.prepare_release_launch(&request).await,
+            Err(Error::InvalidSelection(_))
+        ));
+
+        let (_, emulator_systems) = repository_manager
+            .get_emulator_repository()
+            .get_emulator_with_systems(emulator_id)
+            .await
+            .unwrap();
+        repository_manager
+            .get_emulator_repository()
+            .set_emulator_config_template(
+                emulator_systems[0].id,
+                None,
+                "vicerc".to_string(),
+                "# {system}".to_string(),
+            )
+            .await
+            .unwrap();
+
+        let launch = view_model_service
+            .prepare_release_launch(&ReleaseLaunchRequest {
+                release_id,
+                playlist: true,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(launch.system_id, system_id);
+        assert_eq!(launch.emulator_id, emulator_id);
+        assert_eq!(launch.launched_file, "Game");
+        assert_eq!(
+            launch.plan.playlist.as_ref().unwrap().file_names,
+            vec![
+                "Game (Disk 1).d64".to_string(),
+                "Game (Disk 2).d64".to_string()
+            ]
+        );
+        let config = launch.plan.config.as_ref().unwrap();
+        assert_eq!(config.content, "# Commodore 64");
+        assert_eq!(
+            launch.arguments,
+            vec![
+                "-config".to_string(),
+                config.path.to_string_lossy().to_string()
+            ]
+        );
+        assert_eq!(launch.plan.file_sets.len(), 1);
+        assert_eq!(launch.plan.file_sets[0].role, DEFAULT_LAUNCH_ROLE);
+        assert_eq!(
+            launch.plan.file_sets[0].export_model.output_mapping.len(),
+            2
+        );
+
+        let launch = view_model_service
+            .prepare_release_launch(&ReleaseLaunchRequest {
+                release_id,
+                file_name: Some("Game (Disk 2).d64".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(launch.launched_file, "Game (Disk 2).d64");
+
+        // the launch directory of the file set is in use until the registered launch is done
+        let process_registry = ProcessRegistry::new();
+        let registered = launch.register(&process_registry).unwrap();
+        let other_launch = view_model_service
+            .prepare_release_launch(&ReleaseLaunchRequest {
+                release_id,
+                file_name: Some("Game (Disk 1).d64".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(matches!(
+            other_launch.run(&process_registry).await,
+            Err(EmulatorRunnerError::LaunchDirectoryInUse(_))
+        ));
+        drop(registered);
+        assert!(process_registry.running_launches().is_empty());
+    }
+
+    #[async_std::test]
+    async fn test_prepare_release_launch_with_roles() {
+        let pool = Arc::new(setup_test_db().await);
+        let repository_manager = Arc::new(RepositoryManager::new(pool));
+        let view_model_service = ViewModelService::new(repository_manager.clone());
+        let system_id = repository_manager
+            .get_system_repository()
+            .add_system(&"Arcade".to_string())
+            .await
+            .unwrap();
+        let mut file_set_ids = Vec::new();
+        for (i, name) in ["Game.bin", "BIOS.bin"].iter().enumerate() {
+            let file_set_id = repository_manager
+                .get_file_set_repository()
+                .add_file_set(
+                    name.to_string(),
+                    FileType::Rom,
+                    vec![ImportedFile {
+                        original_file_name: name.to_string(),
+                        archive_file_name: format!("archive_{}", i),
+                        sha1_checksum: [i as u8; 20],
+                        file_size: 1024,
+                    }],
+                    &[system_id],
+                )
+                .await
+                .unwrap();
+            file_set_ids.push(file_set_id);
+        }
+        let (game_id, bios_id) = (file_set_ids[0], file_set_ids[1]);
+        let release_id = repository_manager
+            .get_release_repository()
+            .add_release_full(
+                "Game".to_string(),
+                vec![],
+                vec![game_id, bios_id],
+                vec![system_id],
+            )
+            .await
+            .unwrap();
+        repository_manager
+            .get_emulator_repository()
+            .add_emulator_with_systems(
+                "MAME".to_string(),
+                "mame".to_string(),
+                true,
+                vec![EmulatorSystemUpdateModel {
+                    id: None,
+                    system_id,
+                    arguments: "-bios {bios}".to_string(),
+                }],
+                EmulatorLaunchSettings::default(),
+            )
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            view_model_service
+                .set_file_set_launch_role(release_id, bios_id, Some("../bios"))
+                .await,
+            Err(Error::InvalidSelection(_))
+        ));
+        assert!(matches!(
+            view_model_service
+                .set_file_set_launch_role(release_id, bios_id, Some("config"))
+                .await,
+            Err(Error::InvalidSelection(_))
+        ));
+        view_model_service
+            .set_file_set_launch_role(release_id, bios_id, Some("bios"))
+            .await
+            .unwrap();
+
+        let request = ReleaseLaunchRequest {
+            release_id,
+            file_set_id: Some(game_id),
+            ..Default::default()
+        };
+        let launch = view_model_service
+            .prepare_release_launch(&request)
+            .await
+            .unwrap();
+        assert_eq!(launch.launched_file, "Game.bin");
+        let roles = launch
+            .plan
+            .file_sets
+            .iter()
+            .map(|file_set| file_set.role.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(roles, vec![DEFAULT_LAUNCH_ROLE, "bios"]);
+        let preview = launch.preview().unwrap();
+        assert_eq!(
+            preview.launch_command.args,
+            vec![
+                launch
+                    .plan
+                    .launch_dir
+                    .join("Game.bin")
+                    .to_string_lossy()
+                    .to_string(),
+                "-bios".to_string(),
+                launch
+                    .plan
+                    .launch_dir
+                    .join("bios")
+                    .join("BIOS.bin")
+                    .to_string_lossy()
+                    .to_string()
+            ]
+        );
+        assert_eq!(
+            launch.arguments,
+            vec![
+                "-bios".to_string(),
+                launch
+                    .plan
+                    .launch_dir
+                    .join("bios")
+                    .join("BIOS.bin")
+                    .to_string_lossy()
+                    .to_string()
+            ]
+        );
+
+        // roles given with the request replace the saved roles
+        let result = view_model_service
+            .prepare_release_launch(&ReleaseLaunchRequest {
+                file_set_roles: Some(vec![]),
+                ..request.clone()
+            })
+            .await;
+        assert!(matches!(result, Err(Error::EmulatorError(_))));
+        let launch = view_model_service
+            .prepare_release_launch(&ReleaseLaunchRequest {
+                file_set_roles: Some(vec![FileSetLaunchRole {
+                    file_set_id: game_id,
+                    role: "bios".to_string(),
+                }]),
+                ..request
+            })
+            .await
+            .unwrap();
+        assert_eq!(launch.plan.file_sets.len(), 1);
+        assert_eq!(launch.plan.file_sets[0].role, "bios");
+    }
+}
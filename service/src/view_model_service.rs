@@ -1,25 +1,73 @@
-use std::sync::Arc;
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
-use database::{models::FileType, repository_manager::RepositoryManager};
+use database::{
+    models::{
+        CompanyRole, CompatibilityStatus, EmulatorConfigTemplate, EmulatorSystemUpdateModel,
+        FileType, PartialDate, Release, ReleaseListQuery, ReleaseMetadataFilter, ReleaseSortKey,
+        SearchResult, SoftwareTitle, TagTarget,
+    },
+    repository_manager::RepositoryManager,
+    search_query::SearchQuery,
+};
+use emulator_runner::{
+    emulator_definitions::get_emulator_definitions, emulator_detection::detect_emulators,
+    error::EmulatorRunnerError,
+};
 
 use crate::{
     error::Error,
     view_models::{
-        EmulatorListModel, EmulatorSystemViewModel, EmulatorViewModel, FileSetListModel,
-        FileSetViewModel, ReleaseListModel, ReleaseViewModel, Settings, SoftwareTitleListModel,
-        SystemListModel,
+        CompanyListModel, CompanyViewModel, EmulatorFirmwareViewModel, EmulatorListModel,
+        EmulatorProposal, EmulatorSystemViewModel, EmulatorViewModel, FileSetListModel,
+        FileSetViewModel, FranchiseListModel, FranchiseViewModel, RankedEmulatorViewModel,
+        ReleaseLaunchSelection, ReleaseListModel, ReleaseViewModel, Settings,
+        SoftwareTitleListModel, SystemListModel,
     },
 };
 
-#[derive(Debug, Clone)]
+/// Releases to list, releases match all the given filters and any of the values of a filter.
+#[derive(Debug, Clone, Default)]
 pub struct ReleaseFilter {
-    pub system_id: Option<i64>,
-    pub software_title_id: Option<i64>,
+    pub system_ids: Vec<i64>,
+    pub software_title_ids: Vec<i64>,
+    /// Releases with a file set of one of the file types.
+    pub file_types: Vec<FileType>,
+    /// Whole release names ignoring case, `*` matches any characters and `?` a single
+    /// character.
+    pub name_patterns: Vec<String>,
+    pub region: Option<String>,
+    pub language: Option<String>,
+    /// Releases dated within or after this date.
+    pub released_from: Option<PartialDate>,
+    /// Releases dated within or before this date.
+    pub released_to: Option<PartialDate>,
+    /// Releases the company is credited for.
+    pub company_id: Option<i64>,
+    /// Role of the company, any role if not set.
+    pub company_role: Option<CompanyRole>,
+    /// Releases tagged with the tag or its subtags, directly or via their software titles or
+    /// file sets.
+    pub tag: Option<String>,
+    pub sort_key: ReleaseSortKey,
+    pub descending: bool,
+    /// Only the releases after this release in the sort order, to page from the last listed
+    /// release.
+    pub after_release_id: Option<i64>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SoftwareTitleFilter {
+    /// Matched against the name, sort name and aliases.
+    pub search: Option<String>,
+    /// Software titles tagged with the tag or its subtags.
+    pub tag: Option<String>,
 }
 
 #[derive(Debug)]
 pub struct ViewModelService {
-    repository_manager: Arc<RepositoryManager>,
+    pub(crate) repository_manager: Arc<RepositoryManager>,
 }
 
 impl ViewModelService {
@@ -37,8 +85,11 @@ impl ViewModelService {
             .get_emulator_with_systems(emulator_id)
             .await?;
 
-        println!("Emulator: {:?}", emulator);
-        println!("Emulator Systems: {:?}", emulator_systems);
+        let launch_settings = self
+            .repository_manager
+            .get_emulator_repository()
+            .get_emulator_launch_settings(emulator_id)
+            .await?;
 
         Ok(EmulatorViewModel {
             id: emulator.id,
@@ -54,6 +105,7 @@ impl ViewModelService {
                     arguments: es.arguments,
                 })
                 .collect(),
+            launch_settings,
         })
     }
 
@@ -92,6 +144,13 @@ impl ViewModelService {
                 .await
                 .map_err(|err| Error::DbError(err.to_string()))?;
 
+            let launch_settings = self
+                .repository_manager
+                .get_emulator_repository()
+                .get_emulator_launch_settings(emulator.id)
+                .await
+                .map_err(|err| Error::DbError(err.to_string()))?;
+
             let view_model = EmulatorViewModel {
                 id: emulator.id,
                 name: emulator.name,
@@ -106,6 +165,7 @@ impl ViewModelService {
                         arguments: es.arguments,
                     })
                     .collect(),
+                launch_settings,
             };
 
             emulator_view_models.push(view_model);
@@ -114,6 +174,375 @@ impl ViewModelService {
         Ok(emulator_view_models)
     }
 
+    /// Returns the emulators for the system ranked by the recorded compatibility with the release:
+    /// working emulators first, then the ones with minor issues, untested ones and finally the
+    /// broken ones. Emulators with the same status are ordered by the most recently tested.
+    pub async fn get_ranked_emulators_for_release(
+        &self,
+        release_id: i64,
+        system_id: i64,
+    ) -> Result<Vec<RankedEmulatorViewModel>, Error> {
+        let emulators = self
+            .get_emulator_view_models_for_systems(&[system_id])
+            .await?;
+        let compatibility = self
+            .repository_manager
+            .get_compatibility_repository()
+            .get_release_compatibility(release_id, system_id)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+
+        let mut ranked_emulators = emulators
+            .into_iter()
+            .map(|emulator| RankedEmulatorViewModel {
+                compatibility: compatibility
+                    .iter()
+                    .find(|c| c.emulator_id == emulator.id)
+                    .cloned(),
+                emulator,
+            })
+            .collect::<Vec<_>>();
+        ranked_emulators.sort_by(|a, b| {
+            compatibility_rank(a)
+                .cmp(&compatibility_rank(b))
+                .then_with(|| tested_at(b).cmp(&tested_at(a)))
+        });
+        Ok(ranked_emulators)
+    }
+
+    /// Adds the launch to the launch history when the emulator was run, also when it failed, was
+    /// stopped or timed out. Launches that failed before the emulator ran aren't added.
+    pub async fn record_launch(
+        &self,
+        release_id: i64,
+        emulator_id: i64,
+        system_id: i64,
+        launch_result: &Result<(), EmulatorRunnerError>,
+    ) -> Result<Option<i64>, Error> {
+        let emulator_ran = matches!(
+            launch_result,
+            Ok(())
+                | Err(EmulatorRunnerError::EmulatorFailed(_)
+                    | EmulatorRunnerError::Stopped
+                    | EmulatorRunnerError::Timeout(_)
+                    | EmulatorRunnerError::PostLaunchHookFailed(_))
+        );
+        if !emulator_ran {
+            return Ok(None);
+        }
+        self.repository_manager
+            .get_release_repository()
+            .add_release_launch(release_id, Some(emulator_id), Some(system_id), None)
+            .await
+            .map(Some)
+            .map_err(|err| Error::DbError(err.to_string()))
+    }
+
+    /// Updates the compatibility of the release with the emulator from the result of a launch.
+    /// Emulator exiting with a failure status is recorded as broken, earlier notes are kept.
+    /// Successful launch is recorded as working when the release hasn't been tested on the
+    /// emulator, otherwise only the tested date is updated since a launch alone doesn't tell
+    /// about minor issues found earlier. Launches that failed before the emulator ran, were
+    /// stopped or timed out don't tell about the compatibility and aren't recorded.
+    pub async fn record_launch_result(
+        &self,
+        release_id: i64,
+        emulator_id: i64,
+        system_id: i64,
+        launch_result: Result<(), EmulatorRunnerError>,
+    ) -> Result<Option<i64>, Error> {
+        let emulator_failure = match launch_result {
+            // post-launch command is run after the emulator has exited successfully
+            Ok(()) | Err(EmulatorRunnerError::PostLaunchHookFailed(_)) => None,
+            Err(EmulatorRunnerError::EmulatorFailed(status)) => Some(status),
+            Err(_) => return Ok(None),
+        };
+        let compatibility_repository = self.repository_manager.get_compatibility_repository();
+        let existing = compatibility_repository
+            .get_compatibility(release_id, emulator_id, system_id)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+        let (status, notes) = match (emulator_failure, existing) {
+            (Some(failure), existing) => (
+                CompatibilityStatus::Broken,
+                existing
+                    .and_then(|existing| existing.notes)
+                    .or_else(|| Some(format!("Emulator failed with status: {}", failure))),
+            ),
+            (None, Some(existing)) => (existing.status, existing.notes),
+            (None, None) => (CompatibilityStatus::Works, None),
+        };
+        compatibility_repository
+            .set_compatibility(release_id, emulator_id, system_id, status, notes, None)
+            .await
+            .map(Some)
+            .map_err(|err| Error::DbError(err.to_string()))
+    }
+
+    /// Proposes emulators from the bundled catalogue that are installed in the search directories
+    /// and support systems in the collection. Emulators with a name already in use are skipped.
+    pub async fn get_emulator_proposals(
+        &self,
+        search_dirs: &[PathBuf],
+    ) -> Result<Vec<EmulatorProposal>, Error> {
+        let definitions =
+            get_emulator_definitions().map_err(|err| Error::EmulatorError(err.to_string()))?;
+        let systems = self
+            .repository_manager
+            .get_system_repository()
+            .get_systems()
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+        let emulators = self
+            .repository_manager
+            .get_emulator_repository()
+            .get_emulators()
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+
+        let proposals = detect_emulators(&definitions, search_dirs)
+            .into_iter()
+            .filter(|detected| {
+                !emulators
+                    .iter()
+                    .any(|emulator| emulator.name == detected.definition.name)
+            })
+            .filter_map(|detected| {
+                let (systems, system_names): (Vec<_>, Vec<_>) = systems
+                    .iter()
+                    .filter_map(|system| {
+                        detected
+                            .definition
+                            .systems
+                            .iter()
+                            .find(|definition_system| definition_system.matches(&system.name))
+                            .map(|definition_system| {
+                                (
+                                    EmulatorSystemUpdateModel {
+                                        id: None,
+                                        system_id: system.id,
+                                        arguments: definition_system.arguments.clone(),
+                                    },
+                                    system.name.clone(),
+                                )
+                            })
+                    })
+                    .unzip();
+                if systems.is_empty() {
+                    return None;
+                }
+                Some(EmulatorProposal {
+                    name: detected.definition.name,
+                    executable: detected.executable_path.to_string_lossy().to_string(),
+                    extract_files: detected.definition.extract_files,
+                    systems,
+                    system_names,
+                })
+            })
+            .collect();
+
+        Ok(proposals)
+    }
+
+    /// Resolves the system, file set, entry file and emulator for launching the release.
+    ///
+    /// Emulator set for the release overrides the default emulators of the release systems. When
+    /// there are no defaults, the only emulator supporting the release systems is used.
+    pub async fn get_release_launch_selection(
+        &self,
+        release_id: i64,
+    ) -> Result<ReleaseLaunchSelection, Error> {
+        let release = self.get_release_view_model(release_id).await?;
+        let emulator_repository = self.repository_manager.get_emulator_repository();
+        let release_system_ids = release.systems.iter().map(|s| s.id).collect::<Vec<_>>();
+
+        let mut selection = ReleaseLaunchSelection::default();
+        if let [system] = release.systems.as_slice() {
+            selection.system_id = Some(system.id);
+        }
+
+        let launchable_file_sets = release
+            .file_sets
+            .iter()
+            .filter(|file_set| is_launchable_file_type(&file_set.file_type))
+            .collect::<Vec<_>>();
+        let file_set = match launchable_file_sets.as_slice() {
+            [file_set] => Some(*file_set),
+            _ => None,
+        };
+        selection.file_set_id = file_set.map(|file_set| file_set.id);
+
+        // (system id, emulator id) pairs the emulator could be resolved from
+        let mut candidates: Vec<(i64, i64)> = vec![];
+        if let Some(emulator_id) = emulator_repository
+            .get_release_emulator(release_id)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?
+        {
+            let (_, emulator_systems) = emulator_repository
+                .get_emulator_with_systems(emulator_id)
+                .await
+                .map_err(|err| Error::DbError(err.to_string()))?;
+            candidates = emulator_systems
+                .iter()
+                .filter(|es| release_system_ids.contains(&es.system_id))
+                .map(|es| (es.system_id, emulator_id))
+                .collect();
+        }
+        if candidates.is_empty() {
+            for system_id in &release_system_ids {
+                if let Some(emulator_id) = emulator_repository
+                    .get_default_emulator_for_system(*system_id)
+                    .await
+                    .map_err(|err| Error::DbError(err.to_string()))?
+                {
+                    candidates.push((*system_id, emulator_id));
+                }
+            }
+        }
+        if candidates.is_empty() {
+            for emulator in emulator_repository
+                .get_emulators_for_systems(&release_system_ids)
+                .await
+                .map_err(|err| Error::DbError(err.to_string()))?
+            {
+                let (_, emulator_systems) = emulator_repository
+                    .get_emulator_with_systems(emulator.id)
+                    .await
+                    .map_err(|err| Error::DbError(err.to_string()))?;
+                candidates.extend(
+                    emulator_systems
+                        .iter()
+                        .filter(|es| release_system_ids.contains(&es.system_id))
+                        .map(|es| (es.system_id, emulator.id)),
+                );
+            }
+        }
+
+        let mut emulator_ids = candidates.iter().map(|(_, e)| *e).collect::<Vec<_>>();
+        emulator_ids.sort_unstable();
+        emulator_ids.dedup();
+        if let [emulator_id] = emulator_ids.as_slice() {
+            selection.emulator_id = Some(*emulator_id);
+        }
+        if let [(system_id, _)] = candidates.as_slice() {
+            selection.system_id = Some(*system_id);
+        }
+
+        if let Some(file_set) = file_set {
+            let entry_point = file_set.files.iter().find(|file| file.is_entry_point);
+            selection.file_info_id = match (entry_point, file_set.files.as_slice()) {
+                (Some(file), _) | (None, [file]) => Some(file.file_info_id),
+                _ => None,
+            };
+            // when files are not extracted, the whole file set is launched as a zip file
+            if selection.file_info_id.is_none() {
+                if let Some(emulator_id) = selection.emulator_id {
+                    let (emulator, _) = emulator_repository
+                        .get_emulator_with_systems(emulator_id)
+                        .await
+                        .map_err(|err| Error::DbError(err.to_string()))?;
+                    if !emulator.extract_files {
+                        selection.file_info_id =
+                            file_set.files.first().map(|file| file.file_info_id);
+                    }
+                }
+            }
+        }
+
+        Ok(selection)
+    }
+
+    /// Returns the firmware the emulator needs for the system. Firmware files are checked to be
+    /// found in the collection.
+    pub async fn get_emulator_firmware(
+        &self,
+        emulator_id: i64,
+        system_id: i64,
+    ) -> Result<Vec<EmulatorFirmwareViewModel>, Error> {
+        let settings = self.get_settings().await?;
+        let file_set_repository = self.repository_manager.get_file_set_repository();
+        let emulator_system_firmware = self
+            .repository_manager
+            .get_emulator_repository()
+            .get_emulator_system_firmware(emulator_id, system_id)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+
+        let mut firmware_view_models = vec![];
+        for firmware in emulator_system_firmware {
+            let file_set = file_set_repository
+                .get_file_sets(vec![firmware.file_set_id])
+                .await
+                .map_err(|err| Error::DbError(err.to_string()))?
+                .into_iter()
+                .next()
+                .ok_or_else(|| {
+                    Error::DbError(format!("File set {} not found", firmware.file_set_id))
+                })?;
+            let files = file_set_repository
+                .get_file_set_file_info(file_set.id)
+                .await
+                .map_err(|err| Error::DbError(err.to_string()))?;
+
+            let file_type_dir = settings
+                .collection_root_dir
+                .join(file_set.file_type.dir_name());
+            let missing_files = files
+                .iter()
+                .filter(|file| {
+                    !file_type_dir
+                        .join(&file.archive_file_name)
+                        .with_extension("zst")
+                        .is_file()
+                })
+                .map(|file| file.file_name.clone())
+                .collect();
+
+            firmware_view_models.push(EmulatorFirmwareViewModel {
+                id: firmware.id,
+                file_set: FileSetViewModel {
+                    id: file_set.id,
+                    file_set_name: file_set.file_name,
+                    file_type: file_set.file_type,
+                    files,
+                },
+                target_path: firmware.target_path,
+                required: firmware.required,
+                missing_files,
+            });
+        }
+        Ok(firmware_view_models)
+    }
+
+    /// Returns the required firmware that is missing, the emulator shouldn't be launched for the
+    /// system until it has been added to the collection.
+    pub async fn get_missing_firmware(
+        &self,
+        emulator_id: i64,
+        system_id: i64,
+    ) -> Result<Vec<EmulatorFirmwareViewModel>, Error> {
+        let firmware = self.get_emulator_firmware(emulator_id, system_id).await?;
+        Ok(firmware
+            .into_iter()
+            .filter(|firmware| firmware.required && firmware.is_missing())
+            .collect())
+    }
+
+    /// Returns the configuration template of the emulator system for the release, the default
+    /// template of the emulator system when the release doesn't have its own.
+    pub async fn get_emulator_config_template(
+        &self,
+        emulator_system_id: i64,
+        release_id: i64,
+    ) -> Result<Option<EmulatorConfigTemplate>, Error> {
+        self.repository_manager
+            .get_emulator_repository()
+            .get_emulator_config_template(emulator_system_id, release_id)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))
+    }
+
     pub async fn get_settings(&self) -> Result<Settings, Error> {
         let settings_map = self
             .repository_manager
@@ -147,25 +576,80 @@ impl ViewModelService {
         Ok(list_models)
     }
 
+    /// Software titles ordered by their sort names.
     pub async fn get_software_title_list_models(
         &self,
     ) -> Result<Vec<SoftwareTitleListModel>, Error> {
-        let systems = self
+        let software_titles = self
             .repository_manager
             .get_software_title_repository()
-            // TODO: add search filter
             .get_all_software_titles()
             .await
             .map_err(|err| Error::DbError(err.to_string()))?;
 
-        let mut list_models: Vec<SoftwareTitleListModel> =
-            systems.iter().map(SoftwareTitleListModel::from).collect();
+        self.to_software_title_list_models(software_titles).await
+    }
 
-        for system in list_models.iter_mut() {
-            system.can_delete = !self
+    /// Software titles matching the filter, ordered by their sort names.
+    pub async fn search_software_title_list_models(
+        &self,
+        filter: &SoftwareTitleFilter,
+    ) -> Result<Vec<SoftwareTitleListModel>, Error> {
+        let software_titles = self
+            .repository_manager
+            .get_software_title_repository()
+            .search_software_titles(filter.search.as_deref(), filter.tag.as_deref())
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+
+        self.to_software_title_list_models(software_titles).await
+    }
+
+    /// Software titles, releases and file sets whose names, title aliases or file names have
+    /// words starting with the words of the query, best matches first.
+    pub async fn search(&self, query: &str, limit: i64) -> Result<Vec<SearchResult>, Error> {
+        self.repository_manager
+            .get_search_repository()
+            .search(query, limit)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))
+    }
+
+    async fn to_software_title_list_models(
+        &self,
+        software_titles: Vec<SoftwareTitle>,
+    ) -> Result<Vec<SoftwareTitleListModel>, Error> {
+        let mut list_models: Vec<SoftwareTitleListModel> = software_titles
+            .iter()
+            .map(SoftwareTitleListModel::from)
+            .collect();
+
+        for software_title in list_models.iter_mut() {
+            software_title.can_delete = !self
                 .repository_manager
                 .get_software_title_repository()
-                .is_software_title_in_use(system.id)
+                .is_software_title_in_use(software_title.id)
+                .await
+                .map_err(|err| Error::DbError(err.to_string()))?;
+        }
+
+        list_models.sort_by_key(|software_title| software_title.sort_name.to_lowercase());
+        Ok(list_models)
+    }
+
+    pub async fn get_franchise_list_models(&self) -> Result<Vec<FranchiseListModel>, Error> {
+        let franchise_repository = self.repository_manager.get_franchise_repository();
+        let franchises = franchise_repository
+            .get_all_franchises()
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+
+        let mut list_models: Vec<FranchiseListModel> =
+            franchises.iter().map(FranchiseListModel::from).collect();
+
+        for franchise in list_models.iter_mut() {
+            franchise.can_delete = !franchise_repository
+                .is_franchise_in_use(franchise.id)
                 .await
                 .map_err(|err| Error::DbError(err.to_string()))?;
         }
@@ -173,6 +657,111 @@ impl ViewModelService {
         Ok(list_models)
     }
 
+    pub async fn get_franchise_view_model(
+        &self,
+        franchise_id: i64,
+    ) -> Result<FranchiseViewModel, Error> {
+        let franchise = self
+            .repository_manager
+            .get_franchise_repository()
+            .get_franchise(franchise_id)
+            .await
+            .map_err(|err| {
+                if err.is_not_found() {
+                    Error::NotFound(format!("Franchise {}", franchise_id))
+                } else {
+                    Error::DbError(err.to_string())
+                }
+            })?;
+
+        let software_titles = self
+            .repository_manager
+            .get_software_title_repository()
+            .get_software_titles_by_franchise(franchise_id)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+
+        // no software titles would be no filter
+        let releases = if software_titles.is_empty() {
+            vec![]
+        } else {
+            self.get_release_list_models(ReleaseFilter {
+                software_title_ids: software_titles.iter().map(|title| title.id).collect(),
+                ..Default::default()
+            })
+            .await?
+        };
+
+        Ok(FranchiseViewModel {
+            id: franchise.id,
+            name: franchise.name,
+            software_titles: software_titles
+                .iter()
+                .map(SoftwareTitleListModel::from)
+                .collect(),
+            releases,
+        })
+    }
+
+    pub async fn get_company_list_models(&self) -> Result<Vec<CompanyListModel>, Error> {
+        let company_repository = self.repository_manager.get_company_repository();
+        let companies = company_repository
+            .get_all_companies()
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+
+        let mut list_models: Vec<CompanyListModel> =
+            companies.iter().map(CompanyListModel::from).collect();
+
+        for company in list_models.iter_mut() {
+            company.can_delete = !company_repository
+                .is_company_in_use(company.id)
+                .await
+                .map_err(|err| Error::DbError(err.to_string()))?;
+        }
+
+        Ok(list_models)
+    }
+
+    pub async fn get_company_view_model(&self, company_id: i64) -> Result<CompanyViewModel, Error> {
+        let company_repository = self.repository_manager.get_company_repository();
+        let company = company_repository
+            .get_company(company_id)
+            .await
+            .map_err(|err| {
+                if err.is_not_found() {
+                    Error::NotFound(format!("Company {}", company_id))
+                } else {
+                    Error::DbError(err.to_string())
+                }
+            })?;
+
+        let developed_software_titles = self
+            .repository_manager
+            .get_software_title_repository()
+            .get_software_titles_by_developer(company_id)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+
+        let published_releases = company_repository
+            .get_releases_by_company(company_id, Some(CompanyRole::Publisher), None)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+
+        let distributed_releases = company_repository
+            .get_releases_by_company(company_id, Some(CompanyRole::Distributor), None)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+
+        Ok(CompanyViewModel {
+            id: company.id,
+            name: company.name,
+            developed_software_titles,
+            published_releases,
+            distributed_releases,
+        })
+    }
+
     pub async fn get_file_set_list_models(
         &self,
         file_type: FileType,
@@ -206,6 +795,31 @@ impl ViewModelService {
         Ok(list_models)
     }
 
+    pub async fn get_file_set_view_model(
+        &self,
+        file_set_id: i64,
+    ) -> Result<FileSetViewModel, Error> {
+        let file_set_repository = self.repository_manager.get_file_set_repository();
+        let file_set = file_set_repository
+            .get_file_sets(vec![file_set_id])
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::NotFound(format!("File set {}", file_set_id)))?;
+        let files = file_set_repository
+            .get_file_set_file_info(file_set_id)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+
+        Ok(FileSetViewModel {
+            id: file_set.id,
+            file_set_name: file_set.file_name,
+            file_type: file_set.file_type,
+            files,
+        })
+    }
+
     pub async fn get_release_list_models(
         &self,
         filters: ReleaseFilter,
@@ -213,12 +827,76 @@ impl ViewModelService {
         let releases = self
             .repository_manager
             .get_release_repository()
-            .get_releases(filters.system_id, filters.software_title_id)
+            .get_releases(&ReleaseListQuery {
+                release_ids: vec![],
+                system_ids: filters.system_ids,
+                software_title_ids: filters.software_title_ids,
+                file_types: filters.file_types,
+                name_patterns: filters.name_patterns,
+                metadata: ReleaseMetadataFilter {
+                    region: filters.region,
+                    language: filters.language,
+                    released_from: filters.released_from,
+                    released_to: filters.released_to,
+                    tag: filters.tag,
+                },
+                company_id: filters.company_id,
+                company_role: filters.company_role,
+                sort_key: filters.sort_key,
+                descending: filters.descending,
+                after_release_id: filters.after_release_id,
+                limit: filters.limit,
+                offset: filters.offset,
+            })
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+
+        Ok(releases.iter().map(ReleaseListModel::from).collect())
+    }
+
+    /// Releases matching the search query ordered by name, see `SearchQuery` for the syntax.
+    pub async fn search_release_list_models(
+        &self,
+        query: &str,
+    ) -> Result<Vec<ReleaseListModel>, Error> {
+        let query = query
+            .parse::<SearchQuery>()
+            .map_err(|err| Error::InvalidQuery(err.to_string()))?;
+        let releases = self
+            .repository_manager
+            .get_search_repository()
+            .search_releases(&query)
             .await
             .map_err(|err| Error::DbError(err.to_string()))?;
+        self.to_release_list_models(releases).await
+    }
+
+    /// List models of the releases in the given order.
+    pub(crate) async fn to_release_list_models(
+        &self,
+        releases: Vec<Release>,
+    ) -> Result<Vec<ReleaseListModel>, Error> {
+        if releases.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut extended_releases = self
+            .repository_manager
+            .get_release_repository()
+            .get_releases(&ReleaseListQuery {
+                release_ids: releases.iter().map(|release| release.id).collect(),
+                ..Default::default()
+            })
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?
+            .into_iter()
+            .map(|release| (release.id, release))
+            .collect::<HashMap<_, _>>();
 
-        let release_models = releases.iter().map(ReleaseListModel::from).collect();
-        Ok(release_models)
+        Ok(releases
+            .iter()
+            .filter_map(|release| extended_releases.remove(&release.id))
+            .map(|release| ReleaseListModel::from(&release))
+            .collect())
     }
 
     pub async fn get_release_view_model(&self, release_id: i64) -> Result<ReleaseViewModel, Error> {
@@ -227,7 +905,13 @@ impl ViewModelService {
             .get_release_repository()
             .get_release(release_id)
             .await
-            .map_err(|err| Error::DbError(err.to_string()))?;
+            .map_err(|err| {
+                if err.is_not_found() {
+                    Error::NotFound(format!("Release {}", release_id))
+                } else {
+                    Error::DbError(err.to_string())
+                }
+            })?;
 
         let software_titles = self
             .repository_manager
@@ -268,18 +952,65 @@ impl ViewModelService {
             });
         }
 
+        let metadata = self
+            .repository_manager
+            .get_release_repository()
+            .get_release_metadata(release_id)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+
+        let companies = self
+            .repository_manager
+            .get_company_repository()
+            .get_companies_by_release(release_id)
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+
+        let tags = self
+            .repository_manager
+            .get_tag_repository()
+            .get_tags(TagTarget::Release(release_id))
+            .await
+            .map_err(|err| Error::DbError(err.to_string()))?;
+
         let release_view_model = ReleaseViewModel {
             id: release.id,
             name: release.name.clone(),
             systems,
             software_titles,
             file_sets: file_set_view_models,
+            metadata,
+            companies,
+            tags,
         };
 
         Ok(release_view_model)
     }
 }
 
+fn is_launchable_file_type(file_type: &FileType) -> bool {
+    matches!(
+        file_type,
+        FileType::Rom | FileType::DiskImage | FileType::TapeImage | FileType::MemorySnapshot
+    )
+}
+
+fn compatibility_rank(emulator: &RankedEmulatorViewModel) -> u8 {
+    match emulator.compatibility.as_ref().map(|c| c.status) {
+        Some(CompatibilityStatus::Works) => 0,
+        Some(CompatibilityStatus::MinorIssues) => 1,
+        None => 2,
+        Some(CompatibilityStatus::Broken) => 3,
+    }
+}
+
+fn tested_at(emulator: &RankedEmulatorViewModel) -> Option<&str> {
+    emulator
+        .compatibility
+        .as_ref()
+        .map(|c| c.tested_at.as_str())
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -287,9 +1018,13 @@ mod tests {
 
     use super::*;
     use database::{
-        models::{EmulatorSystemUpdateModel, SettingName},
+        models::{
+            EmulatorLaunchSettings, EmulatorSystemUpdateModel, ExecutableKind, ReleaseCriterion,
+            SettingName,
+        },
         setup_test_db,
     };
+    use emulator_runner::executable::EmulatorExecutable;
 
     #[async_std::test]
     async fn test_get_emulator_view_model() {
@@ -315,6 +1050,7 @@ mod tests {
                 "temu".to_string(),
                 false,
                 emulator_systems,
+                EmulatorLaunchSettings::default(),
             )
             .await
             .unwrap();
@@ -332,6 +1068,21 @@ mod tests {
         assert_eq!(emulator_view_model.systems[0].system_id, system_id);
         assert_eq!(emulator_view_model.systems[0].system_name, "Test System");
         assert_eq!(emulator_view_model.systems[0].arguments, "args");
+        assert_eq!(
+            emulator_view_model.emulator_executable(),
+            EmulatorExecutable::Binary("temu".to_string())
+        );
+
+        let mut wine_emulator = emulator_view_model.clone();
+        wine_emulator.launch_settings.executable_kind = ExecutableKind::Wine;
+        wine_emulator.launch_settings.wine_prefix = Some("/wine/temu".to_string());
+        assert_eq!(
+            wine_emulator.emulator_executable(),
+            EmulatorExecutable::Wine {
+                executable: PathBuf::from("temu"),
+                prefix: Some(PathBuf::from("/wine/temu")),
+            }
+        );
     }
 
     #[async_std::test]
@@ -350,4 +1101,760 @@ mod tests {
         let settings = view_model_service.get_settings().await.unwrap();
         assert_eq!(settings.collection_root_dir, PathBuf::from("test_value"));
     }
+
+    #[cfg(unix)]
+    #[async_std::test]
+    async fn test_get_emulator_proposals() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let pool = Arc::new(setup_test_db().await);
+        let repository_manager = Arc::new(RepositoryManager::new(pool.clone()));
+        let view_model_service = ViewModelService::new(repository_manager.clone());
+        let system_id = repository_manager
+            .get_system_repository()
+            .add_system(&"Commodore 64".to_string())
+            .await
+            .unwrap();
+        repository_manager
+            .get_system_repository()
+            .add_system(&"Atari 2600".to_string())
+            .await
+            .unwrap();
+        repository_manager
+            .get_emulator_repository()
+            .add_emulator_with_systems(
+                "Stella".to_string(),
+                "stella".to_string(),
+                true,
+                vec![],
+                EmulatorLaunchSettings::default(),
+            )
+            .await
+            .unwrap();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        // x64sc matches a system in the collection, stella is already added, fs-uae has no system
+        for executable in ["x64sc", "stella", "fs-uae"] {
+            let path = temp_dir.path().join(executable);
+            std::fs::write(&path, "").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let proposals = view_model_service
+            .get_emulator_proposals(&[temp_dir.path().to_path_buf()])
+            .await
+            .unwrap();
+
+        assert_eq!(proposals.len(), 1);
+        assert_eq!(proposals[0].name, "VICE (C64)");
+        assert_eq!(
+            proposals[0].executable,
+            temp_dir.path().join("x64sc").to_string_lossy()
+        );
+        assert!(proposals[0].extract_files);
+        assert_eq!(
+            proposals[0].systems,
+            vec![EmulatorSystemUpdateModel {
+                id: None,
+                system_id,
+                arguments: String::new(),
+            }]
+        );
+        assert_eq!(proposals[0].system_names, vec!["Commodore 64"]);
+    }
+
+    #[async_std::test]
+    async fn test_get_release_launch_selection() {
+        let pool = Arc::new(setup_test_db().await);
+        let repository_manager = Arc::new(RepositoryManager::new(pool.clone()));
+        let view_model_service = ViewModelService::new(repository_manager.clone());
+        let system_id = repository_manager
+            .get_system_repository()
+            .add_system(&"Commodore 64".to_string())
+            .await
+            .unwrap();
+        let files = ["disk 1.d64", "disk 2.d64"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| core_types::ImportedFile {
+                original_file_name: name.to_string(),
+                archive_file_name: format!("archive_{}", i),
+                sha1_checksum: [i as u8; 20],
+                file_size: 123,
+            })
+            .collect();
+        let file_set_id = repository_manager
+            .get_file_set_repository()
+            .add_file_set("Game".to_string(), FileType::DiskImage, files, &[system_id])
+            .await
+            .unwrap();
+        let release_id = repository_manager
+            .get_release_repository()
+            .add_release_full(
+                "Game".to_string(),
+                vec![],
+                vec![file_set_id],
+                vec![system_id],
+            )
+            .await
+            .unwrap();
+
+        let emulator_repository = repository_manager.get_emulator_repository();
+        let mut emulator_ids = vec![];
+        for (name, extract_files) in [("Extracting", true), ("Zipped", false)] {
+            let emulator_id = emulator_repository
+                .add_emulator_with_systems(
+                    name.to_string(),
+                    "emu".to_string(),
+                    extract_files,
+                    vec![EmulatorSystemUpdateModel {
+                        id: None,
+                        system_id,
+                        arguments: String::new(),
+                    }],
+                    EmulatorLaunchSettings::default(),
+                )
+                .await
+                .unwrap();
+            emulator_ids.push(emulator_id);
+        }
+
+        // two emulators and two files to choose from
+        let selection = view_model_service
+            .get_release_launch_selection(release_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            selection,
+            ReleaseLaunchSelection {
+                system_id: Some(system_id),
+                file_set_id: Some(file_set_id),
+                file_info_id: None,
+                emulator_id: None,
+            }
+        );
+
+        // default emulator launches the file set as a zip file
+        emulator_repository
+            .set_default_emulator_for_system(system_id, Some(emulator_ids[1]))
+            .await
+            .unwrap();
+        let selection = view_model_service
+            .get_release_launch_selection(release_id)
+            .await
+            .unwrap();
+        assert_eq!(selection.emulator_id, Some(emulator_ids[1]));
+        assert!(selection.is_complete());
+
+        // release override extracts the files, entry file has to be picked
+        emulator_repository
+            .set_release_emulator(release_id, Some(emulator_ids[0]))
+            .await
+            .unwrap();
+        let selection = view_model_service
+            .get_release_launch_selection(release_id)
+            .await
+            .unwrap();
+        assert_eq!(selection.emulator_id, Some(emulator_ids[0]));
+        assert_eq!(selection.file_info_id, None);
+        assert!(!selection.is_complete());
+
+        // entry point of the file set is launched by default
+        let file_set_repository = repository_manager.get_file_set_repository();
+        let files = file_set_repository
+            .get_file_set_file_info(file_set_id)
+            .await
+            .unwrap();
+        file_set_repository
+            .set_file_set_entry_point(file_set_id, Some(files[1].file_info_id))
+            .await
+            .unwrap();
+        let selection = view_model_service
+            .get_release_launch_selection(release_id)
+            .await
+            .unwrap();
+        assert_eq!(selection.file_info_id, Some(files[1].file_info_id));
+        assert!(selection.is_complete());
+    }
+
+    #[async_std::test]
+    async fn test_get_company_view_model_and_releases() {
+        let pool = Arc::new(setup_test_db().await);
+        let repository_manager = Arc::new(RepositoryManager::new(pool.clone()));
+        let view_model_service = ViewModelService::new(repository_manager.clone());
+        let company_repository = repository_manager.get_company_repository();
+        let ocean_id = company_repository.add_company("Ocean").await.unwrap();
+        let sensible_id = company_repository
+            .add_company("Sensible Software")
+            .await
+            .unwrap();
+
+        let title_id = repository_manager
+            .get_software_title_repository()
+            .add_software_title(&"Wizball".to_string(), None)
+            .await
+            .unwrap();
+        company_repository
+            .add_developer_to_software_title(title_id, sensible_id)
+            .await
+            .unwrap();
+
+        let mut release_ids = vec![];
+        for (i, system_name) in ["Commodore 64", "Amiga"].iter().enumerate() {
+            let system_id = repository_manager
+                .get_system_repository()
+                .add_system(&system_name.to_string())
+                .await
+                .unwrap();
+            let files = vec![core_types::ImportedFile {
+                original_file_name: format!("wizball_{}", i),
+                archive_file_name: format!("archive_{}", i),
+                sha1_checksum: [i as u8; 20],
+                file_size: 123,
+            }];
+            let file_set_id = repository_manager
+                .get_file_set_repository()
+                .add_file_set(
+                    format!("Wizball {}", system_name),
+                    FileType::DiskImage,
+                    files,
+                    &[system_id],
+                )
+                .await
+                .unwrap();
+            let release_id = repository_manager
+                .get_release_repository()
+                .add_release_full(
+                    format!("Wizball ({})", system_name),
+                    vec![title_id],
+                    vec![file_set_id],
+                    vec![system_id],
+                )
+                .await
+                .unwrap();
+            release_ids.push((release_id, system_id));
+        }
+        let (c64_release_id, c64_id) = release_ids[0];
+        company_repository
+            .add_company_to_release(c64_release_id, ocean_id, CompanyRole::Publisher)
+            .await
+            .unwrap();
+
+        let ocean = view_model_service
+            .get_company_view_model(ocean_id)
+            .await
+            .unwrap();
+        assert!(ocean.developed_software_titles.is_empty());
+        assert_eq!(ocean.published_releases.len(), 1);
+        assert_eq!(ocean.published_releases[0].id, c64_release_id);
+
+        let sensible = view_model_service
+            .get_company_view_model(sensible_id)
+            .await
+            .unwrap();
+        assert_eq!(sensible.developed_software_titles.len(), 1);
+        assert_eq!(sensible.developed_software_titles[0].id, title_id);
+
+        let developed = view_model_service
+            .get_release_list_models(ReleaseFilter {
+                company_id: Some(sensible_id),
+                company_role: Some(CompanyRole::Developer),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(developed.len(), 2);
+
+        let developed_on_c64 = view_model_service
+            .get_release_list_models(ReleaseFilter {
+                system_ids: vec![c64_id],
+                company_id: Some(sensible_id),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(developed_on_c64.len(), 1);
+        assert_eq!(developed_on_c64[0].id, c64_release_id);
+        assert_eq!(developed_on_c64[0].system_names, vec!["Commodore 64"]);
+
+        let release = view_model_service
+            .get_release_view_model(c64_release_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            release
+                .companies
+                .iter()
+                .map(|c| (c.company.id, c.role))
+                .collect::<Vec<_>>(),
+            vec![
+                (sensible_id, CompanyRole::Developer),
+                (ocean_id, CompanyRole::Publisher)
+            ]
+        );
+
+        let companies = view_model_service.get_company_list_models().await.unwrap();
+        assert_eq!(companies.len(), 2);
+        assert!(companies.iter().all(|company| !company.can_delete));
+
+        assert!(matches!(
+            view_model_service.get_company_view_model(999).await,
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[async_std::test]
+    async fn test_get_franchise_view_model() {
+        let pool = Arc::new(setup_test_db().await);
+        let repository_manager = Arc::new(RepositoryManager::new(pool.clone()));
+        let view_model_service = ViewModelService::new(repository_manager.clone());
+        let franchise_id = repository_manager
+            .get_franchise_repository()
+            .add_franchise("Turrican")
+            .await
+            .unwrap();
+
+        let software_title_repository = repository_manager.get_software_title_repository();
+        let mut software_title_ids = vec![];
+        for (i, (name, system_name)) in [("Turrican", "Commodore 64"), ("Turrican II", "Amiga")]
+            .iter()
+            .enumerate()
+        {
+            let software_title_id = software_title_repository
+                .add_software_title(&name.to_string(), None)
+                .await
+                .unwrap();
+            software_title_repository
+                .set_software_title_franchise(software_title_id, Some(franchise_id))
+                .await
+                .unwrap();
+            let system_id = repository_manager
+                .get_system_repository()
+                .add_system(&system_name.to_string())
+                .await
+                .unwrap();
+            let files = vec![core_types::ImportedFile {
+                original_file_name: format!("{}.d64", name),
+                archive_file_name: format!("archive_{}", i),
+                sha1_checksum: [i as u8; 20],
+                file_size: 123,
+            }];
+            let file_set_id = repository_manager
+                .get_file_set_repository()
+                .add_file_set(name.to_string(), FileType::DiskImage, files, &[system_id])
+                .await
+                .unwrap();
+            repository_manager
+                .get_release_repository()
+                .add_release_full(
+                    name.to_string(),
+                    vec![software_title_id],
+                    vec![file_set_id],
+                    vec![system_id],
+                )
+                .await
+                .unwrap();
+            software_title_ids.push(software_title_id);
+        }
+        software_title_repository
+            .add_software_title(&"Katakis".to_string(), None)
+            .await
+            .unwrap();
+
+        let franchise = view_model_service
+            .get_franchise_view_model(franchise_id)
+            .await
+            .unwrap();
+        assert_eq!(franchise.name, "Turrican");
+        assert_eq!(
+            franchise
+                .software_titles
+                .iter()
+                .map(|software_title| software_title.id)
+                .collect::<Vec<_>>(),
+            software_title_ids
+        );
+        assert_eq!(
+            franchise
+                .releases
+                .iter()
+                .map(|release| release.system_names.clone())
+                .collect::<Vec<_>>(),
+            vec![vec!["Commodore 64"], vec!["Amiga"]]
+        );
+
+        let franchises = view_model_service
+            .get_franchise_list_models()
+            .await
+            .unwrap();
+        assert_eq!(franchises.len(), 1);
+        assert!(!franchises[0].can_delete);
+
+        assert!(matches!(
+            view_model_service.get_franchise_view_model(999).await,
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[async_std::test]
+    async fn test_filter_by_tag() {
+        let pool = Arc::new(setup_test_db().await);
+        let repository_manager = Arc::new(RepositoryManager::new(pool.clone()));
+        let view_model_service = ViewModelService::new(repository_manager.clone());
+        let tag_repository = repository_manager.get_tag_repository();
+
+        let software_title_repository = repository_manager.get_software_title_repository();
+        let mut release_ids = vec![];
+        for name in ["Uridium", "Paradroid"] {
+            let software_title_id = software_title_repository
+                .add_software_title(&name.to_string(), None)
+                .await
+                .unwrap();
+            tag_repository
+                .add_tag(
+                    TagTarget::SoftwareTitle(software_title_id),
+                    "developer/Braybrook",
+                )
+                .await
+                .unwrap();
+            let release_id = repository_manager
+                .get_release_repository()
+                .add_release_full(name.to_string(), vec![software_title_id], vec![], vec![])
+                .await
+                .unwrap();
+            release_ids.push(release_id);
+        }
+        tag_repository
+            .add_tag(TagTarget::Release(release_ids[0]), "favourite")
+            .await
+            .unwrap();
+
+        let software_titles = view_model_service
+            .search_software_title_list_models(&SoftwareTitleFilter {
+                search: Some("uri".to_string()),
+                tag: Some("developer".to_string()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(software_titles.len(), 1);
+        assert_eq!(software_titles[0].name, "Uridium");
+
+        let software_titles = view_model_service
+            .search_software_title_list_models(&SoftwareTitleFilter {
+                tag: Some("developer/braybrook".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(software_titles.len(), 2);
+
+        let release = view_model_service
+            .get_release_view_model(release_ids[0])
+            .await
+            .unwrap();
+        assert_eq!(
+            release
+                .tags
+                .iter()
+                .map(|tag| tag.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["favourite"]
+        );
+    }
+
+    #[async_std::test]
+    async fn test_get_missing_firmware() {
+        let pool = Arc::new(setup_test_db().await);
+        let repository_manager = Arc::new(RepositoryManager::new(pool.clone()));
+        let view_model_service = ViewModelService::new(repository_manager.clone());
+        let collection_dir = tempfile::tempdir().unwrap();
+        repository_manager
+            .settings()
+            .add_setting(
+                SettingName::CollectionRootDir.as_str(),
+                collection_dir.path().to_str().unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let system_id = repository_manager
+            .get_system_repository()
+            .add_system(&"Amiga".to_string())
+            .await
+            .unwrap();
+        let emulator_repository = repository_manager.get_emulator_repository();
+        let emulator_id = emulator_repository
+            .add_emulator_with_systems(
+                "FS-UAE".to_string(),
+                "fs-uae".to_string(),
+                true,
+                vec![EmulatorSystemUpdateModel {
+                    id: None,
+                    system_id,
+                    arguments: String::new(),
+                }],
+                EmulatorLaunchSettings::default(),
+            )
+            .await
+            .unwrap();
+        let (_, emulator_systems) = emulator_repository
+            .get_emulator_with_systems(emulator_id)
+            .await
+            .unwrap();
+
+        // first firmware file is in the collection, second is not
+        let firmware_dir = collection_dir.path().join(FileType::Firmware.dir_name());
+        std::fs::create_dir_all(&firmware_dir).unwrap();
+        std::fs::write(firmware_dir.join("kick13.zst"), "").unwrap();
+        for (i, (name, required)) in [("kick13", true), ("kick31", true)].iter().enumerate() {
+            let file_set_id = repository_manager
+                .get_file_set_repository()
+                .add_file_set(
+                    name.to_string(),
+                    FileType::Firmware,
+                    vec![core_types::ImportedFile {
+                        original_file_name: format!("{}.rom", name),
+                        archive_file_name: name.to_string(),
+                        sha1_checksum: [i as u8; 20],
+                        file_size: 256,
+                    }],
+                    &[system_id],
+                )
+                .await
+                .unwrap();
+            emulator_repository
+                .add_emulator_system_firmware(
+                    emulator_systems[0].id,
+                    file_set_id,
+                    "kickstarts".to_string(),
+                    *required,
+                )
+                .await
+                .unwrap();
+        }
+
+        let firmware = view_model_service
+            .get_emulator_firmware(emulator_id, system_id)
+            .await
+            .unwrap();
+        assert_eq!(firmware.len(), 2);
+        assert!(!firmware[0].is_missing());
+
+        let missing = view_model_service
+            .get_missing_firmware(emulator_id, system_id)
+            .await
+            .unwrap();
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].file_set.file_set_name, "kick31");
+        assert_eq!(missing[0].missing_files, vec!["kick31.rom".to_string()]);
+    }
+
+    #[async_std::test]
+    async fn test_get_ranked_emulators_for_release() {
+        let pool = Arc::new(setup_test_db().await);
+        let repository_manager = Arc::new(RepositoryManager::new(pool.clone()));
+        let view_model_service = ViewModelService::new(repository_manager.clone());
+        let system_id = repository_manager
+            .get_system_repository()
+            .add_system(&"Commodore 64".to_string())
+            .await
+            .unwrap();
+        let release_id = repository_manager
+            .get_release_repository()
+            .add_release("Test Release")
+            .await
+            .unwrap();
+        let mut emulator_ids = vec![];
+        for name in ["CCS64", "Frodo", "VICE"] {
+            let emulator_id = repository_manager
+                .get_emulator_repository()
+                .add_emulator_with_systems(
+                    name.to_string(),
+                    name.to_lowercase(),
+                    true,
+                    vec![EmulatorSystemUpdateModel {
+                        id: None,
+                        system_id,
+                        arguments: String::new(),
+                    }],
+                    EmulatorLaunchSettings::default(),
+                )
+                .await
+                .unwrap();
+            emulator_ids.push(emulator_id);
+        }
+        let (ccs64_id, frodo_id, vice_id) = (emulator_ids[0], emulator_ids[1], emulator_ids[2]);
+        let compatibility_repository = repository_manager.get_compatibility_repository();
+        compatibility_repository
+            .set_compatibility(
+                release_id,
+                frodo_id,
+                system_id,
+                CompatibilityStatus::Broken,
+                None,
+                Some("2025-01-01".to_string()),
+            )
+            .await
+            .unwrap();
+        compatibility_repository
+            .set_compatibility(
+                release_id,
+                vice_id,
+                system_id,
+                CompatibilityStatus::Works,
+                Some("True drive emulation needed".to_string()),
+                Some("2025-01-01".to_string()),
+            )
+            .await
+            .unwrap();
+
+        let ranked_ids = |ranked: Vec<RankedEmulatorViewModel>| {
+            ranked.iter().map(|r| r.emulator.id).collect::<Vec<_>>()
+        };
+        let ranked = view_model_service
+            .get_ranked_emulators_for_release(release_id, system_id)
+            .await
+            .unwrap();
+        assert_eq!(ranked_ids(ranked), vec![vice_id, ccs64_id, frodo_id]);
+
+        // successful launch of an untested emulator is recorded as working today
+        view_model_service
+            .record_launch_result(release_id, ccs64_id, system_id, Ok(()))
+            .await
+            .unwrap();
+        // successful launch keeps the earlier notes
+        view_model_service
+            .record_launch_result(release_id, vice_id, system_id, Ok(()))
+            .await
+            .unwrap();
+        let vice = compatibility_repository
+            .get_compatibility(release_id, vice_id, system_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(vice.status, CompatibilityStatus::Works);
+        assert_eq!(vice.notes, Some("True drive emulation needed".to_string()));
+        assert_ne!(vice.tested_at, "2025-01-01");
+
+        // launches that didn't get to run the emulator to the end aren't recorded
+        for launch_result in [
+            EmulatorRunnerError::ExecutableNotAvailable("x64sc".to_string()),
+            EmulatorRunnerError::LaunchDirectoryInUse("launch".to_string()),
+            EmulatorRunnerError::PreLaunchHookFailed("exit status: 1".to_string()),
+            EmulatorRunnerError::IoError("Failed to export file".to_string()),
+            EmulatorRunnerError::Stopped,
+            EmulatorRunnerError::Timeout(60),
+        ] {
+            let recorded = view_model_service
+                .record_launch_result(release_id, frodo_id, system_id, Err(launch_result))
+                .await
+                .unwrap();
+            assert_eq!(recorded, None);
+        }
+        let frodo = compatibility_repository
+            .get_compatibility(release_id, frodo_id, system_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(frodo.tested_at, "2025-01-01");
+        // launches where the emulator ran are added to the launch history
+        let played_id = view_model_service
+            .add_smart_collection("Played", &[ReleaseCriterion::PlayedWithin(30)])
+            .await
+            .unwrap();
+        let played = || view_model_service.get_collection_release_list_models(played_id);
+        assert_eq!(
+            view_model_service
+                .record_launch(
+                    release_id,
+                    frodo_id,
+                    system_id,
+                    &Err(EmulatorRunnerError::LaunchDirectoryInUse(
+                        "launch".to_string()
+                    )),
+                )
+                .await
+                .unwrap(),
+            None
+        );
+        assert!(played().await.unwrap().is_empty());
+        view_model_service
+            .record_launch(
+                release_id,
+                frodo_id,
+                system_id,
+                &Err(EmulatorRunnerError::Stopped),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(played().await.unwrap().len(), 1);
+
+        // failing post-launch command doesn't tell about the emulator
+        view_model_service
+            .record_launch_result(
+                release_id,
+                frodo_id,
+                system_id,
+                Err(EmulatorRunnerError::PostLaunchHookFailed(
+                    "exit status: 1".to_string(),
+                )),
+            )
+            .await
+            .unwrap();
+        let frodo = compatibility_repository
+            .get_compatibility(release_id, frodo_id, system_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(frodo.status, CompatibilityStatus::Broken);
+        assert_ne!(frodo.tested_at, "2025-01-01");
+
+        // emulator failing is recorded as broken, earlier notes are kept
+        view_model_service
+            .record_launch_result(
+                release_id,
+                vice_id,
+                system_id,
+                Err(EmulatorRunnerError::EmulatorFailed(
+                    "exit status: 1".to_string(),
+                )),
+            )
+            .await
+            .unwrap();
+        view_model_service
+            .record_launch_result(
+                release_id,
+                ccs64_id,
+                system_id,
+                Err(EmulatorRunnerError::EmulatorFailed(
+                    "exit status: 1".to_string(),
+                )),
+            )
+            .await
+            .unwrap();
+        let ranked = view_model_service
+            .get_ranked_emulators_for_release(release_id, system_id)
+            .await
+            .unwrap();
+        assert!(ranked.iter().all(|ranked| ranked
+            .compatibility
+            .as_ref()
+            .is_some_and(|compatibility| compatibility.status == CompatibilityStatus::Broken)));
+        let notes = |emulator_id: i64| {
+            ranked
+                .iter()
+                .find(|ranked| ranked.emulator.id == emulator_id)
+                .and_then(|ranked| ranked.compatibility.as_ref())
+                .and_then(|compatibility| compatibility.notes.clone())
+        };
+        assert_eq!(
+            notes(vice_id),
+            Some("True drive emulation needed".to_string())
+        );
+        assert_eq!(
+            notes(ccs64_id),
+            Some("Emulator failed with status: exit status: 1".to_string())
+        );
+    }
 }
@@ -2,12 +2,15 @@ use std::{
     collections::HashMap,
     fmt::{self, Display, Formatter},
     path::PathBuf,
+    time::Duration,
 };
 
 use database::models::{
-    Emulator, FileSet, FileSetFileInfo, FileType, ReleaseExtended, SettingName, SoftwareTitle,
-    System,
+    Company, Emulator, EmulatorLaunchSettings, EmulatorSystemUpdateModel, ExecutableKind, FileSet,
+    FileSetFileInfo, FileType, Franchise, Release, ReleaseCompany, ReleaseEmulatorCompatibility,
+    ReleaseExtended, ReleaseMetadata, SettingName, SoftwareTitle, System, Tag,
 };
+use emulator_runner::{executable::EmulatorExecutable, LaunchOptions};
 use file_system::get_files_root_dir;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -17,6 +20,48 @@ pub struct EmulatorViewModel {
     pub executable: String,
     pub extract_files: bool,
     pub systems: Vec<EmulatorSystemViewModel>,
+    pub launch_settings: EmulatorLaunchSettings,
+}
+
+impl EmulatorViewModel {
+    /// Emulator executable of the configured kind. For Flatpak the executable is the application
+    /// id and for Wine the path to the Windows executable.
+    pub fn emulator_executable(&self) -> EmulatorExecutable {
+        match self.launch_settings.executable_kind {
+            ExecutableKind::Binary => EmulatorExecutable::Binary(self.executable.clone()),
+            ExecutableKind::Flatpak => EmulatorExecutable::Flatpak {
+                app_id: self.executable.clone(),
+            },
+            ExecutableKind::AppImage => {
+                EmulatorExecutable::AppImage(PathBuf::from(&self.executable))
+            }
+            ExecutableKind::Wine => EmulatorExecutable::Wine {
+                executable: PathBuf::from(&self.executable),
+                prefix: self.launch_settings.wine_prefix.as_ref().map(PathBuf::from),
+            },
+        }
+    }
+
+    /// Launch options from the emulator launch settings.
+    pub fn launch_options(&self) -> LaunchOptions {
+        let launch_settings = &self.launch_settings;
+        LaunchOptions {
+            environment: launch_settings
+                .environment_variables
+                .iter()
+                .map(|v| (v.name.clone(), v.value.clone()))
+                .collect(),
+            working_directory: launch_settings
+                .working_directory
+                .as_ref()
+                .map(PathBuf::from),
+            pre_launch_command: launch_settings.pre_launch_command.clone(),
+            post_launch_command: launch_settings.post_launch_command.clone(),
+            timeout: launch_settings
+                .launch_timeout_seconds
+                .map(|seconds| Duration::from_secs(seconds as u64)),
+        }
+    }
 }
 
 impl Display for EmulatorViewModel {
@@ -25,6 +70,27 @@ impl Display for EmulatorViewModel {
     }
 }
 
+/// Emulator with the recorded compatibility for a release, see
+/// `ViewModelService::get_ranked_emulators_for_release`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedEmulatorViewModel {
+    pub emulator: EmulatorViewModel,
+    pub compatibility: Option<ReleaseEmulatorCompatibility>,
+}
+
+impl Display for RankedEmulatorViewModel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.compatibility {
+            Some(compatibility) => write!(
+                f,
+                "{} - {} ({})",
+                self.emulator.name, compatibility.status, compatibility.tested_at
+            ),
+            None => write!(f, "{}", self.emulator.name),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct EmulatorSystemViewModel {
     pub id: i64,
@@ -54,6 +120,28 @@ impl Display for EmulatorListModel {
     }
 }
 
+/// Emulator detected on the system that can be added with `add_emulator_with_systems`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmulatorProposal {
+    pub name: String,
+    pub executable: String,
+    pub extract_files: bool,
+    pub systems: Vec<EmulatorSystemUpdateModel>,
+    pub system_names: Vec<String>,
+}
+
+impl Display for EmulatorProposal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}) - {}",
+            self.name,
+            self.executable,
+            self.system_names.join(", ")
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct EmulatorSystemListModel {
     pub id: i64,
@@ -104,6 +192,8 @@ impl Display for SystemListModel {
 pub struct SoftwareTitleListModel {
     pub id: i64,
     pub name: String,
+    /// Sort name of the title, derived from the name when not set.
+    pub sort_name: String,
     pub can_delete: bool,
 }
 
@@ -112,6 +202,7 @@ impl From<&SoftwareTitle> for SoftwareTitleListModel {
         SoftwareTitleListModel {
             id: software_title.id,
             name: software_title.name.clone(),
+            sort_name: software_title.sort_key(),
             can_delete: false,
         }
     }
@@ -123,6 +214,87 @@ impl Display for SoftwareTitleListModel {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct FranchiseListModel {
+    pub id: i64,
+    pub name: String,
+    pub can_delete: bool,
+}
+
+impl From<&Franchise> for FranchiseListModel {
+    fn from(franchise: &Franchise) -> Self {
+        FranchiseListModel {
+            id: franchise.id,
+            name: franchise.name.clone(),
+            can_delete: false,
+        }
+    }
+}
+
+impl Display for FranchiseListModel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Franchise with its software titles and their releases on all systems.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FranchiseViewModel {
+    pub id: i64,
+    pub name: String,
+    pub software_titles: Vec<SoftwareTitleListModel>,
+    pub releases: Vec<ReleaseListModel>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectionListModel {
+    pub id: i64,
+    pub name: String,
+    pub is_favorites: bool,
+    /// Releases of a smart collection are the releases matching its criteria.
+    pub is_smart: bool,
+    pub release_count: usize,
+}
+
+impl Display for CollectionListModel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.release_count)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompanyListModel {
+    pub id: i64,
+    pub name: String,
+    pub can_delete: bool,
+}
+
+impl From<&Company> for CompanyListModel {
+    fn from(company: &Company) -> Self {
+        CompanyListModel {
+            id: company.id,
+            name: company.name.clone(),
+            can_delete: false,
+        }
+    }
+}
+
+impl Display for CompanyListModel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Company with the software titles it developed and the releases it published or distributed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompanyViewModel {
+    pub id: i64,
+    pub name: String,
+    pub developed_software_titles: Vec<SoftwareTitle>,
+    pub published_releases: Vec<Release>,
+    pub distributed_releases: Vec<Release>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct FileSetListModel {
     pub id: i64,
@@ -160,6 +332,36 @@ impl Display for FileSetViewModel {
     }
 }
 
+/// Firmware an emulator needs for a system, with the files not found in the collection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmulatorFirmwareViewModel {
+    pub id: i64,
+    pub file_set: FileSetViewModel,
+    pub target_path: String,
+    pub required: bool,
+    pub missing_files: Vec<String>,
+}
+
+impl EmulatorFirmwareViewModel {
+    /// Firmware file set without any files is missing as well.
+    pub fn is_missing(&self) -> bool {
+        self.file_set.files.is_empty() || !self.missing_files.is_empty()
+    }
+}
+
+impl Display for EmulatorFirmwareViewModel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {}", self.file_set.file_set_name, self.target_path)?;
+        if self.required {
+            write!(f, " (required)")?;
+        }
+        if self.is_missing() {
+            write!(f, " [missing]")?;
+        }
+        Ok(())
+    }
+}
+
 pub struct FileSetFileViewModel {
     pub id: i64,
     pub file_name: String,
@@ -192,6 +394,25 @@ impl Display for ReleaseListModel {
     }
 }
 
+/// Emulator launch resolved for a release. Choices that couldn't be made unambiguously are
+/// `None` and have to be picked by the user.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ReleaseLaunchSelection {
+    pub system_id: Option<i64>,
+    pub file_set_id: Option<i64>,
+    pub file_info_id: Option<i64>,
+    pub emulator_id: Option<i64>,
+}
+
+impl ReleaseLaunchSelection {
+    pub fn is_complete(&self) -> bool {
+        self.system_id.is_some()
+            && self.file_set_id.is_some()
+            && self.file_info_id.is_some()
+            && self.emulator_id.is_some()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ReleaseViewModel {
     pub id: i64,
@@ -199,4 +420,8 @@ pub struct ReleaseViewModel {
     pub systems: Vec<System>,
     pub software_titles: Vec<SoftwareTitle>,
     pub file_sets: Vec<FileSetViewModel>,
+    pub metadata: ReleaseMetadata,
+    pub companies: Vec<ReleaseCompany>,
+    /// Tags of the release itself, tags of its software titles and file sets are not included.
+    pub tags: Vec<Tag>,
 }
@@ -0,0 +1,519 @@
+use std::sync::Arc;
+
+use async_std::channel::{self, Receiver};
+use emulator_runner::{
+    error::EmulatorRunnerError,
+    process_registry::{LaunchId, ProcessRegistry},
+};
+use ratatui::crossterm::event::KeyCode;
+use service::{
+    error::Error,
+    release_launch::ReleaseLaunchRequest,
+    view_model_service::{ReleaseFilter, ViewModelService},
+    view_models::{RankedEmulatorViewModel, ReleaseListModel, ReleaseViewModel},
+};
+
+use crate::selectable_list::SelectableList;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Focus {
+    Systems,
+    SoftwareTitles,
+    Releases,
+    Emulators,
+}
+
+impl Focus {
+    fn next(self) -> Self {
+        match self {
+            Focus::Systems => Focus::SoftwareTitles,
+            Focus::SoftwareTitles => Focus::Releases,
+            Focus::Releases => Focus::Emulators,
+            Focus::Emulators => Focus::Systems,
+        }
+    }
+
+    fn previous(self) -> Self {
+        match self {
+            Focus::Systems => Focus::Emulators,
+            Focus::SoftwareTitles => Focus::Systems,
+            Focus::Releases => Focus::SoftwareTitles,
+            Focus::Emulators => Focus::Releases,
+        }
+    }
+}
+
+/// Entry of the system and software title lists the releases are filtered with, `None` doesn't
+/// filter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterItem {
+    pub id: Option<i64>,
+    pub name: String,
+}
+
+/// Work resulting from a key press, done by the main loop.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    LoadReleases,
+    LoadRelease,
+    Launch(ReleaseLaunchRequest),
+    Quit,
+}
+
+/// Launch running in the background, the result is received when the emulator has exited.
+struct RunningLaunch {
+    launch_id: LaunchId,
+    description: String,
+    result: Receiver<Result<(), EmulatorRunnerError>>,
+}
+
+pub struct App {
+    view_model_service: Arc<ViewModelService>,
+    process_registry: ProcessRegistry,
+    running_launch: Option<RunningLaunch>,
+    pub focus: Focus,
+    pub systems: SelectableList<FilterItem>,
+    pub software_titles: SelectableList<FilterItem>,
+    pub releases: SelectableList<ReleaseListModel>,
+    pub release: Option<ReleaseViewModel>,
+    /// File set of the release to launch, resolved for the release when not chosen.
+    pub file_set_index: Option<usize>,
+    /// Emulators ranked by compatibility with the release on `emulator_system_id`.
+    pub emulators: SelectableList<RankedEmulatorViewModel>,
+    pub emulator_system_id: Option<i64>,
+    pub playlist: bool,
+    pub status: String,
+}
+
+impl App {
+    pub fn new(view_model_service: Arc<ViewModelService>) -> Self {
+        Self {
+            view_model_service,
+            process_registry: ProcessRegistry::new(),
+            running_launch: None,
+            focus: Focus::Systems,
+            systems: SelectableList::default(),
+            software_titles: SelectableList::default(),
+            releases: SelectableList::default(),
+            release: None,
+            file_set_index: None,
+            emulators: SelectableList::default(),
+            emulator_system_id: None,
+            playlist: false,
+            status: String::new(),
+        }
+    }
+
+    pub async fn load(&mut self) -> Result<(), Error> {
+        let systems = self.view_model_service.get_system_list_models().await?;
+        let software_titles = self
+            .view_model_service
+            .get_software_title_list_models()
+            .await?;
+        self.systems = SelectableList::new(
+            std::iter::once(FilterItem {
+                id: None,
+                name: "All systems".to_string(),
+            })
+            .chain(systems.into_iter().map(|system| FilterItem {
+                id: Some(system.id),
+                name: system.name,
+            }))
+            .collect(),
+        );
+        self.software_titles = SelectableList::new(
+            std::iter::once(FilterItem {
+                id: None,
+                name: "All titles".to_string(),
+            })
+            .chain(
+                software_titles
+                    .into_iter()
+                    .map(|software_title| FilterItem {
+                        id: Some(software_title.id),
+                        name: software_title.name,
+                    }),
+            )
+            .collect(),
+        );
+        self.load_releases().await
+    }
+
+    pub async fn load_releases(&mut self) -> Result<(), Error> {
+        let filter = ReleaseFilter {
+            system_ids: self.selected_system_id().into_iter().collect(),
+            software_title_ids: self
+                .software_titles
+                .selected()
+                .and_then(|item| item.id)
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+        let releases = self
+            .view_model_service
+            .get_release_list_models(filter)
+            .await?;
+        self.releases.set_items(releases);
+        self.load_release().await
+    }
+
+    pub async fn load_release(&mut self) -> Result<(), Error> {
+        self.file_set_index = None;
+        let Some(release_id) = self.releases.selected().map(|release| release.id) else {
+            self.release = None;
+            self.emulators = SelectableList::default();
+            self.emulator_system_id = None;
+            return Ok(());
+        };
+        let release = self
+            .view_model_service
+            .get_release_view_model(release_id)
+            .await?;
+        // emulators are listed for the filtered system when the release is for several systems
+        let system_id = release
+            .systems
+            .iter()
+            .find(|system| Some(system.id) == self.selected_system_id())
+            .or(release.systems.first())
+            .map(|system| system.id);
+        let emulators = match system_id {
+            Some(system_id) => {
+                self.view_model_service
+                    .get_ranked_emulators_for_release(release_id, system_id)
+                    .await?
+            }
+            None => vec![],
+        };
+        self.emulators = SelectableList::new(emulators);
+        self.emulator_system_id = system_id;
+        self.release = Some(release);
+        Ok(())
+    }
+
+    /// Starts launching the release, the emulator runs in the background until it exits or is
+    /// stopped, see `update_launch`.
+    pub async fn launch(&mut self, request: &ReleaseLaunchRequest) {
+        if let Some(running_launch) = &self.running_launch {
+            self.status = format!("Running {}, s to stop", running_launch.description);
+            return;
+        }
+        let launch = match self
+            .view_model_service
+            .prepare_release_launch(request)
+            .await
+        {
+            Ok(launch) => launch,
+            Err(Error::InvalidSelection(message)) if request.emulator_id.is_none() => {
+                self.focus = Focus::Emulators;
+                self.status = format!("{}, select the emulator and press Enter", message);
+                return;
+            }
+            Err(e) => {
+                self.status = e.to_string();
+                return;
+            }
+        };
+        let description = format!(
+            "{} with {} on {}",
+            launch.launched_file, launch.emulator_name, launch.system_name
+        );
+        let launch = match launch.register(&self.process_registry) {
+            Ok(launch) => launch,
+            Err(e) => {
+                self.status = format!("Failed running {}: {}", description, e);
+                return;
+            }
+        };
+        let (sender, result) = channel::bounded(1);
+        self.running_launch = Some(RunningLaunch {
+            launch_id: launch.launch_id(),
+            description: description.clone(),
+            result,
+        });
+        let view_model_service = Arc::clone(&self.view_model_service);
+        async_std::task::spawn(async move {
+            let (release_id, emulator_id, system_id) = (
+                launch.launch.release_id,
+                launch.launch.emulator_id,
+                launch.launch.system_id,
+            );
+            let result = launch.run().await;
+            // launch history is best effort, the result of the launch is shown anyway
+            let _ = view_model_service
+                .record_launch(release_id, emulator_id, system_id, &result)
+                .await;
+            let _ = sender.send(result).await;
+        });
+        self.status = format!("Running {}, s to stop", description);
+    }
+
+    pub fn is_launch_running(&self) -> bool {
+        self.running_launch.is_some()
+    }
+
+    /// Shows the result of the running launch once the emulator has exited, returns whether the
+    /// launch finished.
+    pub fn update_launch(&mut self) -> bool {
+        let Some(running_launch) = &self.running_launch else {
+            return false;
+        };
+        let Ok(result) = running_launch.result.try_recv() else {
+            return false;
+        };
+        self.status = match result {
+            Ok(()) => format!("Finished {}", running_launch.description),
+            Err(EmulatorRunnerError::Stopped) => format!("Stopped {}", running_launch.description),
+            Err(e) => format!("Failed running {}: {}", running_launch.description, e),
+        };
+        self.running_launch = None;
+        true
+    }
+
+    /// Asks the emulator of the running launch to exit.
+    fn stop_launch(&mut self) {
+        let Some(running_launch) = &self.running_launch else {
+            return;
+        };
+        self.status = match self.process_registry.terminate(running_launch.launch_id) {
+            Ok(()) => format!("Stopping {}", running_launch.description),
+            Err(e) => e.to_string(),
+        };
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode) -> Option<Action> {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => Some(Action::Quit),
+            KeyCode::Tab | KeyCode::Right => {
+                self.focus = self.focus.next();
+                None
+            }
+            KeyCode::BackTab | KeyCode::Left => {
+                self.focus = self.focus.previous();
+                None
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(true),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(false),
+            KeyCode::Char('f') => {
+                self.select_next_file_set();
+                None
+            }
+            KeyCode::Char('p') => {
+                self.playlist = !self.playlist;
+                None
+            }
+            KeyCode::Char('s') => {
+                self.stop_launch();
+                None
+            }
+            KeyCode::Enter => match self.focus {
+                Focus::Systems | Focus::SoftwareTitles => {
+                    self.focus = Focus::Releases;
+                    None
+                }
+                Focus::Releases | Focus::Emulators => self.launch_request().map(Action::Launch),
+            },
+            _ => None,
+        }
+    }
+
+    fn selected_system_id(&self) -> Option<i64> {
+        self.systems.selected().and_then(|item| item.id)
+    }
+
+    fn move_selection(&mut self, down: bool) -> Option<Action> {
+        fn select<T>(list: &mut SelectableList<T>, down: bool) -> bool {
+            let selected = list.state.selected();
+            if down {
+                list.select_next();
+            } else {
+                list.select_previous();
+            }
+            list.state.selected() != selected
+        }
+        match self.focus {
+            Focus::Systems => select(&mut self.systems, down).then_some(Action::LoadReleases),
+            Focus::SoftwareTitles => {
+                select(&mut self.software_titles, down).then_some(Action::LoadReleases)
+            }
+            Focus::Releases => select(&mut self.releases, down).then_some(Action::LoadRelease),
+            Focus::Emulators => {
+                select(&mut self.emulators, down);
+                None
+            }
+        }
+    }
+
+    /// Cycles through the file sets of the release, after the last one the file set is resolved
+    /// again for the release.
+    fn select_next_file_set(&mut self) {
+        let file_set_count = self
+            .release
+            .as_ref()
+            .map_or(0, |release| release.file_sets.len());
+        self.file_set_index = match self.file_set_index {
+            None if file_set_count > 0 => Some(0),
+            Some(index) if index + 1 < file_set_count => Some(index + 1),
+            _ => None,
+        };
+    }
+
+    /// Emulator is chosen when launching from the emulator list, otherwise it's resolved for the
+    /// release.
+    fn launch_request(&self) -> Option<ReleaseLaunchRequest> {
+        let release = self.release.as_ref()?;
+        let emulator = match self.focus {
+            Focus::Emulators => Some(self.emulators.selected()?),
+            _ => None,
+        };
+        Some(ReleaseLaunchRequest {
+            release_id: release.id,
+            system_id: emulator.and(self.emulator_system_id),
+            emulator_id: emulator.map(|emulator| emulator.emulator.id),
+            file_set_id: self
+                .file_set_index
+                .and_then(|index| release.file_sets.get(index))
+                .map(|file_set| file_set.id),
+            file_name: None,
+            playlist: self.playlist,
+            // file sets with a role are launched along with the roles saved for the release
+            file_set_roles: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core_types::ImportedFile;
+    use database::{
+        models::{EmulatorLaunchSettings, EmulatorSystemUpdateModel, FileType},
+        repository_manager::RepositoryManager,
+        setup_test_db,
+    };
+
+    use super::*;
+
+    #[async_std::test]
+    async fn test_browse_and_launch_request() {
+        let pool = Arc::new(setup_test_db().await);
+        let repository_manager = Arc::new(RepositoryManager::new(pool));
+        let mut system_ids = vec![];
+        for name in ["Commodore 64", "ZX Spectrum"] {
+            system_ids.push(
+                repository_manager
+                    .get_system_repository()
+                    .add_system(&name.to_string())
+                    .await
+                    .unwrap(),
+            );
+        }
+        let software_title_id = repository_manager
+            .get_software_title_repository()
+            .add_software_title(&"Game".to_string(), None)
+            .await
+            .unwrap();
+        for (i, system_id) in system_ids.iter().enumerate() {
+            let file_set_id = repository_manager
+                .get_file_set_repository()
+                .add_file_set(
+                    format!("Game {}", i),
+                    FileType::DiskImage,
+                    vec![ImportedFile {
+                        original_file_name: "game.dsk".to_string(),
+                        archive_file_name: format!("archive_{}", i),
+                        sha1_checksum: [i as u8; 20],
+                        file_size: 1,
+                    }],
+                    &[*system_id],
+                )
+                .await
+                .unwrap();
+            repository_manager
+                .get_release_repository()
+                .add_release_full(
+                    format!("Game {}", i),
+                    vec![software_title_id],
+                    vec![file_set_id],
+                    vec![*system_id],
+                )
+                .await
+                .unwrap();
+        }
+        let emulator_id = repository_manager
+            .get_emulator_repository()
+            .add_emulator_with_systems(
+                "Fuse".to_string(),
+                "fuse".to_string(),
+                true,
+                vec![EmulatorSystemUpdateModel {
+                    id: None,
+                    system_id: system_ids[1],
+                    arguments: String::new(),
+                }],
+                EmulatorLaunchSettings::default(),
+            )
+            .await
+            .unwrap();
+
+        let mut app = App::new(Arc::new(ViewModelService::new(repository_manager)));
+        app.load().await.unwrap();
+        assert_eq!(app.systems.items.len(), 3);
+        assert_eq!(app.releases.items.len(), 2);
+
+        // filter by the second system
+        assert_eq!(app.handle_key(KeyCode::Down), Some(Action::LoadReleases));
+        assert_eq!(app.handle_key(KeyCode::Down), Some(Action::LoadReleases));
+        // already at the last system
+        assert_eq!(app.handle_key(KeyCode::Down), None);
+        app.load_releases().await.unwrap();
+        assert_eq!(app.releases.items.len(), 1);
+        assert_eq!(app.releases.items[0].name, "Game 1");
+        assert_eq!(app.emulator_system_id, Some(system_ids[1]));
+        assert_eq!(app.emulators.items.len(), 1);
+
+        assert_eq!(app.handle_key(KeyCode::Enter), None);
+        assert_eq!(app.focus, Focus::Releases);
+        app.handle_key(KeyCode::Char('f'));
+        app.handle_key(KeyCode::Char('p'));
+        let release = app.release.as_ref().unwrap();
+        let (release_id, file_set_id) = (release.id, release.file_sets[0].id);
+        assert_eq!(
+            app.handle_key(KeyCode::Enter),
+            Some(Action::Launch(ReleaseLaunchRequest {
+                release_id,
+                system_id: None,
+                emulator_id: None,
+                file_set_id: Some(file_set_id),
+                file_name: None,
+                playlist: true,
+                file_set_roles: None,
+            }))
+        );
+
+        app.handle_key(KeyCode::Tab);
+        assert_eq!(app.focus, Focus::Emulators);
+        let Some(Action::Launch(request)) = app.handle_key(KeyCode::Enter) else {
+            panic!("Expected launch");
+        };
+        assert_eq!(request.emulator_id, Some(emulator_id));
+        assert_eq!(request.system_id, Some(system_ids[1]));
+
+        // launch directory of the file set is used by a launch still running
+        let running_launch = app
+            .view_model_service
+            .prepare_release_launch(&request)
+            .await
+            .unwrap()
+            .register(&app.process_registry)
+            .unwrap();
+        app.launch(&request).await;
+        assert!(!app.is_launch_running());
+        assert!(app.status.contains("in use"), "{}", app.status);
+        drop(running_launch);
+
+        // nothing to stop
+        app.status.clear();
+        assert_eq!(app.handle_key(KeyCode::Char('s')), None);
+        assert!(app.status.is_empty());
+    }
+}
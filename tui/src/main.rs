@@ -0,0 +1,71 @@
+mod app;
+mod selectable_list;
+mod ui;
+
+use std::{io, sync::Arc, time::Duration};
+
+use app::{Action, App};
+use database::{get_db_pool, repository_manager::RepositoryManager};
+use ratatui::{
+    crossterm::event::{self, Event, KeyEventKind},
+    DefaultTerminal,
+};
+use service::view_model_service::ViewModelService;
+
+#[async_std::main]
+async fn main() -> io::Result<()> {
+    let pool = match get_db_pool().await {
+        Ok(pool) => pool,
+        Err(err) => {
+            eprintln!("Failed to open the database: {}", err);
+            std::process::exit(1);
+        }
+    };
+    let repository_manager = Arc::new(RepositoryManager::new(pool));
+    let mut app = App::new(Arc::new(ViewModelService::new(repository_manager)));
+    if let Err(err) = app.load().await {
+        app.status = err.to_string();
+    }
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut app).await;
+    ratatui::restore();
+    result
+}
+
+/// How often the result of a running launch is checked while waiting for keys.
+const LAUNCH_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+async fn run(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+        if app.is_launch_running() && !event::poll(LAUNCH_POLL_INTERVAL)? {
+            if app.update_launch() {
+                // emulator output may have been written over the screen
+                terminal.clear()?;
+            }
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let Some(action) = app.handle_key(key.code) else {
+            continue;
+        };
+        let result = match action {
+            Action::Quit => return Ok(()),
+            Action::LoadReleases => app.load_releases().await,
+            Action::LoadRelease => app.load_release().await,
+            Action::Launch(request) => {
+                app.launch(&request).await;
+                Ok(())
+            }
+        };
+        if let Err(err) = result {
+            app.status = err.to_string();
+        }
+    }
+}
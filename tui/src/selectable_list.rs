@@ -0,0 +1,56 @@
+use ratatui::widgets::ListState;
+
+/// List items with the selection kept by the list widget state.
+#[derive(Debug)]
+pub struct SelectableList<T> {
+    pub items: Vec<T>,
+    pub state: ListState,
+}
+
+impl<T> Default for SelectableList<T> {
+    fn default() -> Self {
+        Self {
+            items: vec![],
+            state: ListState::default(),
+        }
+    }
+}
+
+impl<T> SelectableList<T> {
+    pub fn new(items: Vec<T>) -> Self {
+        let mut list = Self::default();
+        list.set_items(items);
+        list
+    }
+
+    /// Replaces the items, selection stays at the same position when possible.
+    pub fn set_items(&mut self, items: Vec<T>) {
+        let selected = match (self.state.selected(), items.len()) {
+            (_, 0) => None,
+            (Some(index), len) => Some(index.min(len - 1)),
+            (None, _) => Some(0),
+        };
+        self.items = items;
+        self.state.select(selected);
+    }
+
+    pub fn selected(&self) -> Option<&T> {
+        self.state
+            .selected()
+            .and_then(|index| self.items.get(index))
+    }
+
+    pub fn select_next(&mut self) {
+        if let Some(index) = self.state.selected() {
+            if index + 1 < self.items.len() {
+                self.state.select(Some(index + 1));
+            }
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if let Some(index) = self.state.selected() {
+            self.state.select(Some(index.saturating_sub(1)));
+        }
+    }
+}
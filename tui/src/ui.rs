@@ -0,0 +1,168 @@
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, List, ListItem, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::{App, Focus};
+
+const HELP: &str =
+    "Tab/←→ switch list  ↑↓/jk move  Enter launch  s stop  f file set  p playlist  q quit";
+
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let [main_area, status_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(2)]).areas(frame.area());
+    let [systems_area, software_titles_area, releases_area, details_area] = Layout::horizontal([
+        Constraint::Percentage(18),
+        Constraint::Percentage(22),
+        Constraint::Percentage(25),
+        Constraint::Percentage(35),
+    ])
+    .areas(main_area);
+    let [release_area, emulators_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(8)]).areas(details_area);
+
+    let focus = app.focus;
+    draw_list(
+        frame,
+        systems_area,
+        "Systems",
+        focus == Focus::Systems,
+        app.systems.items.iter().map(|item| item.name.clone()),
+        &mut app.systems.state,
+    );
+    draw_list(
+        frame,
+        software_titles_area,
+        "Software titles",
+        focus == Focus::SoftwareTitles,
+        app.software_titles
+            .items
+            .iter()
+            .map(|item| item.name.clone()),
+        &mut app.software_titles.state,
+    );
+    draw_list(
+        frame,
+        releases_area,
+        "Releases",
+        focus == Focus::Releases,
+        app.releases
+            .items
+            .iter()
+            .map(|release| format!("{} ({})", release.name, release.system_names.join(", "))),
+        &mut app.releases.state,
+    );
+    frame.render_widget(
+        Paragraph::new(release_lines(app))
+            .block(Block::bordered().title("Release"))
+            .wrap(Wrap { trim: false }),
+        release_area,
+    );
+    draw_list(
+        frame,
+        emulators_area,
+        "Emulators",
+        focus == Focus::Emulators,
+        app.emulators
+            .items
+            .iter()
+            .map(|emulator| emulator.to_string()),
+        &mut app.emulators.state,
+    );
+    frame.render_widget(
+        Paragraph::new(vec![Line::from(app.status.as_str()), Line::from(HELP)]),
+        status_area,
+    );
+}
+
+fn draw_list(
+    frame: &mut Frame,
+    area: Rect,
+    title: &str,
+    focused: bool,
+    items: impl Iterator<Item = String>,
+    state: &mut ratatui::widgets::ListState,
+) {
+    let border_style = if focused {
+        Style::default().add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().add_modifier(Modifier::DIM)
+    };
+    let list = List::new(items.map(ListItem::new))
+        .block(Block::bordered().title(title).border_style(border_style))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, area, state);
+}
+
+fn release_lines(app: &App) -> Vec<Line<'static>> {
+    let Some(release) = &app.release else {
+        return vec![Line::from("No release selected")];
+    };
+    let mut lines = vec![
+        Line::from(release.name.clone()).style(Style::default().add_modifier(Modifier::BOLD)),
+        Line::from(format!(
+            "Systems: {}",
+            release
+                .systems
+                .iter()
+                .map(|system| system.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        Line::from(format!(
+            "Software titles: {}",
+            release
+                .software_titles
+                .iter()
+                .map(|software_title| software_title.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    ];
+    let metadata = &release.metadata;
+    let details = [
+        (
+            "Released",
+            metadata.release_date.map(|date| date.to_string()),
+        ),
+        ("Regions", Some(metadata.regions.join(", "))),
+        ("Languages", Some(metadata.languages.join(", "))),
+        ("Version", metadata.version.clone()),
+        ("Product code", metadata.product_code.clone()),
+        ("Barcode", metadata.barcode.clone()),
+    ];
+    for (label, value) in details {
+        if let Some(value) = value.filter(|value| !value.is_empty()) {
+            lines.push(Line::from(format!("{}: {}", label, value)));
+        }
+    }
+    lines.push(Line::from(format!(
+        "Playlist: {}",
+        if app.playlist { "yes" } else { "no" }
+    )));
+    for (index, file_set) in release.file_sets.iter().enumerate() {
+        let marker = if app.file_set_index == Some(index) {
+            "*"
+        } else {
+            " "
+        };
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!("{} {}", marker, file_set)));
+        for file in &file_set.files {
+            let entry_point = if file.is_entry_point {
+                " (entry point)"
+            } else {
+                ""
+            };
+            lines.push(Line::from(format!(
+                "    {}  {} bytes{}",
+                file.file_name, file.file_size, entry_point
+            )));
+        }
+    }
+    lines
+}